@@ -29,11 +29,16 @@ pub trait GroupMap<F> {
 }
 
 pub struct BWParameters<G: SWModelParameters> {
-    u: G::BaseField,
-    fu: G::BaseField,
-    sqrt_neg_three_u_squared_minus_u_over_2: G::BaseField,
-    sqrt_neg_three_u_squared: G::BaseField,
-    inv_three_u_squared: G::BaseField,
+    /// The smallest positive `u` such that `f(u) = u^3 + A u + B` is not zero
+    pub u: G::BaseField,
+    /// `f(u)`
+    pub fu: G::BaseField,
+    /// `(sqrt(-3 u^2) - u) / 2`
+    pub sqrt_neg_three_u_squared_minus_u_over_2: G::BaseField,
+    /// `sqrt(-3 u^2)`
+    pub sqrt_neg_three_u_squared: G::BaseField,
+    /// `1 / (3 u^2)`
+    pub inv_three_u_squared: G::BaseField,
 }
 
 /// returns the right-hand side of the Short Weierstrass curve equation for a given x
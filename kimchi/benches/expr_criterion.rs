@@ -0,0 +1,155 @@
+use ark_ff::UniformRand;
+use ark_poly::{EvaluationDomain, Evaluations};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, SamplingMode};
+use kimchi::circuits::{
+    domains::EvaluationDomains,
+    expr::{l0_1, Column, Constants, Environment, PolishToken},
+    program::{Op, Program},
+    scalars::ProofEvaluations,
+    wires::COLUMNS,
+};
+use kimchi::linearization::{constraints_expr, linearization_columns};
+use mina_curves::pasta::fp::Fp as F;
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::HashMap;
+
+fn random_constants(rng: &mut StdRng) -> Constants<F> {
+    Constants {
+        alpha: F::rand(rng),
+        beta: F::rand(rng),
+        gamma: F::rand(rng),
+        joint_combiner: F::rand(rng),
+        endo_coefficient: F::rand(rng),
+        mds: (0..3)
+            .map(|_| (0..3).map(|_| F::rand(rng)).collect())
+            .collect(),
+    }
+}
+
+pub fn bench_compilation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Constraints compilation");
+    group.sample_size(10);
+
+    let domain = EvaluationDomains::<F>::create(1 << 10).unwrap();
+    let (expr, _) = constraints_expr(domain.d1, true, true, true, None);
+    group.bench_function("compile constraints_expr", |b| {
+        b.iter(|| black_box(Program::compile(&expr)))
+    });
+}
+
+pub fn bench_evaluation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Constraints evaluation at a point");
+    let rng = &mut StdRng::from_seed([0; 32]);
+
+    let domain = EvaluationDomains::<F>::create(1 << 10).unwrap();
+    let (expr, _) = constraints_expr(domain.d1, true, true, true, None);
+    let lowered = Program::lower(&expr);
+    let optimized = lowered.optimize();
+
+    let mut cells = HashMap::new();
+    for op in &lowered.ops {
+        if let Op::Cell(v) = op {
+            cells.entry(*v).or_insert_with(|| F::rand(rng));
+        }
+    }
+    let constants = random_constants(rng);
+    let pt = F::rand(rng);
+    let cell = |v: &_| Ok(cells[v]);
+    group.bench_function("lowered program", |b| {
+        b.iter(|| black_box(lowered.evaluate_with(domain.d1, pt, &constants, cell)))
+    });
+    group.bench_function("optimized program", |b| {
+        b.iter(|| black_box(optimized.evaluate_with(domain.d1, pt, &constants, cell)))
+    });
+
+    // the linearization, as evaluated by the verifier
+    let linearization = expr
+        .linearize(linearization_columns::<F>(true, None))
+        .unwrap();
+    let unoptimized = linearization.map(|e| e.to_polish());
+    let compiled = linearization.map(|e| Program::compile(e).to_polish());
+    let w: [F; COLUMNS] = array_init::array_init(|_| F::rand(rng));
+    let evals = [
+        ProofEvaluations::dummy_with_witness_evaluations(w),
+        ProofEvaluations::dummy_with_witness_evaluations(w),
+    ];
+    group.bench_function("linearization", |b| {
+        b.iter(|| {
+            black_box(PolishToken::evaluate(
+                &unoptimized.constant_term,
+                domain.d1,
+                pt,
+                &evals,
+                &constants,
+            ))
+        })
+    });
+    group.bench_function("compiled linearization", |b| {
+        b.iter(|| {
+            black_box(PolishToken::evaluate(
+                &compiled.constant_term,
+                domain.d1,
+                pt,
+                &evals,
+                &constants,
+            ))
+        })
+    });
+}
+
+pub fn bench_evaluations(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Constraints evaluations over d8");
+    group.sample_size(10).sampling_mode(SamplingMode::Flat); // for slow benchmarks
+    let rng = &mut StdRng::from_seed([0; 32]);
+
+    let domain = EvaluationDomains::<F>::create(1 << 14).unwrap();
+    let (expr, _) = constraints_expr(domain.d1, true, true, true, None);
+    let optimized = Program::compile(&expr);
+
+    let mut random_evals = || {
+        Evaluations::from_vec_and_domain(
+            (0..domain.d8.size()).map(|_| F::rand(rng)).collect(),
+            domain.d8,
+        )
+    };
+    let witness: [_; COLUMNS] = array_init::array_init(|_| random_evals());
+    let coefficient: [_; COLUMNS] = array_init::array_init(|_| random_evals());
+    let vanishes_on_last_4_rows = random_evals();
+    let z = random_evals();
+    let selectors: HashMap<_, _> = optimized
+        .ops
+        .iter()
+        .filter_map(|op| match op {
+            Op::Cell(v) => match v.col {
+                Column::Index(t) => Some(t),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|t| (t, random_evals()))
+        .collect();
+    let env = Environment {
+        witness: &witness,
+        coefficient: &coefficient,
+        vanishes_on_last_4_rows: &vanishes_on_last_4_rows,
+        z: &z,
+        index: selectors.iter().map(|(t, e)| (*t, e)).collect(),
+        l0_1: l0_1(domain.d1),
+        constants: random_constants(rng),
+        domain,
+        lookup: None,
+    };
+
+    group.bench_function("expr", |b| b.iter(|| black_box(expr.evaluations(&env))));
+    group.bench_function("optimized program", |b| {
+        b.iter(|| black_box(optimized.evaluations(&env)))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_compilation,
+    bench_evaluation,
+    bench_evaluations
+);
+criterion_main!(benches);
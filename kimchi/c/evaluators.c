@@ -0,0 +1,1382 @@
+/* This file evaluates the constraints of the gates.
+ * It is generated by `cargo run --example codegen`, do not edit it.
+ *
+ * It includes "field.h", which defines the type `field` of the elements of the field, and the functions
+ *
+ *   void field_add(field *r, const field *x, const field *y);
+ *   void field_sub(field *r, const field *x, const field *y);
+ *   void field_mul(field *r, const field *x, const field *y);
+ *   void field_from_fraction(field *r, int negative, uint64_t n, uint64_t d);
+ *   void field_from_limbs(field *r, const uint64_t *limbs, size_t n);
+ *
+ * where `field_from_fraction` sets `r` to `n / d`, or to `-n / d` if `negative` is not zero,
+ * and `field_from_limbs` sets `r` to the element whose little-endian 64-bit limbs are `limbs[0..n]`.
+ * The literals which are small fractions do not depend on the field,
+ * the others are written as the limbs of the elements of the field for which the code was generated.
+ * The MDS matrix is passed in row-major order.
+ */
+
+#include <stddef.h>
+#include <stdint.h>
+#include "field.h"
+
+/* Evaluates the 7 constraints of the gate `complete_add` into out[0..7]. */
+void complete_add(const field *curr, const field *next, const field *coeffs, const field *endo_coefficient, const field *mds, field *out)
+{
+    (void)curr; (void)next; (void)coeffs; (void)endo_coefficient; (void)mds;
+    field r0 = curr[10];
+    field r1 = curr[2];
+    field r2 = curr[0];
+    field r3; field_sub(&r3, &r1, &r2);
+    field r4; field_mul(&r4, &r0, &r3);
+    field r5; field_from_fraction(&r5, 0, 0x1, 1);
+    field r6 = curr[7];
+    field r7; field_sub(&r7, &r5, &r6);
+    field r8; field_sub(&r8, &r4, &r7);
+    field r9; field_mul(&r9, &r3, &r6);
+    field r10 = curr[8];
+    field r11; field_add(&r11, &r10, &r10);
+    field r12 = curr[1];
+    field r13; field_mul(&r13, &r11, &r12);
+    field r14; field_mul(&r14, &r2, &r2);
+    field r15; field_add(&r15, &r14, &r14);
+    field r16; field_sub(&r16, &r13, &r15);
+    field r17; field_sub(&r17, &r16, &r14);
+    field r18; field_mul(&r18, &r6, &r17);
+    field r19; field_mul(&r19, &r3, &r10);
+    field r20 = curr[3];
+    field r21; field_sub(&r21, &r20, &r12);
+    field r22; field_sub(&r22, &r19, &r21);
+    field r23; field_mul(&r23, &r7, &r22);
+    field r24; field_add(&r24, &r18, &r23);
+    field r25; field_add(&r25, &r1, &r2);
+    field r26 = curr[4];
+    field r27; field_add(&r27, &r25, &r26);
+    field r28; field_mul(&r28, &r10, &r10);
+    field r29; field_sub(&r29, &r27, &r28);
+    field r30; field_sub(&r30, &r2, &r26);
+    field r31; field_mul(&r31, &r10, &r30);
+    field r32; field_sub(&r32, &r31, &r12);
+    field r33 = curr[5];
+    field r34; field_sub(&r34, &r32, &r33);
+    field r35 = curr[6];
+    field r36; field_sub(&r36, &r6, &r35);
+    field r37; field_mul(&r37, &r21, &r36);
+    field r38 = curr[9];
+    field r39; field_mul(&r39, &r21, &r38);
+    field r40; field_sub(&r40, &r39, &r35);
+    out[0] = r8;
+    out[1] = r9;
+    out[2] = r24;
+    out[3] = r29;
+    out[4] = r34;
+    out[5] = r37;
+    out[6] = r40;
+}
+
+/* Evaluates the 21 constraints of the gate `varbasemul` into out[0..21]. */
+void varbasemul(const field *curr, const field *next, const field *coeffs, const field *endo_coefficient, const field *mds, field *out)
+{
+    (void)curr; (void)next; (void)coeffs; (void)endo_coefficient; (void)mds;
+    field r0 = curr[5];
+    field r1 = next[6];
+    field r2 = next[5];
+    field r3 = next[4];
+    field r4 = next[3];
+    field r5 = next[2];
+    field r6 = curr[4];
+    field r7; field_add(&r7, &r6, &r6);
+    field r8; field_add(&r8, &r5, &r7);
+    field r9; field_add(&r9, &r8, &r8);
+    field r10; field_add(&r10, &r4, &r9);
+    field r11; field_add(&r11, &r10, &r10);
+    field r12; field_add(&r12, &r3, &r11);
+    field r13; field_add(&r13, &r12, &r12);
+    field r14; field_add(&r14, &r2, &r13);
+    field r15; field_add(&r15, &r14, &r14);
+    field r16; field_add(&r16, &r1, &r15);
+    field r17; field_sub(&r17, &r0, &r16);
+    field r18; field_mul(&r18, &r5, &r5);
+    field r19; field_sub(&r19, &r18, &r5);
+    field r20 = curr[2];
+    field r21 = curr[0];
+    field r22; field_sub(&r22, &r20, &r21);
+    field r23 = next[7];
+    field r24; field_mul(&r24, &r22, &r23);
+    field r25 = curr[3];
+    field r26; field_add(&r26, &r5, &r5);
+    field r27; field_from_fraction(&r27, 0, 0x1, 1);
+    field r28; field_sub(&r28, &r26, &r27);
+    field r29 = curr[1];
+    field r30; field_mul(&r30, &r28, &r29);
+    field r31; field_sub(&r31, &r25, &r30);
+    field r32; field_sub(&r32, &r24, &r31);
+    field r33; field_add(&r33, &r25, &r25);
+    field r34; field_mul(&r34, &r23, &r23);
+    field r35; field_sub(&r35, &r34, &r20);
+    field r36; field_sub(&r36, &r35, &r21);
+    field r37; field_sub(&r37, &r20, &r36);
+    field r38; field_mul(&r38, &r23, &r37);
+    field r39; field_sub(&r39, &r33, &r38);
+    field r40; field_mul(&r40, &r39, &r39);
+    field r41; field_mul(&r41, &r37, &r37);
+    field r42 = curr[7];
+    field r43; field_sub(&r43, &r42, &r21);
+    field r44; field_add(&r44, &r34, &r43);
+    field r45; field_mul(&r45, &r41, &r44);
+    field r46; field_sub(&r46, &r40, &r45);
+    field r47 = curr[8];
+    field r48; field_add(&r48, &r25, &r47);
+    field r49; field_mul(&r49, &r37, &r48);
+    field r50; field_sub(&r50, &r20, &r42);
+    field r51; field_mul(&r51, &r39, &r50);
+    field r52; field_sub(&r52, &r49, &r51);
+    field r53; field_mul(&r53, &r4, &r4);
+    field r54; field_sub(&r54, &r53, &r4);
+    field r55 = next[8];
+    field r56; field_mul(&r56, &r43, &r55);
+    field r57; field_add(&r57, &r4, &r4);
+    field r58; field_sub(&r58, &r57, &r27);
+    field r59; field_mul(&r59, &r29, &r58);
+    field r60; field_sub(&r60, &r47, &r59);
+    field r61; field_sub(&r61, &r56, &r60);
+    field r62; field_add(&r62, &r47, &r47);
+    field r63; field_mul(&r63, &r55, &r55);
+    field r64; field_sub(&r64, &r63, &r42);
+    field r65; field_sub(&r65, &r64, &r21);
+    field r66; field_sub(&r66, &r42, &r65);
+    field r67; field_mul(&r67, &r55, &r66);
+    field r68; field_sub(&r68, &r62, &r67);
+    field r69; field_mul(&r69, &r68, &r68);
+    field r70; field_mul(&r70, &r66, &r66);
+    field r71 = curr[9];
+    field r72; field_sub(&r72, &r71, &r21);
+    field r73; field_add(&r73, &r63, &r72);
+    field r74; field_mul(&r74, &r70, &r73);
+    field r75; field_sub(&r75, &r69, &r74);
+    field r76 = curr[10];
+    field r77; field_add(&r77, &r47, &r76);
+    field r78; field_mul(&r78, &r66, &r77);
+    field r79; field_sub(&r79, &r42, &r71);
+    field r80; field_mul(&r80, &r68, &r79);
+    field r81; field_sub(&r81, &r78, &r80);
+    field r82; field_mul(&r82, &r3, &r3);
+    field r83; field_sub(&r83, &r82, &r3);
+    field r84 = next[9];
+    field r85; field_mul(&r85, &r72, &r84);
+    field r86; field_add(&r86, &r3, &r3);
+    field r87; field_sub(&r87, &r86, &r27);
+    field r88; field_mul(&r88, &r29, &r87);
+    field r89; field_sub(&r89, &r76, &r88);
+    field r90; field_sub(&r90, &r85, &r89);
+    field r91; field_add(&r91, &r76, &r76);
+    field r92; field_mul(&r92, &r84, &r84);
+    field r93; field_sub(&r93, &r92, &r71);
+    field r94; field_sub(&r94, &r93, &r21);
+    field r95; field_sub(&r95, &r71, &r94);
+    field r96; field_mul(&r96, &r84, &r95);
+    field r97; field_sub(&r97, &r91, &r96);
+    field r98; field_mul(&r98, &r97, &r97);
+    field r99; field_mul(&r99, &r95, &r95);
+    field r100 = curr[11];
+    field r101; field_sub(&r101, &r100, &r21);
+    field r102; field_add(&r102, &r92, &r101);
+    field r103; field_mul(&r103, &r99, &r102);
+    field r104; field_sub(&r104, &r98, &r103);
+    field r105 = curr[12];
+    field r106; field_add(&r106, &r76, &r105);
+    field r107; field_mul(&r107, &r95, &r106);
+    field r108; field_sub(&r108, &r71, &r100);
+    field r109; field_mul(&r109, &r97, &r108);
+    field r110; field_sub(&r110, &r107, &r109);
+    field r111; field_mul(&r111, &r2, &r2);
+    field r112; field_sub(&r112, &r111, &r2);
+    field r113 = next[10];
+    field r114; field_mul(&r114, &r101, &r113);
+    field r115; field_add(&r115, &r2, &r2);
+    field r116; field_sub(&r116, &r115, &r27);
+    field r117; field_mul(&r117, &r29, &r116);
+    field r118; field_sub(&r118, &r105, &r117);
+    field r119; field_sub(&r119, &r114, &r118);
+    field r120; field_add(&r120, &r105, &r105);
+    field r121; field_mul(&r121, &r113, &r113);
+    field r122; field_sub(&r122, &r121, &r100);
+    field r123; field_sub(&r123, &r122, &r21);
+    field r124; field_sub(&r124, &r100, &r123);
+    field r125; field_mul(&r125, &r113, &r124);
+    field r126; field_sub(&r126, &r120, &r125);
+    field r127; field_mul(&r127, &r126, &r126);
+    field r128; field_mul(&r128, &r124, &r124);
+    field r129 = curr[13];
+    field r130; field_sub(&r130, &r129, &r21);
+    field r131; field_add(&r131, &r121, &r130);
+    field r132; field_mul(&r132, &r128, &r131);
+    field r133; field_sub(&r133, &r127, &r132);
+    field r134 = curr[14];
+    field r135; field_add(&r135, &r105, &r134);
+    field r136; field_mul(&r136, &r124, &r135);
+    field r137; field_sub(&r137, &r100, &r129);
+    field r138; field_mul(&r138, &r126, &r137);
+    field r139; field_sub(&r139, &r136, &r138);
+    field r140; field_mul(&r140, &r1, &r1);
+    field r141; field_sub(&r141, &r140, &r1);
+    field r142 = next[11];
+    field r143; field_mul(&r143, &r130, &r142);
+    field r144; field_add(&r144, &r1, &r1);
+    field r145; field_sub(&r145, &r144, &r27);
+    field r146; field_mul(&r146, &r29, &r145);
+    field r147; field_sub(&r147, &r134, &r146);
+    field r148; field_sub(&r148, &r143, &r147);
+    field r149; field_add(&r149, &r134, &r134);
+    field r150; field_mul(&r150, &r142, &r142);
+    field r151; field_sub(&r151, &r150, &r129);
+    field r152; field_sub(&r152, &r151, &r21);
+    field r153; field_sub(&r153, &r129, &r152);
+    field r154; field_mul(&r154, &r142, &r153);
+    field r155; field_sub(&r155, &r149, &r154);
+    field r156; field_mul(&r156, &r155, &r155);
+    field r157; field_mul(&r157, &r153, &r153);
+    field r158 = next[0];
+    field r159; field_sub(&r159, &r158, &r21);
+    field r160; field_add(&r160, &r150, &r159);
+    field r161; field_mul(&r161, &r157, &r160);
+    field r162; field_sub(&r162, &r156, &r161);
+    field r163 = next[1];
+    field r164; field_add(&r164, &r134, &r163);
+    field r165; field_mul(&r165, &r153, &r164);
+    field r166; field_sub(&r166, &r129, &r158);
+    field r167; field_mul(&r167, &r155, &r166);
+    field r168; field_sub(&r168, &r165, &r167);
+    out[0] = r17;
+    out[1] = r19;
+    out[2] = r32;
+    out[3] = r46;
+    out[4] = r52;
+    out[5] = r54;
+    out[6] = r61;
+    out[7] = r75;
+    out[8] = r81;
+    out[9] = r83;
+    out[10] = r90;
+    out[11] = r104;
+    out[12] = r110;
+    out[13] = r112;
+    out[14] = r119;
+    out[15] = r133;
+    out[16] = r139;
+    out[17] = r141;
+    out[18] = r148;
+    out[19] = r162;
+    out[20] = r168;
+}
+
+/* Evaluates the 11 constraints of the gate `endosclmul` into out[0..11]. */
+void endosclmul(const field *curr, const field *next, const field *coeffs, const field *endo_coefficient, const field *mds, field *out)
+{
+    (void)curr; (void)next; (void)coeffs; (void)endo_coefficient; (void)mds;
+    field r0 = curr[11];
+    field r1; field_mul(&r1, &r0, &r0);
+    field r2; field_sub(&r2, &r1, &r0);
+    field r3 = curr[12];
+    field r4; field_mul(&r4, &r3, &r3);
+    field r5; field_sub(&r5, &r4, &r3);
+    field r6 = curr[13];
+    field r7; field_mul(&r7, &r6, &r6);
+    field r8; field_sub(&r8, &r7, &r6);
+    field r9 = curr[14];
+    field r10; field_mul(&r10, &r9, &r9);
+    field r11; field_sub(&r11, &r10, &r9);
+    field r12; field_from_fraction(&r12, 0, 0x1, 1);
+    field r13 = *endo_coefficient;
+    field r14; field_sub(&r14, &r13, &r12);
+    field r15; field_mul(&r15, &r0, &r14);
+    field r16; field_add(&r16, &r12, &r15);
+    field r17 = curr[0];
+    field r18; field_mul(&r18, &r16, &r17);
+    field r19 = curr[4];
+    field r20; field_sub(&r20, &r18, &r19);
+    field r21 = curr[9];
+    field r22; field_mul(&r22, &r20, &r21);
+    field r23; field_add(&r23, &r3, &r3);
+    field r24; field_sub(&r24, &r23, &r12);
+    field r25 = curr[1];
+    field r26; field_mul(&r26, &r24, &r25);
+    field r27 = curr[5];
+    field r28; field_sub(&r28, &r26, &r27);
+    field r29; field_sub(&r29, &r22, &r28);
+    field r30; field_add(&r30, &r19, &r19);
+    field r31; field_mul(&r31, &r21, &r21);
+    field r32; field_sub(&r32, &r30, &r31);
+    field r33; field_add(&r33, &r18, &r32);
+    field r34 = curr[7];
+    field r35; field_sub(&r35, &r19, &r34);
+    field r36; field_mul(&r36, &r21, &r35);
+    field r37 = curr[8];
+    field r38; field_add(&r38, &r27, &r37);
+    field r39; field_add(&r39, &r36, &r38);
+    field r40; field_mul(&r40, &r33, &r39);
+    field r41; field_add(&r41, &r27, &r27);
+    field r42; field_mul(&r42, &r35, &r41);
+    field r43; field_sub(&r43, &r40, &r42);
+    field r44; field_mul(&r44, &r38, &r38);
+    field r45; field_mul(&r45, &r35, &r35);
+    field r46; field_sub(&r46, &r31, &r18);
+    field r47; field_add(&r47, &r34, &r46);
+    field r48; field_mul(&r48, &r45, &r47);
+    field r49; field_sub(&r49, &r44, &r48);
+    field r50; field_mul(&r50, &r6, &r14);
+    field r51; field_add(&r51, &r12, &r50);
+    field r52; field_mul(&r52, &r17, &r51);
+    field r53; field_sub(&r53, &r52, &r34);
+    field r54 = curr[10];
+    field r55; field_mul(&r55, &r53, &r54);
+    field r56; field_add(&r56, &r9, &r9);
+    field r57; field_sub(&r57, &r56, &r12);
+    field r58; field_mul(&r58, &r25, &r57);
+    field r59; field_sub(&r59, &r58, &r37);
+    field r60; field_sub(&r60, &r55, &r59);
+    field r61; field_add(&r61, &r34, &r34);
+    field r62; field_mul(&r62, &r54, &r54);
+    field r63; field_sub(&r63, &r61, &r62);
+    field r64; field_add(&r64, &r52, &r63);
+    field r65 = next[4];
+    field r66; field_sub(&r66, &r34, &r65);
+    field r67; field_mul(&r67, &r54, &r66);
+    field r68 = next[5];
+    field r69; field_add(&r69, &r37, &r68);
+    field r70; field_add(&r70, &r67, &r69);
+    field r71; field_mul(&r71, &r64, &r70);
+    field r72; field_add(&r72, &r37, &r37);
+    field r73; field_mul(&r73, &r66, &r72);
+    field r74; field_sub(&r74, &r71, &r73);
+    field r75; field_mul(&r75, &r69, &r69);
+    field r76; field_mul(&r76, &r66, &r66);
+    field r77; field_sub(&r77, &r62, &r52);
+    field r78; field_add(&r78, &r65, &r77);
+    field r79; field_mul(&r79, &r76, &r78);
+    field r80; field_sub(&r80, &r75, &r79);
+    field r81 = curr[6];
+    field r82; field_add(&r82, &r81, &r81);
+    field r83; field_add(&r83, &r0, &r82);
+    field r84; field_add(&r84, &r83, &r83);
+    field r85; field_add(&r85, &r3, &r84);
+    field r86; field_add(&r86, &r85, &r85);
+    field r87; field_add(&r87, &r6, &r86);
+    field r88; field_add(&r88, &r87, &r87);
+    field r89; field_add(&r89, &r9, &r88);
+    field r90 = next[6];
+    field r91; field_sub(&r91, &r89, &r90);
+    out[0] = r2;
+    out[1] = r5;
+    out[2] = r8;
+    out[3] = r11;
+    out[4] = r29;
+    out[5] = r43;
+    out[6] = r49;
+    out[7] = r60;
+    out[8] = r74;
+    out[9] = r80;
+    out[10] = r91;
+}
+
+/* Evaluates the 11 constraints of the gate `endomul_scalar` into out[0..11]. */
+void endomul_scalar(const field *curr, const field *next, const field *coeffs, const field *endo_coefficient, const field *mds, field *out)
+{
+    (void)curr; (void)next; (void)coeffs; (void)endo_coefficient; (void)mds;
+    field r0 = curr[0];
+    field r1; field_add(&r1, &r0, &r0);
+    field r2; field_add(&r2, &r1, &r1);
+    field r3 = curr[6];
+    field r4; field_add(&r4, &r2, &r3);
+    field r5; field_add(&r5, &r4, &r4);
+    field r6; field_add(&r6, &r5, &r5);
+    field r7 = curr[7];
+    field r8; field_add(&r8, &r6, &r7);
+    field r9; field_add(&r9, &r8, &r8);
+    field r10; field_add(&r10, &r9, &r9);
+    field r11 = curr[8];
+    field r12; field_add(&r12, &r10, &r11);
+    field r13; field_add(&r13, &r12, &r12);
+    field r14; field_add(&r14, &r13, &r13);
+    field r15 = curr[9];
+    field r16; field_add(&r16, &r14, &r15);
+    field r17; field_add(&r17, &r16, &r16);
+    field r18; field_add(&r18, &r17, &r17);
+    field r19 = curr[10];
+    field r20; field_add(&r20, &r18, &r19);
+    field r21; field_add(&r21, &r20, &r20);
+    field r22; field_add(&r22, &r21, &r21);
+    field r23 = curr[11];
+    field r24; field_add(&r24, &r22, &r23);
+    field r25; field_add(&r25, &r24, &r24);
+    field r26; field_add(&r26, &r25, &r25);
+    field r27 = curr[12];
+    field r28; field_add(&r28, &r26, &r27);
+    field r29; field_add(&r29, &r28, &r28);
+    field r30; field_add(&r30, &r29, &r29);
+    field r31 = curr[13];
+    field r32; field_add(&r32, &r30, &r31);
+    field r33 = curr[1];
+    field r34; field_sub(&r34, &r32, &r33);
+    field r35 = curr[2];
+    field r36; field_add(&r36, &r35, &r35);
+    field r37; field_from_fraction(&r37, 0, 0x2, 3);
+    field r38; field_mul(&r38, &r3, &r37);
+    field r39; field_from_fraction(&r39, 1, 0x5, 2);
+    field r40; field_add(&r40, &r38, &r39);
+    field r41; field_mul(&r41, &r3, &r40);
+    field r42; field_from_fraction(&r42, 0, 0xb, 6);
+    field r43; field_add(&r43, &r41, &r42);
+    field r44; field_mul(&r44, &r3, &r43);
+    field r45; field_add(&r45, &r36, &r44);
+    field r46; field_add(&r46, &r45, &r45);
+    field r47; field_mul(&r47, &r7, &r37);
+    field r48; field_add(&r48, &r39, &r47);
+    field r49; field_mul(&r49, &r7, &r48);
+    field r50; field_add(&r50, &r42, &r49);
+    field r51; field_mul(&r51, &r7, &r50);
+    field r52; field_add(&r52, &r46, &r51);
+    field r53; field_add(&r53, &r52, &r52);
+    field r54; field_mul(&r54, &r11, &r37);
+    field r55; field_add(&r55, &r39, &r54);
+    field r56; field_mul(&r56, &r11, &r55);
+    field r57; field_add(&r57, &r42, &r56);
+    field r58; field_mul(&r58, &r11, &r57);
+    field r59; field_add(&r59, &r53, &r58);
+    field r60; field_add(&r60, &r59, &r59);
+    field r61; field_mul(&r61, &r15, &r37);
+    field r62; field_add(&r62, &r39, &r61);
+    field r63; field_mul(&r63, &r15, &r62);
+    field r64; field_add(&r64, &r42, &r63);
+    field r65; field_mul(&r65, &r15, &r64);
+    field r66; field_add(&r66, &r60, &r65);
+    field r67; field_add(&r67, &r66, &r66);
+    field r68; field_mul(&r68, &r19, &r37);
+    field r69; field_add(&r69, &r39, &r68);
+    field r70; field_mul(&r70, &r19, &r69);
+    field r71; field_add(&r71, &r42, &r70);
+    field r72; field_mul(&r72, &r19, &r71);
+    field r73; field_add(&r73, &r67, &r72);
+    field r74; field_add(&r74, &r73, &r73);
+    field r75; field_mul(&r75, &r23, &r37);
+    field r76; field_add(&r76, &r39, &r75);
+    field r77; field_mul(&r77, &r23, &r76);
+    field r78; field_add(&r78, &r42, &r77);
+    field r79; field_mul(&r79, &r23, &r78);
+    field r80; field_add(&r80, &r74, &r79);
+    field r81; field_add(&r81, &r80, &r80);
+    field r82; field_mul(&r82, &r27, &r37);
+    field r83; field_add(&r83, &r39, &r82);
+    field r84; field_mul(&r84, &r27, &r83);
+    field r85; field_add(&r85, &r42, &r84);
+    field r86; field_mul(&r86, &r27, &r85);
+    field r87; field_add(&r87, &r81, &r86);
+    field r88; field_add(&r88, &r87, &r87);
+    field r89; field_mul(&r89, &r31, &r37);
+    field r90; field_add(&r90, &r39, &r89);
+    field r91; field_mul(&r91, &r31, &r90);
+    field r92; field_add(&r92, &r42, &r91);
+    field r93; field_mul(&r93, &r31, &r92);
+    field r94; field_add(&r94, &r88, &r93);
+    field r95 = curr[4];
+    field r96; field_sub(&r96, &r94, &r95);
+    field r97 = curr[3];
+    field r98; field_add(&r98, &r97, &r97);
+    field r99; field_from_fraction(&r99, 1, 0x1, 1);
+    field r100; field_mul(&r100, &r3, &r99);
+    field r101; field_from_fraction(&r101, 0, 0x3, 1);
+    field r102; field_add(&r102, &r100, &r101);
+    field r103; field_mul(&r103, &r3, &r102);
+    field r104; field_add(&r104, &r99, &r103);
+    field r105; field_add(&r105, &r44, &r104);
+    field r106; field_add(&r106, &r98, &r105);
+    field r107; field_add(&r107, &r106, &r106);
+    field r108; field_mul(&r108, &r7, &r99);
+    field r109; field_add(&r109, &r101, &r108);
+    field r110; field_mul(&r110, &r7, &r109);
+    field r111; field_add(&r111, &r99, &r110);
+    field r112; field_add(&r112, &r51, &r111);
+    field r113; field_add(&r113, &r107, &r112);
+    field r114; field_add(&r114, &r113, &r113);
+    field r115; field_mul(&r115, &r11, &r99);
+    field r116; field_add(&r116, &r101, &r115);
+    field r117; field_mul(&r117, &r11, &r116);
+    field r118; field_add(&r118, &r99, &r117);
+    field r119; field_add(&r119, &r58, &r118);
+    field r120; field_add(&r120, &r114, &r119);
+    field r121; field_add(&r121, &r120, &r120);
+    field r122; field_mul(&r122, &r15, &r99);
+    field r123; field_add(&r123, &r101, &r122);
+    field r124; field_mul(&r124, &r15, &r123);
+    field r125; field_add(&r125, &r99, &r124);
+    field r126; field_add(&r126, &r65, &r125);
+    field r127; field_add(&r127, &r121, &r126);
+    field r128; field_add(&r128, &r127, &r127);
+    field r129; field_mul(&r129, &r19, &r99);
+    field r130; field_add(&r130, &r101, &r129);
+    field r131; field_mul(&r131, &r19, &r130);
+    field r132; field_add(&r132, &r99, &r131);
+    field r133; field_add(&r133, &r72, &r132);
+    field r134; field_add(&r134, &r128, &r133);
+    field r135; field_add(&r135, &r134, &r134);
+    field r136; field_mul(&r136, &r23, &r99);
+    field r137; field_add(&r137, &r101, &r136);
+    field r138; field_mul(&r138, &r23, &r137);
+    field r139; field_add(&r139, &r99, &r138);
+    field r140; field_add(&r140, &r79, &r139);
+    field r141; field_add(&r141, &r135, &r140);
+    field r142; field_add(&r142, &r141, &r141);
+    field r143; field_mul(&r143, &r27, &r99);
+    field r144; field_add(&r144, &r101, &r143);
+    field r145; field_mul(&r145, &r27, &r144);
+    field r146; field_add(&r146, &r99, &r145);
+    field r147; field_add(&r147, &r86, &r146);
+    field r148; field_add(&r148, &r142, &r147);
+    field r149; field_add(&r149, &r148, &r148);
+    field r150; field_mul(&r150, &r31, &r99);
+    field r151; field_add(&r151, &r101, &r150);
+    field r152; field_mul(&r152, &r31, &r151);
+    field r153; field_add(&r153, &r99, &r152);
+    field r154; field_add(&r154, &r93, &r153);
+    field r155; field_add(&r155, &r149, &r154);
+    field r156 = curr[5];
+    field r157; field_sub(&r157, &r155, &r156);
+    field r158; field_from_fraction(&r158, 1, 0x6, 1);
+    field r159; field_add(&r159, &r3, &r158);
+    field r160; field_mul(&r160, &r3, &r159);
+    field r161; field_from_fraction(&r161, 0, 0xb, 1);
+    field r162; field_add(&r162, &r160, &r161);
+    field r163; field_mul(&r163, &r3, &r162);
+    field r164; field_add(&r164, &r158, &r163);
+    field r165; field_mul(&r165, &r3, &r164);
+    field r166; field_add(&r166, &r7, &r158);
+    field r167; field_mul(&r167, &r7, &r166);
+    field r168; field_add(&r168, &r161, &r167);
+    field r169; field_mul(&r169, &r7, &r168);
+    field r170; field_add(&r170, &r158, &r169);
+    field r171; field_mul(&r171, &r7, &r170);
+    field r172; field_add(&r172, &r11, &r158);
+    field r173; field_mul(&r173, &r11, &r172);
+    field r174; field_add(&r174, &r161, &r173);
+    field r175; field_mul(&r175, &r11, &r174);
+    field r176; field_add(&r176, &r158, &r175);
+    field r177; field_mul(&r177, &r11, &r176);
+    field r178; field_add(&r178, &r15, &r158);
+    field r179; field_mul(&r179, &r15, &r178);
+    field r180; field_add(&r180, &r161, &r179);
+    field r181; field_mul(&r181, &r15, &r180);
+    field r182; field_add(&r182, &r158, &r181);
+    field r183; field_mul(&r183, &r15, &r182);
+    field r184; field_add(&r184, &r19, &r158);
+    field r185; field_mul(&r185, &r19, &r184);
+    field r186; field_add(&r186, &r161, &r185);
+    field r187; field_mul(&r187, &r19, &r186);
+    field r188; field_add(&r188, &r158, &r187);
+    field r189; field_mul(&r189, &r19, &r188);
+    field r190; field_add(&r190, &r23, &r158);
+    field r191; field_mul(&r191, &r23, &r190);
+    field r192; field_add(&r192, &r161, &r191);
+    field r193; field_mul(&r193, &r23, &r192);
+    field r194; field_add(&r194, &r158, &r193);
+    field r195; field_mul(&r195, &r23, &r194);
+    field r196; field_add(&r196, &r27, &r158);
+    field r197; field_mul(&r197, &r27, &r196);
+    field r198; field_add(&r198, &r161, &r197);
+    field r199; field_mul(&r199, &r27, &r198);
+    field r200; field_add(&r200, &r158, &r199);
+    field r201; field_mul(&r201, &r27, &r200);
+    field r202; field_add(&r202, &r31, &r158);
+    field r203; field_mul(&r203, &r31, &r202);
+    field r204; field_add(&r204, &r161, &r203);
+    field r205; field_mul(&r205, &r31, &r204);
+    field r206; field_add(&r206, &r158, &r205);
+    field r207; field_mul(&r207, &r31, &r206);
+    out[0] = r34;
+    out[1] = r96;
+    out[2] = r157;
+    out[3] = r165;
+    out[4] = r171;
+    out[5] = r177;
+    out[6] = r183;
+    out[7] = r189;
+    out[8] = r195;
+    out[9] = r201;
+    out[10] = r207;
+}
+
+/* Evaluates the 7 constraints of the gate `fixed_base_mul` into out[0..7]. */
+void fixed_base_mul(const field *curr, const field *next, const field *coeffs, const field *endo_coefficient, const field *mds, field *out)
+{
+    (void)curr; (void)next; (void)coeffs; (void)endo_coefficient; (void)mds;
+    field r0 = next[3];
+    field r1 = curr[3];
+    field r2; field_sub(&r2, &r0, &r1);
+    field r3; field_from_fraction(&r3, 0, 0x1, 1);
+    field r4; field_add(&r4, &r2, &r3);
+    field r5 = next[4];
+    field r6 = curr[4];
+    field r7; field_sub(&r7, &r5, &r6);
+    field r8 = next[2];
+    field r9 = curr[2];
+    field r10; field_mul(&r10, &r6, &r9);
+    field r11 = curr[5];
+    field r12; field_add(&r12, &r10, &r11);
+    field r13; field_sub(&r13, &r8, &r12);
+    field r14 = curr[6];
+    field r15 = curr[0];
+    field r16; field_sub(&r16, &r14, &r15);
+    field r17 = curr[9];
+    field r18; field_mul(&r18, &r16, &r17);
+    field r19; field_sub(&r19, &r18, &r3);
+    field r20 = curr[8];
+    field r21; field_mul(&r21, &r16, &r20);
+    field r22 = curr[7];
+    field r23 = curr[1];
+    field r24; field_sub(&r24, &r22, &r23);
+    field r25; field_sub(&r25, &r21, &r24);
+    field r26; field_mul(&r26, &r20, &r20);
+    field r27; field_sub(&r27, &r26, &r15);
+    field r28; field_sub(&r28, &r27, &r14);
+    field r29 = next[0];
+    field r30; field_sub(&r30, &r28, &r29);
+    field r31; field_sub(&r31, &r15, &r29);
+    field r32; field_mul(&r32, &r20, &r31);
+    field r33; field_sub(&r33, &r32, &r23);
+    field r34 = next[1];
+    field r35; field_sub(&r35, &r33, &r34);
+    out[0] = r4;
+    out[1] = r7;
+    out[2] = r13;
+    out[3] = r19;
+    out[4] = r25;
+    out[5] = r30;
+    out[6] = r35;
+}
+
+/* Evaluates the 15 constraints of the gate `poseidon` into out[0..15]. */
+void poseidon(const field *curr, const field *next, const field *coeffs, const field *endo_coefficient, const field *mds, field *out)
+{
+    (void)curr; (void)next; (void)coeffs; (void)endo_coefficient; (void)mds;
+    field r0 = curr[6];
+    field r1 = coeffs[0];
+    field r2 = mds[0];
+    field r3 = curr[0];
+    field r4; field_mul(&r4, &r3, &r3);
+    field r5; field_mul(&r5, &r4, &r3);
+    field r6; field_mul(&r6, &r5, &r5);
+    field r7; field_mul(&r7, &r6, &r3);
+    field r8; field_mul(&r8, &r2, &r7);
+    field r9; field_add(&r9, &r1, &r8);
+    field r10 = mds[1];
+    field r11 = curr[1];
+    field r12; field_mul(&r12, &r11, &r11);
+    field r13; field_mul(&r13, &r12, &r11);
+    field r14; field_mul(&r14, &r13, &r13);
+    field r15; field_mul(&r15, &r14, &r11);
+    field r16; field_mul(&r16, &r10, &r15);
+    field r17; field_add(&r17, &r9, &r16);
+    field r18 = mds[2];
+    field r19 = curr[2];
+    field r20; field_mul(&r20, &r19, &r19);
+    field r21; field_mul(&r21, &r20, &r19);
+    field r22; field_mul(&r22, &r21, &r21);
+    field r23; field_mul(&r23, &r22, &r19);
+    field r24; field_mul(&r24, &r18, &r23);
+    field r25; field_add(&r25, &r17, &r24);
+    field r26; field_sub(&r26, &r0, &r25);
+    field r27 = curr[7];
+    field r28 = coeffs[1];
+    field r29 = mds[3];
+    field r30; field_mul(&r30, &r7, &r29);
+    field r31; field_add(&r31, &r28, &r30);
+    field r32 = mds[4];
+    field r33; field_mul(&r33, &r15, &r32);
+    field r34; field_add(&r34, &r31, &r33);
+    field r35 = mds[5];
+    field r36; field_mul(&r36, &r23, &r35);
+    field r37; field_add(&r37, &r34, &r36);
+    field r38; field_sub(&r38, &r27, &r37);
+    field r39 = curr[8];
+    field r40 = coeffs[2];
+    field r41 = mds[6];
+    field r42; field_mul(&r42, &r7, &r41);
+    field r43; field_add(&r43, &r40, &r42);
+    field r44 = mds[7];
+    field r45; field_mul(&r45, &r15, &r44);
+    field r46; field_add(&r46, &r43, &r45);
+    field r47 = mds[8];
+    field r48; field_mul(&r48, &r23, &r47);
+    field r49; field_add(&r49, &r46, &r48);
+    field r50; field_sub(&r50, &r39, &r49);
+    field r51 = curr[9];
+    field r52 = coeffs[3];
+    field r53; field_mul(&r53, &r0, &r0);
+    field r54; field_mul(&r54, &r53, &r0);
+    field r55; field_mul(&r55, &r54, &r54);
+    field r56; field_mul(&r56, &r55, &r0);
+    field r57; field_mul(&r57, &r2, &r56);
+    field r58; field_add(&r58, &r52, &r57);
+    field r59; field_mul(&r59, &r27, &r27);
+    field r60; field_mul(&r60, &r59, &r27);
+    field r61; field_mul(&r61, &r60, &r60);
+    field r62; field_mul(&r62, &r61, &r27);
+    field r63; field_mul(&r63, &r10, &r62);
+    field r64; field_add(&r64, &r58, &r63);
+    field r65; field_mul(&r65, &r39, &r39);
+    field r66; field_mul(&r66, &r65, &r39);
+    field r67; field_mul(&r67, &r66, &r66);
+    field r68; field_mul(&r68, &r67, &r39);
+    field r69; field_mul(&r69, &r18, &r68);
+    field r70; field_add(&r70, &r64, &r69);
+    field r71; field_sub(&r71, &r51, &r70);
+    field r72 = curr[10];
+    field r73 = coeffs[4];
+    field r74; field_mul(&r74, &r29, &r56);
+    field r75; field_add(&r75, &r73, &r74);
+    field r76; field_mul(&r76, &r32, &r62);
+    field r77; field_add(&r77, &r75, &r76);
+    field r78; field_mul(&r78, &r35, &r68);
+    field r79; field_add(&r79, &r77, &r78);
+    field r80; field_sub(&r80, &r72, &r79);
+    field r81 = curr[11];
+    field r82 = coeffs[5];
+    field r83; field_mul(&r83, &r41, &r56);
+    field r84; field_add(&r84, &r82, &r83);
+    field r85; field_mul(&r85, &r44, &r62);
+    field r86; field_add(&r86, &r84, &r85);
+    field r87; field_mul(&r87, &r47, &r68);
+    field r88; field_add(&r88, &r86, &r87);
+    field r89; field_sub(&r89, &r81, &r88);
+    field r90 = curr[12];
+    field r91 = coeffs[6];
+    field r92; field_mul(&r92, &r51, &r51);
+    field r93; field_mul(&r93, &r92, &r51);
+    field r94; field_mul(&r94, &r93, &r93);
+    field r95; field_mul(&r95, &r94, &r51);
+    field r96; field_mul(&r96, &r2, &r95);
+    field r97; field_add(&r97, &r91, &r96);
+    field r98; field_mul(&r98, &r72, &r72);
+    field r99; field_mul(&r99, &r98, &r72);
+    field r100; field_mul(&r100, &r99, &r99);
+    field r101; field_mul(&r101, &r100, &r72);
+    field r102; field_mul(&r102, &r10, &r101);
+    field r103; field_add(&r103, &r97, &r102);
+    field r104; field_mul(&r104, &r81, &r81);
+    field r105; field_mul(&r105, &r104, &r81);
+    field r106; field_mul(&r106, &r105, &r105);
+    field r107; field_mul(&r107, &r106, &r81);
+    field r108; field_mul(&r108, &r18, &r107);
+    field r109; field_add(&r109, &r103, &r108);
+    field r110; field_sub(&r110, &r90, &r109);
+    field r111 = curr[13];
+    field r112 = coeffs[7];
+    field r113; field_mul(&r113, &r29, &r95);
+    field r114; field_add(&r114, &r112, &r113);
+    field r115; field_mul(&r115, &r32, &r101);
+    field r116; field_add(&r116, &r114, &r115);
+    field r117; field_mul(&r117, &r35, &r107);
+    field r118; field_add(&r118, &r116, &r117);
+    field r119; field_sub(&r119, &r111, &r118);
+    field r120 = curr[14];
+    field r121 = coeffs[8];
+    field r122; field_mul(&r122, &r41, &r95);
+    field r123; field_add(&r123, &r121, &r122);
+    field r124; field_mul(&r124, &r44, &r101);
+    field r125; field_add(&r125, &r123, &r124);
+    field r126; field_mul(&r126, &r47, &r107);
+    field r127; field_add(&r127, &r125, &r126);
+    field r128; field_sub(&r128, &r120, &r127);
+    field r129 = curr[3];
+    field r130 = coeffs[9];
+    field r131; field_mul(&r131, &r90, &r90);
+    field r132; field_mul(&r132, &r131, &r90);
+    field r133; field_mul(&r133, &r132, &r132);
+    field r134; field_mul(&r134, &r133, &r90);
+    field r135; field_mul(&r135, &r2, &r134);
+    field r136; field_add(&r136, &r130, &r135);
+    field r137; field_mul(&r137, &r111, &r111);
+    field r138; field_mul(&r138, &r137, &r111);
+    field r139; field_mul(&r139, &r138, &r138);
+    field r140; field_mul(&r140, &r139, &r111);
+    field r141; field_mul(&r141, &r10, &r140);
+    field r142; field_add(&r142, &r136, &r141);
+    field r143; field_mul(&r143, &r120, &r120);
+    field r144; field_mul(&r144, &r143, &r120);
+    field r145; field_mul(&r145, &r144, &r144);
+    field r146; field_mul(&r146, &r145, &r120);
+    field r147; field_mul(&r147, &r18, &r146);
+    field r148; field_add(&r148, &r142, &r147);
+    field r149; field_sub(&r149, &r129, &r148);
+    field r150 = curr[4];
+    field r151 = coeffs[10];
+    field r152; field_mul(&r152, &r29, &r134);
+    field r153; field_add(&r153, &r151, &r152);
+    field r154; field_mul(&r154, &r32, &r140);
+    field r155; field_add(&r155, &r153, &r154);
+    field r156; field_mul(&r156, &r35, &r146);
+    field r157; field_add(&r157, &r155, &r156);
+    field r158; field_sub(&r158, &r150, &r157);
+    field r159 = curr[5];
+    field r160 = coeffs[11];
+    field r161; field_mul(&r161, &r41, &r134);
+    field r162; field_add(&r162, &r160, &r161);
+    field r163; field_mul(&r163, &r44, &r140);
+    field r164; field_add(&r164, &r162, &r163);
+    field r165; field_mul(&r165, &r47, &r146);
+    field r166; field_add(&r166, &r164, &r165);
+    field r167; field_sub(&r167, &r159, &r166);
+    field r168 = next[0];
+    field r169 = coeffs[12];
+    field r170; field_mul(&r170, &r129, &r129);
+    field r171; field_mul(&r171, &r170, &r129);
+    field r172; field_mul(&r172, &r171, &r171);
+    field r173; field_mul(&r173, &r172, &r129);
+    field r174; field_mul(&r174, &r2, &r173);
+    field r175; field_add(&r175, &r169, &r174);
+    field r176; field_mul(&r176, &r150, &r150);
+    field r177; field_mul(&r177, &r176, &r150);
+    field r178; field_mul(&r178, &r177, &r177);
+    field r179; field_mul(&r179, &r178, &r150);
+    field r180; field_mul(&r180, &r10, &r179);
+    field r181; field_add(&r181, &r175, &r180);
+    field r182; field_mul(&r182, &r159, &r159);
+    field r183; field_mul(&r183, &r182, &r159);
+    field r184; field_mul(&r184, &r183, &r183);
+    field r185; field_mul(&r185, &r184, &r159);
+    field r186; field_mul(&r186, &r18, &r185);
+    field r187; field_add(&r187, &r181, &r186);
+    field r188; field_sub(&r188, &r168, &r187);
+    field r189 = next[1];
+    field r190 = coeffs[13];
+    field r191; field_mul(&r191, &r29, &r173);
+    field r192; field_add(&r192, &r190, &r191);
+    field r193; field_mul(&r193, &r32, &r179);
+    field r194; field_add(&r194, &r192, &r193);
+    field r195; field_mul(&r195, &r35, &r185);
+    field r196; field_add(&r196, &r194, &r195);
+    field r197; field_sub(&r197, &r189, &r196);
+    field r198 = next[2];
+    field r199 = coeffs[14];
+    field r200; field_mul(&r200, &r41, &r173);
+    field r201; field_add(&r201, &r199, &r200);
+    field r202; field_mul(&r202, &r44, &r179);
+    field r203; field_add(&r203, &r201, &r202);
+    field r204; field_mul(&r204, &r47, &r185);
+    field r205; field_add(&r205, &r203, &r204);
+    field r206; field_sub(&r206, &r198, &r205);
+    out[0] = r26;
+    out[1] = r38;
+    out[2] = r50;
+    out[3] = r71;
+    out[4] = r80;
+    out[5] = r89;
+    out[6] = r110;
+    out[7] = r119;
+    out[8] = r128;
+    out[9] = r149;
+    out[10] = r158;
+    out[11] = r167;
+    out[12] = r188;
+    out[13] = r197;
+    out[14] = r206;
+}
+
+/* Evaluates the 6 constraints of the gate `poseidon_legacy` into out[0..6]. */
+void poseidon_legacy(const field *curr, const field *next, const field *coeffs, const field *endo_coefficient, const field *mds, field *out)
+{
+    (void)curr; (void)next; (void)coeffs; (void)endo_coefficient; (void)mds;
+    field r0 = curr[3];
+    field r1 = coeffs[9];
+    field r2 = coeffs[0];
+    field r3 = curr[0];
+    field r4; field_mul(&r4, &r3, &r3);
+    field r5; field_mul(&r5, &r4, &r4);
+    field r6; field_mul(&r6, &r5, &r3);
+    field r7; field_mul(&r7, &r2, &r6);
+    field r8; field_add(&r8, &r1, &r7);
+    field r9 = coeffs[1];
+    field r10 = curr[1];
+    field r11; field_mul(&r11, &r10, &r10);
+    field r12; field_mul(&r12, &r11, &r11);
+    field r13; field_mul(&r13, &r12, &r10);
+    field r14; field_mul(&r14, &r9, &r13);
+    field r15; field_add(&r15, &r8, &r14);
+    field r16 = coeffs[2];
+    field r17 = curr[2];
+    field r18; field_mul(&r18, &r17, &r17);
+    field r19; field_mul(&r19, &r18, &r18);
+    field r20; field_mul(&r20, &r19, &r17);
+    field r21; field_mul(&r21, &r16, &r20);
+    field r22; field_add(&r22, &r15, &r21);
+    field r23; field_sub(&r23, &r0, &r22);
+    field r24 = curr[4];
+    field r25 = coeffs[10];
+    field r26 = coeffs[3];
+    field r27; field_mul(&r27, &r6, &r26);
+    field r28; field_add(&r28, &r25, &r27);
+    field r29 = coeffs[4];
+    field r30; field_mul(&r30, &r13, &r29);
+    field r31; field_add(&r31, &r28, &r30);
+    field r32 = coeffs[5];
+    field r33; field_mul(&r33, &r20, &r32);
+    field r34; field_add(&r34, &r31, &r33);
+    field r35; field_sub(&r35, &r24, &r34);
+    field r36 = curr[5];
+    field r37 = coeffs[11];
+    field r38 = coeffs[6];
+    field r39; field_mul(&r39, &r6, &r38);
+    field r40; field_add(&r40, &r37, &r39);
+    field r41 = coeffs[7];
+    field r42; field_mul(&r42, &r13, &r41);
+    field r43; field_add(&r43, &r40, &r42);
+    field r44 = coeffs[8];
+    field r45; field_mul(&r45, &r20, &r44);
+    field r46; field_add(&r46, &r43, &r45);
+    field r47; field_sub(&r47, &r36, &r46);
+    field r48 = next[0];
+    field r49 = coeffs[12];
+    field r50; field_mul(&r50, &r0, &r0);
+    field r51; field_mul(&r51, &r50, &r50);
+    field r52; field_mul(&r52, &r51, &r0);
+    field r53; field_mul(&r53, &r2, &r52);
+    field r54; field_add(&r54, &r49, &r53);
+    field r55; field_mul(&r55, &r24, &r24);
+    field r56; field_mul(&r56, &r55, &r55);
+    field r57; field_mul(&r57, &r56, &r24);
+    field r58; field_mul(&r58, &r9, &r57);
+    field r59; field_add(&r59, &r54, &r58);
+    field r60; field_mul(&r60, &r36, &r36);
+    field r61; field_mul(&r61, &r60, &r60);
+    field r62; field_mul(&r62, &r61, &r36);
+    field r63; field_mul(&r63, &r16, &r62);
+    field r64; field_add(&r64, &r59, &r63);
+    field r65; field_sub(&r65, &r48, &r64);
+    field r66 = next[1];
+    field r67 = coeffs[13];
+    field r68; field_mul(&r68, &r26, &r52);
+    field r69; field_add(&r69, &r67, &r68);
+    field r70; field_mul(&r70, &r29, &r57);
+    field r71; field_add(&r71, &r69, &r70);
+    field r72; field_mul(&r72, &r32, &r62);
+    field r73; field_add(&r73, &r71, &r72);
+    field r74; field_sub(&r74, &r66, &r73);
+    field r75 = next[2];
+    field r76 = coeffs[14];
+    field r77; field_mul(&r77, &r38, &r52);
+    field r78; field_add(&r78, &r76, &r77);
+    field r79; field_mul(&r79, &r41, &r57);
+    field r80; field_add(&r80, &r78, &r79);
+    field r81; field_mul(&r81, &r44, &r62);
+    field r82; field_add(&r82, &r80, &r81);
+    field r83; field_sub(&r83, &r75, &r82);
+    out[0] = r23;
+    out[1] = r35;
+    out[2] = r47;
+    out[3] = r65;
+    out[4] = r74;
+    out[5] = r83;
+}
+
+/* Evaluates the 5 constraints of the gate `chacha0` into out[0..5]. */
+void chacha0(const field *curr, const field *next, const field *coeffs, const field *endo_coefficient, const field *mds, field *out)
+{
+    (void)curr; (void)next; (void)coeffs; (void)endo_coefficient; (void)mds;
+    field r0 = next[2];
+    field r1; field_mul(&r1, &r0, &r0);
+    field r2; field_sub(&r2, &r1, &r0);
+    field r3 = curr[7];
+    field r4; field_from_fraction(&r4, 0, 0x10, 1);
+    field r5 = curr[8];
+    field r6; field_mul(&r6, &r4, &r5);
+    field r7; field_add(&r7, &r3, &r6);
+    field r8; field_from_fraction(&r8, 0, 0x100, 1);
+    field r9 = curr[9];
+    field r10; field_mul(&r10, &r8, &r9);
+    field r11; field_add(&r11, &r7, &r10);
+    field r12; field_from_fraction(&r12, 0, 0x1000, 1);
+    field r13 = curr[10];
+    field r14; field_mul(&r14, &r12, &r13);
+    field r15; field_add(&r15, &r11, &r14);
+    field r16; field_from_fraction(&r16, 0, 0x10000, 1);
+    field r17 = next[7];
+    field r18; field_mul(&r18, &r16, &r17);
+    field r19; field_add(&r19, &r15, &r18);
+    field r20; field_from_fraction(&r20, 0, 0x100000, 1);
+    field r21 = next[8];
+    field r22; field_mul(&r22, &r20, &r21);
+    field r23; field_add(&r23, &r19, &r22);
+    field r24; field_from_fraction(&r24, 0, 0x1000000, 1);
+    field r25 = next[9];
+    field r26; field_mul(&r26, &r24, &r25);
+    field r27; field_add(&r27, &r23, &r26);
+    field r28; field_from_fraction(&r28, 0, 0x10000000, 1);
+    field r29 = next[10];
+    field r30; field_mul(&r30, &r28, &r29);
+    field r31; field_add(&r31, &r27, &r30);
+    field r32 = next[0];
+    field r33; field_sub(&r33, &r31, &r32);
+    field r34; field_from_fraction(&r34, 0, 0x100000000, 1);
+    field r35; field_mul(&r35, &r0, &r34);
+    field r36; field_add(&r36, &r32, &r35);
+    field r37 = curr[0];
+    field r38 = curr[2];
+    field r39; field_add(&r39, &r37, &r38);
+    field r40; field_sub(&r40, &r36, &r39);
+    field r41 = curr[11];
+    field r42 = curr[12];
+    field r43; field_mul(&r43, &r4, &r42);
+    field r44; field_add(&r44, &r41, &r43);
+    field r45 = curr[13];
+    field r46; field_mul(&r46, &r8, &r45);
+    field r47; field_add(&r47, &r44, &r46);
+    field r48 = curr[14];
+    field r49; field_mul(&r49, &r12, &r48);
+    field r50; field_add(&r50, &r47, &r49);
+    field r51 = next[11];
+    field r52; field_mul(&r52, &r16, &r51);
+    field r53; field_add(&r53, &r50, &r52);
+    field r54 = next[12];
+    field r55; field_mul(&r55, &r20, &r54);
+    field r56; field_add(&r56, &r53, &r55);
+    field r57 = next[13];
+    field r58; field_mul(&r58, &r24, &r57);
+    field r59; field_add(&r59, &r56, &r58);
+    field r60 = next[14];
+    field r61; field_mul(&r61, &r28, &r60);
+    field r62; field_add(&r62, &r59, &r61);
+    field r63 = curr[1];
+    field r64; field_sub(&r64, &r62, &r63);
+    field r65 = next[3];
+    field r66 = next[4];
+    field r67; field_mul(&r67, &r4, &r66);
+    field r68; field_add(&r68, &r65, &r67);
+    field r69 = next[5];
+    field r70; field_mul(&r70, &r8, &r69);
+    field r71; field_add(&r71, &r68, &r70);
+    field r72 = next[6];
+    field r73; field_mul(&r73, &r12, &r72);
+    field r74; field_add(&r74, &r71, &r73);
+    field r75 = curr[3];
+    field r76; field_mul(&r76, &r16, &r75);
+    field r77; field_add(&r77, &r74, &r76);
+    field r78 = curr[4];
+    field r79; field_mul(&r79, &r20, &r78);
+    field r80; field_add(&r80, &r77, &r79);
+    field r81 = curr[5];
+    field r82; field_mul(&r82, &r24, &r81);
+    field r83; field_add(&r83, &r80, &r82);
+    field r84 = curr[6];
+    field r85; field_mul(&r85, &r28, &r84);
+    field r86; field_add(&r86, &r83, &r85);
+    field r87 = next[1];
+    field r88; field_sub(&r88, &r86, &r87);
+    out[0] = r2;
+    out[1] = r33;
+    out[2] = r40;
+    out[3] = r64;
+    out[4] = r88;
+}
+
+/* Evaluates the 5 constraints of the gate `chacha1` into out[0..5]. */
+void chacha1(const field *curr, const field *next, const field *coeffs, const field *endo_coefficient, const field *mds, field *out)
+{
+    (void)curr; (void)next; (void)coeffs; (void)endo_coefficient; (void)mds;
+    field r0 = next[2];
+    field r1; field_mul(&r1, &r0, &r0);
+    field r2; field_sub(&r2, &r1, &r0);
+    field r3 = curr[7];
+    field r4; field_from_fraction(&r4, 0, 0x10, 1);
+    field r5 = curr[8];
+    field r6; field_mul(&r6, &r4, &r5);
+    field r7; field_add(&r7, &r3, &r6);
+    field r8; field_from_fraction(&r8, 0, 0x100, 1);
+    field r9 = curr[9];
+    field r10; field_mul(&r10, &r8, &r9);
+    field r11; field_add(&r11, &r7, &r10);
+    field r12; field_from_fraction(&r12, 0, 0x1000, 1);
+    field r13 = curr[10];
+    field r14; field_mul(&r14, &r12, &r13);
+    field r15; field_add(&r15, &r11, &r14);
+    field r16; field_from_fraction(&r16, 0, 0x10000, 1);
+    field r17 = next[7];
+    field r18; field_mul(&r18, &r16, &r17);
+    field r19; field_add(&r19, &r15, &r18);
+    field r20; field_from_fraction(&r20, 0, 0x100000, 1);
+    field r21 = next[8];
+    field r22; field_mul(&r22, &r20, &r21);
+    field r23; field_add(&r23, &r19, &r22);
+    field r24; field_from_fraction(&r24, 0, 0x1000000, 1);
+    field r25 = next[9];
+    field r26; field_mul(&r26, &r24, &r25);
+    field r27; field_add(&r27, &r23, &r26);
+    field r28; field_from_fraction(&r28, 0, 0x10000000, 1);
+    field r29 = next[10];
+    field r30; field_mul(&r30, &r28, &r29);
+    field r31; field_add(&r31, &r27, &r30);
+    field r32 = next[0];
+    field r33; field_sub(&r33, &r31, &r32);
+    field r34; field_from_fraction(&r34, 0, 0x100000000, 1);
+    field r35; field_mul(&r35, &r0, &r34);
+    field r36; field_add(&r36, &r32, &r35);
+    field r37 = curr[0];
+    field r38 = curr[2];
+    field r39; field_add(&r39, &r37, &r38);
+    field r40; field_sub(&r40, &r36, &r39);
+    field r41 = curr[11];
+    field r42 = curr[12];
+    field r43; field_mul(&r43, &r4, &r42);
+    field r44; field_add(&r44, &r41, &r43);
+    field r45 = curr[13];
+    field r46; field_mul(&r46, &r8, &r45);
+    field r47; field_add(&r47, &r44, &r46);
+    field r48 = curr[14];
+    field r49; field_mul(&r49, &r12, &r48);
+    field r50; field_add(&r50, &r47, &r49);
+    field r51 = next[11];
+    field r52; field_mul(&r52, &r16, &r51);
+    field r53; field_add(&r53, &r50, &r52);
+    field r54 = next[12];
+    field r55; field_mul(&r55, &r20, &r54);
+    field r56; field_add(&r56, &r53, &r55);
+    field r57 = next[13];
+    field r58; field_mul(&r58, &r24, &r57);
+    field r59; field_add(&r59, &r56, &r58);
+    field r60 = next[14];
+    field r61; field_mul(&r61, &r28, &r60);
+    field r62; field_add(&r62, &r59, &r61);
+    field r63 = curr[1];
+    field r64; field_sub(&r64, &r62, &r63);
+    field r65 = next[4];
+    field r66 = next[5];
+    field r67; field_mul(&r67, &r4, &r66);
+    field r68; field_add(&r68, &r65, &r67);
+    field r69 = next[6];
+    field r70; field_mul(&r70, &r8, &r69);
+    field r71; field_add(&r71, &r68, &r70);
+    field r72 = curr[3];
+    field r73; field_mul(&r73, &r12, &r72);
+    field r74; field_add(&r74, &r71, &r73);
+    field r75 = curr[4];
+    field r76; field_mul(&r76, &r16, &r75);
+    field r77; field_add(&r77, &r74, &r76);
+    field r78 = curr[5];
+    field r79; field_mul(&r79, &r20, &r78);
+    field r80; field_add(&r80, &r77, &r79);
+    field r81 = curr[6];
+    field r82; field_mul(&r82, &r24, &r81);
+    field r83; field_add(&r83, &r80, &r82);
+    field r84 = next[3];
+    field r85; field_mul(&r85, &r28, &r84);
+    field r86; field_add(&r86, &r83, &r85);
+    field r87 = next[1];
+    field r88; field_sub(&r88, &r86, &r87);
+    out[0] = r2;
+    out[1] = r33;
+    out[2] = r40;
+    out[3] = r64;
+    out[4] = r88;
+}
+
+/* Evaluates the 5 constraints of the gate `chacha2` into out[0..5]. */
+void chacha2(const field *curr, const field *next, const field *coeffs, const field *endo_coefficient, const field *mds, field *out)
+{
+    (void)curr; (void)next; (void)coeffs; (void)endo_coefficient; (void)mds;
+    field r0 = next[2];
+    field r1; field_mul(&r1, &r0, &r0);
+    field r2; field_sub(&r2, &r1, &r0);
+    field r3 = curr[7];
+    field r4; field_from_fraction(&r4, 0, 0x10, 1);
+    field r5 = curr[8];
+    field r6; field_mul(&r6, &r4, &r5);
+    field r7; field_add(&r7, &r3, &r6);
+    field r8; field_from_fraction(&r8, 0, 0x100, 1);
+    field r9 = curr[9];
+    field r10; field_mul(&r10, &r8, &r9);
+    field r11; field_add(&r11, &r7, &r10);
+    field r12; field_from_fraction(&r12, 0, 0x1000, 1);
+    field r13 = curr[10];
+    field r14; field_mul(&r14, &r12, &r13);
+    field r15; field_add(&r15, &r11, &r14);
+    field r16; field_from_fraction(&r16, 0, 0x10000, 1);
+    field r17 = next[7];
+    field r18; field_mul(&r18, &r16, &r17);
+    field r19; field_add(&r19, &r15, &r18);
+    field r20; field_from_fraction(&r20, 0, 0x100000, 1);
+    field r21 = next[8];
+    field r22; field_mul(&r22, &r20, &r21);
+    field r23; field_add(&r23, &r19, &r22);
+    field r24; field_from_fraction(&r24, 0, 0x1000000, 1);
+    field r25 = next[9];
+    field r26; field_mul(&r26, &r24, &r25);
+    field r27; field_add(&r27, &r23, &r26);
+    field r28; field_from_fraction(&r28, 0, 0x10000000, 1);
+    field r29 = next[10];
+    field r30; field_mul(&r30, &r28, &r29);
+    field r31; field_add(&r31, &r27, &r30);
+    field r32 = next[0];
+    field r33; field_sub(&r33, &r31, &r32);
+    field r34; field_from_fraction(&r34, 0, 0x100000000, 1);
+    field r35; field_mul(&r35, &r0, &r34);
+    field r36; field_add(&r36, &r32, &r35);
+    field r37 = curr[0];
+    field r38 = curr[2];
+    field r39; field_add(&r39, &r37, &r38);
+    field r40; field_sub(&r40, &r36, &r39);
+    field r41 = curr[11];
+    field r42 = curr[12];
+    field r43; field_mul(&r43, &r4, &r42);
+    field r44; field_add(&r44, &r41, &r43);
+    field r45 = curr[13];
+    field r46; field_mul(&r46, &r8, &r45);
+    field r47; field_add(&r47, &r44, &r46);
+    field r48 = curr[14];
+    field r49; field_mul(&r49, &r12, &r48);
+    field r50; field_add(&r50, &r47, &r49);
+    field r51 = next[11];
+    field r52; field_mul(&r52, &r16, &r51);
+    field r53; field_add(&r53, &r50, &r52);
+    field r54 = next[12];
+    field r55; field_mul(&r55, &r20, &r54);
+    field r56; field_add(&r56, &r53, &r55);
+    field r57 = next[13];
+    field r58; field_mul(&r58, &r24, &r57);
+    field r59; field_add(&r59, &r56, &r58);
+    field r60 = next[14];
+    field r61; field_mul(&r61, &r28, &r60);
+    field r62; field_add(&r62, &r59, &r61);
+    field r63 = curr[1];
+    field r64; field_sub(&r64, &r62, &r63);
+    field r65 = next[5];
+    field r66 = next[6];
+    field r67; field_mul(&r67, &r4, &r66);
+    field r68; field_add(&r68, &r65, &r67);
+    field r69 = curr[3];
+    field r70; field_mul(&r70, &r8, &r69);
+    field r71; field_add(&r71, &r68, &r70);
+    field r72 = curr[4];
+    field r73; field_mul(&r73, &r12, &r72);
+    field r74; field_add(&r74, &r71, &r73);
+    field r75 = curr[5];
+    field r76; field_mul(&r76, &r16, &r75);
+    field r77; field_add(&r77, &r74, &r76);
+    field r78 = curr[6];
+    field r79; field_mul(&r79, &r20, &r78);
+    field r80; field_add(&r80, &r77, &r79);
+    field r81 = next[3];
+    field r82; field_mul(&r82, &r24, &r81);
+    field r83; field_add(&r83, &r80, &r82);
+    field r84 = next[4];
+    field r85; field_mul(&r85, &r28, &r84);
+    field r86; field_add(&r86, &r83, &r85);
+    field r87 = next[1];
+    field r88; field_sub(&r88, &r86, &r87);
+    out[0] = r2;
+    out[1] = r33;
+    out[2] = r40;
+    out[3] = r64;
+    out[4] = r88;
+}
+
+/* Evaluates the 9 constraints of the gate `chacha_final` into out[0..9]. */
+void chacha_final(const field *curr, const field *next, const field *coeffs, const field *endo_coefficient, const field *mds, field *out)
+{
+    (void)curr; (void)next; (void)coeffs; (void)endo_coefficient; (void)mds;
+    field r0 = curr[5];
+    field r1; field_mul(&r1, &r0, &r0);
+    field r2; field_sub(&r2, &r1, &r0);
+    field r3 = curr[6];
+    field r4; field_mul(&r4, &r3, &r3);
+    field r5; field_sub(&r5, &r4, &r3);
+    field r6 = curr[7];
+    field r7; field_mul(&r7, &r6, &r6);
+    field r8; field_sub(&r8, &r7, &r6);
+    field r9 = curr[8];
+    field r10; field_mul(&r10, &r9, &r9);
+    field r11; field_sub(&r11, &r10, &r9);
+    field r12 = next[5];
+    field r13; field_mul(&r13, &r12, &r12);
+    field r14; field_sub(&r14, &r13, &r12);
+    field r15 = next[6];
+    field r16; field_mul(&r16, &r15, &r15);
+    field r17; field_sub(&r17, &r16, &r15);
+    field r18 = next[7];
+    field r19; field_mul(&r19, &r18, &r18);
+    field r20; field_sub(&r20, &r19, &r18);
+    field r21 = next[8];
+    field r22; field_mul(&r22, &r21, &r21);
+    field r23; field_sub(&r23, &r22, &r21);
+    field r24; field_from_fraction(&r24, 0, 0x8, 1);
+    field r25; field_mul(&r25, &r21, &r24);
+    field r26; field_from_fraction(&r26, 0, 0x1, 2);
+    field r27 = next[3];
+    field r28; field_sub(&r28, &r27, &r18);
+    field r29; field_mul(&r29, &r26, &r28);
+    field r30; field_add(&r30, &r25, &r29);
+    field r31; field_from_fraction(&r31, 0, 0x10, 1);
+    field r32; field_mul(&r32, &r0, &r24);
+    field r33 = next[4];
+    field r34; field_sub(&r34, &r33, &r21);
+    field r35; field_mul(&r35, &r26, &r34);
+    field r36; field_add(&r36, &r32, &r35);
+    field r37; field_mul(&r37, &r31, &r36);
+    field r38; field_add(&r38, &r30, &r37);
+    field r39; field_from_fraction(&r39, 0, 0x100, 1);
+    field r40; field_mul(&r40, &r3, &r24);
+    field r41 = curr[1];
+    field r42; field_sub(&r42, &r41, &r0);
+    field r43; field_mul(&r43, &r26, &r42);
+    field r44; field_add(&r44, &r40, &r43);
+    field r45; field_mul(&r45, &r39, &r44);
+    field r46; field_add(&r46, &r38, &r45);
+    field r47; field_from_fraction(&r47, 0, 0x1000, 1);
+    field r48; field_mul(&r48, &r6, &r24);
+    field r49 = curr[2];
+    field r50; field_sub(&r50, &r49, &r3);
+    field r51; field_mul(&r51, &r26, &r50);
+    field r52; field_add(&r52, &r48, &r51);
+    field r53; field_mul(&r53, &r47, &r52);
+    field r54; field_add(&r54, &r46, &r53);
+    field r55; field_from_fraction(&r55, 0, 0x10000, 1);
+    field r56; field_mul(&r56, &r9, &r24);
+    field r57 = curr[3];
+    field r58; field_sub(&r58, &r57, &r6);
+    field r59; field_mul(&r59, &r26, &r58);
+    field r60; field_add(&r60, &r56, &r59);
+    field r61; field_mul(&r61, &r55, &r60);
+    field r62; field_add(&r62, &r54, &r61);
+    field r63; field_from_fraction(&r63, 0, 0x100000, 1);
+    field r64; field_mul(&r64, &r12, &r24);
+    field r65 = curr[4];
+    field r66; field_sub(&r66, &r65, &r9);
+    field r67; field_mul(&r67, &r26, &r66);
+    field r68; field_add(&r68, &r64, &r67);
+    field r69; field_mul(&r69, &r63, &r68);
+    field r70; field_add(&r70, &r62, &r69);
+    field r71; field_from_fraction(&r71, 0, 0x1000000, 1);
+    field r72; field_mul(&r72, &r15, &r24);
+    field r73 = next[1];
+    field r74; field_sub(&r74, &r73, &r12);
+    field r75; field_mul(&r75, &r26, &r74);
+    field r76; field_add(&r76, &r72, &r75);
+    field r77; field_mul(&r77, &r71, &r76);
+    field r78; field_add(&r78, &r70, &r77);
+    field r79; field_from_fraction(&r79, 0, 0x10000000, 1);
+    field r80; field_mul(&r80, &r18, &r24);
+    field r81 = next[2];
+    field r82; field_sub(&r82, &r81, &r15);
+    field r83; field_mul(&r83, &r26, &r82);
+    field r84; field_add(&r84, &r80, &r83);
+    field r85; field_mul(&r85, &r79, &r84);
+    field r86; field_add(&r86, &r78, &r85);
+    field r87 = curr[0];
+    field r88; field_sub(&r88, &r86, &r87);
+    out[0] = r2;
+    out[1] = r5;
+    out[2] = r8;
+    out[3] = r11;
+    out[4] = r14;
+    out[5] = r17;
+    out[6] = r20;
+    out[7] = r23;
+    out[8] = r88;
+}
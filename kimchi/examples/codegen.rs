@@ -0,0 +1,19 @@
+//! Generates the evaluators of the constraints of the gates (see [kimchi::circuits::codegen]):
+//! the Rust module `src/circuits/evaluators.rs`, and the C source `c/evaluators.c`.
+//!
+//! Run it with `cargo run --example codegen` after changing the constraints of a gate.
+
+use kimchi::circuits::codegen::{arguments, c_module, rust_module};
+use mina_curves::pasta::fp::Fp;
+use std::path::Path;
+
+fn main() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let gates = arguments::<Fp>();
+
+    let rust = rust_module(&gates).expect("the constraints of the gates can be generated");
+    std::fs::write(dir.join("src/circuits/evaluators.rs"), rust).unwrap();
+
+    let c = c_module(&gates).expect("the constraints of the gates can be generated");
+    std::fs::write(dir.join("c/evaluators.c"), c).unwrap();
+}
@@ -23,8 +23,9 @@
 use crate::circuits::{argument::ArgumentType, gate::GateType};
 use ark_ff::Field;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use std::{
-    collections::HashMap,
+    collections::BTreeMap,
     fmt::Display,
     iter::{Cloned, Skip, Take},
     ops::Range,
@@ -38,15 +39,19 @@ use std::{
 /// See [Self::default] to create one,
 /// and [Self::register] to register a new mapping.
 /// Once you know the alpha value, you can convert this type to a [Alphas].
+#[serde_as]
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(bound = "F: Field")]
 pub struct Alphas<F> {
     /// The next power of alpha to use
     /// the end result will be [1, alpha^{next_power - 1}]
     next_power: u32,
     /// The mapping between constraint types and powers of alpha
-    mapping: HashMap<ArgumentType, (u32, u32)>,
+    #[serde_as(as = "Vec<(_, _)>")]
+    mapping: BTreeMap<ArgumentType, (u32, u32)>,
     /// The powers of alpha: 1, alpha, alpha^2, etc.
     /// If set to [Some], you can't register new constraints.
+    #[serde_as(as = "Option<Vec<o1_utils::serialization::SerdeAs>>")]
     alphas: Option<Vec<F>>,
 }
 
@@ -164,6 +169,18 @@ impl<F: Field> Alphas<F> {
             }
         }
     }
+
+    /// Returns the range of powers of alpha registered for each [ArgumentType], by increasing powers.
+    /// The gates share a single range, registered for [GateType::Zero].
+    pub fn ranges(&self) -> Vec<(ArgumentType, Range<u32>)> {
+        let mut ranges: Vec<_> = self
+            .mapping
+            .iter()
+            .map(|(ty, (start, powers))| (*ty, *start..start + powers))
+            .collect();
+        ranges.sort_by_key(|(_, range)| range.start);
+        ranges
+    }
 }
 
 impl<T> Display for Alphas<T> {
@@ -326,7 +343,9 @@ mod tests {
         let (_linearization, powers_of_alpha) = expr_linearization(
             index.cs.domain.d1,
             index.cs.chacha8.is_some(),
-            &index.cs.lookup_constraint_system,
+            index.cs.poseidon_legacy8.is_some(),
+            index.cs.fixed_base_mull4.is_some(),
+            index.cs.lookup_parameters(),
         );
 
         // make sure this is present in the specification
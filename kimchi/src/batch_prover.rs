@@ -0,0 +1,140 @@
+//! This module implements a batch prover, which creates many proofs for the same circuit.
+//!
+//! All the proofs of a batch borrow the same [ProverIndex].
+//! Before the first proof, the batch prover computes what the index caches for its proofs:
+//! the compiled constraints of the gates (see [ProverIndex::constraints_programs])
+//! and the roots of unity of the FFTs (see [ProverIndex::twiddles]).
+//! The proofs are then created in parallel with rayon, from a queue of the witnesses:
+//! as many proofs are in flight as the configured memory budget allows,
+//! and each proof that completes makes room for the next witness of the queue.
+
+use crate::{
+    circuits::wires::{COLUMNS, PERMUTS},
+    error::Result,
+    plonk_sponge::FrSponge,
+    prover::ProverProof,
+    prover_index::ProverIndex,
+};
+use ark_ec::AffineCurve;
+use ark_ff::PrimeField;
+use commitment_dlog::commitment::{CommitmentCurve, PolyComm};
+use oracle::FqSponge;
+use rayon::prelude::*;
+use std::sync::Mutex;
+
+type Fr<G> = <G as AffineCurve>::ScalarField;
+type Fq<G> = <G as AffineCurve>::BaseField;
+
+/// A witness to prove as part of a batch
+pub struct BatchWitness<G: AffineCurve> {
+    /// The witness (execution trace)
+    pub witness: [Vec<Fr<G>>; COLUMNS],
+    /// The challenges underlying the optional polynomials folded into the proof
+    pub prev_challenges: Vec<(Vec<Fr<G>>, PolyComm<G>)>,
+}
+
+impl<G: AffineCurve> From<[Vec<Fr<G>>; COLUMNS]> for BatchWitness<G> {
+    fn from(witness: [Vec<Fr<G>>; COLUMNS]) -> Self {
+        Self {
+            witness,
+            prev_challenges: vec![],
+        }
+    }
+}
+
+/// The configuration of the batch prover
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BatchConfig {
+    /// The maximum amount of memory (in bytes) that the proofs being created at the same time can use.
+    /// If not set, all the proofs of the batch are created at the same time.
+    pub memory_budget: Option<usize>,
+}
+
+impl BatchConfig {
+    /// The number of proofs that can be created at the same time for the given index.
+    /// This is always at least one, even if a single proof does not fit in the budget.
+    pub fn max_concurrent_proofs<G: CommitmentCurve>(&self, index: &ProverIndex<G>) -> usize {
+        match self.memory_budget {
+            None => usize::MAX,
+            Some(budget) => std::cmp::max(1, budget / proof_memory_estimate(index)),
+        }
+    }
+}
+
+/// A rough estimate (in bytes) of the memory used by the prover to create a single proof for the given index.
+/// It accounts for the witness in coefficient and evaluation form (over d1, d4 and d8),
+/// the permutation and lookup polynomials, and the evaluations of the quotient contributions.
+pub fn proof_memory_estimate<G: CommitmentCurve>(index: &ProverIndex<G>) -> usize {
+    let n = index.cs.domain.d1.size as usize;
+    let lookup_columns = index
+        .cs
+        .lookup_constraint_system
+        .as_ref()
+        .map_or(0, |lcs| lcs.max_lookups_per_row + 3);
+
+    // witness: columns + coefficients (d1), evaluations over d4 and d8
+    let witness = COLUMNS * (1 + 1 + 4 + 8) * n;
+    // permutation aggregation: coefficients and evaluations over d4 and d8
+    let permutation = (1 + 4 + 8) * n;
+    // quotient: t4, t8, one gate contribution at a time, and the final polynomial
+    let quotient = (4 + 8 + 8 + PERMUTS) * n;
+    // lookup: sorted, aggregation and combined table over d1 and d8
+    let lookup = lookup_columns * (1 + 8) * n;
+
+    (witness + permutation + quotient + lookup) * std::mem::size_of::<Fr<G>>()
+}
+
+impl<G: CommitmentCurve> ProverProof<G>
+where
+    G::BaseField: PrimeField,
+    G::Map: Sync,
+{
+    /// This function creates one proof per witness of the batch, against the same [ProverIndex].
+    /// Proofs are created in parallel, with at most [BatchConfig::max_concurrent_proofs] proofs in flight at once.
+    /// The result of each witness is returned at the same position as the witness in the batch.
+    pub fn create_batch<
+        EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>,
+        EFrSponge: FrSponge<Fr<G>>,
+    >(
+        group_map: &G::Map,
+        witnesses: Vec<BatchWitness<G>>,
+        index: &ProverIndex<G>,
+        config: &BatchConfig,
+    ) -> Vec<Result<Self>> {
+        let max_concurrent_proofs = config.max_concurrent_proofs(index);
+
+        // the per-index data shared by the proofs
+        index.constraints_programs();
+        index.twiddles();
+
+        // each worker proves the next witness of the queue until it is empty,
+        // so that at most `max_concurrent_proofs` proofs are in flight
+        let len = witnesses.len();
+        let queue = Mutex::new(witnesses.into_iter().enumerate());
+        let workers = std::cmp::min(max_concurrent_proofs, len);
+        let mut proofs: Vec<(usize, Result<Self>)> = (0..workers)
+            .into_par_iter()
+            .flat_map_iter(|_| {
+                let mut proofs = vec![];
+                loop {
+                    let next = queue.lock().expect("a proof panicked").next();
+                    let (i, w) = match next {
+                        Some(next) => next,
+                        None => break proofs,
+                    };
+                    let proof = Self::create_recursive::<EFqSponge, EFrSponge>(
+                        group_map,
+                        w.witness,
+                        index,
+                        w.prev_challenges,
+                    );
+                    proofs.push((i, proof));
+                }
+            })
+            .collect();
+
+        // the proofs in the order of the witnesses
+        proofs.sort_by_key(|(i, _)| *i);
+        proofs.into_iter().map(|(_, proof)| proof).collect()
+    }
+}
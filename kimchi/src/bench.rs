@@ -16,6 +16,7 @@ use commitment_dlog::commitment::{b_poly_coefficients, ceil_log2, CommitmentCurv
 use groupmap::{BWParameters, GroupMap};
 use mina_curves::pasta::vesta::VestaParameters;
 use mina_curves::pasta::{fp::Fp, vesta::Affine};
+use o1_utils::profiling;
 use oracle::{
     constants::PlonkSpongeConstantsKimchi,
     sponge::{DefaultFqSponge, DefaultFrSponge},
@@ -115,6 +116,17 @@ impl BenchmarkCtx {
             .collect();
         batch_verify::<Affine, BaseSponge, ScalarSponge>(&self.group_map, &batch).unwrap();
     }
+
+    /// Creates and verifies a proof, and returns the time spent in each phase of the prover and the verifier.
+    /// Note that timings are only collected if the `profiling` feature is enabled.
+    /// Only the timings of this proof are collected, even if other proofs are created at the same time.
+    pub fn profile(&self) -> profiling::Summary {
+        let ((), summary) = profiling::scoped(|| {
+            let proof = self.create_proof();
+            self.batch_verification(vec![proof]);
+        });
+        summary
+    }
 }
 
 #[cfg(test)]
@@ -140,4 +152,27 @@ mod tests {
         ctx.batch_verification(vec![proof.clone()]);
         println!("proof verified in {}", start.elapsed().as_millis());
     }
+
+    #[cfg(feature = "profiling")]
+    #[test]
+    fn test_bench_profile() {
+        let ctx = BenchmarkCtx::new(1 << 4);
+        let summary = ctx.profile();
+
+        // every step of the prover was timed
+        for step in 1..=45 {
+            let phase = format!("prover.step_{:02}", step);
+            assert_eq!(summary.phase(&phase).unwrap().calls, 1);
+        }
+
+        // as well as the verifier and the polynomial commitment scheme
+        for phase in [
+            "srs.open",
+            "verifier.oracles",
+            "verifier.to_batch.f_comm",
+            "srs.verify.msm",
+        ] {
+            assert_eq!(summary.phase(phase).unwrap().calls, 1);
+        }
+    }
 }
@@ -29,6 +29,11 @@ fn main() {
                 ctx.batch_verification(black_box(vec![proof.clone()]));
             }
         }
-        _ => panic!("you must provide an argument (prove or verify)"),
+        Some("profile") => {
+            let ctx = BenchmarkCtx::new(1 << 14);
+            let summary = serde_json::to_string_pretty(&ctx.profile()).unwrap();
+            println!("{}", summary);
+        }
+        _ => panic!("you must provide an argument (prove, verify or profile)"),
     };
 }
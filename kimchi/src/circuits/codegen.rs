@@ -0,0 +1,330 @@
+//! This module generates Rust and C source code evaluating the constraints of the gates (see [Argument]),
+//! given the witness of the current and next rows, the coefficients of the current row,
+//! the endomorphism coefficient and the MDS matrix.
+//!
+//! The constraints of a gate are compiled together (see [Program::compile_all]),
+//! and each register of the program becomes a local variable of a function returning the values of the constraints.
+//! The generated code is meant for external verifiers and fuzzers, and for [ConstraintSystem::verify](crate::circuits::constraints::ConstraintSystem::verify),
+//! which uses the Rust evaluators of [crate::circuits::evaluators].
+//! They are generated with `cargo run --example codegen`.
+//!
+//! The generated Rust functions are generic over the field, and only depend on [ark_ff::Field].
+//! The generated C functions depend on a header `field.h`, provided by the embedder,
+//! which defines a type `field` and the arithmetic functions documented at [c_module].
+//! In both languages, the literals do not depend on the field either, unless they are not small fractions
+//! (see [fraction]): the constraints of the gates only have small fractions,
+//! so that the generated code can be used with the fields of both Pasta curves.
+
+use crate::circuits::{
+    argument::Argument,
+    expr::{prologue::E, Column},
+    gate::CurrOrNext,
+    polynomials::{
+        chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFinal},
+        complete_add::CompleteAdd,
+        endomul_scalar::EndomulScalar,
+        endosclmul::EndosclMul,
+        fixed_base_mul::FixedBaseMul,
+        poseidon::{Poseidon, SPONGE_WIDTH},
+        poseidon_legacy::PoseidonLegacy,
+        varbasemul::VarbaseMul,
+    },
+    program::{Op, Program},
+};
+use ark_ff::PrimeField;
+use std::fmt::Write;
+
+/// The header of the generated Rust module
+const RUST_HEADER: &str = "\
+//! This module evaluates the constraints of the gates.
+//! It is generated by `cargo run --example codegen` (see [crate::circuits::codegen]), do not edit it.
+
+#![allow(clippy::all, dead_code, unused_variables)]
+
+use ark_ff::Field;
+
+/// Returns the field element whose little-endian 64-bit limbs are `limbs`.
+fn literal<F: Field>(limbs: &[u64]) -> F {
+    let base = F::from(1u128 << 64);
+    limbs
+        .iter()
+        .rev()
+        .fold(F::zero(), |acc, limb| acc * base + F::from(*limb))
+}
+";
+
+/// The header of the generated C source
+const C_HEADER: &str = "\
+/* This file evaluates the constraints of the gates.
+ * It is generated by `cargo run --example codegen`, do not edit it.
+ *
+ * It includes \"field.h\", which defines the type `field` of the elements of the field, and the functions
+ *
+ *   void field_add(field *r, const field *x, const field *y);
+ *   void field_sub(field *r, const field *x, const field *y);
+ *   void field_mul(field *r, const field *x, const field *y);
+ *   void field_from_fraction(field *r, int negative, uint64_t n, uint64_t d);
+ *   void field_from_limbs(field *r, const uint64_t *limbs, size_t n);
+ *
+ * where `field_from_fraction` sets `r` to `n / d`, or to `-n / d` if `negative` is not zero,
+ * and `field_from_limbs` sets `r` to the element whose little-endian 64-bit limbs are `limbs[0..n]`.
+ * The literals which are small fractions do not depend on the field,
+ * the others are written as the limbs of the elements of the field for which the code was generated.
+ * The MDS matrix is passed in row-major order.
+ */
+
+#include <stddef.h>
+#include <stdint.h>
+#include \"field.h\"
+";
+
+/// Returns the names and the constraints of the gates, for which [rust_module] and [c_module] generate evaluators.
+pub fn arguments<F: PrimeField>() -> Vec<(&'static str, Vec<E<F>>)> {
+    vec![
+        ("complete_add", CompleteAdd::<F>::constraints()),
+        ("varbasemul", VarbaseMul::<F>::constraints()),
+        ("endosclmul", EndosclMul::<F>::constraints()),
+        ("endomul_scalar", EndomulScalar::<F>::constraints()),
+        ("fixed_base_mul", FixedBaseMul::<F>::constraints()),
+        ("poseidon", Poseidon::<F>::constraints()),
+        ("poseidon_legacy", PoseidonLegacy::<F>::constraints()),
+        ("chacha0", ChaCha0::<F>::constraints()),
+        ("chacha1", ChaCha1::<F>::constraints()),
+        ("chacha2", ChaCha2::<F>::constraints()),
+        ("chacha_final", ChaChaFinal::<F>::constraints()),
+    ]
+}
+
+/// A leaf of a program, as read by a generated function
+enum Leaf<F> {
+    Curr(usize),
+    Next(usize),
+    Coefficient(usize),
+    EndoCoefficient,
+    Mds(usize, usize),
+    Literal(F),
+}
+
+/// Returns the leaf read by an operation of the program of the gate `name`,
+/// or an error if the generated functions cannot read it.
+fn leaf<F: PrimeField>(name: &str, op: &Op<F>) -> Result<Option<Leaf<F>>, String> {
+    let leaf = match op {
+        Op::Cell(v) => match (v.col, v.row) {
+            (Column::Witness(i), CurrOrNext::Curr) => Leaf::Curr(i),
+            (Column::Witness(i), CurrOrNext::Next) => Leaf::Next(i),
+            (Column::Coefficient(i), CurrOrNext::Curr) => Leaf::Coefficient(i),
+            (col, row) => {
+                return Err(format!(
+                    "the constraints of {} read the column {:?} of the row {:?}, which is not a witness or a coefficient of the current row",
+                    name, col, row
+                ))
+            }
+        },
+        Op::EndoCoefficient => Leaf::EndoCoefficient,
+        Op::Mds { row, col } => Leaf::Mds(*row, *col),
+        Op::Literal(x) => Leaf::Literal(*x),
+        Op::Alpha | Op::Beta | Op::Gamma | Op::JointCombiner => {
+            return Err(format!(
+                "the constraints of {} read the challenge {:?}",
+                name, op
+            ))
+        }
+        Op::VanishesOnLast4Rows | Op::UnnormalizedLagrangeBasis(_) => {
+            return Err(format!(
+                "the constraints of {} read the polynomial {:?}, which depends on the domain",
+                name, op
+            ))
+        }
+        Op::Pow(..) => unreachable!("powers are expanded by Program::factor_powers"),
+        Op::Add(..) | Op::Sub(..) | Op::Mul(..) => return Ok(None),
+    };
+    Ok(Some(leaf))
+}
+
+/// Returns the limbs of a field element, without the most significant zero limbs.
+fn limbs<F: PrimeField>(x: F) -> Vec<u64> {
+    let repr = x.into_repr();
+    let mut limbs = repr.as_ref().to_vec();
+    while limbs.len() > 1 && limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    limbs
+}
+
+/// The largest denominator of the literals written as fractions in the generated code
+const MAX_DENOMINATOR: u64 = 256;
+
+/// Returns `(negative, n, d)` if the field element `x` is the fraction `n / d`, or `-n / d` if `negative`,
+/// with `n` fitting in 64 bits and `d` small, so that it can be written independently of the field.
+fn fraction<F: PrimeField>(x: F) -> Option<(bool, u64, u64)> {
+    (1..=MAX_DENOMINATOR).find_map(|d| {
+        let n = x * F::from(d);
+        match (limbs(n).as_slice(), limbs(-n).as_slice()) {
+            (&[n], _) => Some((false, n, d)),
+            (_, &[n]) => Some((true, n, d)),
+            _ => None,
+        }
+    })
+}
+
+/// Returns a Rust expression of the field element `x`,
+/// which does not depend on the field if `x` is a small fraction (see [fraction]).
+fn rust_literal<F: PrimeField>(x: F) -> String {
+    match fraction(x) {
+        Some((negative, n, d)) => {
+            let sign = if negative { "-" } else { "" };
+            if d == 1 {
+                format!("{}F::from({}u64)", sign, n)
+            } else {
+                format!("{}F::from({}u64) / F::from({}u64)", sign, n, d)
+            }
+        }
+        None => format!("literal(&{:?})", limbs(x)),
+    }
+}
+
+/// Returns a C statement declaring the variable `r` and setting it to the field element `x`,
+/// which does not depend on the field if `x` is a small fraction (see [fraction]).
+fn c_literal<F: PrimeField>(r: &str, x: F) -> String {
+    match fraction(x) {
+        Some((negative, n, d)) => format!(
+            "field {r}; field_from_fraction(&{r}, {}, {:#x}, {});",
+            negative as u8,
+            n,
+            d,
+            r = r
+        ),
+        None => {
+            let limbs: Vec<_> = limbs(x).iter().map(|l| format!("{:#x}", l)).collect();
+            format!(
+                "field {r}; field_from_limbs(&{r}, (const uint64_t[]){{{}}}, {});",
+                limbs.join(", "),
+                limbs.len(),
+                r = r
+            )
+        }
+    }
+}
+
+/// Generates a Rust function `name` evaluating `constraints`.
+pub fn rust_function<F: PrimeField>(name: &str, constraints: &[E<F>]) -> Result<String, String> {
+    let program = Program::compile_all(constraints);
+    let mut res = String::new();
+    writeln!(
+        res,
+        "/// Evaluates the {} constraints of the gate `{}`.",
+        constraints.len(),
+        name
+    )
+    .unwrap();
+    writeln!(
+        res,
+        "pub fn {}<F: Field>(curr: &[F], next: &[F], coeffs: &[F], endo_coefficient: F, mds: &[Vec<F>]) -> [F; {}] {{",
+        name,
+        constraints.len()
+    )
+    .unwrap();
+    for (i, op) in program.ops.iter().enumerate() {
+        let value = match leaf(name, op)? {
+            Some(Leaf::Curr(j)) => format!("curr[{}]", j),
+            Some(Leaf::Next(j)) => format!("next[{}]", j),
+            Some(Leaf::Coefficient(j)) => format!("coeffs[{}]", j),
+            Some(Leaf::EndoCoefficient) => "endo_coefficient".to_string(),
+            Some(Leaf::Mds(row, col)) => format!("mds[{}][{}]", row, col),
+            Some(Leaf::Literal(x)) => rust_literal(x),
+            None => match op {
+                Op::Add(x, y) if x == y => format!("r{}.double()", x),
+                Op::Mul(x, y) if x == y => format!("r{}.square()", x),
+                Op::Add(x, y) => format!("r{} + r{}", x, y),
+                Op::Sub(x, y) => format!("r{} - r{}", x, y),
+                Op::Mul(x, y) => format!("r{} * r{}", x, y),
+                _ => unreachable!("the other operations are leaves"),
+            },
+        };
+        writeln!(res, "    let r{} = {};", i, value).unwrap();
+    }
+    let outputs: Vec<_> = program.outputs.iter().map(|r| format!("r{}", r)).collect();
+    writeln!(res, "    [{}]", outputs.join(", ")).unwrap();
+    res.push_str("}\n");
+    Ok(res)
+}
+
+/// Generates a C function `name` evaluating `constraints` into its argument `out`.
+pub fn c_function<F: PrimeField>(name: &str, constraints: &[E<F>]) -> Result<String, String> {
+    let program = Program::compile_all(constraints);
+    let mut res = String::new();
+    writeln!(
+        res,
+        "/* Evaluates the {} constraints of the gate `{}` into out[0..{}]. */",
+        constraints.len(),
+        name,
+        constraints.len()
+    )
+    .unwrap();
+    writeln!(
+        res,
+        "void {}(const field *curr, const field *next, const field *coeffs, const field *endo_coefficient, const field *mds, field *out)\n{{",
+        name
+    )
+    .unwrap();
+    // not all the gates read all the arguments
+    res.push_str("    (void)curr; (void)next; (void)coeffs; (void)endo_coefficient; (void)mds;\n");
+    for (i, op) in program.ops.iter().enumerate() {
+        let statement = match leaf(name, op)? {
+            Some(Leaf::Curr(j)) => format!("field r{} = curr[{}];", i, j),
+            Some(Leaf::Next(j)) => format!("field r{} = next[{}];", i, j),
+            Some(Leaf::Coefficient(j)) => format!("field r{} = coeffs[{}];", i, j),
+            Some(Leaf::EndoCoefficient) => format!("field r{} = *endo_coefficient;", i),
+            Some(Leaf::Mds(row, col)) => {
+                format!("field r{} = mds[{}];", i, SPONGE_WIDTH * row + col)
+            }
+            Some(Leaf::Literal(x)) => c_literal(&format!("r{}", i), x),
+            None => {
+                let (f, x, y) = match op {
+                    Op::Add(x, y) => ("field_add", x, y),
+                    Op::Sub(x, y) => ("field_sub", x, y),
+                    Op::Mul(x, y) => ("field_mul", x, y),
+                    _ => unreachable!("the other operations are leaves"),
+                };
+                format!("field r{i}; {}(&r{i}, &r{}, &r{});", f, x, y)
+            }
+        };
+        writeln!(res, "    {}", statement).unwrap();
+    }
+    for (i, r) in program.outputs.iter().enumerate() {
+        writeln!(res, "    out[{}] = r{};", i, r).unwrap();
+    }
+    res.push_str("}\n");
+    Ok(res)
+}
+
+/// Generates a Rust module with a function evaluating the constraints of each gate of `gates`.
+pub fn rust_module<F: PrimeField>(gates: &[(&str, Vec<E<F>>)]) -> Result<String, String> {
+    let mut res = RUST_HEADER.to_string();
+    for (name, constraints) in gates {
+        res.push('\n');
+        res.push_str(&rust_function(name, constraints)?);
+    }
+    Ok(res)
+}
+
+/// Generates a C source file with a function evaluating the constraints of each gate of `gates`.
+/// It expects a header `field.h` defining the type `field` and the functions
+///
+/// ```c
+/// void field_add(field *r, const field *x, const field *y);
+/// void field_sub(field *r, const field *x, const field *y);
+/// void field_mul(field *r, const field *x, const field *y);
+/// void field_from_fraction(field *r, int negative, uint64_t n, uint64_t d);
+/// void field_from_limbs(field *r, const uint64_t *limbs, size_t n);
+/// ```
+///
+/// as documented at the beginning of the generated file.
+pub fn c_module<F: PrimeField>(gates: &[(&str, Vec<E<F>>)]) -> Result<String, String> {
+    let mut res = C_HEADER.to_string();
+    for (name, constraints) in gates {
+        res.push('\n');
+        res.push_str(&c_function(name, constraints)?);
+    }
+    Ok(res)
+}
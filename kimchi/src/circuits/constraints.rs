@@ -2,10 +2,12 @@
 
 use crate::circuits::{
     domains::EvaluationDomains,
-    gate::{CircuitGate, GateType, LookupInfo, LookupsUsed},
+    fft::Twiddles,
+    gate::{CircuitGate, GateType, LookupInfo, LookupTable, LookupsUsed},
     polynomial::{WitnessEvals, WitnessOverDomains, WitnessShifts},
     wires::*,
 };
+use crate::linearization::LookupParameters;
 use ark_ff::{FftField, SquareRootField, Zero};
 use ark_poly::UVPolynomial;
 use ark_poly::{
@@ -18,6 +20,7 @@ use o1_utils::ExtendedEvaluations;
 use oracle::poseidon::ArithmeticSpongeParams;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
+use thiserror::Error;
 
 //
 // Constants
@@ -96,6 +99,12 @@ pub struct ConstraintSystem<F: FftField> {
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub psm: DP<F>,
 
+    // Poseidon legacy selector polynomials
+    // ------------------------------------
+    /// poseidon legacy constraint selector polynomial, if the circuit has legacy Poseidon gates
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub poseidon_legacym: Option<DP<F>>,
+
     // Generic constraint selector polynomials
     // ---------------------------------------
     /// multiplication evaluations over domain.d4
@@ -119,6 +128,9 @@ pub struct ConstraintSystem<F: FftField> {
     /// poseidon selector over domain.d8
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub ps8: E<F, D<F>>,
+    /// poseidon legacy selector over domain.d8, if the circuit has legacy Poseidon gates
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub poseidon_legacy8: Option<E<F, D<F>>>,
 
     // ECC arithmetic selector polynomials
     // -----------------------------------
@@ -137,6 +149,10 @@ pub struct ConstraintSystem<F: FftField> {
     /// EC point addition selector evaluations w over domain.d8
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub endomul_scalar8: E<F, D<F>>,
+    /// EC fixed base scalar multiplication selector evaluations over domain.d4,
+    /// if the circuit has fixed base scalar multiplication gates
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub fixed_base_mull4: Option<E<F, D<F>>>,
 
     // Constant polynomials
     // --------------------
@@ -321,33 +337,88 @@ pub enum GateError {
     Custom { row: usize, err: String },
 }
 
+/// Represents an error found when creating the lookup constraint system of a circuit
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LookupError {
+    /// The gates look up a table which is neither fixed nor passed with them
+    #[error("the gates look up the table of id {0}, which is missing")]
+    MissingTable(usize),
+    /// Several of the tables used by the gates or passed with them have the same id
+    #[error("several lookup tables have the id {0}")]
+    DuplicateTableId(usize),
+    /// A table has more columns than the lookups, which would overlap with the column of the table ids
+    #[error("the lookup table of id {0} has more columns than the lookups")]
+    TableTooWide(usize),
+}
+
+/// Represents an error found when creating a constraint system
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupError {
+    /// The circuit, or its lookup tables, do not fit in the evaluation domains of the field
+    #[error("the circuit is too large for the evaluation domains of the field")]
+    DomainCreation,
+    /// The lookup tables of the circuit are invalid
+    #[error(transparent)]
+    Lookup(#[from] LookupError),
+}
+
 impl<F: FftField + SquareRootField> LookupConstraintSystem<F> {
+    /// Creates the lookup constraint system of the gates, if they use lookups,
+    /// from the tables used by the gates and the tables `lookup_tables` passed with them,
+    /// which must have distinct ids (see [LookupTable]).
     pub fn create(
         gates: &[CircuitGate<F>],
-        lookup_tables: Vec<Vec<Vec<F>>>,
+        lookup_tables: Vec<LookupTable<F>>,
         domain: &EvaluationDomains<F>,
-    ) -> Option<Self> {
+    ) -> Result<Option<Self>, LookupError> {
         let lookup_info = LookupInfo::<F>::create();
         match lookup_info.lookup_used(gates) {
-            None => None,
+            None => Ok(None),
             Some(lookup_used) => {
                 let d1_size = domain.d1.size();
 
                 let (lookup_selectors, gate_lookup_tables) =
                     lookup_info.selector_polynomials_and_tables(domain, gates);
 
-                let lookup_tables: Vec<_> = gate_lookup_tables
+                let mut lookup_tables: Vec<_> = gate_lookup_tables
                     .into_iter()
-                    .chain(lookup_tables.into_iter())
+                    .chain(lookup_tables)
                     .collect();
-
-                if lookup_tables.len() > 1 {
-                    panic!("Multiple lookup tables are currently not supported");
+                lookup_tables.sort_by_key(|t| t.id);
+                if let Some(w) = lookup_tables.windows(2).find(|w| w[0].id == w[1].id) {
+                    return Err(LookupError::DuplicateTableId(w[0].id));
+                }
+                for lookup in lookup_info.by_row(gates).into_iter().flatten() {
+                    if !lookup_tables.iter().any(|t| t.id == lookup.table_id) {
+                        return Err(LookupError::MissingTable(lookup.table_id));
+                    }
                 }
 
-                let lookup_table = lookup_tables.into_iter().next().unwrap();
+                // concatenate the tables, with a column of ids unless there is only the table of id 0
+                let lookup_table: Vec<Vec<F>> = if lookup_tables.iter().all(|t| t.id == 0) {
+                    lookup_tables.remove(0).data
+                } else {
+                    let width = lookup_info.max_joint_size;
+                    let mut columns = vec![vec![]; width + 1];
+                    for table in lookup_tables {
+                        if table.data.len() > width {
+                            return Err(LookupError::TableTooWide(table.id));
+                        }
+                        let len = table.len();
+                        let mut data = table.data.into_iter();
+                        for col in &mut columns[..width] {
+                            match data.next() {
+                                Some(c) => col.extend(c),
+                                None => col.extend(std::iter::repeat(F::zero()).take(len)),
+                            }
+                        }
+                        columns[width]
+                            .extend(std::iter::repeat(F::from(table.id as u64)).take(len));
+                    }
+                    columns
+                };
 
-                // get the last entry in each column of each table
+                // get the last entry in each column of the table
                 let dummy_lookup_value: Vec<F> =
                     lookup_table.iter().map(|col| col[col.len() - 1]).collect();
 
@@ -366,7 +437,7 @@ impl<F: FftField + SquareRootField> LookupConstraintSystem<F> {
                 }
 
                 // generate the look up selector polynomials
-                Some(Self {
+                Ok(Some(Self {
                     lookup_selectors,
                     dummy_lookup_value,
                     lookup_table8,
@@ -374,28 +445,49 @@ impl<F: FftField + SquareRootField> LookupConstraintSystem<F> {
                     lookup_used,
                     max_lookups_per_row: lookup_info.max_per_row as usize,
                     max_joint_size: lookup_info.max_joint_size,
-                })
+                }))
             }
         }
     }
+
+    /// Returns the parameters of the lookup argument which the linearization depends on.
+    pub fn parameters(&self) -> LookupParameters<'_, F> {
+        LookupParameters {
+            dummy_lookup_value: &self.dummy_lookup_value,
+            max_lookups_per_row: self.max_lookups_per_row,
+        }
+    }
 }
 
 impl<F: FftField + SquareRootField> ConstraintSystem<F> {
-    /// creates a constraint system from a vector of gates ([CircuitGate]), some sponge parameters ([ArithmeticSpongeParams]), and the number of public inputs.
+    /// creates a constraint system from a vector of gates ([CircuitGate]), the lookup tables passed with them ([LookupTable]),
+    /// some sponge parameters ([ArithmeticSpongeParams]), and the number of public inputs.
+    /// Fails if the circuit is too large, or if its lookup tables are invalid (see [LookupError]).
     pub fn create(
         mut gates: Vec<CircuitGate<F>>,
-        lookup_tables: Vec<Vec<Vec<F>>>,
+        lookup_tables: Vec<LookupTable<F>>,
         fr_sponge_params: ArithmeticSpongeParams<F>,
         public: usize,
-    ) -> Option<Self> {
+    ) -> Result<Self, SetupError> {
         //~ 1. If the circuit is less than 2 gates, abort.
         // for some reason we need more than 1 gate for the circuit to work, see TODO below
         assert!(gates.len() > 1);
 
         //~ 2. Create a domain for the circuit. That is,
         //~    compute the smallest subgroup of the field that
-        //~    has order greater or equal to `n + ZK_ROWS` elements.
-        let domain = EvaluationDomains::<F>::create(gates.len() + ZK_ROWS as usize)?;
+        //~    has order greater or equal to `n + ZK_ROWS` elements,
+        //~    where `n` is the number of gates, or the total length of the lookup tables plus one if it is larger.
+        let table_rows = LookupInfo::<F>::create()
+            .gate_tables(&gates)
+            .iter()
+            .chain(&lookup_tables)
+            .map(LookupTable::len)
+            .sum::<usize>()
+            + 1;
+        let domain = EvaluationDomains::<F>::create(
+            std::cmp::max(gates.len(), table_rows) + ZK_ROWS as usize,
+        )
+        .ok_or(SetupError::DomainCreation)?;
         assert!(domain.d1.size > ZK_ROWS);
 
         //~ 3. Pad the circuit: add zero gates to reach the domain size.
@@ -468,6 +560,27 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         .interpolate();
         let ps8 = psm.evaluate_over_domain_by_ref(domain.d8);
 
+        // poseidon legacy gate
+        let has_poseidon_legacy_gate = gates
+            .iter()
+            .any(|gate| gate.typ == GateType::PoseidonLegacy);
+        let poseidon_legacym = if !has_poseidon_legacy_gate {
+            None
+        } else {
+            let m = E::<F, D<F>>::from_vec_and_domain(
+                gates
+                    .iter()
+                    .map(|gate| F::from((gate.typ == GateType::PoseidonLegacy) as u64))
+                    .collect(),
+                domain.d1,
+            )
+            .interpolate();
+            Some(m)
+        };
+        let poseidon_legacy8 = poseidon_legacym
+            .as_ref()
+            .map(|m| m.evaluate_over_domain_by_ref(domain.d8));
+
         // ECC gates
         let complete_addm = E::<F, D<F>>::from_vec_and_domain(
             gates
@@ -503,6 +616,22 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         .interpolate();
         let endomul_scalar8 = endomul_scalarm.evaluate_over_domain_by_ref(domain.d8);
 
+        let has_fixed_base_mul_gate = gates.iter().any(|gate| gate.typ == GateType::FixedBaseMul);
+        let fixed_base_mull4 = if !has_fixed_base_mul_gate {
+            None
+        } else {
+            let l4 = E::<F, D<F>>::from_vec_and_domain(
+                gates
+                    .iter()
+                    .map(|gate| F::from((gate.typ == GateType::FixedBaseMul) as u64))
+                    .collect(),
+                domain.d1,
+            )
+            .interpolate()
+            .evaluate_over_domain(domain.d4);
+            Some(l4)
+        };
+
         // double generic gate
         let genericm = E::<F, D<F>>::from_vec_and_domain(
             gates
@@ -573,7 +702,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         // ------
 
         let lookup_constraint_system =
-            LookupConstraintSystem::create(&gates, lookup_tables, &domain);
+            LookupConstraintSystem::create(&gates, lookup_tables, &domain)?;
 
         //
         // Constant polynomials
@@ -599,9 +728,10 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         // TODO: remove endo as a field
         let endo = F::zero();
 
-        Some(ConstraintSystem {
+        Ok(ConstraintSystem {
             chacha8,
             endomul_scalar8,
+            fixed_base_mull4,
             domain,
             public,
             sid,
@@ -613,6 +743,8 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
             coefficients8,
             ps8,
             psm,
+            poseidon_legacy8,
+            poseidon_legacym,
             complete_addl4,
             mull8,
             emull,
@@ -632,6 +764,14 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         })
     }
 
+    /// Returns the parameters of the lookup argument which the linearization depends on,
+    /// if the circuit uses lookups.
+    pub fn lookup_parameters(&self) -> Option<LookupParameters<'_, F>> {
+        self.lookup_constraint_system
+            .as_ref()
+            .map(LookupConstraintSystem::parameters)
+    }
+
     /// This function verifies the consistency of the wire
     /// assignements (witness) against the constraints
     ///     witness: wire assignement witness
@@ -687,11 +827,16 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
     }
 
     /// evaluate witness polynomials over domains
-    pub fn evaluate(&self, w: &[DP<F>; COLUMNS], z: &DP<F>) -> WitnessOverDomains<F> {
+    pub fn evaluate(
+        &self,
+        w: &[DP<F>; COLUMNS],
+        z: &DP<F>,
+        twiddles: &Twiddles<F>,
+    ) -> WitnessOverDomains<F> {
         // compute shifted witness polynomials
         let w8: [E<F, D<F>>; COLUMNS] =
-            array_init(|i| w[i].evaluate_over_domain_by_ref(self.domain.d8));
-        let z8 = z.evaluate_over_domain_by_ref(self.domain.d8);
+            array_init(|i| twiddles.evaluate_over_domain(&w[i], self.domain.d8));
+        let z8 = twiddles.evaluate_over_domain(z, self.domain.d8);
 
         let w4: [E<F, D<F>>; COLUMNS] = array_init(|i| {
             E::<F, D<F>>::from_vec_and_domain(
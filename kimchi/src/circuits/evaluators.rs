@@ -0,0 +1,1263 @@
+//! This module evaluates the constraints of the gates.
+//! It is generated by `cargo run --example codegen` (see [crate::circuits::codegen]), do not edit it.
+
+#![allow(clippy::all, dead_code, unused_variables)]
+
+use ark_ff::Field;
+
+/// Returns the field element whose little-endian 64-bit limbs are `limbs`.
+fn literal<F: Field>(limbs: &[u64]) -> F {
+    let base = F::from(1u128 << 64);
+    limbs
+        .iter()
+        .rev()
+        .fold(F::zero(), |acc, limb| acc * base + F::from(*limb))
+}
+
+/// Evaluates the 7 constraints of the gate `complete_add`.
+pub fn complete_add<F: Field>(curr: &[F], next: &[F], coeffs: &[F], endo_coefficient: F, mds: &[Vec<F>]) -> [F; 7] {
+    let r0 = curr[10];
+    let r1 = curr[2];
+    let r2 = curr[0];
+    let r3 = r1 - r2;
+    let r4 = r0 * r3;
+    let r5 = F::from(1u64);
+    let r6 = curr[7];
+    let r7 = r5 - r6;
+    let r8 = r4 - r7;
+    let r9 = r3 * r6;
+    let r10 = curr[8];
+    let r11 = r10.double();
+    let r12 = curr[1];
+    let r13 = r11 * r12;
+    let r14 = r2.square();
+    let r15 = r14.double();
+    let r16 = r13 - r15;
+    let r17 = r16 - r14;
+    let r18 = r6 * r17;
+    let r19 = r3 * r10;
+    let r20 = curr[3];
+    let r21 = r20 - r12;
+    let r22 = r19 - r21;
+    let r23 = r7 * r22;
+    let r24 = r18 + r23;
+    let r25 = r1 + r2;
+    let r26 = curr[4];
+    let r27 = r25 + r26;
+    let r28 = r10.square();
+    let r29 = r27 - r28;
+    let r30 = r2 - r26;
+    let r31 = r10 * r30;
+    let r32 = r31 - r12;
+    let r33 = curr[5];
+    let r34 = r32 - r33;
+    let r35 = curr[6];
+    let r36 = r6 - r35;
+    let r37 = r21 * r36;
+    let r38 = curr[9];
+    let r39 = r21 * r38;
+    let r40 = r39 - r35;
+    [r8, r9, r24, r29, r34, r37, r40]
+}
+
+/// Evaluates the 21 constraints of the gate `varbasemul`.
+pub fn varbasemul<F: Field>(curr: &[F], next: &[F], coeffs: &[F], endo_coefficient: F, mds: &[Vec<F>]) -> [F; 21] {
+    let r0 = curr[5];
+    let r1 = next[6];
+    let r2 = next[5];
+    let r3 = next[4];
+    let r4 = next[3];
+    let r5 = next[2];
+    let r6 = curr[4];
+    let r7 = r6.double();
+    let r8 = r5 + r7;
+    let r9 = r8.double();
+    let r10 = r4 + r9;
+    let r11 = r10.double();
+    let r12 = r3 + r11;
+    let r13 = r12.double();
+    let r14 = r2 + r13;
+    let r15 = r14.double();
+    let r16 = r1 + r15;
+    let r17 = r0 - r16;
+    let r18 = r5.square();
+    let r19 = r18 - r5;
+    let r20 = curr[2];
+    let r21 = curr[0];
+    let r22 = r20 - r21;
+    let r23 = next[7];
+    let r24 = r22 * r23;
+    let r25 = curr[3];
+    let r26 = r5.double();
+    let r27 = F::from(1u64);
+    let r28 = r26 - r27;
+    let r29 = curr[1];
+    let r30 = r28 * r29;
+    let r31 = r25 - r30;
+    let r32 = r24 - r31;
+    let r33 = r25.double();
+    let r34 = r23.square();
+    let r35 = r34 - r20;
+    let r36 = r35 - r21;
+    let r37 = r20 - r36;
+    let r38 = r23 * r37;
+    let r39 = r33 - r38;
+    let r40 = r39.square();
+    let r41 = r37.square();
+    let r42 = curr[7];
+    let r43 = r42 - r21;
+    let r44 = r34 + r43;
+    let r45 = r41 * r44;
+    let r46 = r40 - r45;
+    let r47 = curr[8];
+    let r48 = r25 + r47;
+    let r49 = r37 * r48;
+    let r50 = r20 - r42;
+    let r51 = r39 * r50;
+    let r52 = r49 - r51;
+    let r53 = r4.square();
+    let r54 = r53 - r4;
+    let r55 = next[8];
+    let r56 = r43 * r55;
+    let r57 = r4.double();
+    let r58 = r57 - r27;
+    let r59 = r29 * r58;
+    let r60 = r47 - r59;
+    let r61 = r56 - r60;
+    let r62 = r47.double();
+    let r63 = r55.square();
+    let r64 = r63 - r42;
+    let r65 = r64 - r21;
+    let r66 = r42 - r65;
+    let r67 = r55 * r66;
+    let r68 = r62 - r67;
+    let r69 = r68.square();
+    let r70 = r66.square();
+    let r71 = curr[9];
+    let r72 = r71 - r21;
+    let r73 = r63 + r72;
+    let r74 = r70 * r73;
+    let r75 = r69 - r74;
+    let r76 = curr[10];
+    let r77 = r47 + r76;
+    let r78 = r66 * r77;
+    let r79 = r42 - r71;
+    let r80 = r68 * r79;
+    let r81 = r78 - r80;
+    let r82 = r3.square();
+    let r83 = r82 - r3;
+    let r84 = next[9];
+    let r85 = r72 * r84;
+    let r86 = r3.double();
+    let r87 = r86 - r27;
+    let r88 = r29 * r87;
+    let r89 = r76 - r88;
+    let r90 = r85 - r89;
+    let r91 = r76.double();
+    let r92 = r84.square();
+    let r93 = r92 - r71;
+    let r94 = r93 - r21;
+    let r95 = r71 - r94;
+    let r96 = r84 * r95;
+    let r97 = r91 - r96;
+    let r98 = r97.square();
+    let r99 = r95.square();
+    let r100 = curr[11];
+    let r101 = r100 - r21;
+    let r102 = r92 + r101;
+    let r103 = r99 * r102;
+    let r104 = r98 - r103;
+    let r105 = curr[12];
+    let r106 = r76 + r105;
+    let r107 = r95 * r106;
+    let r108 = r71 - r100;
+    let r109 = r97 * r108;
+    let r110 = r107 - r109;
+    let r111 = r2.square();
+    let r112 = r111 - r2;
+    let r113 = next[10];
+    let r114 = r101 * r113;
+    let r115 = r2.double();
+    let r116 = r115 - r27;
+    let r117 = r29 * r116;
+    let r118 = r105 - r117;
+    let r119 = r114 - r118;
+    let r120 = r105.double();
+    let r121 = r113.square();
+    let r122 = r121 - r100;
+    let r123 = r122 - r21;
+    let r124 = r100 - r123;
+    let r125 = r113 * r124;
+    let r126 = r120 - r125;
+    let r127 = r126.square();
+    let r128 = r124.square();
+    let r129 = curr[13];
+    let r130 = r129 - r21;
+    let r131 = r121 + r130;
+    let r132 = r128 * r131;
+    let r133 = r127 - r132;
+    let r134 = curr[14];
+    let r135 = r105 + r134;
+    let r136 = r124 * r135;
+    let r137 = r100 - r129;
+    let r138 = r126 * r137;
+    let r139 = r136 - r138;
+    let r140 = r1.square();
+    let r141 = r140 - r1;
+    let r142 = next[11];
+    let r143 = r130 * r142;
+    let r144 = r1.double();
+    let r145 = r144 - r27;
+    let r146 = r29 * r145;
+    let r147 = r134 - r146;
+    let r148 = r143 - r147;
+    let r149 = r134.double();
+    let r150 = r142.square();
+    let r151 = r150 - r129;
+    let r152 = r151 - r21;
+    let r153 = r129 - r152;
+    let r154 = r142 * r153;
+    let r155 = r149 - r154;
+    let r156 = r155.square();
+    let r157 = r153.square();
+    let r158 = next[0];
+    let r159 = r158 - r21;
+    let r160 = r150 + r159;
+    let r161 = r157 * r160;
+    let r162 = r156 - r161;
+    let r163 = next[1];
+    let r164 = r134 + r163;
+    let r165 = r153 * r164;
+    let r166 = r129 - r158;
+    let r167 = r155 * r166;
+    let r168 = r165 - r167;
+    [r17, r19, r32, r46, r52, r54, r61, r75, r81, r83, r90, r104, r110, r112, r119, r133, r139, r141, r148, r162, r168]
+}
+
+/// Evaluates the 11 constraints of the gate `endosclmul`.
+pub fn endosclmul<F: Field>(curr: &[F], next: &[F], coeffs: &[F], endo_coefficient: F, mds: &[Vec<F>]) -> [F; 11] {
+    let r0 = curr[11];
+    let r1 = r0.square();
+    let r2 = r1 - r0;
+    let r3 = curr[12];
+    let r4 = r3.square();
+    let r5 = r4 - r3;
+    let r6 = curr[13];
+    let r7 = r6.square();
+    let r8 = r7 - r6;
+    let r9 = curr[14];
+    let r10 = r9.square();
+    let r11 = r10 - r9;
+    let r12 = F::from(1u64);
+    let r13 = endo_coefficient;
+    let r14 = r13 - r12;
+    let r15 = r0 * r14;
+    let r16 = r12 + r15;
+    let r17 = curr[0];
+    let r18 = r16 * r17;
+    let r19 = curr[4];
+    let r20 = r18 - r19;
+    let r21 = curr[9];
+    let r22 = r20 * r21;
+    let r23 = r3.double();
+    let r24 = r23 - r12;
+    let r25 = curr[1];
+    let r26 = r24 * r25;
+    let r27 = curr[5];
+    let r28 = r26 - r27;
+    let r29 = r22 - r28;
+    let r30 = r19.double();
+    let r31 = r21.square();
+    let r32 = r30 - r31;
+    let r33 = r18 + r32;
+    let r34 = curr[7];
+    let r35 = r19 - r34;
+    let r36 = r21 * r35;
+    let r37 = curr[8];
+    let r38 = r27 + r37;
+    let r39 = r36 + r38;
+    let r40 = r33 * r39;
+    let r41 = r27.double();
+    let r42 = r35 * r41;
+    let r43 = r40 - r42;
+    let r44 = r38.square();
+    let r45 = r35.square();
+    let r46 = r31 - r18;
+    let r47 = r34 + r46;
+    let r48 = r45 * r47;
+    let r49 = r44 - r48;
+    let r50 = r6 * r14;
+    let r51 = r12 + r50;
+    let r52 = r17 * r51;
+    let r53 = r52 - r34;
+    let r54 = curr[10];
+    let r55 = r53 * r54;
+    let r56 = r9.double();
+    let r57 = r56 - r12;
+    let r58 = r25 * r57;
+    let r59 = r58 - r37;
+    let r60 = r55 - r59;
+    let r61 = r34.double();
+    let r62 = r54.square();
+    let r63 = r61 - r62;
+    let r64 = r52 + r63;
+    let r65 = next[4];
+    let r66 = r34 - r65;
+    let r67 = r54 * r66;
+    let r68 = next[5];
+    let r69 = r37 + r68;
+    let r70 = r67 + r69;
+    let r71 = r64 * r70;
+    let r72 = r37.double();
+    let r73 = r66 * r72;
+    let r74 = r71 - r73;
+    let r75 = r69.square();
+    let r76 = r66.square();
+    let r77 = r62 - r52;
+    let r78 = r65 + r77;
+    let r79 = r76 * r78;
+    let r80 = r75 - r79;
+    let r81 = curr[6];
+    let r82 = r81.double();
+    let r83 = r0 + r82;
+    let r84 = r83.double();
+    let r85 = r3 + r84;
+    let r86 = r85.double();
+    let r87 = r6 + r86;
+    let r88 = r87.double();
+    let r89 = r9 + r88;
+    let r90 = next[6];
+    let r91 = r89 - r90;
+    [r2, r5, r8, r11, r29, r43, r49, r60, r74, r80, r91]
+}
+
+/// Evaluates the 11 constraints of the gate `endomul_scalar`.
+pub fn endomul_scalar<F: Field>(curr: &[F], next: &[F], coeffs: &[F], endo_coefficient: F, mds: &[Vec<F>]) -> [F; 11] {
+    let r0 = curr[0];
+    let r1 = r0.double();
+    let r2 = r1.double();
+    let r3 = curr[6];
+    let r4 = r2 + r3;
+    let r5 = r4.double();
+    let r6 = r5.double();
+    let r7 = curr[7];
+    let r8 = r6 + r7;
+    let r9 = r8.double();
+    let r10 = r9.double();
+    let r11 = curr[8];
+    let r12 = r10 + r11;
+    let r13 = r12.double();
+    let r14 = r13.double();
+    let r15 = curr[9];
+    let r16 = r14 + r15;
+    let r17 = r16.double();
+    let r18 = r17.double();
+    let r19 = curr[10];
+    let r20 = r18 + r19;
+    let r21 = r20.double();
+    let r22 = r21.double();
+    let r23 = curr[11];
+    let r24 = r22 + r23;
+    let r25 = r24.double();
+    let r26 = r25.double();
+    let r27 = curr[12];
+    let r28 = r26 + r27;
+    let r29 = r28.double();
+    let r30 = r29.double();
+    let r31 = curr[13];
+    let r32 = r30 + r31;
+    let r33 = curr[1];
+    let r34 = r32 - r33;
+    let r35 = curr[2];
+    let r36 = r35.double();
+    let r37 = F::from(2u64) / F::from(3u64);
+    let r38 = r3 * r37;
+    let r39 = -F::from(5u64) / F::from(2u64);
+    let r40 = r38 + r39;
+    let r41 = r3 * r40;
+    let r42 = F::from(11u64) / F::from(6u64);
+    let r43 = r41 + r42;
+    let r44 = r3 * r43;
+    let r45 = r36 + r44;
+    let r46 = r45.double();
+    let r47 = r7 * r37;
+    let r48 = r39 + r47;
+    let r49 = r7 * r48;
+    let r50 = r42 + r49;
+    let r51 = r7 * r50;
+    let r52 = r46 + r51;
+    let r53 = r52.double();
+    let r54 = r11 * r37;
+    let r55 = r39 + r54;
+    let r56 = r11 * r55;
+    let r57 = r42 + r56;
+    let r58 = r11 * r57;
+    let r59 = r53 + r58;
+    let r60 = r59.double();
+    let r61 = r15 * r37;
+    let r62 = r39 + r61;
+    let r63 = r15 * r62;
+    let r64 = r42 + r63;
+    let r65 = r15 * r64;
+    let r66 = r60 + r65;
+    let r67 = r66.double();
+    let r68 = r19 * r37;
+    let r69 = r39 + r68;
+    let r70 = r19 * r69;
+    let r71 = r42 + r70;
+    let r72 = r19 * r71;
+    let r73 = r67 + r72;
+    let r74 = r73.double();
+    let r75 = r23 * r37;
+    let r76 = r39 + r75;
+    let r77 = r23 * r76;
+    let r78 = r42 + r77;
+    let r79 = r23 * r78;
+    let r80 = r74 + r79;
+    let r81 = r80.double();
+    let r82 = r27 * r37;
+    let r83 = r39 + r82;
+    let r84 = r27 * r83;
+    let r85 = r42 + r84;
+    let r86 = r27 * r85;
+    let r87 = r81 + r86;
+    let r88 = r87.double();
+    let r89 = r31 * r37;
+    let r90 = r39 + r89;
+    let r91 = r31 * r90;
+    let r92 = r42 + r91;
+    let r93 = r31 * r92;
+    let r94 = r88 + r93;
+    let r95 = curr[4];
+    let r96 = r94 - r95;
+    let r97 = curr[3];
+    let r98 = r97.double();
+    let r99 = -F::from(1u64);
+    let r100 = r3 * r99;
+    let r101 = F::from(3u64);
+    let r102 = r100 + r101;
+    let r103 = r3 * r102;
+    let r104 = r99 + r103;
+    let r105 = r44 + r104;
+    let r106 = r98 + r105;
+    let r107 = r106.double();
+    let r108 = r7 * r99;
+    let r109 = r101 + r108;
+    let r110 = r7 * r109;
+    let r111 = r99 + r110;
+    let r112 = r51 + r111;
+    let r113 = r107 + r112;
+    let r114 = r113.double();
+    let r115 = r11 * r99;
+    let r116 = r101 + r115;
+    let r117 = r11 * r116;
+    let r118 = r99 + r117;
+    let r119 = r58 + r118;
+    let r120 = r114 + r119;
+    let r121 = r120.double();
+    let r122 = r15 * r99;
+    let r123 = r101 + r122;
+    let r124 = r15 * r123;
+    let r125 = r99 + r124;
+    let r126 = r65 + r125;
+    let r127 = r121 + r126;
+    let r128 = r127.double();
+    let r129 = r19 * r99;
+    let r130 = r101 + r129;
+    let r131 = r19 * r130;
+    let r132 = r99 + r131;
+    let r133 = r72 + r132;
+    let r134 = r128 + r133;
+    let r135 = r134.double();
+    let r136 = r23 * r99;
+    let r137 = r101 + r136;
+    let r138 = r23 * r137;
+    let r139 = r99 + r138;
+    let r140 = r79 + r139;
+    let r141 = r135 + r140;
+    let r142 = r141.double();
+    let r143 = r27 * r99;
+    let r144 = r101 + r143;
+    let r145 = r27 * r144;
+    let r146 = r99 + r145;
+    let r147 = r86 + r146;
+    let r148 = r142 + r147;
+    let r149 = r148.double();
+    let r150 = r31 * r99;
+    let r151 = r101 + r150;
+    let r152 = r31 * r151;
+    let r153 = r99 + r152;
+    let r154 = r93 + r153;
+    let r155 = r149 + r154;
+    let r156 = curr[5];
+    let r157 = r155 - r156;
+    let r158 = -F::from(6u64);
+    let r159 = r3 + r158;
+    let r160 = r3 * r159;
+    let r161 = F::from(11u64);
+    let r162 = r160 + r161;
+    let r163 = r3 * r162;
+    let r164 = r158 + r163;
+    let r165 = r3 * r164;
+    let r166 = r7 + r158;
+    let r167 = r7 * r166;
+    let r168 = r161 + r167;
+    let r169 = r7 * r168;
+    let r170 = r158 + r169;
+    let r171 = r7 * r170;
+    let r172 = r11 + r158;
+    let r173 = r11 * r172;
+    let r174 = r161 + r173;
+    let r175 = r11 * r174;
+    let r176 = r158 + r175;
+    let r177 = r11 * r176;
+    let r178 = r15 + r158;
+    let r179 = r15 * r178;
+    let r180 = r161 + r179;
+    let r181 = r15 * r180;
+    let r182 = r158 + r181;
+    let r183 = r15 * r182;
+    let r184 = r19 + r158;
+    let r185 = r19 * r184;
+    let r186 = r161 + r185;
+    let r187 = r19 * r186;
+    let r188 = r158 + r187;
+    let r189 = r19 * r188;
+    let r190 = r23 + r158;
+    let r191 = r23 * r190;
+    let r192 = r161 + r191;
+    let r193 = r23 * r192;
+    let r194 = r158 + r193;
+    let r195 = r23 * r194;
+    let r196 = r27 + r158;
+    let r197 = r27 * r196;
+    let r198 = r161 + r197;
+    let r199 = r27 * r198;
+    let r200 = r158 + r199;
+    let r201 = r27 * r200;
+    let r202 = r31 + r158;
+    let r203 = r31 * r202;
+    let r204 = r161 + r203;
+    let r205 = r31 * r204;
+    let r206 = r158 + r205;
+    let r207 = r31 * r206;
+    [r34, r96, r157, r165, r171, r177, r183, r189, r195, r201, r207]
+}
+
+/// Evaluates the 7 constraints of the gate `fixed_base_mul`.
+pub fn fixed_base_mul<F: Field>(curr: &[F], next: &[F], coeffs: &[F], endo_coefficient: F, mds: &[Vec<F>]) -> [F; 7] {
+    let r0 = next[3];
+    let r1 = curr[3];
+    let r2 = r0 - r1;
+    let r3 = F::from(1u64);
+    let r4 = r2 + r3;
+    let r5 = next[4];
+    let r6 = curr[4];
+    let r7 = r5 - r6;
+    let r8 = next[2];
+    let r9 = curr[2];
+    let r10 = r6 * r9;
+    let r11 = curr[5];
+    let r12 = r10 + r11;
+    let r13 = r8 - r12;
+    let r14 = curr[6];
+    let r15 = curr[0];
+    let r16 = r14 - r15;
+    let r17 = curr[9];
+    let r18 = r16 * r17;
+    let r19 = r18 - r3;
+    let r20 = curr[8];
+    let r21 = r16 * r20;
+    let r22 = curr[7];
+    let r23 = curr[1];
+    let r24 = r22 - r23;
+    let r25 = r21 - r24;
+    let r26 = r20.square();
+    let r27 = r26 - r15;
+    let r28 = r27 - r14;
+    let r29 = next[0];
+    let r30 = r28 - r29;
+    let r31 = r15 - r29;
+    let r32 = r20 * r31;
+    let r33 = r32 - r23;
+    let r34 = next[1];
+    let r35 = r33 - r34;
+    [r4, r7, r13, r19, r25, r30, r35]
+}
+
+/// Evaluates the 15 constraints of the gate `poseidon`.
+pub fn poseidon<F: Field>(curr: &[F], next: &[F], coeffs: &[F], endo_coefficient: F, mds: &[Vec<F>]) -> [F; 15] {
+    let r0 = curr[6];
+    let r1 = coeffs[0];
+    let r2 = mds[0][0];
+    let r3 = curr[0];
+    let r4 = r3.square();
+    let r5 = r4 * r3;
+    let r6 = r5.square();
+    let r7 = r6 * r3;
+    let r8 = r2 * r7;
+    let r9 = r1 + r8;
+    let r10 = mds[0][1];
+    let r11 = curr[1];
+    let r12 = r11.square();
+    let r13 = r12 * r11;
+    let r14 = r13.square();
+    let r15 = r14 * r11;
+    let r16 = r10 * r15;
+    let r17 = r9 + r16;
+    let r18 = mds[0][2];
+    let r19 = curr[2];
+    let r20 = r19.square();
+    let r21 = r20 * r19;
+    let r22 = r21.square();
+    let r23 = r22 * r19;
+    let r24 = r18 * r23;
+    let r25 = r17 + r24;
+    let r26 = r0 - r25;
+    let r27 = curr[7];
+    let r28 = coeffs[1];
+    let r29 = mds[1][0];
+    let r30 = r7 * r29;
+    let r31 = r28 + r30;
+    let r32 = mds[1][1];
+    let r33 = r15 * r32;
+    let r34 = r31 + r33;
+    let r35 = mds[1][2];
+    let r36 = r23 * r35;
+    let r37 = r34 + r36;
+    let r38 = r27 - r37;
+    let r39 = curr[8];
+    let r40 = coeffs[2];
+    let r41 = mds[2][0];
+    let r42 = r7 * r41;
+    let r43 = r40 + r42;
+    let r44 = mds[2][1];
+    let r45 = r15 * r44;
+    let r46 = r43 + r45;
+    let r47 = mds[2][2];
+    let r48 = r23 * r47;
+    let r49 = r46 + r48;
+    let r50 = r39 - r49;
+    let r51 = curr[9];
+    let r52 = coeffs[3];
+    let r53 = r0.square();
+    let r54 = r53 * r0;
+    let r55 = r54.square();
+    let r56 = r55 * r0;
+    let r57 = r2 * r56;
+    let r58 = r52 + r57;
+    let r59 = r27.square();
+    let r60 = r59 * r27;
+    let r61 = r60.square();
+    let r62 = r61 * r27;
+    let r63 = r10 * r62;
+    let r64 = r58 + r63;
+    let r65 = r39.square();
+    let r66 = r65 * r39;
+    let r67 = r66.square();
+    let r68 = r67 * r39;
+    let r69 = r18 * r68;
+    let r70 = r64 + r69;
+    let r71 = r51 - r70;
+    let r72 = curr[10];
+    let r73 = coeffs[4];
+    let r74 = r29 * r56;
+    let r75 = r73 + r74;
+    let r76 = r32 * r62;
+    let r77 = r75 + r76;
+    let r78 = r35 * r68;
+    let r79 = r77 + r78;
+    let r80 = r72 - r79;
+    let r81 = curr[11];
+    let r82 = coeffs[5];
+    let r83 = r41 * r56;
+    let r84 = r82 + r83;
+    let r85 = r44 * r62;
+    let r86 = r84 + r85;
+    let r87 = r47 * r68;
+    let r88 = r86 + r87;
+    let r89 = r81 - r88;
+    let r90 = curr[12];
+    let r91 = coeffs[6];
+    let r92 = r51.square();
+    let r93 = r92 * r51;
+    let r94 = r93.square();
+    let r95 = r94 * r51;
+    let r96 = r2 * r95;
+    let r97 = r91 + r96;
+    let r98 = r72.square();
+    let r99 = r98 * r72;
+    let r100 = r99.square();
+    let r101 = r100 * r72;
+    let r102 = r10 * r101;
+    let r103 = r97 + r102;
+    let r104 = r81.square();
+    let r105 = r104 * r81;
+    let r106 = r105.square();
+    let r107 = r106 * r81;
+    let r108 = r18 * r107;
+    let r109 = r103 + r108;
+    let r110 = r90 - r109;
+    let r111 = curr[13];
+    let r112 = coeffs[7];
+    let r113 = r29 * r95;
+    let r114 = r112 + r113;
+    let r115 = r32 * r101;
+    let r116 = r114 + r115;
+    let r117 = r35 * r107;
+    let r118 = r116 + r117;
+    let r119 = r111 - r118;
+    let r120 = curr[14];
+    let r121 = coeffs[8];
+    let r122 = r41 * r95;
+    let r123 = r121 + r122;
+    let r124 = r44 * r101;
+    let r125 = r123 + r124;
+    let r126 = r47 * r107;
+    let r127 = r125 + r126;
+    let r128 = r120 - r127;
+    let r129 = curr[3];
+    let r130 = coeffs[9];
+    let r131 = r90.square();
+    let r132 = r131 * r90;
+    let r133 = r132.square();
+    let r134 = r133 * r90;
+    let r135 = r2 * r134;
+    let r136 = r130 + r135;
+    let r137 = r111.square();
+    let r138 = r137 * r111;
+    let r139 = r138.square();
+    let r140 = r139 * r111;
+    let r141 = r10 * r140;
+    let r142 = r136 + r141;
+    let r143 = r120.square();
+    let r144 = r143 * r120;
+    let r145 = r144.square();
+    let r146 = r145 * r120;
+    let r147 = r18 * r146;
+    let r148 = r142 + r147;
+    let r149 = r129 - r148;
+    let r150 = curr[4];
+    let r151 = coeffs[10];
+    let r152 = r29 * r134;
+    let r153 = r151 + r152;
+    let r154 = r32 * r140;
+    let r155 = r153 + r154;
+    let r156 = r35 * r146;
+    let r157 = r155 + r156;
+    let r158 = r150 - r157;
+    let r159 = curr[5];
+    let r160 = coeffs[11];
+    let r161 = r41 * r134;
+    let r162 = r160 + r161;
+    let r163 = r44 * r140;
+    let r164 = r162 + r163;
+    let r165 = r47 * r146;
+    let r166 = r164 + r165;
+    let r167 = r159 - r166;
+    let r168 = next[0];
+    let r169 = coeffs[12];
+    let r170 = r129.square();
+    let r171 = r170 * r129;
+    let r172 = r171.square();
+    let r173 = r172 * r129;
+    let r174 = r2 * r173;
+    let r175 = r169 + r174;
+    let r176 = r150.square();
+    let r177 = r176 * r150;
+    let r178 = r177.square();
+    let r179 = r178 * r150;
+    let r180 = r10 * r179;
+    let r181 = r175 + r180;
+    let r182 = r159.square();
+    let r183 = r182 * r159;
+    let r184 = r183.square();
+    let r185 = r184 * r159;
+    let r186 = r18 * r185;
+    let r187 = r181 + r186;
+    let r188 = r168 - r187;
+    let r189 = next[1];
+    let r190 = coeffs[13];
+    let r191 = r29 * r173;
+    let r192 = r190 + r191;
+    let r193 = r32 * r179;
+    let r194 = r192 + r193;
+    let r195 = r35 * r185;
+    let r196 = r194 + r195;
+    let r197 = r189 - r196;
+    let r198 = next[2];
+    let r199 = coeffs[14];
+    let r200 = r41 * r173;
+    let r201 = r199 + r200;
+    let r202 = r44 * r179;
+    let r203 = r201 + r202;
+    let r204 = r47 * r185;
+    let r205 = r203 + r204;
+    let r206 = r198 - r205;
+    [r26, r38, r50, r71, r80, r89, r110, r119, r128, r149, r158, r167, r188, r197, r206]
+}
+
+/// Evaluates the 6 constraints of the gate `poseidon_legacy`.
+pub fn poseidon_legacy<F: Field>(curr: &[F], next: &[F], coeffs: &[F], endo_coefficient: F, mds: &[Vec<F>]) -> [F; 6] {
+    let r0 = curr[3];
+    let r1 = coeffs[9];
+    let r2 = coeffs[0];
+    let r3 = curr[0];
+    let r4 = r3.square();
+    let r5 = r4.square();
+    let r6 = r5 * r3;
+    let r7 = r2 * r6;
+    let r8 = r1 + r7;
+    let r9 = coeffs[1];
+    let r10 = curr[1];
+    let r11 = r10.square();
+    let r12 = r11.square();
+    let r13 = r12 * r10;
+    let r14 = r9 * r13;
+    let r15 = r8 + r14;
+    let r16 = coeffs[2];
+    let r17 = curr[2];
+    let r18 = r17.square();
+    let r19 = r18.square();
+    let r20 = r19 * r17;
+    let r21 = r16 * r20;
+    let r22 = r15 + r21;
+    let r23 = r0 - r22;
+    let r24 = curr[4];
+    let r25 = coeffs[10];
+    let r26 = coeffs[3];
+    let r27 = r6 * r26;
+    let r28 = r25 + r27;
+    let r29 = coeffs[4];
+    let r30 = r13 * r29;
+    let r31 = r28 + r30;
+    let r32 = coeffs[5];
+    let r33 = r20 * r32;
+    let r34 = r31 + r33;
+    let r35 = r24 - r34;
+    let r36 = curr[5];
+    let r37 = coeffs[11];
+    let r38 = coeffs[6];
+    let r39 = r6 * r38;
+    let r40 = r37 + r39;
+    let r41 = coeffs[7];
+    let r42 = r13 * r41;
+    let r43 = r40 + r42;
+    let r44 = coeffs[8];
+    let r45 = r20 * r44;
+    let r46 = r43 + r45;
+    let r47 = r36 - r46;
+    let r48 = next[0];
+    let r49 = coeffs[12];
+    let r50 = r0.square();
+    let r51 = r50.square();
+    let r52 = r51 * r0;
+    let r53 = r2 * r52;
+    let r54 = r49 + r53;
+    let r55 = r24.square();
+    let r56 = r55.square();
+    let r57 = r56 * r24;
+    let r58 = r9 * r57;
+    let r59 = r54 + r58;
+    let r60 = r36.square();
+    let r61 = r60.square();
+    let r62 = r61 * r36;
+    let r63 = r16 * r62;
+    let r64 = r59 + r63;
+    let r65 = r48 - r64;
+    let r66 = next[1];
+    let r67 = coeffs[13];
+    let r68 = r26 * r52;
+    let r69 = r67 + r68;
+    let r70 = r29 * r57;
+    let r71 = r69 + r70;
+    let r72 = r32 * r62;
+    let r73 = r71 + r72;
+    let r74 = r66 - r73;
+    let r75 = next[2];
+    let r76 = coeffs[14];
+    let r77 = r38 * r52;
+    let r78 = r76 + r77;
+    let r79 = r41 * r57;
+    let r80 = r78 + r79;
+    let r81 = r44 * r62;
+    let r82 = r80 + r81;
+    let r83 = r75 - r82;
+    [r23, r35, r47, r65, r74, r83]
+}
+
+/// Evaluates the 5 constraints of the gate `chacha0`.
+pub fn chacha0<F: Field>(curr: &[F], next: &[F], coeffs: &[F], endo_coefficient: F, mds: &[Vec<F>]) -> [F; 5] {
+    let r0 = next[2];
+    let r1 = r0.square();
+    let r2 = r1 - r0;
+    let r3 = curr[7];
+    let r4 = F::from(16u64);
+    let r5 = curr[8];
+    let r6 = r4 * r5;
+    let r7 = r3 + r6;
+    let r8 = F::from(256u64);
+    let r9 = curr[9];
+    let r10 = r8 * r9;
+    let r11 = r7 + r10;
+    let r12 = F::from(4096u64);
+    let r13 = curr[10];
+    let r14 = r12 * r13;
+    let r15 = r11 + r14;
+    let r16 = F::from(65536u64);
+    let r17 = next[7];
+    let r18 = r16 * r17;
+    let r19 = r15 + r18;
+    let r20 = F::from(1048576u64);
+    let r21 = next[8];
+    let r22 = r20 * r21;
+    let r23 = r19 + r22;
+    let r24 = F::from(16777216u64);
+    let r25 = next[9];
+    let r26 = r24 * r25;
+    let r27 = r23 + r26;
+    let r28 = F::from(268435456u64);
+    let r29 = next[10];
+    let r30 = r28 * r29;
+    let r31 = r27 + r30;
+    let r32 = next[0];
+    let r33 = r31 - r32;
+    let r34 = F::from(4294967296u64);
+    let r35 = r0 * r34;
+    let r36 = r32 + r35;
+    let r37 = curr[0];
+    let r38 = curr[2];
+    let r39 = r37 + r38;
+    let r40 = r36 - r39;
+    let r41 = curr[11];
+    let r42 = curr[12];
+    let r43 = r4 * r42;
+    let r44 = r41 + r43;
+    let r45 = curr[13];
+    let r46 = r8 * r45;
+    let r47 = r44 + r46;
+    let r48 = curr[14];
+    let r49 = r12 * r48;
+    let r50 = r47 + r49;
+    let r51 = next[11];
+    let r52 = r16 * r51;
+    let r53 = r50 + r52;
+    let r54 = next[12];
+    let r55 = r20 * r54;
+    let r56 = r53 + r55;
+    let r57 = next[13];
+    let r58 = r24 * r57;
+    let r59 = r56 + r58;
+    let r60 = next[14];
+    let r61 = r28 * r60;
+    let r62 = r59 + r61;
+    let r63 = curr[1];
+    let r64 = r62 - r63;
+    let r65 = next[3];
+    let r66 = next[4];
+    let r67 = r4 * r66;
+    let r68 = r65 + r67;
+    let r69 = next[5];
+    let r70 = r8 * r69;
+    let r71 = r68 + r70;
+    let r72 = next[6];
+    let r73 = r12 * r72;
+    let r74 = r71 + r73;
+    let r75 = curr[3];
+    let r76 = r16 * r75;
+    let r77 = r74 + r76;
+    let r78 = curr[4];
+    let r79 = r20 * r78;
+    let r80 = r77 + r79;
+    let r81 = curr[5];
+    let r82 = r24 * r81;
+    let r83 = r80 + r82;
+    let r84 = curr[6];
+    let r85 = r28 * r84;
+    let r86 = r83 + r85;
+    let r87 = next[1];
+    let r88 = r86 - r87;
+    [r2, r33, r40, r64, r88]
+}
+
+/// Evaluates the 5 constraints of the gate `chacha1`.
+pub fn chacha1<F: Field>(curr: &[F], next: &[F], coeffs: &[F], endo_coefficient: F, mds: &[Vec<F>]) -> [F; 5] {
+    let r0 = next[2];
+    let r1 = r0.square();
+    let r2 = r1 - r0;
+    let r3 = curr[7];
+    let r4 = F::from(16u64);
+    let r5 = curr[8];
+    let r6 = r4 * r5;
+    let r7 = r3 + r6;
+    let r8 = F::from(256u64);
+    let r9 = curr[9];
+    let r10 = r8 * r9;
+    let r11 = r7 + r10;
+    let r12 = F::from(4096u64);
+    let r13 = curr[10];
+    let r14 = r12 * r13;
+    let r15 = r11 + r14;
+    let r16 = F::from(65536u64);
+    let r17 = next[7];
+    let r18 = r16 * r17;
+    let r19 = r15 + r18;
+    let r20 = F::from(1048576u64);
+    let r21 = next[8];
+    let r22 = r20 * r21;
+    let r23 = r19 + r22;
+    let r24 = F::from(16777216u64);
+    let r25 = next[9];
+    let r26 = r24 * r25;
+    let r27 = r23 + r26;
+    let r28 = F::from(268435456u64);
+    let r29 = next[10];
+    let r30 = r28 * r29;
+    let r31 = r27 + r30;
+    let r32 = next[0];
+    let r33 = r31 - r32;
+    let r34 = F::from(4294967296u64);
+    let r35 = r0 * r34;
+    let r36 = r32 + r35;
+    let r37 = curr[0];
+    let r38 = curr[2];
+    let r39 = r37 + r38;
+    let r40 = r36 - r39;
+    let r41 = curr[11];
+    let r42 = curr[12];
+    let r43 = r4 * r42;
+    let r44 = r41 + r43;
+    let r45 = curr[13];
+    let r46 = r8 * r45;
+    let r47 = r44 + r46;
+    let r48 = curr[14];
+    let r49 = r12 * r48;
+    let r50 = r47 + r49;
+    let r51 = next[11];
+    let r52 = r16 * r51;
+    let r53 = r50 + r52;
+    let r54 = next[12];
+    let r55 = r20 * r54;
+    let r56 = r53 + r55;
+    let r57 = next[13];
+    let r58 = r24 * r57;
+    let r59 = r56 + r58;
+    let r60 = next[14];
+    let r61 = r28 * r60;
+    let r62 = r59 + r61;
+    let r63 = curr[1];
+    let r64 = r62 - r63;
+    let r65 = next[4];
+    let r66 = next[5];
+    let r67 = r4 * r66;
+    let r68 = r65 + r67;
+    let r69 = next[6];
+    let r70 = r8 * r69;
+    let r71 = r68 + r70;
+    let r72 = curr[3];
+    let r73 = r12 * r72;
+    let r74 = r71 + r73;
+    let r75 = curr[4];
+    let r76 = r16 * r75;
+    let r77 = r74 + r76;
+    let r78 = curr[5];
+    let r79 = r20 * r78;
+    let r80 = r77 + r79;
+    let r81 = curr[6];
+    let r82 = r24 * r81;
+    let r83 = r80 + r82;
+    let r84 = next[3];
+    let r85 = r28 * r84;
+    let r86 = r83 + r85;
+    let r87 = next[1];
+    let r88 = r86 - r87;
+    [r2, r33, r40, r64, r88]
+}
+
+/// Evaluates the 5 constraints of the gate `chacha2`.
+pub fn chacha2<F: Field>(curr: &[F], next: &[F], coeffs: &[F], endo_coefficient: F, mds: &[Vec<F>]) -> [F; 5] {
+    let r0 = next[2];
+    let r1 = r0.square();
+    let r2 = r1 - r0;
+    let r3 = curr[7];
+    let r4 = F::from(16u64);
+    let r5 = curr[8];
+    let r6 = r4 * r5;
+    let r7 = r3 + r6;
+    let r8 = F::from(256u64);
+    let r9 = curr[9];
+    let r10 = r8 * r9;
+    let r11 = r7 + r10;
+    let r12 = F::from(4096u64);
+    let r13 = curr[10];
+    let r14 = r12 * r13;
+    let r15 = r11 + r14;
+    let r16 = F::from(65536u64);
+    let r17 = next[7];
+    let r18 = r16 * r17;
+    let r19 = r15 + r18;
+    let r20 = F::from(1048576u64);
+    let r21 = next[8];
+    let r22 = r20 * r21;
+    let r23 = r19 + r22;
+    let r24 = F::from(16777216u64);
+    let r25 = next[9];
+    let r26 = r24 * r25;
+    let r27 = r23 + r26;
+    let r28 = F::from(268435456u64);
+    let r29 = next[10];
+    let r30 = r28 * r29;
+    let r31 = r27 + r30;
+    let r32 = next[0];
+    let r33 = r31 - r32;
+    let r34 = F::from(4294967296u64);
+    let r35 = r0 * r34;
+    let r36 = r32 + r35;
+    let r37 = curr[0];
+    let r38 = curr[2];
+    let r39 = r37 + r38;
+    let r40 = r36 - r39;
+    let r41 = curr[11];
+    let r42 = curr[12];
+    let r43 = r4 * r42;
+    let r44 = r41 + r43;
+    let r45 = curr[13];
+    let r46 = r8 * r45;
+    let r47 = r44 + r46;
+    let r48 = curr[14];
+    let r49 = r12 * r48;
+    let r50 = r47 + r49;
+    let r51 = next[11];
+    let r52 = r16 * r51;
+    let r53 = r50 + r52;
+    let r54 = next[12];
+    let r55 = r20 * r54;
+    let r56 = r53 + r55;
+    let r57 = next[13];
+    let r58 = r24 * r57;
+    let r59 = r56 + r58;
+    let r60 = next[14];
+    let r61 = r28 * r60;
+    let r62 = r59 + r61;
+    let r63 = curr[1];
+    let r64 = r62 - r63;
+    let r65 = next[5];
+    let r66 = next[6];
+    let r67 = r4 * r66;
+    let r68 = r65 + r67;
+    let r69 = curr[3];
+    let r70 = r8 * r69;
+    let r71 = r68 + r70;
+    let r72 = curr[4];
+    let r73 = r12 * r72;
+    let r74 = r71 + r73;
+    let r75 = curr[5];
+    let r76 = r16 * r75;
+    let r77 = r74 + r76;
+    let r78 = curr[6];
+    let r79 = r20 * r78;
+    let r80 = r77 + r79;
+    let r81 = next[3];
+    let r82 = r24 * r81;
+    let r83 = r80 + r82;
+    let r84 = next[4];
+    let r85 = r28 * r84;
+    let r86 = r83 + r85;
+    let r87 = next[1];
+    let r88 = r86 - r87;
+    [r2, r33, r40, r64, r88]
+}
+
+/// Evaluates the 9 constraints of the gate `chacha_final`.
+pub fn chacha_final<F: Field>(curr: &[F], next: &[F], coeffs: &[F], endo_coefficient: F, mds: &[Vec<F>]) -> [F; 9] {
+    let r0 = curr[5];
+    let r1 = r0.square();
+    let r2 = r1 - r0;
+    let r3 = curr[6];
+    let r4 = r3.square();
+    let r5 = r4 - r3;
+    let r6 = curr[7];
+    let r7 = r6.square();
+    let r8 = r7 - r6;
+    let r9 = curr[8];
+    let r10 = r9.square();
+    let r11 = r10 - r9;
+    let r12 = next[5];
+    let r13 = r12.square();
+    let r14 = r13 - r12;
+    let r15 = next[6];
+    let r16 = r15.square();
+    let r17 = r16 - r15;
+    let r18 = next[7];
+    let r19 = r18.square();
+    let r20 = r19 - r18;
+    let r21 = next[8];
+    let r22 = r21.square();
+    let r23 = r22 - r21;
+    let r24 = F::from(8u64);
+    let r25 = r21 * r24;
+    let r26 = F::from(1u64) / F::from(2u64);
+    let r27 = next[3];
+    let r28 = r27 - r18;
+    let r29 = r26 * r28;
+    let r30 = r25 + r29;
+    let r31 = F::from(16u64);
+    let r32 = r0 * r24;
+    let r33 = next[4];
+    let r34 = r33 - r21;
+    let r35 = r26 * r34;
+    let r36 = r32 + r35;
+    let r37 = r31 * r36;
+    let r38 = r30 + r37;
+    let r39 = F::from(256u64);
+    let r40 = r3 * r24;
+    let r41 = curr[1];
+    let r42 = r41 - r0;
+    let r43 = r26 * r42;
+    let r44 = r40 + r43;
+    let r45 = r39 * r44;
+    let r46 = r38 + r45;
+    let r47 = F::from(4096u64);
+    let r48 = r6 * r24;
+    let r49 = curr[2];
+    let r50 = r49 - r3;
+    let r51 = r26 * r50;
+    let r52 = r48 + r51;
+    let r53 = r47 * r52;
+    let r54 = r46 + r53;
+    let r55 = F::from(65536u64);
+    let r56 = r9 * r24;
+    let r57 = curr[3];
+    let r58 = r57 - r6;
+    let r59 = r26 * r58;
+    let r60 = r56 + r59;
+    let r61 = r55 * r60;
+    let r62 = r54 + r61;
+    let r63 = F::from(1048576u64);
+    let r64 = r12 * r24;
+    let r65 = curr[4];
+    let r66 = r65 - r9;
+    let r67 = r26 * r66;
+    let r68 = r64 + r67;
+    let r69 = r63 * r68;
+    let r70 = r62 + r69;
+    let r71 = F::from(16777216u64);
+    let r72 = r15 * r24;
+    let r73 = next[1];
+    let r74 = r73 - r12;
+    let r75 = r26 * r74;
+    let r76 = r72 + r75;
+    let r77 = r71 * r76;
+    let r78 = r70 + r77;
+    let r79 = F::from(268435456u64);
+    let r80 = r18 * r24;
+    let r81 = next[2];
+    let r82 = r81 - r15;
+    let r83 = r26 * r82;
+    let r84 = r80 + r83;
+    let r85 = r79 * r84;
+    let r86 = r78 + r85;
+    let r87 = curr[0];
+    let r88 = r86 - r87;
+    [r2, r5, r8, r11, r14, r17, r20, r23, r88]
+}
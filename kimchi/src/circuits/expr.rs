@@ -10,9 +10,11 @@ use ark_poly::{
     univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
 };
 use itertools::Itertools;
+use num_traits::FromPrimitive;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use serde_with::serde_as;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::iter::FromIterator;
 use std::ops::{Add, AddAssign, Mul, Neg, Sub};
 use CurrOrNext::*;
@@ -75,7 +77,7 @@ pub struct Environment<'a, F: FftField> {
 }
 
 impl<'a, F: FftField> Environment<'a, F> {
-    fn get_column(&self, col: &Column) -> Option<&'a Evaluations<F, D<F>>> {
+    pub(crate) fn get_column(&self, col: &Column) -> Option<&'a Evaluations<F, D<F>>> {
         use Column::*;
         let lookup = self.lookup.as_ref();
         match col {
@@ -128,13 +130,6 @@ pub enum Column {
 }
 
 impl Column {
-    fn domain(&self) -> Domain {
-        match self {
-            Column::Index(GateType::CompleteAdd) => Domain::D4,
-            _ => Domain::D8,
-        }
-    }
-
     fn latex(&self) -> String {
         match self {
             Column::Witness(i) => format!("w_{{{i}}}"),
@@ -371,7 +366,9 @@ pub enum Expr<C> {
 /// For efficiency of evaluation, we compile expressions to
 /// [reverse Polish notation](https://en.wikipedia.org/wiki/Reverse_Polish_notation)
 /// expressions, which are vectors of the below tokens.
+#[serde_as]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "F: Field")]
 pub enum PolishToken<F> {
     Alpha,
     Beta,
@@ -379,7 +376,7 @@ pub enum PolishToken<F> {
     JointCombiner,
     EndoCoefficient,
     Mds { row: usize, col: usize },
-    Literal(F),
+    Literal(#[serde_as(as = "o1_utils::serialization::SerdeAs")] F),
     Cell(Variable),
     Dup,
     Pow(u64),
@@ -393,7 +390,10 @@ pub enum PolishToken<F> {
 }
 
 impl Variable {
-    fn evaluate<'a, 'b, F: Field>(&self, evals: &'a [ProofEvaluations<F>]) -> Result<F, &'b str> {
+    pub(crate) fn evaluate<'a, 'b, F: Field>(
+        &self,
+        evals: &'a [ProofEvaluations<F>],
+    ) -> Result<F, &'b str> {
         let evals = &evals[self.row.shift()];
         use Column::*;
         let l = evals
@@ -407,6 +407,9 @@ impl Variable {
             LookupAggreg => l.map(|l| l.aggreg),
             LookupTable => l.map(|l| l.table),
             Index(GateType::Poseidon) => Ok(evals.poseidon_selector),
+            Index(GateType::PoseidonLegacy) => evals
+                .poseidon_legacy_selector
+                .ok_or("Poseidon legacy should not have been used"),
             Index(GateType::Generic) => Ok(evals.generic_selector),
             Coefficient(_) | LookupKindIndex(_) | Index(_) => {
                 Err("Cannot get index evaluation (should have been linearized away)")
@@ -497,7 +500,25 @@ impl<C> Expr<C> {
         Expr::Constant(c)
     }
 
-    fn degree(&self, d1_size: u64) -> u64 {
+    /// Returns the total degree of the expression in the cells,
+    /// where the cells and `UnnormalizedLagrangeBasis` have degree 1,
+    /// and the constants and `VanishesOnLast4Rows` have degree 0.
+    pub fn total_degree(&self) -> u64 {
+        use Expr::*;
+        match self {
+            Constant(_) | VanishesOnLast4Rows => 0,
+            Cell(_) | UnnormalizedLagrangeBasis(_) => 1,
+            Double(x) | Cache(_, x) => x.total_degree(),
+            Square(x) => 2 * x.total_degree(),
+            Pow(x, d) => d * x.total_degree(),
+            BinOp(Op2::Mul, x, y) => x.total_degree() + y.total_degree(),
+            BinOp(Op2::Add | Op2::Sub, x, y) => std::cmp::max(x.total_degree(), y.total_degree()),
+        }
+    }
+
+    /// Returns an upper bound of the degree of the polynomial of the expression,
+    /// for a domain of size `d1_size`.
+    pub fn degree(&self, d1_size: u64) -> u64 {
         use Expr::*;
         match self {
             Double(x) => x.degree(d1_size),
@@ -516,14 +537,25 @@ impl<C> Expr<C> {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, FromPrimitive, ToPrimitive)]
-enum Domain {
+/// An evaluation domain, as a multiple of the size of the domain of the circuit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, FromPrimitive, ToPrimitive)]
+pub enum Domain {
     D1 = 1,
     D2 = 2,
     D4 = 4,
     D8 = 8,
 }
 
+impl Domain {
+    /// Returns the smallest evaluation domain which can hold the evaluations of a polynomial of degree `degree`,
+    /// for a domain of the circuit of size `d1_size`, or `None` if the polynomial does not fit in d8.
+    pub fn for_degree(degree: u64, d1_size: u64) -> Option<Domain> {
+        [Domain::D1, Domain::D2, Domain::D4, Domain::D8]
+            .into_iter()
+            .find(|d| degree <= (*d as u64) * d1_size)
+    }
+}
+
 #[derive(Clone)]
 enum EvalResult<'a, F: FftField> {
     Constant(F),
@@ -590,7 +622,7 @@ pub fn pows<F: Field>(x: F, n: usize) -> Vec<F> {
 /// = (omega^{q n} omega_8^{r n} - 1) / (omega_8^k - omega^i)
 /// = ((omega_8^n)^r - 1) / (omega_8^k - omega^i)
 /// = ((omega_8^n)^r - 1) / (omega^q omega_8^r - omega^i)
-fn unnormalized_lagrange_evals<F: FftField>(
+pub(crate) fn unnormalized_lagrange_evals<F: FftField>(
     l0_1: F,
     i: usize,
     res_domain: Domain,
@@ -1060,7 +1092,7 @@ impl<'a, F: FftField> EvalResult<'a, F> {
     }
 }
 
-fn get_domain<F: FftField>(d: Domain, env: &Environment<F>) -> D<F> {
+pub(crate) fn get_domain<F: FftField>(d: Domain, env: &Environment<F>) -> D<F> {
     match d {
         Domain::D1 => env.domain.d1,
         Domain::D2 => env.domain.d2,
@@ -1270,15 +1302,8 @@ impl<F: FftField> Expr<F> {
     pub fn evaluations<'a>(&self, env: &Environment<'a, F>) -> Evaluations<F, D<F>> {
         let d1_size = env.domain.d1.size;
         let deg = self.degree(d1_size);
-        let d = if deg <= d1_size {
-            Domain::D1
-        } else if deg <= 4 * d1_size {
-            Domain::D4
-        } else if deg <= 8 * d1_size {
-            Domain::D8
-        } else {
-            panic!("constraint had degree {} > 8", deg);
-        };
+        let d = Domain::for_degree(deg, d1_size)
+            .unwrap_or_else(|| panic!("constraint had degree {} > 8", deg));
 
         let mut cache = HashMap::new();
 
@@ -1402,8 +1427,10 @@ impl<F: FftField> Expr<F> {
                         Some(e) => e,
                     }
                 };
+                // the evaluations of the columns are over d4 or d8
+                let domain = evals.domain().size / env.domain.d1.size;
                 EvalResult::SubEvals {
-                    domain: col.domain(),
+                    domain: Domain::from_u64(domain).expect("evaluations over an unknown domain"),
                     shift: row.shift(),
                     evals,
                 }
@@ -1540,13 +1567,15 @@ impl<F: One> Expr<F> {
     }
 }
 
-type Monomials<F> = HashMap<Vec<Variable>, Expr<F>>;
+/// The monomials of an expression, and their coefficients.
+/// They are ordered, so that the linearization does not depend on the order of a hash map.
+type Monomials<F> = BTreeMap<Vec<Variable>, Expr<F>>;
 
 fn mul_monomials<F: Neg<Output = F> + Clone + One + Zero + PartialEq>(
     e1: &Monomials<F>,
     e2: &Monomials<F>,
 ) -> Monomials<F> {
-    let mut res: HashMap<_, Expr<F>> = HashMap::new();
+    let mut res: Monomials<F> = BTreeMap::new();
     for (m1, c1) in e1.iter() {
         for (m2, c2) in e2.iter() {
             let mut m = m1.clone();
@@ -1580,9 +1609,9 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
         }
     }
 
-    fn monomials(&self, ev: &HashSet<Column>) -> HashMap<Vec<Variable>, Expr<F>> {
+    fn monomials(&self, ev: &HashSet<Column>) -> Monomials<F> {
         let sing = |v: Vec<Variable>, c: Expr<F>| {
-            let mut h = HashMap::new();
+            let mut h = BTreeMap::new();
             h.insert(v, c);
             h
         };
@@ -1615,7 +1644,7 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
                 acc
             }
             Double(e) => {
-                HashMap::from_iter(e.monomials(ev).into_iter().map(|(m, c)| (m, c.double())))
+                BTreeMap::from_iter(e.monomials(ev).into_iter().map(|(m, c)| (m, c.double())))
             }
             Cache(_, e) => e.monomials(ev),
             UnnormalizedLagrangeBasis(i) => constant(UnnormalizedLagrangeBasis(*i)),
@@ -1682,8 +1711,11 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
     /// this function computes `lin_or_err(factor_{V_0}(e))`, although it does not
     /// compute it in that way. Instead, it computes it by reducing the expression into
     /// a sum of monomials with `F` coefficients, and then factors the monomials.
+    ///
+    /// The result is deterministic: the monomials are summed in order,
+    /// and the terms of the linearization are sorted by column.
     pub fn linearize(&self, evaluated: HashSet<Column>) -> Result<Linearization<Expr<F>>, &str> {
-        let mut res: HashMap<Column, Expr<F>> = HashMap::new();
+        let mut res: BTreeMap<Column, Expr<F>> = BTreeMap::new();
         let mut constant_term: Expr<F> = Self::zero();
         let monomials = self.monomials(&evaluated);
 
@@ -1716,7 +1748,7 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
                         // with the above that moves v out of the map with .remove and
                         // into v + c.
                         //
-                        // I'm not sure if there's a way to do it with the HashMap API
+                        // I'm not sure if there's a way to do it with the map API
                         // without calling remove.
                     }
                 }
@@ -2056,6 +2088,12 @@ where
     }
 }
 
+//
+// Text format
+//
+
+pub mod text;
+
 //
 // Constraints
 //
@@ -0,0 +1,686 @@
+//! A text format for constraints, which [print_constraints] prints and [parse_constraints] parses back.
+//!
+//! A text is a list of statements, each terminated by a `;`:
+//!
+//! * `let x = e;` binds the cached subexpression `e` to the name `x`, for the statements after it.
+//!   The names `x_i` and `x_i_k` cache `e` with the id `i` (the latter for distinct subexpressions with the same id),
+//!   and the other names get fresh ids.
+//! * `e;` is a constraint.
+//!
+//! An expression `e` is made of
+//!
+//! * the cells of the current row `w[i]` (witness), `z` (permutation), `sorted[i]`, `aggreg`, `table`,
+//!   `kind_index[i]` (lookup), `index[Gate]` (selector of the gate `Gate`) and `coeff[i]` (coefficient),
+//!   and the cells of the next row, with the suffix `_next` (e.g. `w_next[i]`);
+//! * the constants `alpha`, `beta`, `gamma`, `joint_combiner`, `endo_coefficient` and `mds[row][col]`,
+//!   and the literals, in decimal (optionally negated) or in hexadecimal with the prefix `0x`;
+//! * the constant expressions `const(c)`, where `c` only contains constants;
+//! * `vanishes_on_last_4_rows` and `unnormalized_lagrange_basis(i)`;
+//! * `double(e)`, `square(e)`, `e^n`, `e * e`, `e + e` and `e - e`, with the usual precedences, and parentheses.
+//!
+//! Comments start with `//` and end with the line.
+//!
+//! Printing and then parsing an expression returns the same expression.
+
+use super::*;
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+//
+// Errors
+//
+
+/// Represents a syntax error in a text
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxError {
+    #[error("unexpected character '{0}'")]
+    UnexpectedCharacter(char),
+    #[error("expected '{0}'")]
+    Expected(char),
+    #[error("expected a name")]
+    ExpectedName,
+    #[error("expected a number")]
+    ExpectedNumber,
+    #[error("expected a constant")]
+    ExpectedConstant,
+    #[error("expected an expression")]
+    ExpectedExpression,
+    #[error("expected a literal after '-'")]
+    ExpectedLiteral,
+    #[error("invalid number {0}")]
+    InvalidNumber(String),
+    #[error("invalid literal {0}")]
+    InvalidLiteral(String),
+    #[error("unknown gate {0}")]
+    UnknownGate(String),
+    #[error("unknown name {0}")]
+    UnknownName(String),
+    #[error("the name {0} is already used")]
+    NameAlreadyUsed(String),
+    #[error("the id of {0} is already used")]
+    IdAlreadyUsed(String),
+}
+
+/// Represents an error found when parsing constraints in the text format
+#[derive(Error, Debug)]
+pub enum TextError {
+    /// The text is not well formed
+    #[error("line {line}: {error}")]
+    Syntax { line: usize, error: SyntaxError },
+    /// The text does not contain exactly one constraint, see [Expr::from_text]
+    #[error("expected a single constraint, found {0}")]
+    NotSingleConstraint(usize),
+    /// The file of the text could not be read, see [parse_file]
+    #[error("cannot read {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+}
+
+//
+// Printing
+//
+
+// The precedences of the operations, from the loosest to the tightest
+const SUM: usize = 0;
+const PRODUCT: usize = 1;
+const POWER: usize = 2;
+const ATOM: usize = 3;
+
+/// Wraps `s`, of precedence `prec`, in parentheses if its context requires at least `min_prec`.
+fn parens(s: String, prec: usize, min_prec: usize) -> String {
+    if prec < min_prec {
+        format!("({s})")
+    } else {
+        s
+    }
+}
+
+/// The symbol and the precedence of a binary operation
+fn op_text(op: &Op2) -> (&'static str, usize) {
+    match op {
+        Op2::Add => ("+", SUM),
+        Op2::Sub => ("-", SUM),
+        Op2::Mul => ("*", PRODUCT),
+    }
+}
+
+/// Prints a literal in decimal if it or its opposite fits in a `u64`, and in hexadecimal otherwise.
+fn literal_text<F: PrimeField>(x: F) -> String {
+    let small = |x: F| {
+        let repr = x.into_repr();
+        let limbs = repr.as_ref();
+        limbs[1..].iter().all(|l| *l == 0).then(|| limbs[0])
+    };
+    match (small(x), small(-x)) {
+        (Some(n), _) => n.to_string(),
+        (_, Some(n)) => format!("-{n}"),
+        _ => format!("0x{}", x.into_repr()),
+    }
+}
+
+fn constant_text<F: PrimeField>(c: &ConstantExpr<F>, min_prec: usize) -> String {
+    use ConstantExpr::*;
+    match c {
+        Alpha => "alpha".to_string(),
+        Beta => "beta".to_string(),
+        Gamma => "gamma".to_string(),
+        JointCombiner => "joint_combiner".to_string(),
+        EndoCoefficient => "endo_coefficient".to_string(),
+        Mds { row, col } => format!("mds[{row}][{col}]"),
+        Literal(x) => literal_text(*x),
+        Pow(x, n) => parens(format!("{}^{n}", constant_text(x, ATOM)), POWER, min_prec),
+        Add(x, y) => {
+            let (symbol, prec) = op_text(&Op2::Add);
+            // the operations are left associative
+            let (x, y) = (constant_text(x, prec), constant_text(y, prec + 1));
+            parens(format!("{x} {symbol} {y}"), prec, min_prec)
+        }
+        Mul(x, y) => {
+            let (symbol, prec) = op_text(&Op2::Mul);
+            // the operations are left associative
+            let (x, y) = (constant_text(x, prec), constant_text(y, prec + 1));
+            parens(format!("{x} {symbol} {y}"), prec, min_prec)
+        }
+        Sub(x, y) => {
+            let (symbol, prec) = op_text(&Op2::Sub);
+            // the operations are left associative
+            let (x, y) = (constant_text(x, prec), constant_text(y, prec + 1));
+            parens(format!("{x} {symbol} {y}"), prec, min_prec)
+        }
+    }
+}
+
+fn variable_text(v: &Variable) -> String {
+    let suffix = match v.row {
+        Curr => "",
+        Next => "_next",
+    };
+    match v.col {
+        Column::Witness(i) => format!("w{suffix}[{i}]"),
+        Column::Z => format!("z{suffix}"),
+        Column::LookupSorted(i) => format!("sorted{suffix}[{i}]"),
+        Column::LookupAggreg => format!("aggreg{suffix}"),
+        Column::LookupTable => format!("table{suffix}"),
+        Column::LookupKindIndex(i) => format!("kind_index{suffix}[{i}]"),
+        Column::Index(gate) => format!("index{suffix}[{gate:?}]"),
+        Column::Coefficient(i) => format!("coeff{suffix}[{i}]"),
+    }
+}
+
+/// A cached subexpression, with its name and its text
+struct CachedText<'a, F> {
+    id: CacheId,
+    expr: &'a E<F>,
+    name: String,
+    body: String,
+}
+
+/// Prints an expression, and pushes the cached subexpressions it contains to `caches`,
+/// after the ones they contain.
+fn expr_text<'a, F: PrimeField>(
+    e: &'a E<F>,
+    min_prec: usize,
+    caches: &mut Vec<CachedText<'a, F>>,
+) -> String {
+    use Expr::*;
+    match e {
+        Constant(
+            c @ (ConstantExpr::Pow(..)
+            | ConstantExpr::Add(..)
+            | ConstantExpr::Mul(..)
+            | ConstantExpr::Sub(..)),
+        ) => format!("const({})", constant_text(c, SUM)),
+        Constant(c) => constant_text(c, min_prec),
+        Cell(v) => variable_text(v),
+        Double(x) => format!("double({})", expr_text(x, SUM, caches)),
+        Square(x) => format!("square({})", expr_text(x, SUM, caches)),
+        BinOp(op, x, y) => {
+            let (symbol, prec) = op_text(op);
+            // the operations are left associative
+            let x = expr_text(x, prec, caches);
+            let y = expr_text(y, prec + 1, caches);
+            parens(format!("{x} {symbol} {y}"), prec, min_prec)
+        }
+        VanishesOnLast4Rows => "vanishes_on_last_4_rows".to_string(),
+        UnnormalizedLagrangeBasis(i) => format!("unnormalized_lagrange_basis({i})"),
+        Pow(x, n) => parens(
+            format!("{}^{n}", expr_text(x, ATOM, caches)),
+            POWER,
+            min_prec,
+        ),
+        Cache(id, x) => {
+            if let Some(cached) = caches.iter().find(|c| c.id == *id && c.expr == x.as_ref()) {
+                return cached.name.clone();
+            }
+            let body = expr_text(x, SUM, caches);
+            // the constraints of different gates can cache different subexpressions with the same id
+            let name = match caches.iter().filter(|c| c.id == *id).count() {
+                0 => id.var_name(),
+                k => format!("{}_{k}", id.var_name()),
+            };
+            caches.push(CachedText {
+                id: *id,
+                expr: x,
+                name: name.clone(),
+                body,
+            });
+            name
+        }
+    }
+}
+
+/// Prints `constraints` in the text format, one statement per line,
+/// with the cached subexpressions bound before the constraints.
+pub fn print_constraints<F: PrimeField>(constraints: &[E<F>]) -> String {
+    let mut caches = vec![];
+    let constraints: Vec<_> = constraints
+        .iter()
+        .map(|c| expr_text(c, SUM, &mut caches))
+        .collect();
+
+    let mut res = String::new();
+    for CachedText { name, body, .. } in caches {
+        res.push_str(&format!("let {name} = {body};\n"));
+    }
+    for c in constraints {
+        res.push_str(&format!("{c};\n"));
+    }
+    res
+}
+
+//
+// Parsing
+//
+
+// The names that cannot be bound by a `let`, as they are parsed as something else
+const KEYWORDS: [&str; 11] = [
+    "let",
+    "alpha",
+    "beta",
+    "gamma",
+    "joint_combiner",
+    "endo_coefficient",
+    "mds",
+    "const",
+    "double",
+    "square",
+    "unnormalized_lagrange_basis",
+];
+const COLUMNS: [&str; 8] = [
+    "w",
+    "z",
+    "sorted",
+    "aggreg",
+    "table",
+    "kind_index",
+    "index",
+    "coeff",
+];
+
+fn is_reserved(name: &str) -> bool {
+    let column = name.strip_suffix("_next").unwrap_or(name);
+    KEYWORDS.contains(&name) || COLUMNS.contains(&column) || name == "vanishes_on_last_4_rows"
+}
+
+/// The id `i` and the index `k` of the cached subexpressions named `x_i` (with `k = 0`) or `x_i_k`
+fn cache_id(name: &str) -> Option<(usize, usize)> {
+    let number = |n: &str| {
+        (!n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+            .then(|| n.parse().ok())
+            .flatten()
+    };
+    let name = name.strip_prefix("x_")?;
+    match name.split_once('_') {
+        Some((i, k)) => Some((number(i)?, number(k)?)),
+        None => Some((number(name)?, 0)),
+    }
+}
+
+fn gate_type(name: &str) -> Option<GateType> {
+    (0..)
+        .map_while(GateType::from_u32)
+        .find(|gate| format!("{gate:?}") == name)
+}
+
+/// Parses a literal in decimal, or in hexadecimal with the prefix `0x`.
+fn literal<F: PrimeField>(s: &str) -> Option<F> {
+    let (digits, radix) = match s.strip_prefix("0x") {
+        Some(hex) => (hex, 16),
+        None => (s, 10),
+    };
+    if digits.is_empty() {
+        return None;
+    }
+    digits.chars().try_fold(F::zero(), |acc, d| {
+        d.to_digit(radix)
+            .map(|d| acc * F::from(radix as u64) + F::from(d as u64))
+    })
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    Symbol(char),
+}
+
+/// Splits `s` into tokens, with the line they are on.
+fn tokenize(s: &str) -> Result<Vec<(usize, Token)>, TextError> {
+    let mut tokens = vec![];
+    let mut chars = s.chars().peekable();
+    let mut line = 1;
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => (),
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|c| *c != '\n').is_some() {},
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    word.push(c);
+                }
+                if c.is_ascii_digit() {
+                    tokens.push((line, Token::Number(word)));
+                } else {
+                    tokens.push((line, Token::Ident(word)));
+                }
+            }
+            '+' | '-' | '*' | '^' | '(' | ')' | '[' | ']' | '=' | ';' => {
+                tokens.push((line, Token::Symbol(c)))
+            }
+            c => {
+                return Err(TextError::Syntax {
+                    line,
+                    error: SyntaxError::UnexpectedCharacter(c),
+                })
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// An expression that the parser can build
+trait Term<F>: Sized {
+    fn primary(p: &mut Parser<F>) -> Result<Self, TextError>;
+    fn binop(op: Op2, x: Self, y: Self) -> Self;
+    fn pow(x: Self, n: u64) -> Self;
+}
+
+struct Parser<F> {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    /// the cached subexpressions bound so far, by name
+    bindings: HashMap<String, E<F>>,
+}
+
+impl<F: PrimeField> Parser<F> {
+    fn error<T>(&self, error: SyntaxError) -> Result<T, TextError> {
+        let line = self
+            .tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map_or(1, |(line, _)| *line);
+        Err(TextError::Syntax { line, error })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(&Token::Symbol(c));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), TextError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            self.error(SyntaxError::Expected(c))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, TextError> {
+        match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => self.error(SyntaxError::ExpectedName),
+        }
+    }
+
+    fn number<T: std::str::FromStr>(&mut self) -> Result<T, TextError> {
+        match self.peek() {
+            Some(Token::Number(n)) => match n.parse() {
+                Ok(n) => {
+                    self.pos += 1;
+                    Ok(n)
+                }
+                Err(_) => self.error(SyntaxError::InvalidNumber(n.clone())),
+            },
+            _ => self.error(SyntaxError::ExpectedNumber),
+        }
+    }
+
+    /// Parses `[i]`.
+    fn index(&mut self) -> Result<usize, TextError> {
+        self.expect('[')?;
+        let i = self.number()?;
+        self.expect(']')?;
+        Ok(i)
+    }
+
+    /// Parses `(e)`.
+    fn argument<T: Term<F>>(&mut self) -> Result<T, TextError> {
+        self.expect('(')?;
+        let e = self.sum()?;
+        self.expect(')')?;
+        Ok(e)
+    }
+
+    fn sum<T: Term<F>>(&mut self) -> Result<T, TextError> {
+        let mut res = self.product()?;
+        loop {
+            let op = if self.eat('+') {
+                Op2::Add
+            } else if self.eat('-') {
+                Op2::Sub
+            } else {
+                return Ok(res);
+            };
+            res = T::binop(op, res, self.product()?);
+        }
+    }
+
+    fn product<T: Term<F>>(&mut self) -> Result<T, TextError> {
+        let mut res = self.power()?;
+        while self.eat('*') {
+            res = T::binop(Op2::Mul, res, self.power()?);
+        }
+        Ok(res)
+    }
+
+    fn power<T: Term<F>>(&mut self) -> Result<T, TextError> {
+        let mut res = T::primary(self)?;
+        while self.eat('^') {
+            res = T::pow(res, self.number()?);
+        }
+        Ok(res)
+    }
+
+    /// Parses a constant which is not a constant expression, if the next token starts one.
+    fn atomic_constant(&mut self) -> Result<Option<ConstantExpr<F>>, TextError> {
+        use ConstantExpr::*;
+        let negate = self.eat('-');
+        let c = match self.peek().cloned() {
+            Some(Token::Number(n)) => match literal::<F>(&n) {
+                Some(x) if negate => Literal(-x),
+                Some(x) => Literal(x),
+                None => return self.error(SyntaxError::InvalidLiteral(n)),
+            },
+            _ if negate => return self.error(SyntaxError::ExpectedLiteral),
+            Some(Token::Ident(name)) => match name.as_str() {
+                "alpha" => Alpha,
+                "beta" => Beta,
+                "gamma" => Gamma,
+                "joint_combiner" => JointCombiner,
+                "endo_coefficient" => EndoCoefficient,
+                "mds" => {
+                    self.pos += 1;
+                    let row = self.index()?;
+                    let col = self.index()?;
+                    return Ok(Some(Mds { row, col }));
+                }
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        self.pos += 1;
+        Ok(Some(c))
+    }
+
+    /// Parses the cell `name`, if it is the name of a column.
+    fn variable(&mut self, name: &str) -> Result<Option<Variable>, TextError> {
+        let (column, row) = match name.strip_suffix("_next") {
+            Some(column) => (column, Next),
+            None => (name, Curr),
+        };
+        let col = match column {
+            "w" => Column::Witness(self.index()?),
+            "z" => Column::Z,
+            "sorted" => Column::LookupSorted(self.index()?),
+            "aggreg" => Column::LookupAggreg,
+            "table" => Column::LookupTable,
+            "kind_index" => Column::LookupKindIndex(self.index()?),
+            "index" => {
+                self.expect('[')?;
+                let gate = self.ident()?;
+                let gate = match gate_type(&gate) {
+                    Some(gate) => gate,
+                    None => return self.error(SyntaxError::UnknownGate(gate)),
+                };
+                self.expect(']')?;
+                Column::Index(gate)
+            }
+            "coeff" => Column::Coefficient(self.index()?),
+            _ => return Ok(None),
+        };
+        Ok(Some(Variable { col, row }))
+    }
+
+    /// Parses the statements until the end of the text.
+    fn statements(&mut self) -> Result<Vec<E<F>>, TextError> {
+        // the names `x_i` use the id `i`, and the other names the ids after them
+        let mut next_id = self
+            .tokens
+            .windows(2)
+            .filter_map(|w| match w {
+                [(_, Token::Ident(l)), (_, Token::Ident(name))] if l == "let" => {
+                    cache_id(name).map(|(i, _)| i)
+                }
+                _ => None,
+            })
+            .max()
+            .map_or(0, |i| i + 1);
+        let mut ids = HashSet::new();
+
+        let mut constraints = vec![];
+        while let Some(token) = self.peek() {
+            if token == &Token::Ident("let".to_string()) {
+                self.pos += 1;
+                let name = self.ident()?;
+                if is_reserved(&name) || self.bindings.contains_key(&name) {
+                    return self.error(SyntaxError::NameAlreadyUsed(name));
+                }
+                let (id, k) = cache_id(&name).unwrap_or_else(|| {
+                    next_id += 1;
+                    (next_id - 1, 0)
+                });
+                if !ids.insert((id, k)) {
+                    return self.error(SyntaxError::IdAlreadyUsed(name));
+                }
+                self.expect('=')?;
+                let e = self.sum()?;
+                self.expect(';')?;
+                self.bindings
+                    .insert(name, Expr::Cache(CacheId(id), Box::new(e)));
+            } else {
+                constraints.push(self.sum()?);
+                self.expect(';')?;
+            }
+        }
+        Ok(constraints)
+    }
+}
+
+impl<F: PrimeField> Term<F> for ConstantExpr<F> {
+    fn primary(p: &mut Parser<F>) -> Result<Self, TextError> {
+        if let Some(c) = p.atomic_constant()? {
+            Ok(c)
+        } else if p.peek() == Some(&Token::Symbol('(')) {
+            p.argument()
+        } else {
+            p.error(SyntaxError::ExpectedConstant)
+        }
+    }
+
+    fn binop(op: Op2, x: Self, y: Self) -> Self {
+        let (x, y) = (Box::new(x), Box::new(y));
+        match op {
+            Op2::Add => ConstantExpr::Add(x, y),
+            Op2::Mul => ConstantExpr::Mul(x, y),
+            Op2::Sub => ConstantExpr::Sub(x, y),
+        }
+    }
+
+    fn pow(x: Self, n: u64) -> Self {
+        ConstantExpr::Pow(Box::new(x), n)
+    }
+}
+
+impl<F: PrimeField> Term<F> for E<F> {
+    fn primary(p: &mut Parser<F>) -> Result<Self, TextError> {
+        if let Some(c) = p.atomic_constant()? {
+            return Ok(Expr::Constant(c));
+        }
+        if p.peek() == Some(&Token::Symbol('(')) {
+            return p.argument();
+        }
+        let name = match p.peek() {
+            Some(Token::Ident(_)) => p.ident()?,
+            _ => return p.error(SyntaxError::ExpectedExpression),
+        };
+        match name.as_str() {
+            "const" => Ok(Expr::Constant(p.argument()?)),
+            "double" => Ok(Expr::Double(Box::new(p.argument()?))),
+            "square" => Ok(Expr::Square(Box::new(p.argument()?))),
+            "vanishes_on_last_4_rows" => Ok(Expr::VanishesOnLast4Rows),
+            "unnormalized_lagrange_basis" => {
+                p.expect('(')?;
+                let i = p.number()?;
+                p.expect(')')?;
+                Ok(Expr::UnnormalizedLagrangeBasis(i))
+            }
+            _ => {
+                if let Some(v) = p.variable(&name)? {
+                    return Ok(Expr::Cell(v));
+                }
+                match p.bindings.get(&name) {
+                    Some(e) => Ok(e.clone()),
+                    None => p.error(SyntaxError::UnknownName(name)),
+                }
+            }
+        }
+    }
+
+    fn binop(op: Op2, x: Self, y: Self) -> Self {
+        Expr::BinOp(op, Box::new(x), Box::new(y))
+    }
+
+    fn pow(x: Self, n: u64) -> Self {
+        Expr::Pow(Box::new(x), n)
+    }
+}
+
+/// Parses the constraints of a text in the text format.
+pub fn parse_constraints<F: PrimeField>(s: &str) -> Result<Vec<E<F>>, TextError> {
+    let mut parser = Parser {
+        tokens: tokenize(s)?,
+        pos: 0,
+        bindings: HashMap::new(),
+    };
+    parser.statements()
+}
+
+/// Parses the constraints of a file in the text format, e.g. the constraints of a gate printed by [print_constraints].
+pub fn parse_file<F: PrimeField>(path: impl AsRef<Path>) -> Result<Vec<E<F>>, TextError> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path).map_err(|source| TextError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    parse_constraints(&text)
+}
+
+impl<F: PrimeField> Expr<ConstantExpr<F>> {
+    /// Converts the expression in the [text] format
+    pub fn text_str(&self) -> String {
+        print_constraints(std::slice::from_ref(self))
+    }
+
+    /// Parses an expression in the [text] format, which contains a single constraint
+    pub fn from_text(s: &str) -> Result<Self, TextError> {
+        let mut constraints = parse_constraints(s)?;
+        match constraints.len() {
+            1 => Ok(constraints.remove(0)),
+            n => Err(TextError::NotSingleConstraint(n)),
+        }
+    }
+}
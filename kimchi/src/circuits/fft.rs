@@ -0,0 +1,234 @@
+//! This module implements the FFTs of the prover over the evaluation domains of a circuit,
+//! with the roots of unity of the domains (the twiddle factors) computed once, in [Twiddles].
+//! The FFTs of `ark-poly` are the same, but compute the roots of unity again for every FFT.
+
+use crate::circuits::domains::EvaluationDomains;
+use ark_ff::FftField;
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
+    UVPolynomial,
+};
+use rayon::prelude::*;
+
+/// The number of chunks of a round of butterflies from which the roots it uses are copied together
+const MIN_NUM_CHUNKS_FOR_COMPACTION: usize = 1 << 7;
+
+/// The size of a butterfly from which its chunk is processed in parallel
+const MIN_GAP_SIZE_FOR_PARALLELISATION: usize = 1 << 10;
+
+/// The number of roots of unity computed by a thread
+const ROOTS_CHUNK_SIZE: usize = 1 << 10;
+
+/// The roots of unity of the largest evaluation domain of a circuit, and their inverses.
+/// The roots of unity of its smaller domains are strided subsets of these.
+#[derive(Debug, Clone)]
+pub struct Twiddles<F: FftField> {
+    /// the largest domain
+    domain: D<F>,
+    /// its first `size / 2` roots of unity
+    roots: Vec<F>,
+    /// the inverses of its first `size / 2` roots of unity
+    roots_inv: Vec<F>,
+}
+
+/// Returns the first `n` powers of `g`.
+fn powers<F: FftField>(g: F, n: usize) -> Vec<F> {
+    let mut powers = vec![F::zero(); n];
+    powers
+        .par_chunks_mut(ROOTS_CHUNK_SIZE)
+        .enumerate()
+        .for_each(|(i, chunk)| {
+            let mut power = g.pow([(i * ROOTS_CHUNK_SIZE) as u64]);
+            for x in chunk {
+                *x = power;
+                power *= g;
+            }
+        });
+    powers
+}
+
+/// Swaps the elements at bit-reversed positions.
+fn derange<F>(xi: &mut [F]) {
+    let log_len = xi.len().trailing_zeros();
+    if log_len == 0 {
+        return;
+    }
+    for idx in 1..xi.len() {
+        let ridx = idx.reverse_bits() >> (usize::BITS - log_len);
+        if idx < ridx {
+            xi.swap(idx, ridx);
+        }
+    }
+}
+
+/// Applies a round of butterflies of size `gap` to `xi`, with the roots `roots[j * step]`.
+fn apply_butterfly<F: FftField>(
+    butterfly: fn(((&mut F, &mut F), &F)),
+    xi: &mut [F],
+    roots: &[F],
+    step: usize,
+    gap: usize,
+) {
+    let num_chunks = xi.len() / (2 * gap);
+    let max_threads = rayon::current_num_threads();
+    xi.par_chunks_mut(2 * gap).for_each(|chunk| {
+        let (lo, hi) = chunk.split_at_mut(gap);
+        if gap > MIN_GAP_SIZE_FOR_PARALLELISATION && num_chunks < max_threads {
+            lo.par_iter_mut()
+                .zip(hi)
+                .zip(roots.par_iter().step_by(step))
+                .for_each(butterfly);
+        } else {
+            lo.iter_mut()
+                .zip(hi)
+                .zip(roots.iter().step_by(step))
+                .for_each(butterfly);
+        }
+    });
+}
+
+/// The roots used by a round of butterflies of size `gap`, and the stride between them,
+/// copied together if they are far apart.
+fn round_roots<'a, F: FftField>(
+    roots: &'a [F],
+    compacted: &'a mut Vec<F>,
+    step: usize,
+    gap: usize,
+) -> (&'a [F], usize) {
+    if step >= MIN_NUM_CHUNKS_FOR_COMPACTION {
+        compacted.clear();
+        compacted.extend(roots.iter().step_by(step).take(gap));
+        (&compacted[..], 1)
+    } else {
+        (roots, step)
+    }
+}
+
+impl<F: FftField> Twiddles<F> {
+    /// Computes the roots of unity of the largest domain of `domains`.
+    pub fn create(domains: &EvaluationDomains<F>) -> Self {
+        let domain = domains.d8;
+        let half = (domain.size / 2) as usize;
+        Twiddles {
+            domain,
+            roots: powers(domain.group_gen, half),
+            roots_inv: powers(domain.group_gen_inv, half),
+        }
+    }
+
+    /// The stride between the roots of unity of `domain` in the roots of the largest domain,
+    /// if it is one of its subgroups.
+    fn stride(&self, domain: D<F>) -> Option<usize> {
+        let stride = self.domain.size.checked_div(domain.size)?;
+        let subgroup = self.domain.size % domain.size == 0
+            && domain.size > 1
+            && self.domain.group_gen.pow([stride]) == domain.group_gen;
+        if subgroup {
+            Some(stride as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Evaluates, in place, the polynomial of coefficients `xi` over `domain`.
+    /// `xi` must have the size of the domain.
+    pub fn fft_in_place(&self, domain: D<F>, xi: &mut [F]) {
+        assert_eq!(xi.len(), domain.size as usize);
+        let stride = match self.stride(domain) {
+            Some(stride) => stride,
+            None => {
+                let evals = domain.fft(xi);
+                xi.copy_from_slice(&evals);
+                return;
+            }
+        };
+
+        // decimation in frequency, from the largest butterflies to the smallest ones
+        let mut compacted = vec![];
+        let mut gap = xi.len() / 2;
+        while gap > 0 {
+            let num_chunks = xi.len() / (2 * gap);
+            let (roots, step) = round_roots(&self.roots, &mut compacted, num_chunks * stride, gap);
+            apply_butterfly(
+                |((lo, hi), root)| {
+                    let neg = *lo - *hi;
+                    *lo += *hi;
+                    *hi = neg * root;
+                },
+                xi,
+                roots,
+                step,
+                gap,
+            );
+            gap /= 2;
+        }
+        derange(xi);
+    }
+
+    /// Interpolates, in place, the evaluations `xi` over `domain` into the coefficients of a polynomial.
+    /// `xi` must have the size of the domain.
+    pub fn ifft_in_place(&self, domain: D<F>, xi: &mut [F]) {
+        assert_eq!(xi.len(), domain.size as usize);
+        let stride = match self.stride(domain) {
+            Some(stride) => stride,
+            None => {
+                let coeffs = domain.ifft(xi);
+                xi.copy_from_slice(&coeffs);
+                return;
+            }
+        };
+
+        // decimation in time, from the smallest butterflies to the largest ones
+        derange(xi);
+        let mut compacted = vec![];
+        let mut gap = 1;
+        while gap < xi.len() {
+            let num_chunks = xi.len() / (2 * gap);
+            let (roots, step) =
+                round_roots(&self.roots_inv, &mut compacted, num_chunks * stride, gap);
+            apply_butterfly(
+                |((lo, hi), root)| {
+                    *hi *= root;
+                    let neg = *lo - *hi;
+                    *lo += *hi;
+                    *hi = neg;
+                },
+                xi,
+                roots,
+                step,
+                gap,
+            );
+            gap *= 2;
+        }
+        xi.par_iter_mut().for_each(|x| *x *= domain.size_inv);
+    }
+
+    /// Interpolates evaluations into a polynomial, like [Evaluations::interpolate].
+    /// The missing evaluations, at the end of the domain, are zero.
+    pub fn interpolate(&self, evals: Evaluations<F, D<F>>) -> DensePolynomial<F> {
+        let domain = evals.domain();
+        let mut coeffs = evals.evals;
+        coeffs.resize(domain.size as usize, F::zero());
+        self.ifft_in_place(domain, &mut coeffs);
+        DensePolynomial::from_coefficients_vec(coeffs)
+    }
+
+    /// Evaluates a polynomial over a domain, like [DensePolynomial::evaluate_over_domain_by_ref].
+    /// The coefficients of the powers larger than the size of the domain are folded onto the smaller ones.
+    pub fn evaluate_over_domain(
+        &self,
+        poly: &DensePolynomial<F>,
+        domain: D<F>,
+    ) -> Evaluations<F, D<F>> {
+        let size = domain.size as usize;
+        let mut evals = vec![F::zero(); size];
+        for chunk in poly.coeffs.chunks(size) {
+            evals
+                .par_iter_mut()
+                .zip(chunk)
+                .for_each(|(eval, coeff)| *eval += coeff);
+        }
+        self.fft_in_place(domain, &mut evals);
+        Evaluations::from_vec_and_domain(evals, domain)
+    }
+}
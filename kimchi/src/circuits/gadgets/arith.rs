@@ -0,0 +1,122 @@
+//! This module implements boolean, selection, equality and comparison gadgets on the generic gate,
+//! which complement the arithmetic of [super::builder::CircuitBuilder].
+//!
+//! Booleans are cells holding 0 or 1. The gadgets taking booleans do not constrain them to be booleans,
+//! which is the responsibility of the gadget that created them (e.g. [CircuitBuilder::boolean]).
+
+use crate::circuits::gadgets::{
+    builder::{CircuitBuilder, Var},
+    scalar::{from_bits, BITS_PER_ROW},
+};
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+
+impl<F: PrimeField> CircuitBuilder<F> {
+    /// Adds a boolean to the witness, and constrains it to be a boolean.
+    pub fn boolean(&mut self, b: bool) -> Var {
+        let b = self.witness(F::from(b as u64));
+        self.assert_boolean(b);
+        b
+    }
+
+    /// Returns `1 - a`, for a boolean `a`.
+    pub fn not(&mut self, a: Var) -> Var {
+        self.arith(a, a, -F::one(), F::zero(), F::zero(), F::one())
+    }
+
+    /// Returns `a and b`, for booleans `a` and `b`.
+    pub fn and(&mut self, a: Var, b: Var) -> Var {
+        self.mul(a, b)
+    }
+
+    /// Returns `a or b`, as `a + b - a * b`, for booleans `a` and `b`.
+    pub fn or(&mut self, a: Var, b: Var) -> Var {
+        self.arith(a, b, F::one(), F::one(), -F::one(), F::zero())
+    }
+
+    /// Returns `a xor b`, as `a + b - 2 * a * b`, for booleans `a` and `b`.
+    pub fn xor(&mut self, a: Var, b: Var) -> Var {
+        self.arith(a, b, F::one(), F::one(), -F::from(2u64), F::zero())
+    }
+
+    /// Returns `x` if `b` is 1, and `y` if `b` is 0, as `b * (x - y) + y`, for a boolean `b`.
+    pub fn select(&mut self, b: Var, x: Var, y: Var) -> Var {
+        let diff = self.sub(x, y);
+        let swap = self.mul(b, diff);
+        self.add(swap, y)
+    }
+
+    /// Returns 1 if `a` is zero, and 0 otherwise.
+    /// The witness holds the inverse of `a` (or zero) as a hint.
+    pub fn is_zero(&mut self, a: Var) -> Var {
+        let av = self.value(a);
+        let inv = av.inverse().unwrap_or_else(F::zero);
+        let out = F::one() - av * inv;
+
+        // a * inv + out - 1 = 0, so that out = 1 when a = 0
+        let [a1, _, out1] = self.generic(
+            [F::zero(), F::zero(), F::one(), F::one(), -F::one()],
+            [av, inv, out],
+        );
+        // a * out = 0, so that out = 0 when a != 0
+        let [a2, out2, _] = self.generic(
+            [F::zero(), F::zero(), F::zero(), F::one(), F::zero()],
+            [av, out, F::zero()],
+        );
+        self.assert_equal(a, a1);
+        self.assert_equal(a, a2);
+        self.assert_equal(out1, out2);
+        out1
+    }
+
+    /// Returns 1 if `a` and `b` are equal, and 0 otherwise.
+    pub fn is_equal(&mut self, a: Var, b: Var) -> Var {
+        let diff = self.sub(a, b);
+        self.is_zero(diff)
+    }
+
+    /// Returns `sum_i 2^i bits[i]`, the value of `bits`, least significant first.
+    /// `bits` are not constrained to be booleans (see [CircuitBuilder::unpack] for the converse).
+    pub fn pack(&mut self, bits: &[Var]) -> Var {
+        let (first, rest) = bits.split_first().expect("at least one bit");
+        rest.iter().enumerate().fold(*first, |acc, (i, b)| {
+            let c = F::from(2u64).pow(&[i as u64 + 1]);
+            self.arith(acc, *b, F::one(), c, F::zero(), F::zero())
+        })
+    }
+
+    /// Returns 1 if `a < b`, and 0 otherwise, for `a` and `b` smaller than `2^num_bits` (with `num_bits > 0`),
+    /// which is not constrained here.
+    /// It decomposes `2^num_bits + a - b`, whose bit `num_bits` is set if and only if `a >= b`;
+    /// the other bits are range checked with [CircuitBuilder::range_check] when `num_bits` is a multiple of 16.
+    pub fn less_than(&mut self, a: Var, b: Var, num_bits: usize) -> Var {
+        assert!(
+            num_bits > 0 && num_bits + 1 < F::Params::MODULUS_BITS as usize,
+            "the comparison does not fit in the field"
+        );
+        let two_n = F::from(2u64).pow(&[num_bits as u64]);
+        let c = self.arith(a, b, F::one(), -F::one(), F::zero(), two_n);
+
+        // c = 2^num_bits * hi + lo, with hi a bit and lo < 2^num_bits
+        let bits = self.value(c).into_repr().to_bits_le();
+        let hi = self.boolean(bits[num_bits]);
+        let lo = if num_bits % BITS_PER_ROW == 0 {
+            let lo = self.witness(from_bits(&bits[..num_bits]));
+            self.range_check(lo, num_bits);
+            lo
+        } else {
+            let lo_bits: Vec<_> = bits[..num_bits].iter().map(|b| self.boolean(*b)).collect();
+            self.pack(&lo_bits)
+        };
+        let recomposed = self.arith(hi, lo, two_n, F::one(), F::zero(), F::zero());
+        self.assert_equal(c, recomposed);
+
+        self.not(hi)
+    }
+
+    /// Constrains `a < b`, for `a` and `b` smaller than `2^num_bits` (see [CircuitBuilder::less_than]).
+    pub fn assert_less_than(&mut self, a: Var, b: Var, num_bits: usize) {
+        let lt = self.less_than(a, b, num_bits);
+        let one = self.constant(F::one());
+        self.assert_equal(lt, one);
+    }
+}
@@ -0,0 +1,295 @@
+//! This module implements [CircuitBuilder], which lays out a circuit row by row,
+//! and the arithmetic gadgets built on the generic gate.
+
+use crate::circuits::{
+    gate::{CircuitGate, GateType},
+    polynomials::generic::{GENERIC_COEFFS, GENERIC_REGISTERS},
+    wires::{Wire, COLUMNS, PERMUTS},
+};
+use ark_ff::PrimeField;
+use array_init::array_init;
+use oracle::poseidon::ArithmeticSpongeParams;
+use std::collections::HashMap;
+
+/// A cell of the witness.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Var {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// The gates, witness and public input produced by a [CircuitBuilder].
+pub struct BuiltCircuit<F: PrimeField> {
+    /// the gates of the circuit, with the public input gates first
+    pub gates: Vec<CircuitGate<F>>,
+    /// a witness satisfying the gates
+    pub witness: [Vec<F>; COLUMNS],
+    /// the public input of the circuit
+    pub public: Vec<F>,
+}
+
+/// Lays out the gates of a circuit and their witness, row by row.
+///
+/// Cells are wired together with [CircuitBuilder::assert_equal],
+/// and the wiring of the gates is only computed by [CircuitBuilder::build].
+pub struct CircuitBuilder<F: PrimeField> {
+    /// the type and coefficients of each row
+    gates: Vec<(GateType, Vec<F>)>,
+    /// the witness of each row
+    witness: [Vec<F>; COLUMNS],
+    /// the number of public inputs
+    public: usize,
+    /// the pairs of cells that must be equal
+    copies: Vec<(Var, Var)>,
+    /// a generic gate whose second half is still free
+    free_generic: Option<usize>,
+    /// the cells already holding a constant
+    constants: HashMap<F, Var>,
+    /// the sponge parameters of the circuit (the ones passed to its constraint system)
+    params: ArithmeticSpongeParams<F>,
+}
+
+impl<F: PrimeField> CircuitBuilder<F> {
+    /// Creates an empty circuit, which will be used with the sponge parameters `params`.
+    pub fn new(params: ArithmeticSpongeParams<F>) -> Self {
+        CircuitBuilder {
+            gates: vec![],
+            witness: array_init(|_| vec![]),
+            public: 0,
+            copies: vec![],
+            free_generic: None,
+            constants: HashMap::new(),
+            params,
+        }
+    }
+
+    /// The sponge parameters of the circuit.
+    pub fn params(&self) -> &ArithmeticSpongeParams<F> {
+        &self.params
+    }
+
+    /// The number of rows laid out so far.
+    pub fn num_rows(&self) -> usize {
+        self.gates.len()
+    }
+
+    /// The value of a cell in the witness.
+    pub fn value(&self, v: Var) -> F {
+        self.witness[v.col][v.row]
+    }
+
+    /// Adds a row with a gate of type `typ`, its coefficients, and its witness.
+    /// Returns the index of the row.
+    pub fn gate(&mut self, typ: GateType, coeffs: Vec<F>, values: [F; COLUMNS]) -> usize {
+        let row = self.gates.len();
+        self.gates.push((typ, coeffs));
+        for (col, value) in values.iter().enumerate() {
+            self.witness[col].push(*value);
+        }
+        row
+    }
+
+    /// Adds the rows of a witness laid out from its first row, with the given gate types.
+    /// Returns the index of the first row.
+    pub fn gates(&mut self, gates: Vec<(GateType, Vec<F>)>, rows: &[Vec<F>; COLUMNS]) -> usize {
+        let first = self.gates.len();
+        for (i, (typ, coeffs)) in gates.into_iter().enumerate() {
+            self.gate(typ, coeffs, array_init(|col| rows[col][i]));
+        }
+        first
+    }
+
+    /// Wires two cells together, constraining them to be equal.
+    pub fn assert_equal(&mut self, a: Var, b: Var) {
+        assert!(
+            a.col < PERMUTS && b.col < PERMUTS,
+            "only the first {} columns can be wired",
+            PERMUTS
+        );
+        if a != b {
+            self.copies.push((a, b));
+        }
+    }
+
+    /// Adds a public input to the circuit.
+    /// Public inputs have to be added before any other gate.
+    pub fn public_input(&mut self, value: F) -> Var {
+        assert_eq!(
+            self.gates.len(),
+            self.public,
+            "public inputs must be added before any other gate"
+        );
+        let mut coeffs = vec![F::zero(); GENERIC_COEFFS * 2];
+        coeffs[0] = F::one();
+        let mut values = [F::zero(); COLUMNS];
+        values[0] = value;
+        let row = self.gate(GateType::Generic, coeffs, values);
+        self.public += 1;
+        Var { row, col: 0 }
+    }
+
+    /// Adds a half generic gate `c0 * l + c1 * r + c2 * o + c3 * l * r + c4 = 0`
+    /// with the `values` of its registers `[l, r, o]`.
+    /// Returns the cells of the registers.
+    pub fn generic(
+        &mut self,
+        coeffs: [F; GENERIC_COEFFS],
+        values: [F; GENERIC_REGISTERS],
+    ) -> [Var; GENERIC_REGISTERS] {
+        let (row, half) = match self.free_generic.take() {
+            Some(row) => (row, 1),
+            None => {
+                let row = self.gate(
+                    GateType::Generic,
+                    vec![F::zero(); GENERIC_COEFFS * 2],
+                    [F::zero(); COLUMNS],
+                );
+                self.free_generic = Some(row);
+                (row, 0)
+            }
+        };
+
+        self.gates[row].1[half * GENERIC_COEFFS..(half + 1) * GENERIC_COEFFS]
+            .copy_from_slice(&coeffs);
+        array_init(|i| {
+            let col = half * GENERIC_REGISTERS + i;
+            self.witness[col][row] = values[i];
+            Var { row, col }
+        })
+    }
+
+    /// Adds an unconstrained value to the witness.
+    pub fn witness(&mut self, value: F) -> Var {
+        let [l, _, _] = self.generic([F::zero(); GENERIC_COEFFS], [value, F::zero(), F::zero()]);
+        l
+    }
+
+    /// Returns a cell constrained to the constant `c`.
+    pub fn constant(&mut self, c: F) -> Var {
+        if let Some(v) = self.constants.get(&c) {
+            return *v;
+        }
+        let mut coeffs = [F::zero(); GENERIC_COEFFS];
+        coeffs[0] = F::one();
+        coeffs[4] = -c;
+        let [l, _, _] = self.generic(coeffs, [c, F::zero(), F::zero()]);
+        self.constants.insert(c, l);
+        l
+    }
+
+    /// Returns a cell constrained to `cl * l + cr * r + cm * l * r + cc`.
+    pub fn arith(&mut self, l: Var, r: Var, cl: F, cr: F, cm: F, cc: F) -> Var {
+        let (lv, rv) = (self.value(l), self.value(r));
+        let out = cl * lv + cr * rv + cm * lv * rv + cc;
+        let [l2, r2, o] = self.generic([cl, cr, -F::one(), cm, cc], [lv, rv, out]);
+        self.assert_equal(l, l2);
+        self.assert_equal(r, r2);
+        o
+    }
+
+    /// Returns `a + b`.
+    pub fn add(&mut self, a: Var, b: Var) -> Var {
+        self.arith(a, b, F::one(), F::one(), F::zero(), F::zero())
+    }
+
+    /// Returns `a - b`.
+    pub fn sub(&mut self, a: Var, b: Var) -> Var {
+        self.arith(a, b, F::one(), -F::one(), F::zero(), F::zero())
+    }
+
+    /// Returns `a * b`.
+    pub fn mul(&mut self, a: Var, b: Var) -> Var {
+        self.arith(a, b, F::zero(), F::zero(), F::one(), F::zero())
+    }
+
+    /// Returns `a^2`.
+    pub fn square(&mut self, a: Var) -> Var {
+        self.mul(a, a)
+    }
+
+    /// Returns `c * a`.
+    pub fn scale(&mut self, a: Var, c: F) -> Var {
+        self.arith(a, a, c, F::zero(), F::zero(), F::zero())
+    }
+
+    /// Returns `a + c`.
+    pub fn add_constant(&mut self, a: Var, c: F) -> Var {
+        self.arith(a, a, F::one(), F::zero(), F::zero(), c)
+    }
+
+    /// Returns `1 / a`. The witness generation panics if `a` is zero.
+    pub fn inv(&mut self, a: Var) -> Var {
+        let av = self.value(a);
+        let inv = av.inverse().expect("inverse of zero");
+        // a * inv - 1 = 0
+        let [a2, out, _] = self.generic(
+            [F::zero(), F::zero(), F::zero(), F::one(), -F::one()],
+            [av, inv, F::zero()],
+        );
+        self.assert_equal(a, a2);
+        out
+    }
+
+    /// Constrains `a` to be a boolean.
+    pub fn assert_boolean(&mut self, a: Var) {
+        let av = self.value(a);
+        // a * a - a = 0
+        let [l, r, _] = self.generic(
+            [-F::one(), F::zero(), F::zero(), F::one(), F::zero()],
+            [av, av, F::zero()],
+        );
+        self.assert_equal(a, l);
+        self.assert_equal(a, r);
+    }
+
+    /// Produces the gates, with their wiring, and the witness of the circuit.
+    pub fn build(self) -> BuiltCircuit<F> {
+        let rows = self.gates.len();
+
+        // group the wired cells into equivalence classes
+        let index = |v: Var| v.row * PERMUTS + v.col;
+        let mut parent: Vec<usize> = (0..rows * PERMUTS).collect();
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for (a, b) in &self.copies {
+            let (ra, rb) = (find(&mut parent, index(*a)), find(&mut parent, index(*b)));
+            parent[ra] = rb;
+        }
+
+        // each class becomes a cycle of the permutation
+        let mut classes: HashMap<usize, Vec<usize>> = HashMap::new();
+        for i in 0..rows * PERMUTS {
+            let root = find(&mut parent, i);
+            classes.entry(root).or_default().push(i);
+        }
+        let mut wires: Vec<[Wire; PERMUTS]> = (0..rows).map(Wire::new).collect();
+        for cells in classes.values() {
+            for (i, cell) in cells.iter().enumerate() {
+                let next = cells[(i + 1) % cells.len()];
+                wires[cell / PERMUTS][cell % PERMUTS] = Wire {
+                    row: next / PERMUTS,
+                    col: next % PERMUTS,
+                };
+            }
+        }
+
+        let gates = self
+            .gates
+            .into_iter()
+            .zip(wires)
+            .map(|((typ, coeffs), wires)| CircuitGate { typ, wires, coeffs })
+            .collect();
+        let public = self.witness[0][..self.public].to_vec();
+
+        BuiltCircuit {
+            gates,
+            witness: self.witness,
+            public,
+        }
+    }
+}
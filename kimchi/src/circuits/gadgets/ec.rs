@@ -0,0 +1,400 @@
+//! This module implements elliptic curve gadgets,
+//! on the `CompleteAdd`, `VarBaseMul`, `EndoMul` and `FixedBaseMul` gates,
+//! for a curve whose base field is the field of the circuit.
+//!
+//! Points enter the circuit with [CircuitBuilder::witness_point] or [CircuitBuilder::constant_point],
+//! and the gadgets lay out the rows of the gates and wire them, so that their layouts need not be known.
+
+use crate::circuits::{
+    gadgets::{
+        builder::{CircuitBuilder, Var},
+        scalar::{from_bits, ShiftedScalar, CHALLENGE_BITS},
+    },
+    gate::GateType,
+    polynomials::{
+        endosclmul,
+        fixed_base_mul::{self, FixedBaseTable},
+        varbasemul,
+    },
+    wires::COLUMNS,
+};
+use ark_ec::{short_weierstrass_jacobian::GroupAffine, SWModelParameters};
+use ark_ff::{BigInteger, PrimeField};
+use array_init::array_init;
+use oracle::sponge::ScalarChallenge;
+
+/// The number of bits of the scalars multiplied by [CircuitBuilder::scale_shifted].
+const SCALAR_BITS: usize = 255;
+
+/// The number of bits processed by a `VarBaseMul` gate.
+const VARBASEMUL_BITS_PER_ROW: usize = 5;
+
+/// The number of bits processed by an `EndoMul` gate.
+const ENDOMUL_BITS_PER_ROW: usize = 4;
+
+/// A point of the curve, which is never the point at infinity.
+#[derive(Clone, Copy, Debug)]
+pub struct PointVar {
+    pub x: Var,
+    pub y: Var,
+}
+
+impl<F: PrimeField> CircuitBuilder<F> {
+    /// The coordinates of a point.
+    pub fn point_value(&self, p: PointVar) -> (F, F) {
+        (self.value(p.x), self.value(p.y))
+    }
+
+    /// Returns cells constrained to the coordinates of the point `g`,
+    /// or `None` if `g` is the point at infinity.
+    pub fn constant_point<P: SWModelParameters<BaseField = F>>(
+        &mut self,
+        g: &GroupAffine<P>,
+    ) -> Option<PointVar> {
+        if g.infinity {
+            return None;
+        }
+        Some(PointVar {
+            x: self.constant(g.x),
+            y: self.constant(g.y),
+        })
+    }
+
+    /// Adds the point `g` to the witness, and constrains it to be on the curve.
+    /// The witness generation panics if `g` is the point at infinity.
+    pub fn witness_point<P: SWModelParameters<BaseField = F>>(
+        &mut self,
+        g: &GroupAffine<P>,
+    ) -> PointVar {
+        assert!(!g.infinity, "the point at infinity is not supported");
+        let p = PointVar {
+            x: self.witness(g.x),
+            y: self.witness(g.y),
+        };
+        self.assert_on_curve::<P>(p);
+        p
+    }
+
+    /// Constrains `p` to be on the curve.
+    pub fn assert_on_curve<P: SWModelParameters<BaseField = F>>(&mut self, p: PointVar) {
+        assert!(P::COEFF_A.is_zero(), "only curves with a = 0 are supported");
+        // y^2 = x^3 + b
+        let x2 = self.square(p.x);
+        let x3 = self.mul(x2, p.x);
+        let y2 = self.square(p.y);
+        let rhs = self.add_constant(x3, P::COEFF_B);
+        self.assert_equal(y2, rhs);
+    }
+
+    /// Constrains two points to be equal.
+    pub fn assert_points_equal(&mut self, p: PointVar, q: PointVar) {
+        self.assert_equal(p.x, q.x);
+        self.assert_equal(p.y, q.y);
+    }
+
+    /// Returns `p + q`, with a `CompleteAdd` gate.
+    /// The result is constrained not to be the point at infinity.
+    pub fn add_points(&mut self, p: PointVar, q: PointVar) -> PointVar {
+        let ((x1, y1), (x2, y2)) = (self.point_value(p), self.point_value(q));
+
+        let mut values = [F::zero(); COLUMNS];
+        values[..4].copy_from_slice(&[x1, y1, x2, y2]);
+        let s = if x1 == x2 {
+            assert!(y1 == y2, "the sum of the points is the point at infinity");
+            // same_x
+            values[7] = F::one();
+            let x1_squared = x1.square();
+            (x1_squared.double() + x1_squared) / y1.double()
+        } else {
+            // x21_inv
+            values[10] = (x2 - x1).inverse().unwrap();
+            (y2 - y1) / (x2 - x1)
+        };
+        let x3 = s.square() - x1 - x2;
+        values[4] = x3;
+        values[5] = s * (x1 - x3) - y1;
+        values[8] = s;
+
+        let row = self.gate(GateType::CompleteAdd, vec![], values);
+
+        let cell = |col| Var { row, col };
+        self.assert_points_equal(
+            p,
+            PointVar {
+                x: cell(0),
+                y: cell(1),
+            },
+        );
+        self.assert_points_equal(
+            q,
+            PointVar {
+                x: cell(2),
+                y: cell(3),
+            },
+        );
+        let zero = self.constant(F::zero());
+        self.assert_equal(zero, cell(6));
+
+        PointVar {
+            x: cell(4),
+            y: cell(5),
+        }
+    }
+
+    /// Returns `[2] p`, with a `CompleteAdd` gate.
+    pub fn double_point(&mut self, p: PointVar) -> PointVar {
+        self.add_points(p, p)
+    }
+
+    /// Returns `[s] t`, with `VarBaseMul` gates, for a scalar `s` of the curve.
+    /// The representation of `s` is added to the witness, and returned with the result.
+    /// The witness generation panics if the result is the point at infinity.
+    pub fn scalar_mul<S: PrimeField>(&mut self, t: PointVar, s: S) -> (PointVar, ShiftedScalar) {
+        let scalar = self.shifted_scalar(s);
+        (self.scale_shifted(t, scalar), scalar)
+    }
+
+    /// Returns `[c] t`, with `EndoMul` gates, where `c` is the scalar of the 128-bit `challenge`.
+    /// The challenge is added to the witness, and returned with the result.
+    /// See [CircuitBuilder::endo_scale] for the requirements on `endo`.
+    pub fn endo_mul<S: PrimeField>(
+        &mut self,
+        t: PointVar,
+        endo: F,
+        challenge: ScalarChallenge<S>,
+    ) -> (PointVar, Var) {
+        let bits = challenge.0.into_repr().to_bits_le();
+        assert!(
+            bits[CHALLENGE_BITS..].iter().all(|b| !b),
+            "the challenge does not fit in {} bits",
+            CHALLENGE_BITS
+        );
+        let challenge = self.witness(from_bits(&bits[..CHALLENGE_BITS]));
+        (self.endo_scale(t, endo, challenge), challenge)
+    }
+
+    /// Returns `[n] G`, with one `FixedBaseMul` gate per window of `table`, where `G` is the base of `table`
+    /// and `n < 2^254` is read as an integer.
+    /// This also constrains `n` to be smaller than `2^254`.
+    /// The lookup table of `table` must be passed to the constraint system of the circuit,
+    /// which cannot then have ChaCha gates, as they need another lookup table.
+    /// The witness generation panics if the result is the point at infinity, in particular if `n` is zero.
+    pub fn fixed_base_mul<P: SWModelParameters<BaseField = F>>(
+        &mut self,
+        table: &FixedBaseTable<P>,
+        n: Var,
+    ) -> PointVar {
+        assert!(
+            !self.value(n).is_zero(),
+            "[0] G is the point at infinity, which is not supported"
+        );
+        let mut rows: [Vec<F>; COLUMNS] = array_init(|_| vec![]);
+        fixed_base_mul::gen_witness(&mut rows, table, self.value(n));
+
+        let num_windows = table.num_windows();
+        let mut gates = vec![(GateType::FixedBaseMul, vec![]); num_windows];
+        gates.push((GateType::Zero, vec![]));
+        let first = self.gates(gates, &rows);
+        let cell = |row, col| Var { row, col };
+
+        // the accumulators start at the offset and 0,
+        // from the last window (numbered from 1), with the factor 2^w
+        let offset = self
+            .constant_point(&table.offset)
+            .expect("the offset is not the point at infinity");
+        self.assert_points_equal(
+            offset,
+            PointVar {
+                x: cell(first, 0),
+                y: cell(first, 1),
+            },
+        );
+        let zero = self.constant(F::zero());
+        self.assert_equal(zero, cell(first, 2));
+        let last_window = self.constant(F::from(num_windows as u64));
+        self.assert_equal(last_window, cell(first, 3));
+        let factor = self.constant(F::from(2u64).pow(&[table.window_bits as u64]));
+        self.assert_equal(factor, cell(first, 4));
+
+        // the row after the last window holds the scalar and the result
+        let last = first + num_windows;
+        self.assert_equal(n, cell(last, 2));
+        PointVar {
+            x: cell(last, 0),
+            y: cell(last, 1),
+        }
+    }
+
+    /// Returns `[s] t`, with `VarBaseMul` gates, where `s` is the scalar represented by `scalar`.
+    /// This also constrains the representation of `s`: `hi` is smaller than `2^254` and `lo` is a bit.
+    pub fn scale_shifted(&mut self, t: PointVar, scalar: ShiftedScalar) -> PointVar {
+        // the bits of 2 * hi + lo, most significant first
+        let hi_bits = self.value(scalar.hi).into_repr().to_bits_le();
+        let lo_bit = self.value(scalar.lo) == F::one();
+        let bits: Vec<_> = std::iter::once(lo_bit)
+            .chain(hi_bits.into_iter().take(SCALAR_BITS - 1))
+            .rev()
+            .collect();
+
+        // the accumulator starts at [2] t
+        let acc0 = self.add_points(t, t);
+
+        let chunks = SCALAR_BITS / VARBASEMUL_BITS_PER_ROW;
+        let mut rows: [Vec<F>; COLUMNS] = array_init(|_| vec![F::zero(); 2 * chunks]);
+        varbasemul::witness(
+            &mut rows,
+            0,
+            self.point_value(t),
+            &bits,
+            self.point_value(acc0),
+        );
+        let gates = (0..chunks)
+            .flat_map(|_| [(GateType::VarBaseMul, vec![]), (GateType::Zero, vec![])])
+            .collect();
+        let first = self.gates(gates, &rows);
+
+        let zero = self.constant(F::zero());
+        for chunk in 0..chunks {
+            let row = first + 2 * chunk;
+            let cell = |col| Var { row, col };
+
+            // the base
+            self.assert_points_equal(
+                t,
+                PointVar {
+                    x: cell(0),
+                    y: cell(1),
+                },
+            );
+
+            // the accumulators
+            let (acc, n_prev) = if chunk == 0 {
+                (acc0, zero)
+            } else {
+                let acc = PointVar {
+                    x: Var {
+                        row: row - 1,
+                        col: 0,
+                    },
+                    y: Var {
+                        row: row - 1,
+                        col: 1,
+                    },
+                };
+                (
+                    acc,
+                    Var {
+                        row: row - 2,
+                        col: 5,
+                    },
+                )
+            };
+            self.assert_points_equal(
+                acc,
+                PointVar {
+                    x: cell(2),
+                    y: cell(3),
+                },
+            );
+            self.assert_equal(n_prev, cell(4));
+        }
+
+        // hi = 16 * n + (the first 4 bits of the last chunk), where n is the value of the
+        // 250 first bits, so that hi is the integer read from the bits, and lo is the last bit.
+        let last = first + 2 * (chunks - 1);
+        let n = Var { row: last, col: 4 };
+        let bit = |i: usize| Var {
+            row: last + 1,
+            col: 2 + i,
+        };
+        let mut acc = self.arith(
+            n,
+            bit(0),
+            F::from(16u64),
+            F::from(8u64),
+            F::zero(),
+            F::zero(),
+        );
+        for (i, c) in [(1, 4u64), (2, 2), (3, 1)] {
+            acc = self.arith(acc, bit(i), F::one(), F::from(c), F::zero(), F::zero());
+        }
+        self.assert_equal(acc, scalar.hi);
+        self.assert_equal(bit(4), scalar.lo);
+
+        PointVar {
+            x: Var {
+                row: last + 1,
+                col: 0,
+            },
+            y: Var {
+                row: last + 1,
+                col: 1,
+            },
+        }
+    }
+
+    /// Returns `[c] t`, with `EndoMul` gates, where `c` is the scalar of the 128-bit `challenge`
+    /// (as computed by [oracle::sponge::ScalarChallenge::to_field]).
+    /// `endo` is the coefficient of the endomorphism on the base field,
+    /// which must be the `endo` of the constraint system of the circuit.
+    /// This also constrains `challenge` to be smaller than `2^128`.
+    pub fn endo_scale(&mut self, t: PointVar, endo: F, challenge: Var) -> PointVar {
+        let bits: Vec<_> = self
+            .value(challenge)
+            .into_repr()
+            .to_bits_le()
+            .into_iter()
+            .take(CHALLENGE_BITS)
+            .rev()
+            .collect();
+
+        // the accumulator starts at [2] (t + endo(t))
+        let phi_t = PointVar {
+            x: self.scale(t.x, endo),
+            y: t.y,
+        };
+        let sum = self.add_points(t, phi_t);
+        let acc0 = self.add_points(sum, sum);
+
+        let num_rows = CHALLENGE_BITS / ENDOMUL_BITS_PER_ROW;
+        let mut rows: [Vec<F>; COLUMNS] = array_init(|_| vec![F::zero(); num_rows + 1]);
+        endosclmul::gen_witness(
+            &mut rows,
+            0,
+            endo,
+            self.point_value(t),
+            &bits,
+            self.point_value(acc0),
+        );
+        let mut gates = vec![(GateType::EndoMul, vec![]); num_rows];
+        // the result is stored in the row following the EndoMul gates
+        gates.push((GateType::Zero, vec![]));
+        let first = self.gates(gates, &rows);
+
+        for row in first..first + num_rows {
+            self.assert_points_equal(
+                t,
+                PointVar {
+                    x: Var { row, col: 0 },
+                    y: Var { row, col: 1 },
+                },
+            );
+        }
+        self.assert_points_equal(
+            acc0,
+            PointVar {
+                x: Var { row: first, col: 4 },
+                y: Var { row: first, col: 5 },
+            },
+        );
+        let zero = self.constant(F::zero());
+        self.assert_equal(zero, Var { row: first, col: 6 });
+
+        let last = first + num_rows;
+        self.assert_equal(challenge, Var { row: last, col: 6 });
+        PointVar {
+            x: Var { row: last, col: 4 },
+            y: Var { row: last, col: 5 },
+        }
+    }
+}
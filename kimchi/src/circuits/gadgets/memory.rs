@@ -0,0 +1,230 @@
+//! This module implements a read/write memory, as a trace of accesses checked by [CircuitBuilder::assert_memory_consistency].
+//!
+//! As in the lookup argument (see [crate::circuits::polynomials::lookup]),
+//! the accesses are checked through a sorted copy of them:
+//!
+//! * the sorted trace is a permutation of the trace, which is checked with the grand products
+//!   `prod_i (gamma - key_i - beta * value_i)` of both traces,
+//!   where the key `(addr * 2^num_bits + timestamp) * 2 + is_write` packs the other fields of an access.
+//!   The challenges `beta` and `gamma` are squeezed from a sponge that absorbed the keys and the values of both traces,
+//!   so that they are only known once the sorted trace is fixed.
+//! * the sorted trace is ordered by address, and then by timestamp,
+//!   so that the accesses to an address are adjacent, in the order they happened.
+//!   A single comparison of `addr * 2^num_bits + timestamp` checks both orders.
+//! * each read of the sorted trace returns the value of the previous access if it has the same address,
+//!   and zero otherwise: the memory is initialized with zeros.
+//!
+//! The sorted columns of the lookup argument cannot hold the sorted trace:
+//! the argument checks the lookups against tables fixed in the index,
+//! whereas both traces are part of the witness.
+//! The permutation is thus checked in the circuit, with challenges derived by Fiat-Shamir
+//! as the recursive verifier does (see [crate::circuits::gadgets::verifier]),
+//! whose sponge is most of the cost of an access (see the test `memory_rows_per_access`).
+
+use crate::circuits::gadgets::{
+    builder::{CircuitBuilder, Var},
+    sponge::SpongeGadget,
+};
+use ark_ff::{FpParameters, PrimeField};
+use std::collections::HashMap;
+
+/// An access to the memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Access<F> {
+    /// the address accessed
+    pub addr: F,
+    /// the value read or written
+    pub value: F,
+    /// the time of the access, which is unique
+    pub timestamp: F,
+    /// whether the value is written to the address, or read from it
+    pub is_write: bool,
+}
+
+/// An access to the memory, in a circuit.
+#[derive(Clone, Copy, Debug)]
+pub struct AccessVar {
+    pub addr: Var,
+    pub value: Var,
+    pub timestamp: Var,
+    /// a boolean, which is 1 for a write
+    pub is_write: Var,
+}
+
+/// Records the accesses to a memory initialized with zeros,
+/// with the timestamps `0, 1, 2, ...`, to produce the trace of a computation.
+#[derive(Clone, Debug, Default)]
+pub struct Memory<F: PrimeField> {
+    values: HashMap<F, F>,
+    /// the accesses so far, in the order they happened
+    pub trace: Vec<Access<F>>,
+}
+
+impl<F: PrimeField> Memory<F> {
+    /// Creates a memory initialized with zeros.
+    pub fn new() -> Self {
+        Memory {
+            values: HashMap::new(),
+            trace: vec![],
+        }
+    }
+
+    fn access(&mut self, addr: F, value: F, is_write: bool) {
+        self.trace.push(Access {
+            addr,
+            value,
+            timestamp: F::from(self.trace.len() as u64),
+            is_write,
+        });
+    }
+
+    /// Reads the value at `addr`.
+    pub fn read(&mut self, addr: F) -> F {
+        let value = self.values.get(&addr).copied().unwrap_or_else(F::zero);
+        self.access(addr, value, false);
+        value
+    }
+
+    /// Writes `value` at `addr`.
+    pub fn write(&mut self, addr: F, value: F) {
+        self.values.insert(addr, value);
+        self.access(addr, value, true);
+    }
+}
+
+/// Returns the accesses of `trace` sorted by address, and then by timestamp.
+pub fn sorted_trace<F: PrimeField>(trace: &[Access<F>]) -> Vec<Access<F>> {
+    let mut sorted = trace.to_vec();
+    sorted.sort_by_key(|a| (a.addr.into_repr(), a.timestamp.into_repr()));
+    sorted
+}
+
+impl<F: PrimeField> CircuitBuilder<F> {
+    /// Adds an access to the witness, and constrains its `is_write` flag to be a boolean.
+    pub fn witness_access(&mut self, access: &Access<F>) -> AccessVar {
+        AccessVar {
+            addr: self.witness(access.addr),
+            value: self.witness(access.value),
+            timestamp: self.witness(access.timestamp),
+            is_write: self.boolean(access.is_write),
+        }
+    }
+
+    /// Returns the value of an access in the witness.
+    pub fn access_value(&self, access: &AccessVar) -> Access<F> {
+        Access {
+            addr: self.value(access.addr),
+            value: self.value(access.value),
+            timestamp: self.value(access.timestamp),
+            is_write: self.value(access.is_write) == F::one(),
+        }
+    }
+
+    /// Constrains `trace` to be the accesses to a memory initialized with zeros:
+    /// each read returns the value of the last write to its address before it, or zero if there is none.
+    /// The accesses can be in any order, as their timestamps order them,
+    /// and their addresses and timestamps are constrained to be smaller than `2^num_bits`,
+    /// where `num_bits` is a multiple of 16.
+    /// The timestamps of the accesses must be distinct.
+    pub fn assert_memory_consistency(&mut self, trace: &[AccessVar], num_bits: usize) {
+        assert!(
+            2 * num_bits + 1 < F::Params::MODULUS_BITS as usize,
+            "the keys of the accesses do not fit in the field"
+        );
+        if trace.is_empty() {
+            return;
+        }
+        let values: Vec<_> = trace.iter().map(|a| self.access_value(a)).collect();
+        let sorted: Vec<_> = sorted_trace(&values)
+            .iter()
+            .map(|a| self.witness_access(a))
+            .collect();
+        let trace_keys: Vec<_> = trace
+            .iter()
+            .map(|a| {
+                // the flags of the sorted accesses are constrained by [CircuitBuilder::witness_access]
+                self.assert_boolean(a.is_write);
+                self.access_key(a, num_bits)
+            })
+            .collect();
+        let sorted_keys: Vec<_> = sorted
+            .iter()
+            .map(|a| self.access_key(a, num_bits))
+            .collect();
+
+        // the sorted trace is a permutation of the trace
+        let mut sponge = SpongeGadget::new(self);
+        let accesses = trace.iter().zip(&trace_keys);
+        for (a, (_, key)) in accesses.clone().chain(sorted.iter().zip(&sorted_keys)) {
+            sponge.absorb(self, *key);
+            sponge.absorb(self, a.value);
+        }
+        let beta = sponge.squeeze(self);
+        let gamma = sponge.squeeze(self);
+        let trace_product = self.access_product(trace, &trace_keys, beta, gamma);
+        let sorted_product = self.access_product(&sorted, &sorted_keys, beta, gamma);
+        self.assert_equal(trace_product, sorted_product);
+
+        // the sorted trace is ordered, and its reads are consistent
+        let zero = self.constant(F::zero());
+        let first = &sorted[0];
+        let read = self.not(first.is_write);
+        let first_read = self.mul(read, first.value);
+        self.assert_equal(first_read, zero);
+
+        for (pair, keys) in sorted.windows(2).zip(sorted_keys.windows(2)) {
+            let (prev, curr) = (&pair[0], &pair[1]);
+
+            // the address increases, or the timestamp increases at the same address
+            self.assert_less_than(keys[0].0, keys[1].0, 2 * num_bits);
+
+            // a read returns the previous value at the same address, or zero
+            let same = self.is_equal(prev.addr, curr.addr);
+            let expected = self.mul(same, prev.value);
+            let diff = self.sub(curr.value, expected);
+            let read = self.not(curr.is_write);
+            let check = self.mul(read, diff);
+            self.assert_equal(check, zero);
+        }
+    }
+
+    /// Returns the position `addr * 2^num_bits + timestamp` of an access, and its key `2 * position + is_write`,
+    /// after constraining its address and its timestamp to be smaller than `2^num_bits`,
+    /// so that the key determines the access, whose `is_write` flag is a boolean.
+    fn access_key(&mut self, a: &AccessVar, num_bits: usize) -> (Var, Var) {
+        self.range_check(a.addr, num_bits);
+        self.range_check(a.timestamp, num_bits);
+        let two_n = F::from(2u64).pow(&[num_bits as u64]);
+        let position = self.arith(a.addr, a.timestamp, two_n, F::one(), F::zero(), F::zero());
+        let key = self.arith(
+            position,
+            a.is_write,
+            F::from(2u64),
+            F::one(),
+            F::zero(),
+            F::zero(),
+        );
+        (position, key)
+    }
+
+    /// Returns `prod_i (gamma - key_i - beta * value_i)`, where `key_i` is the key of the access `trace_i`.
+    fn access_product(
+        &mut self,
+        trace: &[AccessVar],
+        keys: &[(Var, Var)],
+        beta: Var,
+        gamma: Var,
+    ) -> Var {
+        let mut product = None;
+        for (a, (_, key)) in trace.iter().zip(keys) {
+            let scaled = self.mul(beta, a.value);
+            let enc = self.add(*key, scaled);
+            let term = self.sub(gamma, enc);
+            product = Some(match product {
+                None => term,
+                Some(p) => self.mul(p, term),
+            });
+        }
+        product.expect("the trace is not empty")
+    }
+}
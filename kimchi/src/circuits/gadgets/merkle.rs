@@ -0,0 +1,57 @@
+//! This module implements the membership proofs of the Merkle trees of [oracle::merkle].
+//!
+//! Each level of a path hashes the node and its sibling with one Poseidon permutation,
+//! after swapping them when the bit of the level is set, so that the node is the right child.
+
+use crate::circuits::gadgets::builder::{CircuitBuilder, Var};
+use ark_ff::PrimeField;
+use oracle::merkle::MerklePath;
+
+/// A path in a Merkle tree, in a circuit.
+#[derive(Clone, Debug)]
+pub struct MerklePathVar {
+    /// the bits of the position of the leaf, least significant first
+    pub bits: Vec<Var>,
+    /// the siblings of the nodes on the path, from the leaf up to the root (excluded)
+    pub siblings: Vec<Var>,
+}
+
+impl<F: PrimeField> CircuitBuilder<F> {
+    /// Adds a path to the witness, and constrains its bits to be booleans.
+    pub fn witness_merkle_path(&mut self, path: &MerklePath<F>) -> MerklePathVar {
+        let bits = path
+            .bits()
+            .into_iter()
+            .map(|b| {
+                let b = self.witness(F::from(b as u64));
+                self.assert_boolean(b);
+                b
+            })
+            .collect();
+        let siblings = path.siblings.iter().map(|s| self.witness(*s)).collect();
+        MerklePathVar { bits, siblings }
+    }
+
+    /// Returns the root of a tree holding `leaf` on `path`,
+    /// as [oracle::merkle::MerklePath::root] computes it with the sponge parameters of the circuit.
+    pub fn merkle_root(&mut self, leaf: Var, path: &MerklePathVar) -> Var {
+        let zero = self.constant(F::zero());
+        path.bits
+            .iter()
+            .zip(&path.siblings)
+            .fold(leaf, |node, (bit, sibling)| {
+                // left = node + bit * (sibling - node), right = node + sibling - left
+                let diff = self.sub(*sibling, node);
+                let swap = self.mul(*bit, diff);
+                let left = self.add(node, swap);
+                let right = self.sub(*sibling, swap);
+                self.poseidon([left, right, zero])[0]
+            })
+    }
+
+    /// Constrains `leaf` to be under `root` on `path`.
+    pub fn assert_merkle_membership(&mut self, root: Var, leaf: Var, path: &MerklePathVar) {
+        let computed = self.merkle_root(leaf, path);
+        self.assert_equal(root, computed);
+    }
+}
@@ -0,0 +1,16 @@
+//! This module implements gadgets: functions that lay out gates and their witness
+//! in a [builder::CircuitBuilder], and wire them together.
+//!
+//! Gadgets operate on [builder::Var]s, which are cells of the witness.
+//! Each gadget fills the witness as it creates its gates,
+//! so that building a circuit also produces a satisfying assignment of it.
+
+pub mod arith;
+pub mod builder;
+pub mod ec;
+pub mod memory;
+pub mod merkle;
+pub mod scalar;
+pub mod schnorr;
+pub mod sponge;
+pub mod verifier;
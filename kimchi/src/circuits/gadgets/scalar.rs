@@ -0,0 +1,230 @@
+//! This module implements gadgets to decompose field elements,
+//! and the representation of the scalars of a curve in a circuit over its base field.
+
+use crate::circuits::{
+    gadgets::builder::{CircuitBuilder, Var},
+    gate::GateType,
+    polynomials::endomul_scalar,
+    wires::COLUMNS,
+};
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use array_init::array_init;
+
+/// The number of bits decomposed by an `EndoMulScalar` gate.
+pub const BITS_PER_ROW: usize = 16;
+
+/// The number of bits of a challenge.
+pub const CHALLENGE_BITS: usize = 128;
+
+/// The number of bits of the limbs squeezed from an [oracle::sponge::DefaultFqSponge].
+pub const LIMB_BITS: usize = 64;
+
+/// A scalar `s` of a curve whose base field is the field of the circuit.
+/// It is represented by `t = 2 * hi + lo`, with `lo` a bit, where `s = 2^255 + 1 + 2 * t`.
+/// This is the form in which `VarBaseMul` multiplies a point by a scalar,
+/// and `hi` fits in the base field of either Pasta curve.
+#[derive(Clone, Copy, Debug)]
+pub struct ShiftedScalar {
+    pub hi: Var,
+    pub lo: Var,
+}
+
+/// A scalar `x` of a curve whose base field is the field of the circuit, as `x = 2 * hi + lo`, with `lo` a bit.
+/// This is how [oracle::sponge::DefaultFqSponge] absorbs the scalars that do not fit in its base field.
+/// The split is constrained to be canonical by [CircuitBuilder::check_split].
+#[derive(Clone, Copy, Debug)]
+pub struct SplitScalar {
+    pub hi: Var,
+    pub lo: Var,
+}
+
+/// Returns the bits of `t`, the representation of the scalar `s` in a [ShiftedScalar].
+pub fn shifted_bits<S: PrimeField>(s: S) -> Vec<bool> {
+    let two = S::from(2u64);
+    let t = (s - two.pow(&[255]) - S::one()) / two;
+    t.into_repr().to_bits_le()
+}
+
+/// Returns the field element of `F` whose little-endian bits are `bits`.
+pub(crate) fn from_bits<F: PrimeField>(bits: &[bool]) -> F {
+    F::from_repr(F::BigInt::from_bits_le(bits)).expect("value too large for the field")
+}
+
+impl<F: PrimeField> CircuitBuilder<F> {
+    /// Adds the representation of the scalar `s` to the witness.
+    /// It is constrained by [CircuitBuilder::scale_shifted].
+    pub fn shifted_scalar<S: PrimeField>(&mut self, s: S) -> ShiftedScalar {
+        let bits = shifted_bits(s);
+        let hi = self.witness(from_bits(&bits[1..]));
+        let lo = self.witness(F::from(bits[0] as u64));
+        ShiftedScalar { hi, lo }
+    }
+
+    /// Adds the split of the scalar `s` to the witness, constrained by [CircuitBuilder::check_split].
+    pub fn split_scalar<S: PrimeField>(&mut self, s: S) -> SplitScalar {
+        let bits = s.into_repr().to_bits_le();
+        let hi = self.witness(from_bits(&bits[1..]));
+        let lo = self.witness(F::from(bits[0] as u64));
+        let split = SplitScalar { hi, lo };
+        self.check_split(split);
+        split
+    }
+
+    /// Constrains `lo` to be a bit, and `hi` to be smaller than `2^253`.
+    /// As `2 * hi + lo` is then smaller than `2^254`, and than the moduli of both Pasta fields,
+    /// the split is unique, and it only fails to exist for the negligible fraction of scalars larger than `2^254`.
+    pub fn check_split(&mut self, x: SplitScalar) {
+        self.assert_boolean(x.lo);
+
+        // the first row of the decomposition of hi accumulates its 16 most significant bits,
+        // whose 3 most significant bits are zero if 8 times their value has 16 bits
+        let first = self.decompose(x.hi, 2 * CHALLENGE_BITS);
+        let top = Var { row: first, col: 1 };
+        let top8 = self.arith(top, top, F::from(8u64), F::zero(), F::zero(), F::zero());
+        self.range_check(top8, BITS_PER_ROW);
+    }
+
+    /// Returns the representation of the constant scalar `s`.
+    pub fn constant_shifted_scalar<S: PrimeField>(&mut self, s: S) -> ShiftedScalar {
+        let bits = shifted_bits(s);
+        let hi = self.constant(from_bits(&bits[1..]));
+        let lo = self.constant(F::from(bits[0] as u64));
+        ShiftedScalar { hi, lo }
+    }
+
+    /// The value of `t = 2 * hi + lo` represented by a [ShiftedScalar].
+    pub fn shifted_value(&mut self, s: ShiftedScalar) -> Var {
+        self.arith(s.hi, s.lo, F::from(2u64), F::one(), F::zero(), F::zero())
+    }
+
+    /// Decomposes `n` in `bits / 2` crumbs with `EndoMulScalar` gates, most significant first,
+    /// which constrains `n` to be smaller than `2^bits`. `bits` must be a multiple of 16.
+    /// Returns the first row of the gates, whose column 6 holds the most significant crumb.
+    fn decompose(&mut self, n: Var, bits: usize) -> usize {
+        let mut rows: [Vec<F>; COLUMNS] = array_init(|_| vec![]);
+        endomul_scalar::gen_witness(&mut rows, self.value(n), F::zero(), bits);
+
+        let num_rows = bits / BITS_PER_ROW;
+        let first = self.gates(vec![(GateType::EndoMulScalar, vec![]); num_rows], &rows);
+
+        // the accumulators start at n = 0 and a = b = 2
+        let zero = self.constant(F::zero());
+        let two = self.constant(F::from(2u64));
+        self.assert_equal(zero, Var { row: first, col: 0 });
+        self.assert_equal(two, Var { row: first, col: 2 });
+        self.assert_equal(two, Var { row: first, col: 3 });
+
+        // and are passed from one row to the next
+        for row in first + 1..first + num_rows {
+            for (prev, next) in [(1, 0), (4, 2), (5, 3)] {
+                self.assert_equal(
+                    Var {
+                        row: row - 1,
+                        col: prev,
+                    },
+                    Var { row, col: next },
+                );
+            }
+        }
+        self.assert_equal(
+            n,
+            Var {
+                row: first + num_rows - 1,
+                col: 1,
+            },
+        );
+
+        first
+    }
+
+    /// Constrains `n` to be smaller than `2^bits`, where `bits` is a multiple of 16.
+    pub fn range_check(&mut self, n: Var, bits: usize) {
+        assert_eq!(bits % BITS_PER_ROW, 0, "unsupported number of bits");
+        self.decompose(n, bits);
+    }
+
+    /// Constrains `n` to be smaller than `2^(bits - 2)`, where `bits` is a multiple of 16.
+    fn range_check_minus_crumb(&mut self, n: Var, bits: usize) {
+        let first = self.decompose(n, bits);
+        // the most significant crumb is zero
+        let zero = self.constant(F::zero());
+        self.assert_equal(zero, Var { row: first, col: 6 });
+    }
+
+    /// Decomposes `x` into `num_bits` bits, least significant first, with generic gates,
+    /// which constrains `x` to be smaller than `2^num_bits`.
+    /// `num_bits` must be smaller than the size of the modulus, so that the decomposition is unique.
+    pub fn unpack(&mut self, x: Var, num_bits: usize) -> Vec<Var> {
+        assert!(
+            num_bits < F::Params::MODULUS_BITS as usize,
+            "the decomposition is not unique"
+        );
+        let values = self.value(x).into_repr().to_bits_le();
+
+        let bits: Vec<_> = values
+            .into_iter()
+            .take(num_bits)
+            .map(|b| {
+                let b = F::from(b as u64);
+                // b * b - b = 0
+                let [l, r, _] = self.generic(
+                    [-F::one(), F::zero(), F::zero(), F::one(), F::zero()],
+                    [b, b, F::zero()],
+                );
+                self.assert_equal(l, r);
+                l
+            })
+            .collect();
+        let packed = self.pack(&bits);
+        self.assert_equal(x, packed);
+
+        bits
+    }
+
+    /// Splits `x` into `hi * 2^128 + lo`, with `lo < 2^128` and `hi < 2^126`.
+    /// As `2^254` is smaller than the moduli of both Pasta fields, the split is unique,
+    /// and it only fails to exist for the negligible fraction of values larger than `2^254`.
+    /// Returns `(hi, lo)`.
+    pub fn split_low_128(&mut self, x: Var) -> (Var, Var) {
+        let bits = self.value(x).into_repr().to_bits_le();
+        let lo = self.witness(from_bits(&bits[..CHALLENGE_BITS]));
+        let hi = self.witness(from_bits(&bits[CHALLENGE_BITS..]));
+
+        self.range_check(lo, CHALLENGE_BITS);
+        self.range_check_minus_crumb(hi, CHALLENGE_BITS);
+
+        let two_128 = F::from(2u64).pow(&[CHALLENGE_BITS as u64]);
+        let recomposed = self.arith(hi, lo, two_128, F::one(), F::zero(), F::zero());
+        self.assert_equal(x, recomposed);
+
+        (hi, lo)
+    }
+
+    /// Splits `x` into its `num_limbs` least significant limbs of 64 bits,
+    /// and the value of its remaining bits, which is constrained to be smaller than `2^(254 - 64 * num_limbs)`.
+    /// As for [CircuitBuilder::split_low_128], the split is unique.
+    /// Returns the remaining bits and the limbs, least significant first.
+    pub fn split_limbs(&mut self, x: Var, num_limbs: usize) -> (Var, Vec<Var>) {
+        let bits = self.value(x).into_repr().to_bits_le();
+        let limbs: Vec<_> = bits
+            .chunks(LIMB_BITS)
+            .take(num_limbs)
+            .map(|limb| {
+                let limb = self.witness(from_bits(limb));
+                self.range_check(limb, LIMB_BITS);
+                limb
+            })
+            .collect();
+        let rest = self.witness(from_bits(&bits[num_limbs * LIMB_BITS..]));
+        // 254 - 64 * num_limbs is 2 less than a multiple of 16
+        self.range_check_minus_crumb(rest, 256 - num_limbs * LIMB_BITS);
+
+        let two_64 = F::from(2u64).pow(&[LIMB_BITS as u64]);
+        let recomposed = limbs.iter().rev().fold(rest, |acc, limb| {
+            self.arith(acc, *limb, two_64, F::one(), F::zero(), F::zero())
+        });
+        self.assert_equal(x, recomposed);
+
+        (rest, limbs)
+    }
+}
@@ -0,0 +1,148 @@
+//! This module implements the verification of Mina signatures (see [mina_signer::schnorr])
+//! in a circuit over the base field of Pallas.
+//!
+//! The signature `(rx, s)` of a message by the public key `P` is valid if
+//! `R = [s] G - [e] P` is not the point at infinity, has an even y-coordinate, and has the x-coordinate `rx`,
+//! where `e` is the hash of the message, the public key and `rx`.
+//! The gadget adds `R` to the witness, and checks `[2] R + [2^255 + 1 + 2 e] P = [2] ([s] G) + [2^255 + 1] P`,
+//! which holds if and only if `R = [s] G - [e] P` as the order of the group is odd.
+//! This way, `e` is multiplied with a `VarBaseMul` gadget without being converted to the scalar field.
+
+use crate::circuits::gadgets::{
+    builder::{CircuitBuilder, Var},
+    ec::PointVar,
+    scalar::ShiftedScalar,
+    sponge::SpongeGadget,
+};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, Zero};
+use mina_signer::{
+    domain_prefix_to_field, BaseField, CurvePoint, PubKey, ROInput, ScalarField, Signature,
+};
+
+/// The number of bits of the hash of a message, and of the y-coordinate of `R`.
+/// The gadget cannot be satisfied when one of them is larger than `2^254`,
+/// which only happens for a negligible fraction of the signatures.
+const HASH_BITS: usize = 254;
+
+/// A signature in a circuit.
+#[derive(Clone, Copy, Debug)]
+pub struct SignatureVar {
+    /// the x-coordinate of `R`
+    pub rx: Var,
+    /// the scalar `s`
+    pub s: ShiftedScalar,
+}
+
+/// A message in a circuit, as the field elements of its [ROInput::to_fields].
+#[derive(Clone, Debug)]
+pub struct MessageVar {
+    /// the field elements of the message
+    pub fields: Vec<Var>,
+    /// the number of field elements appended to the [ROInput], which come before its packed bits
+    pub num_fields: usize,
+}
+
+impl CircuitBuilder<BaseField> {
+    /// Adds a signature to the witness.
+    pub fn witness_signature(&mut self, sig: &Signature) -> SignatureVar {
+        SignatureVar {
+            rx: self.witness(sig.rx),
+            s: self.shifted_scalar(sig.s),
+        }
+    }
+
+    /// Adds a public key to the witness, and constrains it to be on the curve.
+    pub fn witness_public_key(&mut self, pk: &PubKey) -> PointVar {
+        self.witness_point(&pk.into_point())
+    }
+
+    /// Adds a message to the witness.
+    pub fn witness_message(&mut self, message: &ROInput) -> MessageVar {
+        MessageVar {
+            fields: message
+                .to_fields()
+                .into_iter()
+                .map(|x| self.witness(x))
+                .collect(),
+            num_fields: message.num_fields(),
+        }
+    }
+
+    /// Returns the hash of `message`, `pk` and `rx`, as [mina_signer::Schnorr] computes it,
+    /// with `sponge`, a new sponge with the constants and parameters of the signer,
+    /// and the domain string of the type of the message.
+    pub fn message_hash(
+        &mut self,
+        mut sponge: SpongeGadget<BaseField>,
+        domain_string: &str,
+        pk: PointVar,
+        rx: Var,
+        message: &MessageVar,
+    ) -> Var {
+        let prefix = self.constant(domain_prefix_to_field(domain_string));
+        sponge.absorb(self, prefix);
+        sponge.squeeze(self);
+
+        // the public key and rx are appended to the field elements of the message, before its bits
+        let (fields, bits) = message.fields.split_at(message.num_fields);
+        for x in fields.iter().chain(&[pk.x, pk.y, rx]).chain(bits) {
+            sponge.absorb(self, *x);
+        }
+        sponge.squeeze(self)
+    }
+
+    /// Constrains `sig` to be a valid signature of `message` by `pk`, as [mina_signer::Signer::verify] checks.
+    /// See [CircuitBuilder::message_hash] for `sponge` and `domain_string`.
+    /// The witness generation panics if `R` is the point at infinity.
+    pub fn verify_signature(
+        &mut self,
+        sponge: SpongeGadget<BaseField>,
+        domain_string: &str,
+        pk: PointVar,
+        sig: SignatureVar,
+        message: &MessageVar,
+    ) {
+        let e = self.message_hash(sponge, domain_string, pk, sig.rx, message);
+
+        // e = 2 * hi + lo, with lo a bit and hi < 2^253,
+        // so that e is multiplied as the integer it represents
+        let lo = self.unpack(e, HASH_BITS)[0];
+        let half = BaseField::from(2u64).inverse().unwrap();
+        let hi = self.arith(e, lo, half, -half, BaseField::zero(), BaseField::zero());
+        let e_shifted = ShiftedScalar { hi, lo };
+
+        // R = [s] G - [e] P
+        let r = {
+            let to_scalar = |x: BaseField| ScalarField::from_repr(x.into_repr()).unwrap();
+            let two = ScalarField::from(2u64);
+            let t = two * to_scalar(self.value(sig.s.hi)) + to_scalar(self.value(sig.s.lo));
+            let s = two.pow(&[255]) + ScalarField::one() + two * t;
+            let (x, y) = self.point_value(pk);
+            let pk = CurvePoint::new(x, y, false);
+            let r =
+                CurvePoint::prime_subgroup_generator().mul(s) - pk.mul(to_scalar(self.value(e)));
+            assert!(!r.is_zero(), "R is the point at infinity");
+            r.into_affine()
+        };
+        let r = self.witness_point(&r);
+        self.assert_equal(r.x, sig.rx);
+        let zero = self.constant(BaseField::zero());
+        let y_parity = self.unpack(r.y, HASH_BITS)[0];
+        self.assert_equal(y_parity, zero);
+
+        // [2] R + [2^255 + 1 + 2 e] P = [2] ([s] G) + [2^255 + 1] P
+        let g = self
+            .constant_point(&CurvePoint::prime_subgroup_generator())
+            .unwrap();
+        let sg = self.scale_shifted(g, sig.s);
+        let ep = self.scale_shifted(pk, e_shifted);
+        let shift = self.scale_shifted(pk, ShiftedScalar { hi: zero, lo: zero });
+
+        let r2 = self.double_point(r);
+        let lhs = self.add_points(r2, ep);
+        let sg2 = self.double_point(sg);
+        let rhs = self.add_points(sg2, shift);
+        self.assert_points_equal(lhs, rhs);
+    }
+}
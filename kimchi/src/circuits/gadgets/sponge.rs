@@ -0,0 +1,268 @@
+//! This module implements the Poseidon permutation with the Poseidon gate,
+//! and the legacy Poseidon permutation with the Poseidon legacy gate,
+//! a sponge built on them that mirrors [oracle::poseidon::ArithmeticSponge],
+//! and a sponge that mirrors the [oracle::sponge::DefaultFqSponge] used to compute the challenges of a proof.
+
+use crate::circuits::{
+    gadgets::{
+        builder::{CircuitBuilder, Var},
+        ec::PointVar,
+        scalar::{SplitScalar, LIMB_BITS},
+    },
+    gate::CircuitGate,
+    polynomials::{
+        poseidon::{generate_witness, POS_ROWS_PER_HASH, SPONGE_WIDTH},
+        poseidon_legacy,
+    },
+    wires::{Wire, COLUMNS},
+};
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use array_init::array_init;
+use oracle::{
+    constants::{PlonkSpongeConstantsKimchi, SpongeConstants},
+    poseidon::{ArithmeticSpongeParams, SpongeState},
+    sponge::{CHALLENGE_LENGTH_IN_LIMBS, HIGH_ENTROPY_LIMBS},
+};
+
+impl<F: PrimeField> CircuitBuilder<F> {
+    /// Applies the Poseidon permutation to `state`,
+    /// with the round constants and MDS matrix of the circuit's sponge parameters.
+    pub fn poseidon(&mut self, state: [Var; SPONGE_WIDTH]) -> [Var; SPONGE_WIDTH] {
+        let input = array_init(|i| self.value(state[i]));
+        let mut rows: [Vec<F>; COLUMNS] = array_init(|_| vec![F::zero(); POS_ROWS_PER_HASH + 1]);
+        generate_witness(0, self.params().clone(), &mut rows, input);
+
+        // the Poseidon gates are followed by a Zero gate holding the output of the permutation,
+        // and their wires are computed by the builder
+        let row = self.num_rows();
+        let (gates, _) = CircuitGate::create_poseidon_gadget(
+            row,
+            [Wire::new(row), Wire::new(row + POS_ROWS_PER_HASH)],
+            &self.params().round_constants,
+        );
+        let gates = gates.into_iter().map(|g| (g.typ, g.coeffs)).collect();
+
+        let first = self.gates(gates, &rows);
+        for (col, v) in state.iter().enumerate() {
+            self.assert_equal(*v, Var { row: first, col });
+        }
+        array_init(|col| Var {
+            row: first + POS_ROWS_PER_HASH,
+            col,
+        })
+    }
+
+    /// Applies the legacy Poseidon permutation (see [oracle::constants::PlonkSpongeConstantsLegacy]) to `state`,
+    /// with the round constants and MDS matrix of `params`.
+    pub fn poseidon_legacy(
+        &mut self,
+        params: &ArithmeticSpongeParams<F>,
+        state: [Var; SPONGE_WIDTH],
+    ) -> [Var; SPONGE_WIDTH] {
+        let input = array_init(|i| self.value(state[i]));
+        let mut rows: [Vec<F>; COLUMNS] =
+            array_init(|_| vec![F::zero(); poseidon_legacy::ROWS_PER_HASH + 1]);
+        poseidon_legacy::generate_witness(0, params, &mut rows, input);
+
+        // the initial addition of the round constants is not part of the gates
+        let ark: [Var; SPONGE_WIDTH] =
+            array_init(|i| self.add_constant(state[i], params.round_constants[0][i]));
+
+        let row = self.num_rows();
+        let (gates, _) = CircuitGate::create_poseidon_legacy_gadget(
+            row,
+            [
+                Wire::new(row),
+                Wire::new(row + poseidon_legacy::ROWS_PER_HASH),
+            ],
+            params,
+        );
+        let gates = gates.into_iter().map(|g| (g.typ, g.coeffs)).collect();
+
+        let first = self.gates(gates, &rows);
+        for (col, v) in ark.iter().enumerate() {
+            self.assert_equal(*v, Var { row: first, col });
+        }
+        array_init(|i| Var {
+            row: first + poseidon_legacy::ROWS_PER_HASH - 1,
+            col: poseidon_legacy::OUTPUT_COLS.start + i,
+        })
+    }
+
+    /// Returns the hash of `input` by a [SpongeGadget] with the Kimchi constants,
+    /// which absorbs the elements of `input` and squeezes a single element.
+    pub fn poseidon_hash(&mut self, input: &[Var]) -> Var {
+        let mut sponge = SpongeGadget::new(self);
+        for x in input {
+            sponge.absorb(self, *x);
+        }
+        sponge.squeeze(self)
+    }
+}
+
+/// The permutation of a [SpongeGadget].
+#[derive(Clone, Debug)]
+enum Permutation<F: PrimeField> {
+    /// the Poseidon gate, with the sponge parameters of the circuit
+    Kimchi,
+    /// the legacy permutation, with its parameters
+    Legacy(ArithmeticSpongeParams<F>),
+}
+
+/// A sponge laid out in a circuit, that computes the same values as
+/// an [oracle::poseidon::ArithmeticSponge] with the Kimchi constants, or with the legacy constants.
+#[derive(Clone, Debug)]
+pub struct SpongeGadget<F: PrimeField> {
+    state: [Var; SPONGE_WIDTH],
+    sponge_state: SpongeState,
+    permutation: Permutation<F>,
+}
+
+impl<F: PrimeField> SpongeGadget<F> {
+    /// Creates a sponge with an all-zero state,
+    /// with the Kimchi constants and the sponge parameters of the circuit.
+    pub fn new(b: &mut CircuitBuilder<F>) -> Self {
+        Self::with_permutation(b, Permutation::Kimchi)
+    }
+
+    /// Creates a sponge with an all-zero state,
+    /// with the legacy constants and the sponge parameters `params`.
+    pub fn new_legacy(b: &mut CircuitBuilder<F>, params: ArithmeticSpongeParams<F>) -> Self {
+        Self::with_permutation(b, Permutation::Legacy(params))
+    }
+
+    fn with_permutation(b: &mut CircuitBuilder<F>, permutation: Permutation<F>) -> Self {
+        let zero = b.constant(F::zero());
+        SpongeGadget {
+            state: [zero; SPONGE_WIDTH],
+            sponge_state: SpongeState::Absorbed(0),
+            permutation,
+        }
+    }
+
+    fn permute(&mut self, b: &mut CircuitBuilder<F>) {
+        self.state = match &self.permutation {
+            Permutation::Kimchi => b.poseidon(self.state),
+            Permutation::Legacy(params) => b.poseidon_legacy(params, self.state),
+        };
+    }
+
+    /// Absorbs a field element.
+    pub fn absorb(&mut self, b: &mut CircuitBuilder<F>, x: Var) {
+        // both sets of constants have the same rate
+        let rate = PlonkSpongeConstantsKimchi::SPONGE_RATE;
+        let n = match self.sponge_state {
+            SpongeState::Absorbed(n) if n == rate => {
+                self.permute(b);
+                0
+            }
+            SpongeState::Absorbed(n) => n,
+            SpongeState::Squeezed(_) => 0,
+        };
+        self.state[n] = b.add(self.state[n], x);
+        self.sponge_state = SpongeState::Absorbed(n + 1);
+    }
+
+    /// Squeezes a field element.
+    pub fn squeeze(&mut self, b: &mut CircuitBuilder<F>) -> Var {
+        let rate = PlonkSpongeConstantsKimchi::SPONGE_RATE;
+        let n = match self.sponge_state {
+            SpongeState::Squeezed(n) if n < rate => n,
+            _ => {
+                self.permute(b);
+                0
+            }
+        };
+        self.sponge_state = SpongeState::Squeezed(n + 1);
+        self.state[n]
+    }
+}
+
+/// A sponge laid out in a circuit over the base field of a curve, that computes the same values as
+/// an [oracle::sponge::DefaultFqSponge] with the Kimchi constants.
+/// The points it absorbs cannot be the point at infinity.
+#[derive(Clone, Debug)]
+pub struct FqSpongeGadget<F: PrimeField> {
+    sponge: SpongeGadget<F>,
+    /// the 64-bit limbs squeezed but not consumed yet
+    last_squeezed: Vec<Var>,
+}
+
+impl<F: PrimeField> FqSpongeGadget<F> {
+    /// Creates a sponge with an all-zero state.
+    pub fn new(b: &mut CircuitBuilder<F>) -> Self {
+        FqSpongeGadget {
+            sponge: SpongeGadget::new(b),
+            last_squeezed: vec![],
+        }
+    }
+
+    /// Absorbs points, as [oracle::FqSponge::absorb_g] does.
+    pub fn absorb_g(&mut self, b: &mut CircuitBuilder<F>, g: &[PointVar]) {
+        self.last_squeezed = vec![];
+        for g in g {
+            self.sponge.absorb(b, g.x);
+            self.sponge.absorb(b, g.y);
+        }
+    }
+
+    /// Absorbs elements of the scalar field `S` of the curve, as [oracle::FqSponge::absorb_fr] does:
+    /// the scalars that fit in the base field are absorbed as a single element,
+    /// the other ones as their high bits followed by their low bit.
+    pub fn absorb_fr<S: PrimeField>(&mut self, b: &mut CircuitBuilder<F>, x: &[SplitScalar]) {
+        self.last_squeezed = vec![];
+        let fits = S::Params::MODULUS
+            < S::BigInt::from_bits_le(&<F as PrimeField>::Params::MODULUS.to_bits_le());
+        for x in x {
+            if fits {
+                let value = b.arith(x.hi, x.lo, F::from(2u64), F::one(), F::zero(), F::zero());
+                self.sponge.absorb(b, value);
+            } else {
+                self.sponge.absorb(b, x.hi);
+                self.sponge.absorb(b, x.lo);
+            }
+        }
+    }
+
+    /// Squeezes `num_limbs` limbs of 64 bits, least significant first,
+    /// as [oracle::sponge::DefaultFqSponge::squeeze_limbs] does.
+    pub fn squeeze_limbs(&mut self, b: &mut CircuitBuilder<F>, num_limbs: usize) -> Vec<Var> {
+        while self.last_squeezed.len() < num_limbs {
+            let x = self.sponge.squeeze(b);
+            let (_, limbs) = b.split_limbs(x, HIGH_ENTROPY_LIMBS);
+            self.last_squeezed.extend(limbs);
+        }
+        self.last_squeezed.drain(..num_limbs).collect()
+    }
+
+    /// Squeezes an element of the base field, as [oracle::sponge::DefaultFqSponge::squeeze_field] does.
+    pub fn squeeze_field(&mut self, b: &mut CircuitBuilder<F>) -> Var {
+        self.last_squeezed = vec![];
+        self.sponge.squeeze(b)
+    }
+
+    /// Squeezes a 128-bit challenge, as [oracle::FqSponge::challenge] does.
+    /// The challenge is a scalar, or the [oracle::sponge::ScalarChallenge] of a scalar.
+    pub fn challenge(&mut self, b: &mut CircuitBuilder<F>) -> Var {
+        let two_64 = F::from(2u64).pow(&[LIMB_BITS as u64]);
+        let mut limbs = self
+            .squeeze_limbs(b, CHALLENGE_LENGTH_IN_LIMBS)
+            .into_iter()
+            .rev();
+        let first = limbs.next().expect("challenges have at least one limb");
+        limbs.fold(first, |acc, limb| {
+            b.arith(acc, limb, two_64, F::one(), F::zero(), F::zero())
+        })
+    }
+
+    /// Squeezes an element of the base field, as [oracle::FqSponge::challenge_fq] does.
+    pub fn challenge_fq(&mut self, b: &mut CircuitBuilder<F>) -> Var {
+        self.squeeze_field(b)
+    }
+
+    /// Squeezes the digest of the sponge, as [oracle::FqSponge::digest] does,
+    /// before its conversion to the scalar field.
+    pub fn digest(mut self, b: &mut CircuitBuilder<F>) -> Var {
+        self.squeeze_field(b)
+    }
+}
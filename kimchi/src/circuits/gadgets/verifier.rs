@@ -0,0 +1,664 @@
+//! This module implements a gadget that verifies a kimchi proof in a circuit
+//! over the base field of the proof's curve, that is, over the scalar field of the other curve of the cycle.
+//!
+//! The gadget checks everything that involves the commitments of the proof:
+//!
+//! - it runs the Fq-sponge of the verifier to compute the challenges `beta`, `gamma`, `alpha`, `zeta`
+//!   and the digest absorbed by the Fr-sponge,
+//! - it computes the commitment to the public input,
+//!   and the commitment to the linearized polynomial minus the quotient (the linearization check),
+//! - it checks the opening proof succinctly, combining all the commitments of the proof,
+//!   and outputs the challenges and the `sg` commitment of the opening proof,
+//!   whose correctness is deferred to an accumulator (see [commitment_dlog::accumulator::Accumulator]).
+//!
+//! The values that live in the scalar field of the proof (the [DeferredValues])
+//! cannot be computed efficiently over the base field.
+//! They are added to the witness, and returned with the challenges in a [ProofVars],
+//! so that the next step of the recursion, over the scalar field, can check them.
+//!
+//! The gadget supports proofs without lookups, with at least one public input,
+//! whose commitments are made of a single chunk (the SRS is as large as the domain),
+//! and that do not contain the point at infinity.
+
+use crate::{
+    circuits::{
+        gadgets::{
+            builder::{CircuitBuilder, Var},
+            ec::PointVar,
+            scalar::{from_bits, shifted_bits, ShiftedScalar, SplitScalar},
+            sponge::FqSpongeGadget,
+        },
+        scalars::{ProofEvaluations, RandomOracles},
+        wires::PERMUTS,
+    },
+    plonk_sponge::FrSponge,
+    prover::ProverProof,
+    verifier::{f_comm_terms, to_batch, OraclesResult},
+    verifier_index::VerifierIndex,
+};
+use ark_ec::{
+    short_weierstrass_jacobian::GroupAffine, AffineCurve, ModelParameters, ProjectiveCurve,
+    SWModelParameters,
+};
+use ark_ff::{BigInteger, Field, FpParameters, One, PrimeField, SquareRootField, Zero};
+use commitment_dlog::commitment::{
+    b_poly, combined_inner_product, shift_scalar, squeeze_challenge, PolyComm,
+};
+use groupmap::{BWParameters, GroupMap};
+use oracle::FqSponge;
+
+type Fr<P> = <P as ModelParameters>::ScalarField;
+type Fq<P> = <P as ModelParameters>::BaseField;
+
+/// The values of the verification of a proof that live in its scalar field.
+#[derive(Clone, Debug)]
+pub struct DeferredValues<P: SWModelParameters> {
+    /// the challenges of the Fq-sponge and of the Fr-sponge
+    pub oracles: RandomOracles<Fr<P>>,
+    /// the digest of the Fq-sponge, absorbed by the Fr-sponge
+    pub digest: Fr<P>,
+    /// the scalars of the commitment to the linearized polynomial
+    pub f_scalars: Vec<Fr<P>>,
+    /// the scalars of the chunks of the commitment to the quotient polynomial
+    pub t_scalars: Vec<Fr<P>>,
+    /// the combined inner product of the opening proof
+    pub combined_inner_product: Fr<P>,
+    /// the evaluation of the challenge polynomial of the opening proof, combined over the evaluation points
+    pub b0: Fr<P>,
+    /// the challenges of the opening proof
+    pub challenges: Vec<Fr<P>>,
+}
+
+/// Returns the commitments and scalars of the commitment to the linearized polynomial.
+#[allow(clippy::type_complexity)]
+fn linearization<'a, P>(
+    index: &'a VerifierIndex<GroupAffine<P>>,
+    proof: &'a ProverProof<GroupAffine<P>>,
+    oracles: &RandomOracles<Fr<P>>,
+) -> (Vec<&'a PolyComm<GroupAffine<P>>>, Vec<Fr<P>>)
+where
+    P: SWModelParameters,
+    Fq<P>: PrimeField,
+{
+    let mut all_alphas = index.powers_of_alpha.clone();
+    all_alphas.instantiate(oracles.alpha);
+
+    let zeta_n = oracles.zeta.pow(&[index.max_poly_size as u64]);
+    let zetaw_n = (oracles.zeta * index.domain.group_gen).pow(&[index.max_poly_size as u64]);
+    let evals: Vec<ProofEvaluations<_>> = vec![
+        proof.evals[0].combine(zeta_n),
+        proof.evals[1].combine(zetaw_n),
+    ];
+
+    f_comm_terms(index, proof, oracles, &all_alphas, &evals)
+}
+
+impl<P> DeferredValues<P>
+where
+    P: SWModelParameters,
+    Fq<P>: PrimeField,
+{
+    /// Computes the deferred values of the verification of `proof`.
+    pub fn new<EFqSponge, EFrSponge>(
+        index: &VerifierIndex<GroupAffine<P>>,
+        proof: &ProverProof<GroupAffine<P>>,
+    ) -> Self
+    where
+        EFqSponge: Clone + FqSponge<Fq<P>, GroupAffine<P>, Fr<P>>,
+        EFrSponge: FrSponge<Fr<P>>,
+    {
+        let batch = to_batch::<GroupAffine<P>, EFqSponge, EFrSponge>(index, proof);
+
+        // the public input commitment follows the commitments of the previous challenges
+        let p_comm = &batch.evaluations[proof.prev_challenges.len()].commitment;
+        let OraclesResult {
+            oracles,
+            digest,
+            zeta1,
+            ..
+        } = proof.oracles::<EFqSponge, EFrSponge>(index, p_comm);
+
+        let (_, f_scalars) = linearization(index, proof, &oracles);
+
+        // ft_comm = f_comm - (zeta^n - 1) * sum_i zeta^(n i) t_comm[i]
+        let zeta_n = oracles.zeta.pow(&[index.max_poly_size as u64]);
+        let mut t_scalar = Fr::<P>::one() - zeta1;
+        let t_scalars = proof
+            .commitments
+            .t_comm
+            .unshifted
+            .iter()
+            .map(|_| {
+                let s = t_scalar;
+                t_scalar *= zeta_n;
+                s
+            })
+            .collect();
+
+        let evaluations: Vec<_> = batch
+            .evaluations
+            .iter()
+            .map(|e| (e.evaluations.clone(), None))
+            .collect();
+        let combined_inner_product = combined_inner_product::<GroupAffine<P>>(
+            &batch.evaluation_points,
+            &batch.xi,
+            &batch.r,
+            &evaluations,
+            index.srs.g.len(),
+        );
+
+        let mut sponge = batch.sponge;
+        sponge.absorb_fr(&[shift_scalar::<GroupAffine<P>>(combined_inner_product)]);
+        sponge.challenge_fq();
+        let challenges: Vec<_> = proof
+            .proof
+            .lr
+            .iter()
+            .map(|(l, r)| {
+                sponge.absorb_g(&[*l, *r]);
+                squeeze_challenge(&index.srs.endo_r, &mut sponge)
+            })
+            .collect();
+
+        let mut b0 = Fr::<P>::zero();
+        let mut scale = Fr::<P>::one();
+        for e in &batch.evaluation_points {
+            b0 += scale * b_poly(&challenges, *e);
+            scale *= batch.r;
+        }
+
+        DeferredValues {
+            oracles,
+            digest,
+            f_scalars,
+            t_scalars,
+            combined_inner_product,
+            b0,
+            challenges,
+        }
+    }
+}
+
+/// The cells of a circuit verifying a proof that hold the values
+/// the next step of the recursion has to check over the scalar field.
+/// The 128-bit challenges are the ones that [oracle::sponge::ScalarChallenge::to_field] maps to scalars.
+#[derive(Clone, Debug)]
+pub struct ProofVars {
+    /// the negated public input
+    pub public: Vec<ShiftedScalar>,
+    /// the challenge `beta`
+    pub beta: Var,
+    /// the challenge `gamma`
+    pub gamma: Var,
+    /// the 128-bit challenge of `alpha`
+    pub alpha_chal: Var,
+    /// the 128-bit challenge of `zeta`
+    pub zeta_chal: Var,
+    /// the digest of the Fq-sponge, as `(hi, lo)` with `digest = hi * 2^128 + lo`
+    pub digest: (Var, Var),
+    /// the 128-bit challenge `v` combining the evaluated polynomials
+    pub v_chal: Var,
+    /// the scalars of the commitment to the linearized polynomial
+    pub f_scalars: Vec<ShiftedScalar>,
+    /// the scalars of the chunks of the commitment to the quotient polynomial
+    pub t_scalars: Vec<ShiftedScalar>,
+    /// the combined inner product of the opening proof
+    pub combined_inner_product: ShiftedScalar,
+    /// the shifted combined inner product absorbed by the Fq-sponge (see [shift_scalar]).
+    /// When the scalar field is larger than the base field, it is `combined_inner_product - 2^255`,
+    /// which the next step of the recursion has to check. Otherwise, it is the `t` of `combined_inner_product`.
+    pub shifted_combined_inner_product: SplitScalar,
+    /// the combined evaluation of the challenge polynomial of the opening proof
+    pub b0: ShiftedScalar,
+    /// the 128-bit challenges of the opening proof
+    pub challenges: Vec<Var>,
+    /// the `sg` commitment of the opening proof
+    pub sg: PointVar,
+}
+
+/// Returns the single chunk of a commitment.
+fn single<G: AffineCurve>(comm: &PolyComm<G>) -> &G {
+    assert!(
+        comm.unshifted.len() == 1 && comm.shifted.is_none(),
+        "only commitments of a single chunk are supported"
+    );
+    &comm.unshifted[0]
+}
+
+/// Casts a value smaller than both moduli from one field to another.
+fn cast<F: PrimeField, S: PrimeField>(x: S) -> F {
+    from_bits(&x.into_repr().to_bits_le())
+}
+
+impl<F: PrimeField + SquareRootField> CircuitBuilder<F> {
+    /// Returns cells constrained to a commitment of the verifier index,
+    /// or `None` if it is the point at infinity or an empty commitment.
+    fn constant_comm<P: SWModelParameters<BaseField = F>>(
+        &mut self,
+        comm: &PolyComm<GroupAffine<P>>,
+    ) -> Option<PointVar> {
+        if comm.unshifted.is_empty() {
+            return None;
+        }
+        self.constant_point(single(comm))
+    }
+
+    /// Returns `sum_i [s_i] g_i`, skipping the points at infinity,
+    /// or `None` if all the points are at infinity.
+    ///
+    /// The gates only depend on which points are at infinity, which is given by the verifier index,
+    /// and not on the scalars, which are given by the proof:
+    /// every term is multiplied with `VarBaseMul` gates, even when its scalar is zero.
+    /// As `[0] g` is the point at infinity, which the gates cannot represent,
+    /// a zero scalar is replaced by an arbitrary one, and the term is then not added to the sum.
+    /// (Small scalars such as one do not do, as `VarBaseMul` would then add `g` to `[-1] g`:
+    /// for the same reason, the scalars `1` and `-1` of the proof are not supported.)
+    /// The sum is accumulated from the point `offset`, subtracted at the end,
+    /// so that it is not the point at infinity when its first terms are skipped.
+    fn msm<P: SWModelParameters<BaseField = F>>(
+        &mut self,
+        offset: &GroupAffine<P>,
+        terms: &[(ShiftedScalar, Option<PointVar>)],
+    ) -> Option<PointVar> {
+        if terms.iter().all(|(_, g)| g.is_none()) {
+            return None;
+        }
+        let zero = shifted_bits(P::ScalarField::zero());
+        let other = shifted_bits(P::ScalarField::from(3u64).pow([160]));
+        let mut acc = self.constant_point(offset)?;
+        for (s, g) in terms {
+            if let Some(g) = g {
+                // whether s is zero, that is whether its representation is the one of zero
+                let hi0 = self.constant(from_bits(&zero[1..]));
+                let lo0 = self.constant(F::from(zero[0] as u64));
+                let hi_zero = self.is_equal(s.hi, hi0);
+                let lo_zero = self.is_equal(s.lo, lo0);
+                let is_zero = self.and(hi_zero, lo_zero);
+
+                // [s] g, or a point that is skipped if s is zero
+                let hi1 = self.constant(from_bits(&other[1..]));
+                let lo1 = self.constant(F::from(other[0] as u64));
+                let scalar = ShiftedScalar {
+                    hi: self.select(is_zero, hi1, s.hi),
+                    lo: self.select(is_zero, lo1, s.lo),
+                };
+                let sg = self.scale_shifted(*g, scalar);
+
+                let sum = self.add_points(acc, sg);
+                acc = PointVar {
+                    x: self.select(is_zero, acc.x, sum.x),
+                    y: self.select(is_zero, acc.y, sum.y),
+                };
+            }
+        }
+        let neg_offset = self.constant_point(&-*offset)?;
+        Some(self.add_points(acc, neg_offset))
+    }
+
+    /// Maps a field element to a point of the curve, as [groupmap::GroupMap::to_group] does,
+    /// up to the sign of the `y` coordinate, which is chosen by the prover.
+    fn map_to_group<P: SWModelParameters<BaseField = F>>(
+        &mut self,
+        params: &BWParameters<P>,
+        t: Var,
+    ) -> PointVar {
+        let (x, y) = params.to_group(self.value(t));
+
+        // the three potential x coordinates
+        let t2 = self.square(t);
+        let t2_plus_fu = self.add_constant(t2, params.fu);
+        let alpha_inv = self.mul(t2_plus_fu, t2);
+        let alpha = self.inv(alpha_inv);
+
+        let t4 = self.square(t2);
+        let t4_alpha = self.mul(t4, alpha);
+        let x1 = self.arith(
+            t4_alpha,
+            t4_alpha,
+            -params.sqrt_neg_three_u_squared,
+            F::zero(),
+            F::zero(),
+            params.sqrt_neg_three_u_squared_minus_u_over_2,
+        );
+        let x2 = self.arith(x1, x1, -F::one(), F::zero(), F::zero(), -params.u);
+        let t2_plus_fu_squared = self.square(t2_plus_fu);
+        let t2_inv = self.mul(alpha, t2_plus_fu);
+        let temp = self.mul(t2_plus_fu_squared, t2_inv);
+        let x3 = self.arith(
+            temp,
+            temp,
+            -params.inv_three_u_squared,
+            F::zero(),
+            F::zero(),
+            params.u,
+        );
+
+        // the first potential x whose f(x) is a square is chosen:
+        // is_square = 1 iff there is r with r^2 = f(x), and is_square = 0 iff there is r with r^2 = n f(x),
+        // with n a non-square
+        let non_square = F::multiplicative_generator();
+        assert!(non_square.legendre().is_qnr());
+        let mut is_square = |x: Var| {
+            let x2 = self.square(x);
+            let x3 = self.mul(x2, x);
+            let fx = self.add_constant(x3, P::COEFF_B);
+            let fx_value = self.value(fx);
+            let square = fx_value.legendre().is_qr() || fx_value.is_zero();
+            let root = if square {
+                fx_value.sqrt()
+            } else {
+                (fx_value * non_square).sqrt()
+            };
+
+            let b = self.witness(F::from(square as u64));
+            self.assert_boolean(b);
+            let root = self.witness(root.unwrap());
+            let root_squared = self.square(root);
+            let factor = self.arith(
+                b,
+                b,
+                F::one() - non_square,
+                F::zero(),
+                F::zero(),
+                non_square,
+            );
+            let rhs = self.mul(fx, factor);
+            self.assert_equal(root_squared, rhs);
+            b
+        };
+        let b1 = is_square(x1);
+        let b2 = is_square(x2);
+
+        // select b1 ? x1 : (b2 ? x2 : x3)
+        let mut select = |b: Var, x: Var, y: Var| {
+            let diff = self.sub(x, y);
+            let scaled = self.mul(b, diff);
+            self.add(scaled, y)
+        };
+        let x23 = select(b2, x2, x3);
+        let chosen_x = select(b1, x1, x23);
+        debug_assert_eq!(self.value(chosen_x), x);
+
+        let point = PointVar {
+            x: chosen_x,
+            y: self.witness(y),
+        };
+        self.assert_on_curve::<P>(point);
+        point
+    }
+
+    /// Lays out the verification of `proof` against `index`, as [crate::verifier::batch_verify_deferred] does,
+    /// and fills the witness with the values computed by the verifier.
+    ///
+    /// The gates only depend on the index, on the number of public inputs and of previous challenges of the proof,
+    /// so that the circuit can be built from any proof of the right shape.
+    /// The circuit must be used with the sponge parameters `index.fq_sponge_params`,
+    /// and the `endo` coefficient `index.srs.endo_q`.
+    pub fn verify_proof<P, EFqSponge, EFrSponge>(
+        &mut self,
+        group_map: &BWParameters<P>,
+        index: &VerifierIndex<GroupAffine<P>>,
+        proof: &ProverProof<GroupAffine<P>>,
+    ) -> ProofVars
+    where
+        P: SWModelParameters<BaseField = F>,
+        EFqSponge: Clone + FqSponge<F, GroupAffine<P>, Fr<P>>,
+        EFrSponge: FrSponge<Fr<P>>,
+    {
+        assert!(
+            index.lookup_index.is_none() && proof.commitments.lookup.is_none(),
+            "lookups are not supported"
+        );
+        assert_eq!(
+            index.max_poly_size, index.domain.size as usize,
+            "the SRS must be as large as the domain"
+        );
+        assert!(
+            !proof.public.is_empty(),
+            "proofs without public input are not supported"
+        );
+        assert!(
+            index.fq_sponge_params.mds == self.params().mds,
+            "the circuit must use the sponge parameters of the verifier index"
+        );
+
+        let deferred = DeferredValues::new::<EFqSponge, EFrSponge>(index, proof);
+        let endo = index.srs.endo_q;
+
+        //
+        // the commitment to the public input
+        //
+
+        let lgr_comm = index
+            .srs
+            .lagrange_bases
+            .get(&(index.domain.size as usize))
+            .expect("pre-computed committed lagrange bases not found");
+        let public: Vec<_> = proof
+            .public
+            .iter()
+            .map(|x| self.shifted_scalar(-*x))
+            .collect();
+        let terms: Vec<_> = public
+            .iter()
+            .zip(lgr_comm)
+            .map(|(s, g)| (*s, self.constant_point(g)))
+            .collect();
+        let p_comm = self
+            .msm(&index.srs.h, &terms)
+            .expect("the commitment to the public input is the point at infinity");
+
+        //
+        // the Fq-sponge
+        //
+
+        let commitments = &proof.commitments;
+        let w_comm: Vec<_> = commitments
+            .w_comm
+            .iter()
+            .map(|c| self.witness_point(single(c)))
+            .collect();
+        let z_comm = self.witness_point(single(&commitments.z_comm));
+        let t_comm: Vec<_> = commitments
+            .t_comm
+            .unshifted
+            .iter()
+            .map(|g| self.witness_point(g))
+            .collect();
+
+        let mut sponge = FqSpongeGadget::new(self);
+        sponge.absorb_g(self, &[p_comm]);
+        sponge.absorb_g(self, &w_comm);
+        let beta = sponge.challenge(self);
+        let gamma = sponge.challenge(self);
+
+        sponge.absorb_g(self, &[z_comm]);
+        let alpha_chal = sponge.challenge(self);
+
+        sponge.absorb_g(self, &t_comm);
+        let zeta_chal = sponge.challenge(self);
+
+        let digest = {
+            let x = sponge.clone().digest(self);
+            self.split_low_128(x)
+        };
+
+        //
+        // the commitment to the linearized polynomial, minus the chunked quotient
+        //
+
+        let (f_comms, _) = linearization(index, proof, &deferred.oracles);
+        let f_scalars: Vec<_> = deferred
+            .f_scalars
+            .iter()
+            .map(|s| self.shifted_scalar(*s))
+            .collect();
+        let t_scalars: Vec<_> = deferred
+            .t_scalars
+            .iter()
+            .map(|s| self.shifted_scalar(*s))
+            .collect();
+
+        let mut terms = vec![];
+        for (comm, s) in f_comms.into_iter().zip(&f_scalars) {
+            // the commitments of the proof are already in the witness
+            let base = if let Some(i) = commitments
+                .w_comm
+                .iter()
+                .position(|w| std::ptr::eq(w, comm))
+            {
+                Some(w_comm[i])
+            } else if std::ptr::eq(comm, &commitments.z_comm) {
+                Some(z_comm)
+            } else {
+                self.constant_comm(comm)
+            };
+            terms.push((*s, base));
+        }
+        terms.extend(t_scalars.iter().zip(&t_comm).map(|(s, t)| (*s, Some(*t))));
+        let ft_comm = self
+            .msm(&index.srs.h, &terms)
+            .expect("the commitment to ft is the point at infinity");
+
+        //
+        // the commitments of the evaluated polynomials, combined with powers of v
+        //
+
+        let v_chal = self.witness(cast(deferred.oracles.v_chal.0));
+
+        let mut comms: Vec<Option<PointVar>> = proof
+            .prev_challenges
+            .iter()
+            .map(|(_, c)| Some(self.witness_point(single(c))))
+            .collect();
+        comms.extend([Some(p_comm), Some(ft_comm), Some(z_comm)]);
+        // empty commitments are skipped by the opening proof, and do not use a power of v
+        let mut index_comms = vec![&index.generic_comm, &index.psm_comm];
+        index_comms.extend(&index.poseidon_legacy_comm);
+        for comm in index_comms.into_iter().filter(|c| !c.unshifted.is_empty()) {
+            comms.push(self.constant_comm(comm));
+        }
+        comms.extend(w_comm.iter().map(|w| Some(*w)));
+        for sigma in index.sigma_comm[..PERMUTS - 1]
+            .iter()
+            .filter(|c| !c.unshifted.is_empty())
+        {
+            comms.push(self.constant_comm(sigma));
+        }
+
+        let mut combined: Option<PointVar> = None;
+        for comm in comms.into_iter().rev() {
+            let scaled = combined.map(|acc| self.endo_scale(acc, endo, v_chal));
+            combined = match (scaled, comm) {
+                (Some(acc), Some(comm)) => Some(self.add_points(acc, comm)),
+                (acc, None) => acc,
+                (None, comm) => comm,
+            };
+        }
+        let combined = combined.expect("the combined commitment is the point at infinity");
+
+        //
+        // the opening proof
+        //
+
+        let opening = &proof.proof;
+
+        // the Fq-sponge absorbs the shifted combined inner product as a scalar
+        let combined_inner_product = self.shifted_scalar(deferred.combined_inner_product);
+        let scalar_modulus = <Fr<P> as PrimeField>::Params::MODULUS;
+        let base_modulus = <Fr<P> as PrimeField>::BigInt::from_bits_le(
+            &<F as PrimeField>::Params::MODULUS.to_bits_le(),
+        );
+        let shifted_cip = if scalar_modulus < base_modulus {
+            // the shifted combined inner product is t = 2 * hi + lo,
+            // whose split must be canonical for the sponge to absorb the value of the verifier
+            let t = SplitScalar {
+                hi: combined_inner_product.hi,
+                lo: combined_inner_product.lo,
+            };
+            self.check_split(t);
+            t
+        } else {
+            // the consistency of the shifted combined inner product
+            // with the combined inner product is deferred
+            self.split_scalar(shift_scalar::<GroupAffine<P>>(
+                deferred.combined_inner_product,
+            ))
+        };
+        sponge.absorb_fr::<Fr<P>>(self, &[shifted_cip]);
+
+        let t = sponge.challenge_fq(self);
+        let u = self.map_to_group(group_map, t);
+
+        // sum_i [chal_i^-1] L_i + [chal_i] R_i
+        let mut challenges = vec![];
+        let mut lr_sum: Option<PointVar> = None;
+        for ((l, r), chal) in opening.lr.iter().zip(&deferred.challenges) {
+            let l_var = self.witness_point(l);
+            let r_var = self.witness_point(r);
+            sponge.absorb_g(self, &[l_var, r_var]);
+            let challenge = sponge.challenge(self);
+            challenges.push(challenge);
+
+            // [chal^-1] L is provided by the prover, and checked to be mapped to L by [chal]
+            let chal_inv = chal.inverse().expect("the challenge is zero");
+            let l_inv = self.witness_point(&l.mul(chal_inv.into_repr()).into_affine());
+            let l_check = self.endo_scale(l_inv, endo, challenge);
+            self.assert_points_equal(l_check, l_var);
+
+            let r_scaled = self.endo_scale(r_var, endo, challenge);
+            let term = self.add_points(l_inv, r_scaled);
+            lr_sum = Some(match lr_sum {
+                None => term,
+                Some(acc) => self.add_points(acc, term),
+            });
+        }
+
+        let delta = self.witness_point(&opening.delta);
+        sponge.absorb_g(self, &[delta]);
+        let c = sponge.challenge(self);
+
+        // [c] (sum_i ([chal_i^-1] L_i + [chal_i] R_i) + combined + [combined_inner_product] U) + delta
+        // = [z1] (sg + [b0] U) + [z2] H
+        let cip_u = self.scale_shifted(u, combined_inner_product);
+        let mut q = self.add_points(combined, cip_u);
+        if let Some(lr_sum) = lr_sum {
+            q = self.add_points(lr_sum, q);
+        }
+        let c_q = self.endo_scale(q, endo, c);
+        let lhs = self.add_points(c_q, delta);
+
+        let b0 = self.shifted_scalar(deferred.b0);
+        let z1 = self.shifted_scalar(opening.z1);
+        let z2 = self.shifted_scalar(opening.z2);
+        let sg = self.witness_point(&opening.sg);
+        let h = self
+            .constant_point(&index.srs.h)
+            .expect("H is not the point at infinity");
+
+        let b0_u = self.scale_shifted(u, b0);
+        let sg_b0_u = self.add_points(sg, b0_u);
+        let z1_term = self.scale_shifted(sg_b0_u, z1);
+        let z2_term = self.scale_shifted(h, z2);
+        let rhs = self.add_points(z1_term, z2_term);
+        self.assert_points_equal(lhs, rhs);
+
+        ProofVars {
+            public,
+            beta,
+            gamma,
+            alpha_chal,
+            zeta_chal,
+            digest,
+            v_chal,
+            f_scalars,
+            t_scalars,
+            combined_inner_product,
+            shifted_combined_inner_product: shifted_cip,
+            b0,
+            challenges,
+            sg,
+        }
+    }
+}
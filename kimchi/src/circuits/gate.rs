@@ -92,16 +92,21 @@ pub struct JointLookup<F> {
 }
 
 impl<F: Field> JointLookup<F> {
-    // TODO: Support multiple tables
-    /// Evaluate the combined value of a joint-lookup.
-    pub fn evaluate<G: Fn(LocalPosition) -> F>(&self, joint_combiner: F, eval: &G) -> F {
+    /// Evaluate the combined value of a joint-lookup,
+    /// in which the table id is combined with `table_id_combiner` (see [LookupTable]).
+    pub fn evaluate<G: Fn(LocalPosition) -> F>(
+        &self,
+        joint_combiner: F,
+        table_id_combiner: F,
+        eval: &G,
+    ) -> F {
         let mut res = F::zero();
         let mut c = F::one();
         for s in self.entry.iter() {
             res += c * s.evaluate(eval);
             c *= joint_combiner;
         }
-        res
+        res + table_id_combiner * F::from(self.table_id as u64)
     }
 }
 
@@ -151,6 +156,10 @@ pub enum GateType {
     ChaCha1 = 8,
     ChaCha2 = 9,
     ChaChaFinal = 10,
+    /// EC fixed base scalar multiplication, with a lookup in a table of precomputed multiples
+    FixedBaseMul = 11,
+    /// Poseidon permutation gate with the legacy constants (x^5 S-box, MDS matrix in the coefficients)
+    PoseidonLegacy = 12,
 }
 
 /// Describes the desired lookup configuration.
@@ -191,11 +200,47 @@ pub enum GateLookupTable {
     Xor,
 }
 
-pub type LookupTable<F> = Vec<Vec<F>>;
+/// The id of the XOR table of the ChaCha gates.
+pub const XOR_TABLE_ID: usize = 0;
+
+/// The id of the table of precomputed multiples of the `FixedBaseMul` gates,
+/// which the circuit passes to the constraint system
+/// (see [crate::circuits::polynomials::fixed_base_mul::FixedBaseTable::lookup_table]).
+pub const FIXED_BASE_TABLE_ID: usize = 1;
+
+/// A lookup table, with the id that the lookups into it refer to (see [JointLookup::table_id]).
+///
+/// The lookup argument checks the lookups against the concatenation of the tables of a circuit.
+/// If one of them has a non-zero id, the columns of each table are padded with zeros
+/// to the maximum joint size of the lookups, and followed by a column holding the id of the table,
+/// which the lookups combine with the power of the joint combiner of that column.
+/// A circuit with the single table of id 0 thus has no column of ids.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LookupTable<F> {
+    /// the id of the table
+    pub id: usize,
+    /// the columns of the table
+    pub data: Vec<Vec<F>>,
+}
+
+impl<F> LookupTable<F> {
+    /// The number of entries of the table.
+    pub fn len(&self) -> usize {
+        self.data.first().map_or(0, Vec::len)
+    }
+
+    /// Whether the table has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
 
 pub fn get_table<F: FftField>(table_name: GateLookupTable) -> LookupTable<F> {
     match table_name {
-        GateLookupTable::Xor => crate::circuits::polynomials::chacha::xor_table(),
+        GateLookupTable::Xor => LookupTable {
+            id: XOR_TABLE_ID,
+            data: crate::circuits::polynomials::chacha::xor_table(),
+        },
     }
 }
 
@@ -250,7 +295,6 @@ impl<F: FftField> LookupInfo<F> {
     ) -> (Vec<Evaluations<F>>, Vec<LookupTable<F>>) {
         let n = domain.d1.size as usize;
         let mut selector_values: Vec<_> = self.kinds.iter().map(|_| vec![F::zero(); n]).collect();
-        let mut gate_tables = HashSet::new();
 
         // TODO: is take(n) useful here? I don't see why we need this
         for (i, gate) in gates.iter().enumerate().take(n) {
@@ -262,13 +306,6 @@ impl<F: FftField> LookupInfo<F> {
             if let Some(selector_index) = self.kinds_map.get(&(typ, CurrOrNext::Next)) {
                 selector_values[*selector_index][i + 1] = F::one();
             }
-
-            if let Some(table_kind) = self.kinds_tables.get(&(typ, CurrOrNext::Curr)) {
-                gate_tables.insert(*table_kind);
-            }
-            if let Some(table_kind) = self.kinds_tables.get(&(typ, CurrOrNext::Next)) {
-                gate_tables.insert(*table_kind);
-            }
         }
 
         // Actually, don't need to evaluate over domain 8 here.
@@ -281,8 +318,22 @@ impl<F: FftField> LookupInfo<F> {
                     .evaluate_over_domain(domain.d8)
             })
             .collect();
-        let res_tables: Vec<_> = gate_tables.into_iter().map(get_table).collect();
-        (selector_values8, res_tables)
+        (selector_values8, self.gate_tables(gates))
+    }
+
+    /// The fixed lookup tables used by the gates, sorted by id.
+    pub fn gate_tables(&self, gates: &[CircuitGate<F>]) -> Vec<LookupTable<F>> {
+        let mut gate_tables = HashSet::new();
+        for gate in gates {
+            for r in [CurrOrNext::Curr, CurrOrNext::Next] {
+                if let Some(table_kind) = self.kinds_tables.get(&(gate.typ, r)) {
+                    gate_tables.insert(*table_kind);
+                }
+            }
+        }
+        let mut tables: Vec<_> = gate_tables.into_iter().map(get_table).collect();
+        tables.sort_by_key(|t| t.id);
+        tables
     }
 
     /// For each row in the circuit, which lookup-constraints should be enforced at that row.
@@ -322,11 +373,12 @@ pub struct GatesLookupMaps {
 
 impl GateType {
     /// Which lookup-patterns should be applied on which rows.
-    /// Currently there is only the lookup pattern used in the ChaCha rows, and it
-    /// is applied to each ChaCha row and its successor.
+    /// The lookup pattern used in the ChaCha rows is applied to each ChaCha row and its successor,
+    /// and the one of the FixedBaseMul rows to each of these rows,
+    /// in the table of precomputed multiples passed to the constraint system.
     ///
-    /// See circuits/kimchi/src/polynomials/chacha.rs for an explanation of
-    /// how these work.
+    /// See circuits/kimchi/src/polynomials/chacha.rs and circuits/kimchi/src/polynomials/fixed_base_mul.rs
+    /// for an explanation of how these work.
     pub fn lookup_kinds<F: Field>() -> (Vec<Vec<JointLookup<F>>>, Vec<GatesLookupSpec>) {
         let curr_row = |column| LocalPosition {
             row: CurrOrNext::Curr,
@@ -349,7 +401,7 @@ impl GateType {
                     value: vec![(F::one(), loc)],
                 };
                 JointLookup {
-                    table_id: 0,
+                    table_id: XOR_TABLE_ID,
                     entry: vec![l(left), l(right), l(output)],
                 }
             })
@@ -377,7 +429,7 @@ impl GateType {
                     value: vec![(one_half, nybble), (neg_one_half, low_bit)],
                 };
                 JointLookup {
-                    table_id: 0,
+                    table_id: XOR_TABLE_ID,
                     entry: vec![x.clone(), x, SingleLookup { value: vec![] }],
                 }
             })
@@ -388,6 +440,23 @@ impl GateType {
             chacha_final_where.insert((ChaChaFinal, *r));
         }
 
+        // the window, digit and coordinates of the point added in a FixedBaseMul row
+        //
+        // 0 1 2 3 4 5 6 7
+        // - - - i - d x y
+        let fixed_base_mul_pattern = vec![JointLookup {
+            table_id: FIXED_BASE_TABLE_ID,
+            entry: [3, 5, 6, 7]
+                .iter()
+                .map(|col| SingleLookup {
+                    value: vec![(F::one(), curr_row(*col))],
+                })
+                .collect(),
+        }];
+
+        let mut fixed_base_mul_where = HashSet::new();
+        fixed_base_mul_where.insert((FixedBaseMul, Curr));
+
         let lookups = [
             (chacha_pattern, chacha_where, Some(GateLookupTable::Xor)),
             (
@@ -395,6 +464,7 @@ impl GateType {
                 chacha_final_where,
                 Some(GateLookupTable::Xor),
             ),
+            (fixed_base_mul_pattern, fixed_base_mul_where, None),
         ];
 
         // Convert from an array of tuples to a tuple of vectors
@@ -500,11 +570,12 @@ impl<F: FftField> CircuitGate<F> {
             Generic => self.verify_generic(row, witness, public),
             Poseidon => self.verify_poseidon(row, witness, cs),
             CompleteAdd => self.verify_complete_add(row, witness),
-            VarBaseMul => self.verify_vbmul(row, witness),
+            VarBaseMul => self.verify_vbmul(row, witness, cs),
             EndoMul => self.verify_endomul(row, witness, cs),
             EndoMulScalar => self.verify_endomul_scalar(row, witness, cs),
-            // TODO: implement the verification for chacha
-            ChaCha0 | ChaCha1 | ChaCha2 | ChaChaFinal => Ok(()),
+            ChaCha0 | ChaCha1 | ChaCha2 | ChaChaFinal => self.verify_chacha(row, witness),
+            FixedBaseMul => self.verify_fixed_base_mul(row, witness, cs),
+            PoseidonLegacy => self.verify_poseidon_legacy(row, witness),
         }
     }
 }
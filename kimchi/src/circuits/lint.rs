@@ -0,0 +1,333 @@
+//! This module implements a static analyzer of circuits (see [lint]),
+//! which flags the patterns that usually come with soundness bugs:
+//!
+//! * the cells whose value no constraint reads, although they are wired to other cells (see [Lint::UnconstrainedCells]);
+//! * the gates that are only wired to themselves (see [Lint::IdentityWiring]);
+//! * the public inputs that are not wired to any other gate (see [Lint::UnconnectedPublic]);
+//! * the [GateType::Zero] gates in the middle of the circuit (see [Lint::ZeroGate]).
+//!
+//! Given a witness, [lint_witness] also flags the cells that hold a value,
+//! although no constraint, lookup or copy constraint reads them (see [Lint::UnusedCell]).
+//! Every gate type leaves some of its columns unread (the complete addition reads 11 of the 15 columns,
+//! the generic gate only the registers it uses, and the padding rows none),
+//! so that only the unread cells whose value is not zero are flagged:
+//! they usually hold a value that the circuit meant to constrain.
+//!
+//! The cells read by a gate are given by the [Argument] expressions of its type,
+//! on its row and on the next one, and by the lookups of its type.
+//! The generic gate only reads the registers whose coefficients are not zero,
+//! and the public input of its row.
+
+use crate::circuits::{
+    argument::Argument,
+    expr::{Column, E},
+    gate::{CircuitGate, CurrOrNext, GateType, LookupInfo},
+    polynomials::{
+        chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFinal},
+        complete_add::CompleteAdd,
+        endomul_scalar::EndomulScalar,
+        endosclmul::EndosclMul,
+        fixed_base_mul::FixedBaseMul,
+        generic::{GENERIC_COEFFS, GENERIC_REGISTERS},
+        poseidon::Poseidon,
+        poseidon_legacy::PoseidonLegacy,
+        varbasemul::VarbaseMul,
+    },
+    program::{Op, Program},
+    wires::{Wire, COLUMNS, PERMUTS},
+};
+use ark_ff::FftField;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::{self, Display},
+    ops::Range,
+};
+
+/// A pattern of a circuit that usually comes with a soundness bug
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Lint {
+    /// The cells of a permutation cycle, none of which is read by a constraint, a lookup, or as a public input:
+    /// the copy constraints make them equal, but nothing constrains their value.
+    UnconstrainedCells { cells: Vec<Wire> },
+    /// The cells of the gates of these rows, which read each other's rows, are only wired to these gates:
+    /// their permutation cycles are identities, or stay within the rows.
+    IdentityWiring { rows: Range<usize> },
+    /// The public input of this row is not wired to any other gate.
+    UnconnectedPublic { row: usize },
+    /// This [GateType::Zero] gate comes before other gates,
+    /// and is not the next row of a gate reading it.
+    ZeroGate { row: usize },
+    /// This cell of the witness holds a value that is not zero,
+    /// but no constraint, lookup or copy constraint reads it (see [lint_witness]).
+    UnusedCell { row: usize, col: usize },
+}
+
+impl Lint {
+    /// The first row concerned by the lint
+    pub fn row(&self) -> usize {
+        match self {
+            Lint::UnconstrainedCells { cells } => {
+                cells.iter().map(|cell| cell.row).min().unwrap_or(0)
+            }
+            Lint::IdentityWiring { rows } => rows.start,
+            Lint::UnconnectedPublic { row }
+            | Lint::ZeroGate { row }
+            | Lint::UnusedCell { row, .. } => *row,
+        }
+    }
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lint::UnconstrainedCells { cells } => {
+                let cells: Vec<_> = cells
+                    .iter()
+                    .map(|cell| format!("({}, {})", cell.row, cell.col))
+                    .collect();
+                write!(
+                    f,
+                    "the cells {} are wired together, but no constraint reads them",
+                    cells.join(", ")
+                )
+            }
+            Lint::IdentityWiring { rows } if rows.len() == 1 => {
+                write!(
+                    f,
+                    "the gate of row {} is not wired to any other gate",
+                    rows.start
+                )
+            }
+            Lint::IdentityWiring { rows } => write!(
+                f,
+                "the gates of rows {} to {} are not wired to any other gate",
+                rows.start,
+                rows.end - 1
+            ),
+            Lint::UnconnectedPublic { row } => {
+                write!(
+                    f,
+                    "the public input of row {} is not wired to any gate",
+                    row
+                )
+            }
+            Lint::ZeroGate { row } => write!(
+                f,
+                "the zero gate of row {} is in the middle of the circuit",
+                row
+            ),
+            Lint::UnusedCell { row, col } => write!(
+                f,
+                "the cell ({}, {}) holds a value, but no constraint reads it",
+                row, col
+            ),
+        }
+    }
+}
+
+/// The witness cells read by the constraints of a gate type, relative to its row
+fn gate_cells<F: FftField>(typ: GateType) -> BTreeSet<(CurrOrNext, usize)> {
+    let constraints: Vec<E<F>> = match typ {
+        GateType::Zero | GateType::Generic => vec![],
+        GateType::Poseidon => Poseidon::<F>::constraints(),
+        GateType::CompleteAdd => CompleteAdd::<F>::constraints(),
+        GateType::VarBaseMul => VarbaseMul::<F>::constraints(),
+        GateType::EndoMul => EndosclMul::<F>::constraints(),
+        GateType::EndoMulScalar => EndomulScalar::<F>::constraints(),
+        GateType::ChaCha0 => ChaCha0::<F>::constraints(),
+        GateType::ChaCha1 => ChaCha1::<F>::constraints(),
+        GateType::ChaCha2 => ChaCha2::<F>::constraints(),
+        GateType::ChaChaFinal => ChaChaFinal::<F>::constraints(),
+        GateType::FixedBaseMul => FixedBaseMul::<F>::constraints(),
+        GateType::PoseidonLegacy => PoseidonLegacy::<F>::constraints(),
+    };
+    Program::lower_all(&constraints)
+        .ops
+        .into_iter()
+        .filter_map(|op| match op {
+            Op::Cell(v) => match v.col {
+                Column::Witness(col) => Some((v.row, col)),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// The registers of a generic gate multiplied by a coefficient that is not zero
+fn generic_registers<F: FftField>(gate: &CircuitGate<F>) -> Vec<usize> {
+    let coeff = |i: usize| gate.coeffs.get(i).map_or(false, |c| !c.is_zero());
+    let mut registers = vec![];
+    for half in 0..2 {
+        let (coeffs, regs) = (half * GENERIC_COEFFS, half * GENERIC_REGISTERS);
+        for i in 0..GENERIC_REGISTERS {
+            // the two first registers are multiplied together
+            let mul = i < 2 && coeff(coeffs + GENERIC_REGISTERS);
+            if coeff(coeffs + i) || mul {
+                registers.push(regs + i);
+            }
+        }
+    }
+    registers
+}
+
+/// The cells, as `(row, column)`, read by the lookups of the gates.
+/// The lookups of the last gate can read the row after it.
+pub fn lookup_cells<F: FftField>(gates: &[CircuitGate<F>]) -> BTreeSet<(usize, usize)> {
+    let info = LookupInfo::<F>::create();
+    let mut cells = BTreeSet::new();
+    for (row, kind) in info.by_row(gates).iter().enumerate() {
+        for single in kind.iter().flat_map(|lookup| &lookup.entry) {
+            for (_, pos) in &single.value {
+                cells.insert((row + pos.row.shift(), pos.column));
+            }
+        }
+    }
+    cells
+}
+
+/// The permutation cycles of the gates, including the cells wired to themselves,
+/// in the order of their first cell.
+/// Each cycle follows the wires from its first cell, until coming back to it
+/// (or to a visited cell or out of the circuit, if the wiring is not a permutation).
+pub fn cycles<F: FftField>(gates: &[CircuitGate<F>]) -> impl Iterator<Item = Vec<Wire>> + '_ {
+    let mut visited = BTreeSet::new();
+    (0..gates.len())
+        .flat_map(|row| (0..PERMUTS).map(move |col| Wire { row, col }))
+        .filter_map(move |start| {
+            let mut cycle = vec![];
+            let mut cell = start;
+            while cell.row < gates.len()
+                && cell.col < PERMUTS
+                && visited.insert((cell.row, cell.col))
+            {
+                cycle.push(cell);
+                cell = gates[cell.row].wires[cell.col];
+            }
+            if cycle.is_empty() {
+                None
+            } else {
+                Some(cycle)
+            }
+        })
+}
+
+/// Flags the patterns of the circuit that usually come with soundness bugs, sorted by row.
+/// The `public` first rows of the circuit hold its public inputs.
+pub fn lint<F: FftField>(gates: &[CircuitGate<F>], public: usize) -> Vec<Lint> {
+    lints(gates, public, None)
+}
+
+/// Flags the patterns flagged by [lint], and the cells of the `witness` of the circuit
+/// holding a value that nothing reads (see [Lint::UnusedCell]), sorted by row.
+pub fn lint_witness<F: FftField>(
+    gates: &[CircuitGate<F>],
+    public: usize,
+    witness: &[Vec<F>; COLUMNS],
+) -> Vec<Lint> {
+    lints(gates, public, Some(witness))
+}
+
+fn lints<F: FftField>(
+    gates: &[CircuitGate<F>],
+    public: usize,
+    witness: Option<&[Vec<F>; COLUMNS]>,
+) -> Vec<Lint> {
+    let n = gates.len();
+    let mut cells: HashMap<GateType, BTreeSet<(CurrOrNext, usize)>> = HashMap::new();
+    let mut reads_next = vec![false; n];
+
+    // the cells read by the constraints, the lookups and the public inputs
+    let mut read = vec![[false; COLUMNS]; n + 1];
+    for (row, gate) in gates.iter().enumerate() {
+        if gate.typ == GateType::Generic {
+            for col in generic_registers(gate) {
+                read[row][col] = true;
+            }
+            continue;
+        }
+        let gate_cells = cells
+            .entry(gate.typ)
+            .or_insert_with(|| gate_cells::<F>(gate.typ));
+        for (curr_or_next, col) in gate_cells.iter() {
+            read[row + curr_or_next.shift()][*col] = true;
+            reads_next[row] |= *curr_or_next == CurrOrNext::Next;
+        }
+    }
+    for (row, col) in lookup_cells(gates) {
+        if let Some(read) = read.get_mut(row) {
+            read[col] = true;
+        }
+    }
+    for read in read.iter_mut().take(public) {
+        read[0] = true;
+    }
+
+    // the permutation cycles
+    let mut lints = vec![];
+    let mut cycle_of = vec![[0; PERMUTS]; n];
+    let cycles: Vec<_> = cycles(gates).collect();
+    for (i, cycle) in cycles.iter().enumerate() {
+        for cell in cycle {
+            cycle_of[cell.row][cell.col] = i;
+        }
+        if cycle.len() > 1 && cycle.iter().all(|cell| !read[cell.row][cell.col]) {
+            lints.push(Lint::UnconstrainedCells {
+                cells: cycle.clone(),
+            });
+        }
+    }
+
+    // the public inputs
+    for row in 0..std::cmp::min(public, n) {
+        if cycles[cycle_of[row][0]].iter().all(|cell| cell.row == row) {
+            lints.push(Lint::UnconnectedPublic { row });
+        }
+    }
+
+    // the blocks of gates reading each other's rows
+    let mut start = 0;
+    for (row, reads_next) in reads_next.iter().enumerate() {
+        if row + 1 < n && *reads_next {
+            continue;
+        }
+        let rows = start..row + 1;
+        start = row + 1;
+
+        let zero = gates[rows.clone()].iter().all(|g| g.typ == GateType::Zero);
+        let public = rows.end <= public;
+        let isolated = rows.clone().all(|row| {
+            cycle_of[row]
+                .iter()
+                .all(|cycle| cycles[*cycle].iter().all(|cell| rows.contains(&cell.row)))
+        });
+        if isolated && !zero && !public {
+            lints.push(Lint::IdentityWiring { rows });
+        }
+    }
+
+    // the zero gates before the last gate
+    let last = gates.iter().rposition(|g| g.typ != GateType::Zero);
+    for (row, gate) in gates.iter().enumerate() {
+        let next_row = row > 0 && reads_next[row - 1];
+        if gate.typ == GateType::Zero && last.map_or(false, |last| row < last) && !next_row {
+            lints.push(Lint::ZeroGate { row });
+        }
+    }
+
+    // the cells holding a value that is neither read nor wired
+    if let Some(witness) = witness {
+        for (row, read) in read.iter().enumerate().take(n) {
+            for (col, read) in read.iter().enumerate() {
+                let wired = col < PERMUTS && cycles[cycle_of[row][col]].len() > 1;
+                let value = witness[col].get(row).map_or(false, |x| !x.is_zero());
+                if value && !read && !wired {
+                    lints.push(Lint::UnusedCell { row, col });
+                }
+            }
+        }
+    }
+
+    lints.sort_by_key(|lint| lint.row());
+    lints
+}
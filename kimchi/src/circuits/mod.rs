@@ -2,11 +2,18 @@
 pub mod macros;
 
 pub mod argument;
+pub mod codegen;
 pub mod constraints;
 pub mod domains;
+#[rustfmt::skip]
+pub mod evaluators;
 pub mod expr;
+pub mod fft;
+pub mod gadgets;
 pub mod gate;
+pub mod lint;
 pub mod polynomial;
 pub mod polynomials;
+pub mod program;
 pub mod scalars;
 pub mod wires;
@@ -148,8 +148,10 @@ use std::marker::PhantomData;
 
 use crate::circuits::{
     argument::{Argument, ArgumentType},
+    evaluators,
     expr::{constraints::boolean, prologue::*, ConstantExpr as C},
-    gate::{CurrOrNext, GateType},
+    gate::{CircuitGate, CurrOrNext, GateType},
+    wires::COLUMNS,
 };
 use ark_ff::{FftField, Field, Zero};
 
@@ -344,6 +346,38 @@ where
     }
 }
 
+impl<F: FftField> CircuitGate<F> {
+    /// Check the correctness of witness values for a ChaCha gate.
+    /// The XOR lookups are not checked here, only the gate's constraints.
+    pub fn verify_chacha(&self, row: usize, witness: &[Vec<F>; COLUMNS]) -> Result<(), String> {
+        let this: [F; COLUMNS] = array_init::array_init(|i| witness[i][row]);
+        let next: [F; COLUMNS] = array_init::array_init(|i| witness[i][row + 1]);
+
+        let constraints = match self.typ {
+            GateType::ChaCha0 => {
+                evaluators::chacha0(&this, &next, &self.coeffs, F::zero(), &[]).to_vec()
+            }
+            GateType::ChaCha1 => {
+                evaluators::chacha1(&this, &next, &self.coeffs, F::zero(), &[]).to_vec()
+            }
+            GateType::ChaCha2 => {
+                evaluators::chacha2(&this, &next, &self.coeffs, F::zero(), &[]).to_vec()
+            }
+            GateType::ChaChaFinal => {
+                evaluators::chacha_final(&this, &next, &self.coeffs, F::zero(), &[]).to_vec()
+            }
+            _ => return Err("incorrect gate type".to_string()),
+        };
+        for (i, x) in constraints.iter().enumerate() {
+            if !x.is_zero() {
+                return Err(format!("Bad {:?} equation {}", self.typ, i));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 // TODO: move this to test file
 pub mod testing {
     use super::*;
@@ -517,7 +551,7 @@ mod tests {
             expr::{Column, Constants, PolishToken},
             gate::LookupInfo,
             scalars::{LookupEvaluations, ProofEvaluations},
-            wires::*,
+            wires::COLUMNS,
         },
     };
     use ark_ff::UniformRand;
@@ -562,6 +596,7 @@ mod tests {
             h.insert(Column::LookupAggreg);
             h.insert(Column::LookupTable);
             h.insert(Column::Index(GateType::Poseidon));
+            h.insert(Column::Index(GateType::PoseidonLegacy));
             h.insert(Column::Index(GateType::Generic));
             h
         };
@@ -589,6 +624,7 @@ mod tests {
             s: array_init(|_| F::rand(rng)),
             generic_selector: F::zero(),
             poseidon_selector: F::zero(),
+            poseidon_legacy_selector: None,
             lookup: Some(LookupEvaluations {
                 sorted: (0..(lookup_info.max_per_row + 1))
                     .map(|_| F::rand(rng))
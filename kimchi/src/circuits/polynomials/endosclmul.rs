@@ -35,11 +35,10 @@ use ark_ff::{FftField, Field, One};
 
 use crate::circuits::constraints::ConstraintSystem;
 use crate::circuits::gate::CircuitGate;
-use crate::circuits::scalars::ProofEvaluations;
 use crate::circuits::wires::GateWires;
 use crate::circuits::{
     argument::{Argument, ArgumentType},
-    expr,
+    evaluators,
     expr::{constraints::boolean, prologue::*, Cache, ConstantExpr},
     gate::GateType,
     wires::COLUMNS,
@@ -90,31 +89,10 @@ impl<F: FftField> CircuitGate<F> {
         let this: [F; COLUMNS] = array_init::array_init(|i| witness[i][row]);
         let next: [F; COLUMNS] = array_init::array_init(|i| witness[i][row + 1]);
 
-        let pt = F::from(123456u64);
-
-        let constants = expr::Constants {
-            alpha: F::zero(),
-            beta: F::zero(),
-            gamma: F::zero(),
-            joint_combiner: F::zero(),
-            mds: vec![],
-            endo_coefficient: cs.endo,
-        };
-
-        let evals: [ProofEvaluations<F>; 2] = [
-            ProofEvaluations::dummy_with_witness_evaluations(this),
-            ProofEvaluations::dummy_with_witness_evaluations(next),
-        ];
-
-        let constraints = EndosclMul::constraints();
-        for (i, c) in constraints.iter().enumerate() {
-            match c.evaluate_(cs.domain.d1, pt, &evals, &constants) {
-                Ok(x) => {
-                    if x != F::zero() {
-                        return Err(format!("Bad endo equation {}", i));
-                    }
-                }
-                Err(e) => return Err(format!("evaluation failed: {}", e)),
+        let constraints = evaluators::endosclmul(&this, &next, &self.coeffs, cs.endo, &[]);
+        for (i, x) in constraints.iter().enumerate() {
+            if !x.is_zero() {
+                return Err(format!("Bad endo equation {}", i));
             }
         }
 
@@ -0,0 +1,306 @@
+//! This module implements short Weierstrass curve fixed base scalar multiplication,
+//! with a lookup in a table of precomputed multiples of the base.
+//!
+//! The scalar `n < 2^254` is split in windows of `w` bits, `n = sum_i d_i 2^(w i)`,
+//! and the table holds, for each window `i` and each digit `d`, the point
+//!
+//! ```ignore
+//! T(i, d) = [d 2^(w i)] G + Q_i
+//! ```
+//!
+//! where the offsets `Q_i` sum to `-Q` and keep the table and the accumulator away from the point at infinity:
+//! `Q_i = [2] Q` for all the windows but the first one, and `Q_0 = -[2 W - 1] Q`, with `W` the number of windows.
+//! Starting from `Acc = Q`, the gate adds one point of the table per row, from the last window to the first one,
+//! so that after the first window `Acc = [n] G`.
+//! Until then, the accumulator holds an odd multiple of `Q` and the points of the table an even one,
+//! so that the additions are not exceptional, unless the discrete logarithm of `Q` is known.
+//!
+//! The table is passed to the constraint system as the lookup table of id [FIXED_BASE_TABLE_ID]
+//! (see [FixedBaseTable::lookup_table]), so that a circuit can only multiply a single fixed base.
+
+use std::marker::PhantomData;
+
+use ark_ec::{
+    short_weierstrass_jacobian::{GroupAffine, GroupProjective},
+    AffineCurve, ProjectiveCurve, SWModelParameters,
+};
+use ark_ff::{BigInteger, FftField, Field, One, PrimeField, Zero};
+use blake2::{Blake2b512, Digest};
+use commitment_dlog::{commitment::CommitmentCurve, srs::point_of_random_bytes};
+use groupmap::GroupMap;
+
+use crate::circuits::{
+    argument::{Argument, ArgumentType},
+    constraints::ConstraintSystem,
+    evaluators,
+    expr::{prologue::*, Cache},
+    gate::{CircuitGate, GateType, LookupTable, FIXED_BASE_TABLE_ID},
+    wires::COLUMNS,
+};
+
+//~ The layout is
+//~
+//~ |  0 |  1 | 2 | 3 | 4 | 5 |  6 |  7 | 8 |  9  |
+//~ |:--:|:--:|:-:|:-:|:-:|:-:|:--:|:--:|:-:|:---:|
+//~ | xa | ya | n | i | f | d | xp | yp | s | inv |
+//~
+//~ where
+//~ - `(xa, ya)` is the accumulator and `n` the scalar accumulated so far, which the next row continues,
+//~ - `i` is one more than the window of the row, and decreases from one row to the next,
+//~ - `f = 2^w` is passed unchanged from one row to the next,
+//~ - `d` is the digit of the window, and `(xp, yp) = T(i, d)` is looked up in the table,
+//~ - `s` is the slope of the addition of `(xp, yp)` to the accumulator,
+//~ - `inv` is the inverse of `xp - xa`, which excludes the exceptional cases of the addition.
+//~
+//~ The row following the last window holds the result in its first three columns.
+//~
+
+/// The number of bits of the scalars multiplied by the `FixedBaseMul` gate.
+/// Scalars below `2^254` have a unique representation in both Pasta fields.
+pub const SCALAR_BITS: usize = 254;
+
+/// The precomputed multiples of a fixed base, for windows of `window_bits` bits.
+#[derive(Clone, Debug)]
+pub struct FixedBaseTable<P: SWModelParameters> {
+    /// the base of the multiplication
+    pub base: GroupAffine<P>,
+    /// the number of bits of a window
+    pub window_bits: usize,
+    /// the initial value of the accumulator
+    pub offset: GroupAffine<P>,
+    /// the points `T(i, d)`, by window `i` and digit `d`
+    points: Vec<Vec<GroupAffine<P>>>,
+}
+
+impl<P: SWModelParameters> FixedBaseTable<P>
+where
+    P::BaseField: PrimeField,
+{
+    /// Precomputes the multiples of `base`.
+    /// The offset is derived from the base by hashing to the curve, so that its discrete logarithm is unknown.
+    pub fn create(base: GroupAffine<P>, window_bits: usize) -> Self {
+        assert!(!base.infinity, "the base cannot be the point at infinity");
+        assert!(
+            (1..=16).contains(&window_bits),
+            "unsupported number of bits per window"
+        );
+
+        let offset: GroupAffine<P> = {
+            let map = <GroupAffine<P> as CommitmentCurve>::Map::setup();
+            let mut h = Blake2b512::new();
+            h.update("fixed_base_offset".as_bytes());
+            h.update(&base.x.into_repr().to_bytes_le());
+            h.update(&base.y.into_repr().to_bytes_le());
+            point_of_random_bytes(&map, &h.finalize())
+        };
+
+        let num_windows = (SCALAR_BITS + window_bits - 1) / window_bits;
+        let mut window_base = base.into_projective();
+        let mut points = Vec::with_capacity(num_windows);
+        for i in 0..num_windows {
+            let bits = std::cmp::min(window_bits, SCALAR_BITS - window_bits * i);
+            let q_i = if i == 0 {
+                -offset.mul(P::ScalarField::from(2 * num_windows as u64 - 1))
+            } else {
+                offset.mul(P::ScalarField::from(2u64))
+            };
+
+            let mut window: Vec<GroupProjective<P>> = Vec::with_capacity(1 << bits);
+            let mut acc = q_i;
+            for _ in 0..(1 << bits) {
+                window.push(acc);
+                acc += &window_base;
+            }
+            points.push(GroupProjective::batch_normalization_into_affine(&window));
+
+            for _ in 0..window_bits {
+                window_base.double_in_place();
+            }
+        }
+
+        FixedBaseTable {
+            base,
+            window_bits,
+            offset,
+            points,
+        }
+    }
+
+    /// The number of windows, which is the number of rows of a multiplication.
+    pub fn num_windows(&self) -> usize {
+        self.points.len()
+    }
+
+    /// The point `T(i, d)` of the window `i` and the digit `d`.
+    pub fn point(&self, i: usize, d: usize) -> GroupAffine<P> {
+        self.points[i][d]
+    }
+
+    /// The lookup table to pass to the constraint system, as the columns `[i + 1, d, x, y]`.
+    /// Its last entry is the zero entry required by the lookup argument,
+    /// which no `FixedBaseMul` row can look up as their first column is never zero.
+    pub fn lookup_table(&self) -> LookupTable<P::BaseField> {
+        let mut table = vec![vec![]; 4];
+        for (i, window) in self.points.iter().enumerate() {
+            for (d, p) in window.iter().enumerate() {
+                table[0].push(P::BaseField::from(i as u64 + 1));
+                table[1].push(P::BaseField::from(d as u64));
+                table[2].push(p.x);
+                table[3].push(p.y);
+            }
+        }
+        for col in &mut table {
+            col.push(P::BaseField::zero());
+        }
+        LookupTable {
+            id: FIXED_BASE_TABLE_ID,
+            data: table,
+        }
+    }
+}
+
+/// Appends to `witness_cols` the rows of the multiplication of the base of `table` by `n`,
+/// followed by the row holding the result.
+/// Panics if `n` is not smaller than `2^254`, or in the exceptional case where `[n] G` is the point at infinity.
+pub fn gen_witness<P: SWModelParameters>(
+    witness_cols: &mut [Vec<P::BaseField>; COLUMNS],
+    table: &FixedBaseTable<P>,
+    n: P::BaseField,
+) where
+    P::BaseField: PrimeField,
+{
+    let bits = n.into_repr().to_bits_le();
+    assert!(
+        bits[SCALAR_BITS..].iter().all(|b| !b),
+        "the scalar does not fit in {} bits",
+        SCALAR_BITS
+    );
+
+    let w = table.window_bits;
+    let f = P::BaseField::from(2u64).pow(&[w as u64]);
+    let (mut xa, mut ya) = (table.offset.x, table.offset.y);
+    let mut acc = P::BaseField::zero();
+
+    for i in (0..table.num_windows()).rev() {
+        let end = std::cmp::min(w * (i + 1), SCALAR_BITS);
+        let d = bits[w * i..end]
+            .iter()
+            .rev()
+            .fold(0usize, |acc, b| 2 * acc + *b as usize);
+        let p = table.point(i, d);
+        let dx = p.x - xa;
+        let inv = dx
+            .inverse()
+            .expect("exceptional case in the fixed base multiplication");
+        let s = (p.y - ya) * inv;
+        let d = P::BaseField::from(d as u64);
+
+        let row = [
+            xa,
+            ya,
+            acc,
+            P::BaseField::from(i as u64 + 1),
+            f,
+            d,
+            p.x,
+            p.y,
+            s,
+            inv,
+        ];
+        for (col, v) in witness_cols.iter_mut().zip(row) {
+            col.push(v);
+        }
+        for col in witness_cols.iter_mut().skip(row.len()) {
+            col.push(P::BaseField::zero());
+        }
+
+        let x_next = s.square() - xa - p.x;
+        ya = s * (xa - x_next) - ya;
+        xa = x_next;
+        acc = f * acc + d;
+    }
+
+    let mut row = [P::BaseField::zero(); COLUMNS];
+    row[..5].copy_from_slice(&[xa, ya, acc, P::BaseField::zero(), f]);
+    for (col, v) in witness_cols.iter_mut().zip(row) {
+        col.push(v);
+    }
+}
+
+impl<F: FftField> CircuitGate<F> {
+    pub fn verify_fixed_base_mul(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        cs: &ConstraintSystem<F>,
+    ) -> Result<(), String> {
+        ensure_eq!(self.typ, GateType::FixedBaseMul, "incorrect gate type");
+
+        let this: [F; COLUMNS] = array_init::array_init(|i| witness[i][row]);
+        let next: [F; COLUMNS] = array_init::array_init(|i| witness[i][row + 1]);
+
+        let constraints = evaluators::fixed_base_mul(&this, &next, &self.coeffs, cs.endo, &[]);
+        for (i, x) in constraints.iter().enumerate() {
+            if !x.is_zero() {
+                return Err(format!("Bad fixed base mul equation {}", i));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Implementation of the FixedBaseMul gate.
+/// It uses the constraints
+///
+///   i' = i - 1
+///   f' = f
+///   n' = f * n + d
+///   (xp - xa) * inv = 1
+///   (xp - xa) * s = yp - ya
+///   s^2 = xa + xp + xa'
+///   ya' = s (xa - xa') - ya
+///
+/// while the lookup argument checks that `(i, d, xp, yp)` is in the table.
+#[derive(Default)]
+pub struct FixedBaseMul<F>(PhantomData<F>);
+
+impl<F> Argument<F> for FixedBaseMul<F>
+where
+    F: FftField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::FixedBaseMul);
+    const CONSTRAINTS: u32 = 7;
+
+    fn constraints() -> Vec<E<F>> {
+        let xa = witness_curr(0);
+        let ya = witness_curr(1);
+        let n = witness_curr(2);
+        let i = witness_curr(3);
+        let f = witness_curr(4);
+        let d = witness_curr(5);
+        let xp = witness_curr(6);
+        let yp = witness_curr(7);
+        let s = witness_curr(8);
+        let inv = witness_curr(9);
+
+        let xa_next = witness_next(0);
+        let ya_next = witness_next(1);
+        let n_next = witness_next(2);
+        let i_next = witness_next(3);
+        let f_next = witness_next(4);
+
+        let mut cache = Cache::default();
+        let dx = cache.cache(xp.clone() - xa.clone());
+
+        vec![
+            i_next - i + E::one(),
+            f_next - f.clone(),
+            n_next - (f * n + d),
+            dx.clone() * inv - E::one(),
+            dx * s.clone() - (yp - ya.clone()),
+            s.clone() * s.clone() - xa.clone() - xp - xa_next.clone(),
+            s * (xa - xa_next) - ya - ya_next,
+        ]
+    }
+}
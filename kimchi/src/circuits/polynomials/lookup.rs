@@ -137,7 +137,6 @@ use CurrOrNext::*;
 /// Number of constraints produced by the argument.
 pub const CONSTRAINTS: u32 = 7;
 
-// TODO: Update for multiple tables
 fn single_lookup<F: FftField>(s: &SingleLookup<F>) -> E<F> {
     // Combine the linear combination.
     s.value
@@ -152,12 +151,21 @@ fn single_lookup<F: FftField>(s: &SingleLookup<F>) -> E<F> {
         .fold(E::zero(), |acc, e| acc + e)
 }
 
-fn joint_lookup<F: FftField>(j: &JointLookup<F>) -> E<F> {
-    j.entry
+/// The combined value of a joint lookup, whose table id is combined with the power `max_joint_size`
+/// of the joint combiner (see [crate::circuits::gate::LookupTable]).
+fn joint_lookup<F: FftField>(j: &JointLookup<F>, max_joint_size: usize) -> E<F> {
+    let combined = j
+        .entry
         .iter()
         .enumerate()
         .map(|(i, s)| E::constant(ConstantExpr::JointCombiner.pow(i as u64)) * single_lookup(s))
-        .fold(E::zero(), |acc, x| acc + x)
+        .fold(E::zero(), |acc, x| acc + x);
+    if j.table_id == 0 {
+        combined
+    } else {
+        let table_id = ConstantExpr::Literal(F::from(j.table_id as u64));
+        combined + E::constant(ConstantExpr::JointCombiner.pow(max_joint_size as u64) * table_id)
+    }
 }
 
 struct AdjacentPairs<A, I: Iterator<Item = A>> {
@@ -267,6 +275,7 @@ pub fn verify<F: FftField, I: Iterator<Item = F>, G: Fn() -> I>(
 
     let lookup_info = LookupInfo::<F>::create();
     let by_row = lookup_info.by_row(gates);
+    let table_id_combiner = joint_combiner.pow(&[lookup_info.max_joint_size as u64]);
 
     // Compute lookups||table and check multiset equality
     let sorted_counts: HashMap<F, usize> = {
@@ -298,7 +307,8 @@ pub fn verify<F: FftField, I: Iterator<Item = F>, G: Fn() -> I>(
             witness[pos.column][row]
         };
         for joint_lookup in spec.iter() {
-            let joint_lookup_evaluation = joint_lookup.evaluate(joint_combiner, &eval);
+            let joint_lookup_evaluation =
+                joint_lookup.evaluate(joint_combiner, table_id_combiner, &eval);
             *all_lookups.entry(joint_lookup_evaluation).or_insert(0) += 1
         }
 
@@ -340,10 +350,11 @@ pub trait Entry {
 pub struct CombinedEntry<F>(pub F);
 impl<F: Field> Entry for CombinedEntry<F> {
     type Field = F;
-    type Params = F;
+    /// the joint combiner, and the table id combiner
+    type Params = (F, F);
 
     fn evaluate(
-        joint_combiner: &F,
+        (joint_combiner, table_id_combiner): &(F, F),
         j: &JointLookup<F>,
         witness: &[Vec<F>; COLUMNS],
         row: usize,
@@ -356,7 +367,7 @@ impl<F: Field> Entry for CombinedEntry<F> {
             witness[pos.column][row]
         };
 
-        CombinedEntry(j.evaluate(*joint_combiner, &eval))
+        CombinedEntry(j.evaluate(*joint_combiner, *table_id_combiner, &eval))
     }
 }
 
@@ -392,7 +403,6 @@ pub fn sorted<
     I: Iterator<Item = E>,
     G: Fn() -> I,
 >(
-    // TODO: Multiple tables
     dummy_lookup_value: E,
     lookup_table: G,
     d1: D<F>,
@@ -460,6 +470,10 @@ pub fn sorted<
             sorted[i].push(end_val);
         }
 
+        // the last column ends with the last entry of the table, which is the dummy value,
+        // as it is not followed by the first entry of another column
+        sorted[max_lookups_per_row].push(dummy_lookup_value);
+
         // snake-ify (see top comment)
         for s in sorted.iter_mut().skip(1).step_by(2) {
             s.reverse();
@@ -532,6 +546,7 @@ pub fn aggregation<R: Rng + ?Sized, F: FftField, I: Iterator<Item = F>>(
 
     let lookup_info = LookupInfo::<F>::create();
     let max_lookups_per_row = lookup_info.max_per_row;
+    let table_id_combiner = joint_combiner.pow(&[lookup_info.max_joint_size as u64]);
 
     let complements_with_beta_term = {
         let mut v = vec![F::one()];
@@ -568,7 +583,7 @@ pub fn aggregation<R: Rng + ?Sized, F: FftField, I: Iterator<Item = F>>(
                 // `max_lookups_per_row (=4) * n` field elements of
                 // memory.
                 spec.iter().fold(padding, |acc, j| {
-                    acc * (gamma + j.evaluate(joint_combiner, &eval))
+                    acc * (gamma + j.evaluate(joint_combiner, table_id_combiner, &eval))
                 })
             };
 
@@ -644,7 +659,7 @@ pub fn constraints<F: FftField>(dummy_lookup: &[F], d1: D<F>) -> Vec<E<F>> {
         let padding = complements_with_beta_term[lookup_info.max_per_row - spec.len()].clone();
 
         spec.iter()
-            .map(|j| E::Constant(ConstantExpr::Gamma) + joint_lookup(j))
+            .map(|j| E::Constant(ConstantExpr::Gamma) + joint_lookup(j, lookup_info.max_joint_size))
             .fold(E::Constant(padding), |acc: E<F>, x| acc * x)
     };
     let f_chunk = lookup_info
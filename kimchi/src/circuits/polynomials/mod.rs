@@ -2,8 +2,10 @@ pub mod chacha;
 pub mod complete_add;
 pub mod endomul_scalar;
 pub mod endosclmul;
+pub mod fixed_base_mul;
 pub mod generic;
 pub mod lookup;
 pub mod permutation;
 pub mod poseidon;
+pub mod poseidon_legacy;
 pub mod varbasemul;
@@ -27,8 +27,9 @@
 
 use crate::circuits::argument::{Argument, ArgumentType};
 use crate::circuits::expr::{prologue::*, Cache, ConstantExpr};
+use crate::circuits::gadgets::builder::{BuiltCircuit, CircuitBuilder, Var};
 use crate::circuits::gate::{CircuitGate, CurrOrNext, GateType};
-use ark_ff::{FftField, Field};
+use ark_ff::{FftField, Field, PrimeField};
 use oracle::constants::{PlonkSpongeConstantsKimchi, SpongeConstants};
 use oracle::poseidon::{sbox, ArithmeticSponge, ArithmeticSpongeParams, Sponge};
 use std::marker::PhantomData;
@@ -55,6 +56,9 @@ pub const ROUNDS_PER_HASH: usize = PlonkSpongeConstantsKimchi::PERM_ROUNDS_FULL;
 /// Number of PLONK rows required to implement Poseidon
 pub const POS_ROWS_PER_HASH: usize = ROUNDS_PER_HASH / ROUNDS_PER_ROW;
 
+/// Rate of the sponge
+pub const SPONGE_RATE: usize = PlonkSpongeConstantsKimchi::SPONGE_RATE;
+
 /// The order in a row in which we store states before and after permutations
 pub const STATE_ORDER: [usize; ROUNDS_PER_ROW] = [
     0, // the first state is stored first
@@ -259,6 +263,76 @@ pub fn generate_witness<F: Field>(
     }
 }
 
+impl<F: PrimeField> CircuitGate<F> {
+    /// `create_poseidon_hash_gadget(row, input_len, params)` creates the gates of the hash
+    /// of `input_len` field elements by a [crate::circuits::gadgets::sponge::SpongeGadget] with the Kimchi constants and the sponge parameters `params`
+    /// (see [CircuitBuilder::poseidon_hash]), starting at row `row`.
+    /// The inputs (see [hash_cells]) are only wired to the gates of the hash.
+    /// The function returns the gates, as well as the next empty absolute row.
+    pub fn create_poseidon_hash_gadget(
+        row: usize,
+        input_len: usize,
+        params: &ArithmeticSpongeParams<F>,
+    ) -> (Vec<Self>, usize) {
+        let (built, _, _) = hash_circuit(params, &vec![F::zero(); input_len]);
+        let gates: Vec<_> = built
+            .gates
+            .into_iter()
+            .map(|mut gate| {
+                for wire in gate.wires.iter_mut() {
+                    wire.row += row;
+                }
+                gate
+            })
+            .collect();
+        let next_row = row + gates.len();
+        (gates, next_row)
+    }
+}
+
+/// Lays out the hash of `input` from the row 0,
+/// and returns the circuit with the cells of the inputs and of the hash.
+fn hash_circuit<F: PrimeField>(
+    params: &ArithmeticSpongeParams<F>,
+    input: &[F],
+) -> (BuiltCircuit<F>, Vec<Var>, Var) {
+    let mut b = CircuitBuilder::new(params.clone());
+    let inputs: Vec<_> = input.iter().map(|x| b.witness(*x)).collect();
+    let output = b.poseidon_hash(&inputs);
+    (b.build(), inputs, output)
+}
+
+/// `generate_hash_witness(row, params, witness_cols, input)` generates the witness of
+/// [CircuitGate::create_poseidon_hash_gadget] starting at row `row` in `witness_cols`,
+/// for the hash of `input` with a sponge initialized with `params`, and returns the hash.
+pub fn generate_hash_witness<F: PrimeField>(
+    row: usize,
+    params: ArithmeticSpongeParams<F>,
+    witness_cols: &mut [Vec<F>; COLUMNS],
+    input: &[F],
+) -> F {
+    let (built, _, output) = hash_circuit(&params, input);
+    for (col, values) in witness_cols.iter_mut().zip(&built.witness) {
+        col[row..row + values.len()].copy_from_slice(values);
+    }
+    built.witness[output.col][output.row]
+}
+
+/// The cells holding the inputs and the output of [CircuitGate::create_poseidon_hash_gadget]
+/// of `input_len` elements, starting at row `row`.
+pub fn hash_cells<F: PrimeField>(
+    row: usize,
+    input_len: usize,
+    params: &ArithmeticSpongeParams<F>,
+) -> (Vec<Wire>, Wire) {
+    let (_, inputs, output) = hash_circuit(params, &vec![F::zero(); input_len]);
+    let cell = |v: Var| Wire {
+        row: row + v.row,
+        col: v.col,
+    };
+    (inputs.into_iter().map(cell).collect(), cell(output))
+}
+
 /// An equation of the form `(curr | next)[i] = round(curr[j])`
 struct RoundEquation {
     pub source: usize,
@@ -0,0 +1,268 @@
+//! This module implements the constraint polynomials of the Poseidon permutation
+//! with the legacy constants (see [PlonkSpongeConstantsLegacy]).
+
+//~ The legacy permutation adds the first round constants to the state,
+//~ and then applies 63 full rounds with the S-box $x^5$.
+//~ Its MDS matrix is not the one of the Kimchi sponge parameters of the constraint system,
+//~ so that the poseidon legacy gate reads it from the first 9 coefficients of the row,
+//~ which leaves room for the round constants of 2 rounds only.
+//~ A state is represented by 3 field elements, and is stored in the register table as follows:
+//~
+//~ |  0 |  1 |  2 |  3 |  4 |  5 |  6 | ... | 14 |
+//~ |:--:|:--:|:--:|:--:|:--:|:--:|:--:|:---:|:--:|
+//~ | s0 | s0 | s0 | s1 | s1 | s1 |    |     |    |
+//~ | s2 | s2 | s2 |    |    |    |    |     |    |
+//~
+//~ The state after the second round is stored on the next row, which is either another poseidon legacy gate,
+//~ or a Zero gate following the last one.
+//~ As 63 is odd, the second round of the last gate is a dummy round (with zero round constants),
+//~ whose output in the next row is not used:
+//~ the output of the permutation is the state in columns 3 to 5 of the last gate.
+//~
+//~ The initial addition of the round constants is not part of the gate,
+//~ and the first gate holds the state after it.
+//~
+
+use crate::circuits::argument::{Argument, ArgumentType};
+use crate::circuits::expr::{prologue::*, Cache};
+use crate::circuits::gate::{CircuitGate, CurrOrNext, GateType};
+use crate::circuits::polynomials::poseidon::SPONGE_WIDTH;
+use crate::circuits::wires::{GateWires, Wire, COLUMNS};
+use ark_ff::{FftField, Field};
+use oracle::constants::{PlonkSpongeConstantsLegacy, SpongeConstants};
+use oracle::poseidon::{sbox, ArithmeticSpongeParams};
+use std::marker::PhantomData;
+use std::ops::Range;
+use CurrOrNext::*;
+
+//
+// Constants
+//
+
+/// Number of rounds of a row
+pub const ROUNDS_PER_ROW: usize = 2;
+
+/// Number of rounds, after the initial addition of the round constants
+pub const ROUNDS_PER_HASH: usize = PlonkSpongeConstantsLegacy::PERM_ROUNDS_FULL;
+
+/// Number of PLONK rows required to implement the legacy Poseidon permutation,
+/// without the Zero gate that follows them
+pub const ROWS_PER_HASH: usize = (ROUNDS_PER_HASH + ROUNDS_PER_ROW - 1) / ROUNDS_PER_ROW;
+
+/// Number of coefficients holding the MDS matrix, before the round constants
+pub const MDS_COEFFS: usize = SPONGE_WIDTH * SPONGE_WIDTH;
+
+/// The columns of the last row of the gates holding the output of the permutation
+pub const OUTPUT_COLS: Range<usize> = SPONGE_WIDTH..2 * SPONGE_WIDTH;
+
+/// For each round of a row, the columns of its input in the current row,
+/// and the row and columns of its output
+const ROUNDS: [(Range<usize>, CurrOrNext, Range<usize>); ROUNDS_PER_ROW] = [
+    (0..SPONGE_WIDTH, Curr, SPONGE_WIDTH..2 * SPONGE_WIDTH),
+    (SPONGE_WIDTH..2 * SPONGE_WIDTH, Next, 0..SPONGE_WIDTH),
+];
+
+/// One round of the legacy permutation: `mds * sbox(state) + rc`
+fn round<F: Field>(mds: &[Vec<F>], rc: &[F], state: &[F]) -> [F; SPONGE_WIDTH] {
+    array_init::array_init(|i| {
+        state.iter().zip(&mds[i]).fold(rc[i], |acc, (s, m)| {
+            acc + sbox::<F, PlonkSpongeConstantsLegacy>(*s) * m
+        })
+    })
+}
+
+impl<F: FftField> CircuitGate<F> {
+    pub fn create_poseidon_legacy(
+        wires: GateWires,
+        mds: &[Vec<F>],
+        // Round constants are passed in in the logical order
+        rc: [[F; SPONGE_WIDTH]; ROUNDS_PER_ROW],
+    ) -> Self {
+        CircuitGate {
+            typ: GateType::PoseidonLegacy,
+            wires,
+            coeffs: mds
+                .iter()
+                .flatten()
+                .chain(rc.iter().flatten())
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// `create_poseidon_legacy_gadget(row, first_and_last_row, params)` creates the gates of
+    /// the 63 rounds of a legacy Poseidon permutation with the sponge parameters `params`,
+    /// followed by a Zero gate holding the output of the dummy round.
+    /// The state after the initial addition of the round constants is in the first 3 columns of the first row,
+    /// and the output in the columns [OUTPUT_COLS] of the row `row + ROWS_PER_HASH - 1`.
+    /// The function returns the gates, as well as the next empty absolute row.
+    pub fn create_poseidon_legacy_gadget(
+        // the absolute row in the circuit
+        row: usize,
+        // first and last row of the gadget (because they are used in the permutation)
+        first_and_last_row: [GateWires; 2],
+        params: &ArithmeticSpongeParams<F>,
+    ) -> (Vec<Self>, usize) {
+        let mut gates = vec![];
+        let last_row = row + ROWS_PER_HASH;
+
+        for (rel_row, abs_row) in (row..last_row).enumerate() {
+            let wires = if rel_row == 0 {
+                first_and_last_row[0]
+            } else {
+                Wire::new(abs_row)
+            };
+
+            // the round constants of the initial addition come first, and the dummy round has none
+            let rc = array_init::array_init(|offset| {
+                params
+                    .round_constants
+                    .get(rel_row * ROUNDS_PER_ROW + offset + 1)
+                    .map(|rc| array_init::array_init(|i| rc[i]))
+                    .unwrap_or([F::zero(); SPONGE_WIDTH])
+            });
+
+            gates.push(CircuitGate::create_poseidon_legacy(wires, &params.mds, rc));
+        }
+
+        gates.push(CircuitGate::zero(first_and_last_row[1]));
+
+        (gates, last_row + 1)
+    }
+
+    /// Checks if a witness verifies a poseidon legacy gate
+    pub fn verify_poseidon_legacy(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+    ) -> Result<(), String> {
+        ensure_eq!(
+            self.typ,
+            GateType::PoseidonLegacy,
+            "incorrect gate type (should be poseidon legacy)"
+        );
+
+        let mds: Vec<Vec<F>> = self.coeffs[..MDS_COEFFS]
+            .chunks(SPONGE_WIDTH)
+            .map(|r| r.to_vec())
+            .collect();
+
+        for (r, (source, target_row, target)) in ROUNDS.iter().enumerate() {
+            let rc = &self.coeffs[MDS_COEFFS + SPONGE_WIDTH * r..][..SPONGE_WIDTH];
+            let state: Vec<F> = source.clone().map(|col| witness[col][row]).collect();
+            let target_row = match target_row {
+                Curr => row,
+                Next => row + 1,
+            };
+            for (i, (expected, col)) in round(&mds, rc, &state)
+                .iter()
+                .zip(target.clone())
+                .enumerate()
+            {
+                ensure_eq!(
+                    *expected,
+                    witness[col][target_row],
+                    format!("poseidon legacy: round {} of state[{}] is incorrect", r, i)
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// `generate_witness(row, params, witness_cols, input)` generates the witness of
+/// [CircuitGate::create_poseidon_legacy_gadget] starting at row `row` in `witness_cols`,
+/// for the legacy permutation of `input` with the sponge parameters `params`:
+/// the first row holds `input` plus the first round constants.
+pub fn generate_witness<F: Field>(
+    row: usize,
+    params: &ArithmeticSpongeParams<F>,
+    witness_cols: &mut [Vec<F>; COLUMNS],
+    input: [F; SPONGE_WIDTH],
+) {
+    let mut state: [F; SPONGE_WIDTH] =
+        array_init::array_init(|i| input[i] + params.round_constants[0][i]);
+    for (i, s) in state.iter().enumerate() {
+        witness_cols[i][row] = *s;
+    }
+
+    let zero = [F::zero(); SPONGE_WIDTH];
+    for r in 0..ROWS_PER_HASH * ROUNDS_PER_ROW {
+        let rc = params.round_constants.get(r + 1).map_or(&zero[..], |rc| rc);
+        state = round(&params.mds, rc, &state);
+
+        let (_, target_row, target) = &ROUNDS[r % ROUNDS_PER_ROW];
+        let target_row = match target_row {
+            Curr => row + r / ROUNDS_PER_ROW,
+            Next => row + r / ROUNDS_PER_ROW + 1,
+        };
+        for (col, s) in target.clone().zip(state) {
+            witness_cols[col][target_row] = s;
+        }
+    }
+}
+
+/// Implementation of the Poseidon legacy gate
+/// Conjunction of:
+///
+/// ```ignore
+/// curr[3..6] = round(curr[0..3])
+/// next[0..3] = round(curr[3..6])
+/// ```
+///
+/// where `round(s)[i] = mds[i][0] * s[0]^5 + mds[i][1] * s[1]^5 + mds[i][2] * s[2]^5 + rc[i]`,
+/// with the MDS matrix and the round constants read from the coefficients.
+#[derive(Default)]
+pub struct PoseidonLegacy<F>(PhantomData<F>);
+
+impl<F> Argument<F> for PoseidonLegacy<F>
+where
+    F: FftField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::PoseidonLegacy);
+    const CONSTRAINTS: u32 = (ROUNDS_PER_ROW * SPONGE_WIDTH) as u32;
+
+    fn constraints() -> Vec<E<F>> {
+        let mut res = vec![];
+        let mut cache = Cache::default();
+
+        //~ We define $M_{r, c}$ as the MDS matrix at row $r$ and column $c$,
+        //~ stored in the coefficient $c_{3r + c}$,
+        //~ followed by the round constants $r_0, \ldots, r_5$ of the 2 rounds.
+        let mds: Vec<Vec<_>> = (0..SPONGE_WIDTH)
+            .map(|row| {
+                (0..SPONGE_WIDTH)
+                    .map(|col| coeff(SPONGE_WIDTH * row + col))
+                    .collect()
+            })
+            .collect();
+
+        //~
+        //~ Each of the 6 registers of the output states is constrained as:
+        //~
+        //~ * $w_{3 + i} - [r_i + (M_{i, 0} w_0^5 + M_{i, 1} w_1^5 + M_{i, 2} w_2^5)]$
+        //~ * $w_{i, next} - [r_{3 + i} + (M_{i, 0} w_3^5 + M_{i, 1} w_4^5 + M_{i, 2} w_5^5)]$
+        //~
+        //~ where $w_{i, next}$ is the polynomial $w_i(\omega x)$ which points to the next row.
+        for (r, (source, target_row, target)) in ROUNDS.iter().enumerate() {
+            let sboxed: Vec<_> = source
+                .clone()
+                .map(|i| {
+                    cache.cache(witness_curr(i).pow(PlonkSpongeConstantsLegacy::PERM_SBOX as u64))
+                })
+                .collect();
+
+            for (j, col) in target.clone().enumerate() {
+                let rc = coeff(MDS_COEFFS + SPONGE_WIDTH * r + j);
+                let constraint = witness(col, *target_row)
+                    - sboxed
+                        .iter()
+                        .zip(mds[j].iter())
+                        .fold(rc, |acc, (x, m)| acc + m.clone() * x.clone());
+                res.push(constraint);
+            }
+        }
+        res
+    }
+}
@@ -17,6 +17,8 @@ use CurrOrNext::*;
 
 use crate::circuits::{
     argument::{Argument, ArgumentType},
+    constraints::ConstraintSystem,
+    evaluators,
     expr::{prologue::*, Cache, Column, Variable},
     gate::{CircuitGate, CurrOrNext, GateType},
     wires::{GateWires, COLUMNS},
@@ -132,8 +134,24 @@ impl<F: FftField> CircuitGate<F> {
         ]
     }
 
-    pub fn verify_vbmul(&self, _row: usize, _witness: &[Vec<F>; COLUMNS]) -> Result<(), String> {
-        // TODO: implement
+    pub fn verify_vbmul(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        cs: &ConstraintSystem<F>,
+    ) -> Result<(), String> {
+        ensure_eq!(self.typ, GateType::VarBaseMul, "incorrect gate type");
+
+        let this: [F; COLUMNS] = array_init::array_init(|i| witness[i][row]);
+        let next: [F; COLUMNS] = array_init::array_init(|i| witness[i][row + 1]);
+
+        let constraints = evaluators::varbasemul(&this, &next, &self.coeffs, cs.endo, &[]);
+        for (i, x) in constraints.iter().enumerate() {
+            if !x.is_zero() {
+                return Err(format!("Bad varbasemul equation {}", i));
+            }
+        }
+
         Ok(())
     }
 
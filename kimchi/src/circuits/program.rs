@@ -0,0 +1,715 @@
+//! This module implements an optimizing compiler of expressions (see [Expr]) to register-based programs.
+//!
+//! A [Program] is a list of operations in static single assignment form:
+//! the operation `i` stores its result in the register `i`, and its operands are registers of previous operations.
+//! An expression is first lowered to a program (see [Program::lower]),
+//! which is then optimized by the following passes (see [Program::optimize]):
+//!
+//! * common subexpression elimination, which computes identical operations only once,
+//!   including across the constraints of different gates;
+//! * constant folding, which computes the operations on literals, and simplifies the identities `x + 0`, `x * 1`, etc;
+//! * power factoring, which computes the powers of a register with multiplications,
+//!   from the smaller powers already computed (e.g. the powers of `alpha` combining the constraints);
+//! * dead code removal, which drops the operations whose result is not used.
+//!
+//! A program can compute several outputs, such as the constraints of a gate sharing their subexpressions (see [Program::compile_all]).
+//! A program evaluates at a point (see [Program::evaluate]), over an evaluation domain (see [Program::evaluations]),
+//! or compiles back to the reverse Polish notation of [PolishToken], with a [PolishToken::Store] for each register used more than once.
+
+use crate::circuits::{
+    constraints::eval_vanishes_on_last_4_rows,
+    expr::{
+        get_domain, unnormalized_lagrange_evals, CacheId, ConstantExpr, Constants, Domain,
+        Environment, Expr, Op2, PolishToken, Variable,
+    },
+    scalars::ProofEvaluations,
+};
+use ark_ff::{FftField, Field};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain as D};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// An operation of a [Program], whose operands are registers.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Op<F> {
+    Alpha,
+    Beta,
+    Gamma,
+    JointCombiner,
+    EndoCoefficient,
+    Mds { row: usize, col: usize },
+    Literal(F),
+    Cell(Variable),
+    VanishesOnLast4Rows,
+    UnnormalizedLagrangeBasis(usize),
+    Add(usize, usize),
+    Sub(usize, usize),
+    Mul(usize, usize),
+    Pow(usize, u64),
+}
+
+impl<F: Copy> Op<F> {
+    /// Returns the registers read by the operation.
+    pub fn registers(&self) -> Vec<usize> {
+        use Op::*;
+        match self {
+            Add(x, y) | Sub(x, y) | Mul(x, y) => vec![*x, *y],
+            Pow(x, _) => vec![*x],
+            _ => vec![],
+        }
+    }
+
+    /// Returns the operation with its registers renamed by `f`.
+    fn map_registers(&self, f: impl Fn(usize) -> usize) -> Self {
+        use Op::*;
+        match self {
+            Add(x, y) => Add(f(*x), f(*y)),
+            Sub(x, y) => Sub(f(*x), f(*y)),
+            Mul(x, y) => Mul(f(*x), f(*y)),
+            Pow(x, n) => Pow(f(*x), *n),
+            op => op.clone(),
+        }
+    }
+}
+
+/// The result of folding an operation: either the register of an equal value, or an operation to add.
+enum Folded<F> {
+    Register(usize),
+    Op(Op<F>),
+}
+
+/// A program in static single assignment form, which computes the values of the registers `outputs`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Program<F> {
+    /// the operations of the program
+    pub ops: Vec<Op<F>>,
+    /// the registers holding the results of the program
+    pub outputs: Vec<usize>,
+}
+
+impl<F: Field> Program<F> {
+    /// Compiles an expression to an optimized program.
+    pub fn compile(expr: &Expr<ConstantExpr<F>>) -> Self {
+        Self::lower(expr).optimize()
+    }
+
+    /// Compiles expressions to an optimized program with an output for each of them.
+    pub fn compile_all(exprs: &[Expr<ConstantExpr<F>>]) -> Self {
+        Self::lower_all(exprs).optimize()
+    }
+
+    /// Lowers an expression to a program, without optimization.
+    /// Each cached subexpression is computed once.
+    pub fn lower(expr: &Expr<ConstantExpr<F>>) -> Self {
+        Self::lower_all(std::slice::from_ref(expr))
+    }
+
+    /// Lowers expressions to a program with an output for each of them, without optimization.
+    pub fn lower_all(exprs: &[Expr<ConstantExpr<F>>]) -> Self {
+        let mut ops = vec![];
+        let mut cache = HashMap::new();
+        let outputs = exprs
+            .iter()
+            .map(|expr| lower_expr(expr, &mut ops, &mut cache))
+            .collect();
+        Program { ops, outputs }
+    }
+
+    /// Returns the output of a program computing a single expression.
+    fn output(&self) -> usize {
+        assert_eq!(self.outputs.len(), 1, "the program has several outputs");
+        self.outputs[0]
+    }
+
+    /// Applies all the optimization passes.
+    pub fn optimize(&self) -> Self {
+        self.eliminate_common_subexpressions()
+            .fold_constants()
+            .eliminate_common_subexpressions()
+            .factor_powers()
+            .remove_dead_code()
+    }
+
+    /// Rebuilds the program by pushing each of its operations, with renamed registers, with `push`,
+    /// which returns the register holding the result of the operation.
+    fn rewrite(&self, mut push: impl FnMut(&mut Vec<Op<F>>, Op<F>) -> usize) -> Self {
+        let mut ops = vec![];
+        let mut registers = Vec::with_capacity(self.ops.len());
+        for op in &self.ops {
+            let op = op.map_registers(|r| registers[r]);
+            registers.push(push(&mut ops, op));
+        }
+        Program {
+            ops,
+            outputs: self.outputs.iter().map(|r| registers[*r]).collect(),
+        }
+    }
+
+    /// Computes identical operations only once.
+    /// The operands of additions and multiplications are sorted, as they commute.
+    pub fn eliminate_common_subexpressions(&self) -> Self {
+        let mut registers = HashMap::new();
+        self.rewrite(|ops, op| {
+            let op = match op {
+                Op::Add(x, y) if y < x => Op::Add(y, x),
+                Op::Mul(x, y) if y < x => Op::Mul(y, x),
+                op => op,
+            };
+            *registers.entry(op.clone()).or_insert_with(|| {
+                ops.push(op);
+                ops.len() - 1
+            })
+        })
+    }
+
+    /// Computes the operations on literals, and simplifies the additions of zero,
+    /// the multiplications by zero or one, and the trivial powers.
+    pub fn fold_constants(&self) -> Self {
+        self.rewrite(|ops, op| match fold(ops, op) {
+            Folded::Register(r) => r,
+            Folded::Op(op) => {
+                ops.push(op);
+                ops.len() - 1
+            }
+        })
+    }
+
+    /// Computes the powers of a register with multiplications, reusing the powers already computed,
+    /// so that the `n` first powers of `alpha` take `n` multiplications, and `x^7` takes 4 of them.
+    pub fn factor_powers(&self) -> Self {
+        let mut powers = HashMap::new();
+        self.rewrite(|ops, op| match op {
+            Op::Pow(base, n) if n > 1 => power(ops, &mut powers, base, n),
+            op => {
+                ops.push(op);
+                ops.len() - 1
+            }
+        })
+    }
+
+    /// Removes the operations whose result is not used to compute the outputs.
+    pub fn remove_dead_code(&self) -> Self {
+        let mut live = vec![false; self.ops.len()];
+        for r in &self.outputs {
+            live[*r] = true;
+        }
+        for (i, op) in self.ops.iter().enumerate().rev() {
+            if live[i] {
+                for r in op.registers() {
+                    live[r] = true;
+                }
+            }
+        }
+
+        let mut ops = vec![];
+        let mut registers = vec![usize::MAX; self.ops.len()];
+        for (i, op) in self.ops.iter().enumerate() {
+            if live[i] {
+                registers[i] = ops.len();
+                ops.push(op.map_registers(|r| registers[r]));
+            }
+        }
+        Program {
+            ops,
+            outputs: self.outputs.iter().map(|r| registers[*r]).collect(),
+        }
+    }
+
+    /// Returns an upper bound of the degree of the polynomial computed in each register,
+    /// for a domain of size `d1_size` (see [Expr::degree]).
+    fn degrees(&self, d1_size: u64) -> Vec<u64> {
+        let mut degrees: Vec<u64> = Vec::with_capacity(self.ops.len());
+        for op in &self.ops {
+            use Op::*;
+            let degree = match op {
+                Cell(_) | UnnormalizedLagrangeBasis(_) => d1_size,
+                VanishesOnLast4Rows => 4,
+                Add(x, y) | Sub(x, y) => std::cmp::max(degrees[*x], degrees[*y]),
+                Mul(x, y) => degrees[*x] + degrees[*y],
+                Pow(x, n) => n * degrees[*x],
+                _ => 0,
+            };
+            degrees.push(degree);
+        }
+        degrees
+    }
+
+    /// Returns an upper bound of the degree of the polynomial computed by the program,
+    /// for a domain of size `d1_size` (see [Expr::degree]).
+    pub fn degree(&self, d1_size: u64) -> u64 {
+        self.degrees(d1_size)[self.output()]
+    }
+
+    /// Evaluates the outputs of the program at `pt`, with the values of the cells given by `cell`.
+    pub fn evaluate_with<'b>(
+        &self,
+        d: D<F>,
+        pt: F,
+        c: &Constants<F>,
+        cell: impl Fn(&Variable) -> Result<F, &'b str>,
+    ) -> Result<Vec<F>, &'b str>
+    where
+        F: FftField,
+    {
+        let mut registers: Vec<F> = Vec::with_capacity(self.ops.len());
+        for op in &self.ops {
+            use Op::*;
+            let x = match op {
+                Alpha => c.alpha,
+                Beta => c.beta,
+                Gamma => c.gamma,
+                JointCombiner => c.joint_combiner,
+                EndoCoefficient => c.endo_coefficient,
+                Mds { row, col } => c.mds[*row][*col],
+                Literal(x) => *x,
+                Cell(v) => cell(v)?,
+                VanishesOnLast4Rows => eval_vanishes_on_last_4_rows(d, pt),
+                UnnormalizedLagrangeBasis(i) => {
+                    d.evaluate_vanishing_polynomial(pt) / (pt - d.group_gen.pow(&[*i as u64]))
+                }
+                Add(x, y) => registers[*x] + registers[*y],
+                Sub(x, y) => registers[*x] - registers[*y],
+                Mul(x, y) => registers[*x] * registers[*y],
+                Pow(x, n) => registers[*x].pow(&[*n]),
+            };
+            registers.push(x);
+        }
+        Ok(self.outputs.iter().map(|r| registers[*r]).collect())
+    }
+
+    /// Evaluates the program at `pt`, like [PolishToken::evaluate].
+    pub fn evaluate<'b>(
+        &self,
+        d: D<F>,
+        pt: F,
+        evals: &[ProofEvaluations<F>],
+        c: &Constants<F>,
+    ) -> Result<F, &'b str>
+    where
+        F: FftField,
+    {
+        let [value]: [F; 1] = self
+            .evaluate_with(d, pt, c, |v| v.evaluate(evals))?
+            .try_into()
+            .expect("the program has several outputs");
+        Ok(value)
+    }
+
+    /// Computes the evaluations of the program over the smallest domain that can hold them
+    /// (see [Domain::for_degree]), like [Expr::evaluations].
+    /// The registers are evaluated by chunks of points, and the ones which only depend on constants are evaluated once.
+    pub fn evaluations(&self, env: &Environment<F>) -> Evaluations<F, D<F>>
+    where
+        F: FftField,
+    {
+        let output = self.output();
+        let d1_size = env.domain.d1.size;
+        let degree = self.degree(d1_size);
+        let d = Domain::for_degree(degree, d1_size)
+            .unwrap_or_else(|| panic!("constraint had degree {} > 8", degree));
+        let res_domain = get_domain(d, env);
+        let size = res_domain.size as usize;
+
+        // the constant registers, including the columns missing from the environment
+        let mut constants: Vec<Option<F>> = Vec::with_capacity(self.ops.len());
+        for op in &self.ops {
+            let value = match op {
+                Op::Cell(v) if env.get_column(&v.col).is_none() => Some(F::zero()),
+                op => evaluate_constant(op, &constants, &env.constants),
+            };
+            constants.push(value);
+        }
+        if let Some(x) = constants[output] {
+            return Evaluations::from_vec_and_domain(vec![x; size], res_domain);
+        }
+
+        // the evaluations of the unnormalized Lagrange basis
+        let lagrange: HashMap<usize, Evaluations<F, D<F>>> = self
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                Op::UnnormalizedLagrangeBasis(i) => {
+                    Some((*i, unnormalized_lagrange_evals(env.l0_1, *i, d, env)))
+                }
+                _ => None,
+            })
+            .collect();
+
+        // the last operation reading each register
+        let mut last_use = vec![0; self.ops.len()];
+        for (i, op) in self.ops.iter().enumerate() {
+            for r in op.registers() {
+                last_use[r] = i;
+            }
+        }
+        last_use[output] = usize::MAX;
+
+        // the registers are stored in slots of `CHUNK` points, which are reused once the registers are dead,
+        // and the constant registers read by the steps have their own slots
+        let mut slots = vec![usize::MAX; self.ops.len()];
+        let mut constant_slots = vec![];
+        let mut free = vec![];
+        let mut slot_count = 0;
+        let mut steps = vec![];
+        for (i, op) in self.ops.iter().enumerate() {
+            if constants[i].is_some() {
+                continue;
+            }
+            let mut slot = |r: usize, slot_count: &mut usize| {
+                if slots[r] == usize::MAX {
+                    // the slots of the constants are initialized once, and never reused
+                    let reused = constants[r].is_none().then(|| free.pop()).flatten();
+                    slots[r] = reused.unwrap_or_else(|| {
+                        *slot_count += 1;
+                        *slot_count - 1
+                    });
+                    if let Some(x) = constants[r] {
+                        constant_slots.push((slots[r], x));
+                    }
+                }
+                slots[r]
+            };
+            let operands: Vec<usize> = op
+                .registers()
+                .into_iter()
+                .map(|r| slot(r, &mut slot_count))
+                .collect();
+            let dst = slot(i, &mut slot_count);
+            let step = match op {
+                Op::Cell(Variable { col, row }) => {
+                    let evals = env.get_column(col).expect("missing columns are constant");
+                    Step::read(dst, evals, size, d1_size as usize, row.shift())
+                }
+                Op::VanishesOnLast4Rows => {
+                    Step::read(dst, env.vanishes_on_last_4_rows, size, d1_size as usize, 0)
+                }
+                Op::UnnormalizedLagrangeBasis(j) => {
+                    Step::read(dst, &lagrange[j], size, d1_size as usize, 0)
+                }
+                Op::Add(..) => Step::Add(dst, operands[0], operands[1]),
+                Op::Sub(..) => Step::Sub(dst, operands[0], operands[1]),
+                Op::Mul(..) => Step::Mul(dst, operands[0], operands[1]),
+                Op::Pow(_, n) => Step::Pow(dst, operands[0], *n),
+                _ => unreachable!("the other operations are constant"),
+            };
+            steps.push(step);
+            let mut dead: Vec<usize> = op
+                .registers()
+                .into_iter()
+                .filter(|r| constants[*r].is_none() && last_use[*r] == i)
+                .collect();
+            dead.dedup();
+            free.extend(dead.into_iter().map(|r| slots[r]));
+        }
+
+        let mut init = vec![F::zero(); slot_count * CHUNK];
+        for (slot, x) in constant_slots {
+            init[slot * CHUNK..(slot + 1) * CHUNK].fill(x);
+        }
+        let output = slots[output] * CHUNK;
+        let mut evals = vec![F::zero(); size];
+        evals.par_chunks_mut(CHUNK).enumerate().for_each_init(
+            || init.clone(),
+            |registers, (c, res)| {
+                for step in &steps {
+                    step.evaluate(registers, c * CHUNK, res.len());
+                }
+                res.copy_from_slice(&registers[output..output + res.len()]);
+            },
+        );
+        Evaluations::from_vec_and_domain(evals, res_domain)
+    }
+
+    /// Compiles the program to reverse Polish notation,
+    /// storing the registers used more than once the first time they are computed.
+    pub fn to_polish(&self) -> Vec<PolishToken<F>> {
+        let mut uses = vec![0; self.ops.len()];
+        let output = self.output();
+        uses[output] += 1;
+        for op in &self.ops {
+            for r in op.registers() {
+                uses[r] += 1;
+            }
+        }
+        let mut stored = vec![None; self.ops.len()];
+        let mut res = vec![];
+        self.to_polish_(output, &uses, &mut stored, &mut 0, &mut res);
+        res
+    }
+
+    fn to_polish_(
+        &self,
+        register: usize,
+        uses: &[usize],
+        stored: &mut Vec<Option<usize>>,
+        stores: &mut usize,
+        res: &mut Vec<PolishToken<F>>,
+    ) {
+        if let Some(pos) = stored[register] {
+            res.push(PolishToken::Load(pos));
+            return;
+        }
+        use Op::*;
+        match &self.ops[register] {
+            Alpha => res.push(PolishToken::Alpha),
+            Beta => res.push(PolishToken::Beta),
+            Gamma => res.push(PolishToken::Gamma),
+            JointCombiner => res.push(PolishToken::JointCombiner),
+            EndoCoefficient => res.push(PolishToken::EndoCoefficient),
+            Mds { row, col } => res.push(PolishToken::Mds {
+                row: *row,
+                col: *col,
+            }),
+            Literal(x) => res.push(PolishToken::Literal(*x)),
+            Cell(v) => res.push(PolishToken::Cell(*v)),
+            VanishesOnLast4Rows => res.push(PolishToken::VanishesOnLast4Rows),
+            UnnormalizedLagrangeBasis(i) => res.push(PolishToken::UnnormalizedLagrangeBasis(*i)),
+            Add(x, y) | Sub(x, y) | Mul(x, y) => {
+                self.to_polish_(*x, uses, stored, stores, res);
+                self.to_polish_(*y, uses, stored, stores, res);
+                res.push(match &self.ops[register] {
+                    Add(..) => PolishToken::Add,
+                    Sub(..) => PolishToken::Sub,
+                    _ => PolishToken::Mul,
+                });
+            }
+            Pow(x, n) => {
+                self.to_polish_(*x, uses, stored, stores, res);
+                res.push(PolishToken::Pow(*n));
+            }
+        }
+        if uses[register] > 1 {
+            res.push(PolishToken::Store);
+            stored[register] = Some(*stores);
+            *stores += 1;
+        }
+    }
+}
+
+/// The number of points of the domain evaluated together by the steps of [Program::evaluations]
+const CHUNK: usize = 64;
+
+/// A step of the evaluation of a program over an evaluation domain,
+/// which computes a slot of `CHUNK` points from other slots
+enum Step<'a, F> {
+    /// reads `evals[(scale * j + offset) % evals.len()]` at the point `j`
+    Read {
+        dst: usize,
+        evals: &'a [F],
+        scale: usize,
+        offset: usize,
+    },
+    Add(usize, usize, usize),
+    Sub(usize, usize, usize),
+    Mul(usize, usize, usize),
+    Pow(usize, usize, u64),
+}
+
+impl<'a, F: FftField> Step<'a, F> {
+    /// Reads evaluations over a domain at least as large as the result domain of size `size`,
+    /// `shift` rows ahead in the domain of the circuit of size `d1_size`.
+    fn read(
+        dst: usize,
+        evals: &'a Evaluations<F, D<F>>,
+        size: usize,
+        d1_size: usize,
+        shift: usize,
+    ) -> Self {
+        let len = evals.evals.len();
+        let scale = len / size;
+        assert!(scale != 0);
+        Step::Read {
+            dst,
+            evals: &evals.evals,
+            scale,
+            offset: (len / d1_size) * shift,
+        }
+    }
+
+    /// Evaluates the step at the `n` points from `start`.
+    fn evaluate(&self, slots: &mut [F], start: usize, n: usize) {
+        let binop = |slots: &mut [F], dst: usize, x: usize, y: usize, f: fn(F, F) -> F| {
+            for k in 0..n {
+                slots[dst * CHUNK + k] = f(slots[x * CHUNK + k], slots[y * CHUNK + k]);
+            }
+        };
+        match *self {
+            Step::Read {
+                dst,
+                evals,
+                scale,
+                offset,
+            } => {
+                for k in 0..n {
+                    slots[dst * CHUNK + k] = evals[(scale * (start + k) + offset) % evals.len()];
+                }
+            }
+            Step::Add(dst, x, y) => binop(slots, dst, x, y, |x, y| x + y),
+            Step::Sub(dst, x, y) => binop(slots, dst, x, y, |x, y| x - y),
+            Step::Mul(dst, x, y) => binop(slots, dst, x, y, |x, y| x * y),
+            Step::Pow(dst, x, e) => {
+                for k in 0..n {
+                    slots[dst * CHUNK + k] = slots[x * CHUNK + k].pow(&[e]);
+                }
+            }
+        }
+    }
+}
+
+/// Evaluates an operation on constants, given the values of the constant registers,
+/// or returns `None` if the operation depends on the point of evaluation.
+fn evaluate_constant<F: Field>(op: &Op<F>, constants: &[Option<F>], c: &Constants<F>) -> Option<F> {
+    let r = |x: &usize| constants[*x];
+    use Op::*;
+    Some(match op {
+        Alpha => c.alpha,
+        Beta => c.beta,
+        Gamma => c.gamma,
+        JointCombiner => c.joint_combiner,
+        EndoCoefficient => c.endo_coefficient,
+        Mds { row, col } => c.mds[*row][*col],
+        Literal(x) => *x,
+        Add(x, y) => r(x)? + r(y)?,
+        Sub(x, y) => r(x)? - r(y)?,
+        Mul(x, y) => r(x)? * r(y)?,
+        Pow(x, n) => r(x)?.pow(&[*n]),
+        Cell(_) | VanishesOnLast4Rows | UnnormalizedLagrangeBasis(_) => return None,
+    })
+}
+
+/// Folds an operation, given the operations of the registers it reads.
+fn fold<F: Field>(ops: &[Op<F>], op: Op<F>) -> Folded<F> {
+    let literal = |r: usize| match ops[r] {
+        Op::Literal(x) => Some(x),
+        _ => None,
+    };
+    let is = |r: usize, x: F| literal(r) == Some(x);
+    let (zero, one) = (F::zero(), F::one());
+    match op {
+        Op::Add(x, y) => match (literal(x), literal(y)) {
+            (Some(a), Some(b)) => Folded::Op(Op::Literal(a + b)),
+            _ if is(x, zero) => Folded::Register(y),
+            _ if is(y, zero) => Folded::Register(x),
+            _ => Folded::Op(op),
+        },
+        Op::Sub(x, y) => match (literal(x), literal(y)) {
+            (Some(a), Some(b)) => Folded::Op(Op::Literal(a - b)),
+            _ if is(y, zero) => Folded::Register(x),
+            _ if x == y => Folded::Op(Op::Literal(zero)),
+            _ => Folded::Op(op),
+        },
+        Op::Mul(x, y) => match (literal(x), literal(y)) {
+            (Some(a), Some(b)) => Folded::Op(Op::Literal(a * b)),
+            _ if is(x, zero) || is(y, zero) => Folded::Op(Op::Literal(zero)),
+            _ if is(x, one) => Folded::Register(y),
+            _ if is(y, one) => Folded::Register(x),
+            _ => Folded::Op(op),
+        },
+        Op::Pow(_, 0) => Folded::Op(Op::Literal(one)),
+        Op::Pow(x, 1) => Folded::Register(x),
+        Op::Pow(x, n) => match literal(x) {
+            Some(a) => Folded::Op(Op::Literal(a.pow(&[n]))),
+            None => Folded::Op(op),
+        },
+        op => Folded::Op(op),
+    }
+}
+
+/// Pushes the computation of `base^n` with multiplications, and returns its register.
+/// It multiplies the largest power of `base` already computed by the remaining power if it is at least `base^(n/2)`,
+/// and otherwise squares and multiplies.
+fn power<F: Copy>(
+    ops: &mut Vec<Op<F>>,
+    powers: &mut HashMap<usize, BTreeMap<u64, usize>>,
+    base: usize,
+    n: u64,
+) -> usize {
+    let known = powers
+        .entry(base)
+        .or_insert_with(|| BTreeMap::from_iter([(1, base)]));
+    if let Some(r) = known.get(&n) {
+        return *r;
+    }
+    let largest = known.range(..n).next_back().map(|(j, r)| (*j, *r));
+    let op = match largest {
+        Some((j, r)) if 2 * j >= n => Op::Mul(r, power(ops, powers, base, n - j)),
+        _ if n % 2 == 0 => {
+            let half = power(ops, powers, base, n / 2);
+            Op::Mul(half, half)
+        }
+        _ => Op::Mul(power(ops, powers, base, n - 1), base),
+    };
+    ops.push(op);
+    let register = ops.len() - 1;
+    powers.entry(base).or_default().insert(n, register);
+    register
+}
+
+fn lower_constant<F: Field>(c: &ConstantExpr<F>, ops: &mut Vec<Op<F>>) -> usize {
+    use ConstantExpr::*;
+    let op = match c {
+        Alpha => Op::Alpha,
+        Beta => Op::Beta,
+        Gamma => Op::Gamma,
+        JointCombiner => Op::JointCombiner,
+        EndoCoefficient => Op::EndoCoefficient,
+        Mds { row, col } => Op::Mds {
+            row: *row,
+            col: *col,
+        },
+        Literal(x) => Op::Literal(*x),
+        Pow(x, n) => Op::Pow(lower_constant(x, ops), *n),
+        Add(x, y) => Op::Add(lower_constant(x, ops), lower_constant(y, ops)),
+        Mul(x, y) => Op::Mul(lower_constant(x, ops), lower_constant(y, ops)),
+        Sub(x, y) => Op::Sub(lower_constant(x, ops), lower_constant(y, ops)),
+    };
+    ops.push(op);
+    ops.len() - 1
+}
+
+/// Lowers an expression, and returns the register holding its value.
+/// The cached subexpressions are looked up by id and by value,
+/// as the constraints of different gates can cache different subexpressions with the same id.
+fn lower_expr<'a, F: Field>(
+    e: &'a Expr<ConstantExpr<F>>,
+    ops: &mut Vec<Op<F>>,
+    cache: &mut HashMap<CacheId, Vec<(&'a Expr<ConstantExpr<F>>, usize)>>,
+) -> usize {
+    use Expr::*;
+    let op = match e {
+        Constant(c) => return lower_constant(c, ops),
+        Cell(v) => Op::Cell(*v),
+        Double(x) => {
+            let x = lower_expr(x, ops, cache);
+            Op::Add(x, x)
+        }
+        Square(x) => {
+            let x = lower_expr(x, ops, cache);
+            Op::Mul(x, x)
+        }
+        BinOp(op, x, y) => {
+            let (x, y) = (lower_expr(x, ops, cache), lower_expr(y, ops, cache));
+            match op {
+                Op2::Add => Op::Add(x, y),
+                Op2::Sub => Op::Sub(x, y),
+                Op2::Mul => Op::Mul(x, y),
+            }
+        }
+        VanishesOnLast4Rows => Op::VanishesOnLast4Rows,
+        UnnormalizedLagrangeBasis(i) => Op::UnnormalizedLagrangeBasis(*i),
+        Pow(x, n) => Op::Pow(lower_expr(x, ops, cache), *n),
+        Cache(id, x) => {
+            if let Some((_, r)) = cache
+                .get(id)
+                .and_then(|cached| cached.iter().find(|(e, _)| *e == x.as_ref()))
+            {
+                return *r;
+            }
+            let r = lower_expr(x, ops, cache);
+            cache.entry(*id).or_default().push((x, r));
+            return r;
+        }
+    };
+    ops.push(op);
+    ops.len() - 1
+}
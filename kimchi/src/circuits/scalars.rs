@@ -34,6 +34,8 @@ pub struct ProofEvaluations<Field> {
     pub generic_selector: Field,
     /// evaluation of the poseidon selector polynomial
     pub poseidon_selector: Field,
+    /// evaluation of the poseidon legacy selector polynomial, if the circuit has legacy Poseidon gates
+    pub poseidon_legacy_selector: Option<Field>,
 }
 
 impl<F: Zero> ProofEvaluations<F> {
@@ -45,6 +47,7 @@ impl<F: Zero> ProofEvaluations<F> {
             lookup: None,
             generic_selector: F::zero(),
             poseidon_selector: F::zero(),
+            poseidon_legacy_selector: None,
         }
     }
 }
@@ -66,6 +69,10 @@ impl<F: FftField> ProofEvaluations<Vec<F>> {
             }),
             generic_selector: DensePolynomial::eval_polynomial(&self.generic_selector, pt),
             poseidon_selector: DensePolynomial::eval_polynomial(&self.poseidon_selector, pt),
+            poseidon_legacy_selector: self
+                .poseidon_legacy_selector
+                .as_ref()
+                .map(|e| DensePolynomial::eval_polynomial(e, pt)),
         }
     }
 }
@@ -193,6 +200,7 @@ pub mod caml {
         ),
         pub generic_selector: Vec<CamlF>,
         pub poseidon_selector: Vec<CamlF>,
+        pub poseidon_legacy_selector: Option<Vec<CamlF>>,
     }
 
     //
@@ -236,6 +244,9 @@ pub mod caml {
                 s,
                 generic_selector: pe.generic_selector.into_iter().map(Into::into).collect(),
                 poseidon_selector: pe.poseidon_selector.into_iter().map(Into::into).collect(),
+                poseidon_legacy_selector: pe
+                    .poseidon_legacy_selector
+                    .map(|e| e.into_iter().map(Into::into).collect()),
             }
         }
     }
@@ -279,6 +290,9 @@ pub mod caml {
                 lookup: None,
                 generic_selector: cpe.generic_selector.into_iter().map(Into::into).collect(),
                 poseidon_selector: cpe.poseidon_selector.into_iter().map(Into::into).collect(),
+                poseidon_legacy_selector: cpe
+                    .poseidon_legacy_selector
+                    .map(|e| e.into_iter().map(Into::into).collect()),
             }
         }
     }
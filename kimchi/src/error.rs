@@ -18,6 +18,12 @@ pub enum ProofError {
     Permutation(&'static str),
     #[error("the opening proof failed to verify")]
     OpenProof,
+    #[error("the SRS of the verifier indexes are not prefixes of one another")]
+    IncompatibleSrs,
     #[error("the lookup failed to find a match in the table")]
     ValueNotInTable,
+    #[error("the evaluations of the proof do not match the selectors of the verifier index")]
+    UnexpectedEvaluations,
+    #[error("the proof creation was cancelled")]
+    Cancelled,
 }
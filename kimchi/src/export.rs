@@ -0,0 +1,447 @@
+//! This module exports a constraint system in JSON, for auditors and alternative verifier implementations.
+//!
+//! The export ([ConstraintSystemExport]) contains
+//!
+//! - the constraints of each custom gate, as expression trees (see [Node]);
+//! - the powers of alpha registered for each [ArgumentType] (see [Alphas]);
+//! - the linearization of the combined constraints, that is, its constant term and the terms multiplied by the index columns;
+//! - the lookup kinds and the map from the gates to the kinds (see [LookupInfo]), if the constraint system uses lookups;
+//! - the columns evaluated at zeta, which the linearization reads.
+//!
+//! Its format is versioned by [SCHEMA_VERSION], which changes with any incompatible change of the types of this module.
+//! The field elements are written in hexadecimal, with the prefix `0x`, as the 64-bit limbs of their canonical representation, from the most significant limb.
+//! The sums and products are n-ary, which keeps the trees shallow.
+//! A cached subexpression is written in full where it first appears in an expression, in a `cache` node with an identifier,
+//! and as a `cached` node with that identifier afterwards.
+
+use crate::alphas::Alphas;
+use crate::circuits::{
+    argument::{Argument, ArgumentType},
+    constraints::ConstraintSystem,
+    expr::{prologue::E, CacheId, Column, ConstantExpr, Expr, Op2},
+    gate::{CurrOrNext, GateType, JointLookup, LookupInfo},
+    polynomials::{
+        chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFinal},
+        complete_add::CompleteAdd,
+        endomul_scalar::EndomulScalar,
+        endosclmul::EndosclMul,
+        fixed_base_mul::FixedBaseMul,
+        poseidon::Poseidon,
+        poseidon_legacy::PoseidonLegacy,
+        varbasemul::VarbaseMul,
+    },
+};
+use crate::linearization::{constraints_expr, linearization_columns};
+use ark_ff::{FpParameters, PrimeField, SquareRootField};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The version of the format of [ConstraintSystemExport]
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A node of an exported expression
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Node {
+    Literal {
+        value: String,
+    },
+    Alpha,
+    Beta,
+    Gamma,
+    JointCombiner,
+    EndoCoefficient,
+    Mds {
+        row: usize,
+        col: usize,
+    },
+    Cell {
+        col: Column,
+        row: CurrOrNext,
+    },
+    #[serde(rename = "vanishes_on_last_4_rows")]
+    VanishesOnLast4Rows,
+    /// `(x^n - 1) / (x - omega^row)`
+    UnnormalizedLagrangeBasis {
+        row: usize,
+    },
+    Add {
+        args: Vec<Node>,
+    },
+    Mul {
+        args: Vec<Node>,
+    },
+    Sub {
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+    Double {
+        arg: Box<Node>,
+    },
+    Square {
+        arg: Box<Node>,
+    },
+    Pow {
+        base: Box<Node>,
+        exponent: u64,
+    },
+    /// The first occurrence of the cached subexpression `id`
+    Cache {
+        id: usize,
+        expr: Box<Node>,
+    },
+    /// A later occurrence of the cached subexpression `id`
+    Cached {
+        id: usize,
+    },
+}
+
+/// The constraints of a custom gate
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GateExport {
+    pub typ: GateType,
+    pub constraints: Vec<Node>,
+}
+
+/// The powers of alpha `alpha^start, ..., alpha^(start + powers - 1)` registered for an argument.
+/// The gates share the powers registered for `Gate(Zero)`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AlphasExport {
+    pub argument: ArgumentType,
+    pub start: u32,
+    pub powers: u32,
+}
+
+/// A term of the linearization, multiplied by the column `col`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IndexTermExport {
+    pub col: Column,
+    pub term: Node,
+}
+
+/// The linearization, `constant_term + sum(term * col)`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LinearizationExport {
+    pub constant_term: Node,
+    pub index_terms: Vec<IndexTermExport>,
+}
+
+/// A term `coefficient * cell` of a looked-up value
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LookupTermExport {
+    pub coefficient: String,
+    pub row: CurrOrNext,
+    pub column: usize,
+}
+
+/// A lookup of a vector, each of whose entries is a sum of [LookupTermExport]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JointLookupExport {
+    pub table_id: usize,
+    pub entry: Vec<Vec<LookupTermExport>>,
+}
+
+/// The lookup kind `kind` is applied at the row `row` of the gates `gate`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KindsMapEntry {
+    pub gate: GateType,
+    pub row: CurrOrNext,
+    pub kind: usize,
+}
+
+/// The lookup configuration (see [LookupInfo])
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LookupExport {
+    pub kinds: Vec<Vec<JointLookupExport>>,
+    pub kinds_map: Vec<KindsMapEntry>,
+}
+
+/// The export of a constraint system
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConstraintSystemExport {
+    /// The version of the format, [SCHEMA_VERSION]
+    pub version: u32,
+    /// The modulus of the scalar field
+    pub field_modulus: String,
+    /// The size of the domain of the circuit
+    pub domain_size: u64,
+    /// The number of public inputs
+    pub public: usize,
+    pub gates: Vec<GateExport>,
+    pub alphas: Vec<AlphasExport>,
+    pub linearization: LinearizationExport,
+    pub lookup: Option<LookupExport>,
+    /// The columns evaluated at zeta (and zeta * omega)
+    pub evaluated_columns: Vec<Column>,
+}
+
+/// Writes a field element in hexadecimal
+fn field_hex<F: PrimeField>(x: F) -> String {
+    format!("0x{}", x.into_repr())
+}
+
+/// Converts expressions to [Node]s, numbering their cached subexpressions
+struct Exporter<'a, F> {
+    /// The numbers of the cached subexpressions.
+    /// The identifiers of the caches of different constraints may collide, so the subexpressions are compared.
+    ids: HashMap<CacheId, Vec<(&'a E<F>, usize)>>,
+    next_id: usize,
+}
+
+impl<'a, F: PrimeField> Exporter<'a, F> {
+    /// Appends the terms of `node` to `args` if it is an operation `op` of [Node::Add] or [Node::Mul],
+    /// and `node` to `args` otherwise.
+    fn flatten(op: &Op2, node: Node, args: &mut Vec<Node>) {
+        match (op, node) {
+            (Op2::Add, Node::Add { args: terms }) | (Op2::Mul, Node::Mul { args: terms }) => {
+                args.extend(terms)
+            }
+            (_, node) => args.push(node),
+        }
+    }
+
+    fn binop(op: Op2, x: Node, y: Node) -> Node {
+        match op {
+            Op2::Sub => Node::Sub {
+                left: Box::new(x),
+                right: Box::new(y),
+            },
+            Op2::Add | Op2::Mul => {
+                let mut args = vec![];
+                Self::flatten(&op, x, &mut args);
+                Self::flatten(&op, y, &mut args);
+                if op == Op2::Add {
+                    Node::Add { args }
+                } else {
+                    Node::Mul { args }
+                }
+            }
+        }
+    }
+
+    fn constant(c: &ConstantExpr<F>) -> Node {
+        use ConstantExpr::*;
+        match c {
+            Alpha => Node::Alpha,
+            Beta => Node::Beta,
+            Gamma => Node::Gamma,
+            JointCombiner => Node::JointCombiner,
+            EndoCoefficient => Node::EndoCoefficient,
+            Mds { row, col } => Node::Mds {
+                row: *row,
+                col: *col,
+            },
+            Literal(x) => Node::Literal {
+                value: field_hex(*x),
+            },
+            Pow(x, n) => Node::Pow {
+                base: Box::new(Self::constant(x)),
+                exponent: *n,
+            },
+            Add(x, y) => Self::binop(Op2::Add, Self::constant(x), Self::constant(y)),
+            Mul(x, y) => Self::binop(Op2::Mul, Self::constant(x), Self::constant(y)),
+            Sub(x, y) => Self::binop(Op2::Sub, Self::constant(x), Self::constant(y)),
+        }
+    }
+
+    fn expr(&mut self, e: &'a E<F>) -> Node {
+        match e {
+            Expr::Constant(c) => Self::constant(c),
+            Expr::Cell(v) => Node::Cell {
+                col: v.col,
+                row: v.row,
+            },
+            Expr::Double(x) => Node::Double {
+                arg: Box::new(self.expr(x)),
+            },
+            Expr::Square(x) => Node::Square {
+                arg: Box::new(self.expr(x)),
+            },
+            Expr::BinOp(op, x, y) => {
+                let (x, y) = (self.expr(x), self.expr(y));
+                Self::binop(op.clone(), x, y)
+            }
+            Expr::VanishesOnLast4Rows => Node::VanishesOnLast4Rows,
+            Expr::UnnormalizedLagrangeBasis(i) => Node::UnnormalizedLagrangeBasis { row: *i },
+            Expr::Pow(x, n) => Node::Pow {
+                base: Box::new(self.expr(x)),
+                exponent: *n,
+            },
+            Expr::Cache(id, x) => {
+                if let Some((_, id)) = self
+                    .ids
+                    .get(id)
+                    .and_then(|cached| cached.iter().find(|(e, _)| *e == x.as_ref()))
+                {
+                    return Node::Cached { id: *id };
+                }
+                let expr = Box::new(self.expr(x));
+                let fresh = self.next_id;
+                self.next_id += 1;
+                self.ids.entry(*id).or_default().push((x, fresh));
+                Node::Cache { id: fresh, expr }
+            }
+        }
+    }
+}
+
+/// Exports an expression, whose cached subexpressions are numbered from 0
+pub fn export_expr<F: PrimeField>(e: &E<F>) -> Node {
+    Exporter {
+        ids: HashMap::new(),
+        next_id: 0,
+    }
+    .expr(e)
+}
+
+fn gate<F: PrimeField, A: Argument<F>>() -> GateExport {
+    let typ = match A::ARGUMENT_TYPE {
+        ArgumentType::Gate(typ) => typ,
+        argument => panic!("{:?} is not a gate", argument),
+    };
+    GateExport {
+        typ,
+        constraints: A::constraints().iter().map(export_expr).collect(),
+    }
+}
+
+fn joint_lookup<F: PrimeField>(lookup: &JointLookup<F>) -> JointLookupExport {
+    JointLookupExport {
+        table_id: lookup.table_id,
+        entry: lookup
+            .entry
+            .iter()
+            .map(|single| {
+                single
+                    .value
+                    .iter()
+                    .map(|(c, pos)| LookupTermExport {
+                        coefficient: field_hex(*c),
+                        row: pos.row,
+                        column: pos.column,
+                    })
+                    .collect()
+            })
+            .collect(),
+    }
+}
+
+impl ConstraintSystemExport {
+    /// Exports a constraint system, and its linearization.
+    pub fn create<F: PrimeField + SquareRootField>(cs: &ConstraintSystem<F>) -> Self {
+        let chacha = cs.chacha8.is_some();
+        let poseidon_legacy = cs.poseidon_legacy8.is_some();
+        let fixed_base_mul = cs.fixed_base_mull4.is_some();
+        let lcs = &cs.lookup_constraint_system;
+
+        let mut gates = vec![
+            gate::<F, Poseidon<F>>(),
+            gate::<F, VarbaseMul<F>>(),
+            gate::<F, CompleteAdd<F>>(),
+            gate::<F, EndosclMul<F>>(),
+            gate::<F, EndomulScalar<F>>(),
+        ];
+        if fixed_base_mul {
+            gates.push(gate::<F, FixedBaseMul<F>>());
+        }
+        if poseidon_legacy {
+            gates.push(gate::<F, PoseidonLegacy<F>>());
+        }
+        if chacha {
+            gates.push(gate::<F, ChaCha0<F>>());
+            gates.push(gate::<F, ChaCha1<F>>());
+            gates.push(gate::<F, ChaCha2<F>>());
+            gates.push(gate::<F, ChaChaFinal<F>>());
+        }
+
+        let (expr, powers_of_alpha): (_, Alphas<F>) = constraints_expr(
+            cs.domain.d1,
+            chacha,
+            poseidon_legacy,
+            fixed_base_mul,
+            cs.lookup_parameters(),
+        );
+        let alphas = powers_of_alpha
+            .ranges()
+            .into_iter()
+            .map(|(argument, range)| AlphasExport {
+                argument,
+                start: range.start,
+                powers: range.end - range.start,
+            })
+            .collect();
+
+        let mut evaluated_columns: Vec<_> =
+            linearization_columns::<F>(poseidon_legacy, cs.lookup_parameters())
+                .into_iter()
+                .collect();
+        evaluated_columns.sort();
+        let linearization = expr
+            .linearize(evaluated_columns.iter().copied().collect())
+            .unwrap();
+        let mut index_terms: Vec<_> = linearization
+            .index_terms
+            .iter()
+            .map(|(col, term)| IndexTermExport {
+                col: *col,
+                term: export_expr(term),
+            })
+            .collect();
+        // the order of the index terms is not deterministic
+        index_terms.sort_by_key(|t| t.col);
+
+        let lookup = lcs.as_ref().map(|_| {
+            let info = LookupInfo::<F>::create();
+            let mut kinds_map: Vec<_> = info
+                .kinds_map
+                .iter()
+                .map(|((gate, row), kind)| KindsMapEntry {
+                    gate: *gate,
+                    row: *row,
+                    kind: *kind,
+                })
+                .collect();
+            kinds_map.sort_by_key(|e| (e.gate, e.row));
+            LookupExport {
+                kinds: info
+                    .kinds
+                    .iter()
+                    .map(|kind| kind.iter().map(joint_lookup).collect())
+                    .collect(),
+                kinds_map,
+            }
+        });
+
+        ConstraintSystemExport {
+            version: SCHEMA_VERSION,
+            field_modulus: format!("0x{}", F::Params::MODULUS),
+            domain_size: cs.domain.d1.size,
+            public: cs.public,
+            gates,
+            alphas,
+            linearization: LinearizationExport {
+                constant_term: export_expr(&linearization.constant_term),
+                index_terms,
+            },
+            lookup,
+            evaluated_columns,
+        }
+    }
+
+    /// Serializes the export in JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("the export is serializable")
+    }
+
+    /// Deserializes an export from JSON, checking its version.
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        let export: Self = serde_json::from_str(s).map_err(|e| e.to_string())?;
+        if export.version != SCHEMA_VERSION {
+            return Err(format!(
+                "the export has version {}, but version {} is expected",
+                export.version, SCHEMA_VERSION
+            ));
+        }
+        Ok(export)
+    }
+}
@@ -4,9 +4,11 @@
 extern crate num_derive;
 
 pub mod alphas;
+pub mod batch_prover;
 pub mod bench;
 pub mod circuits;
 pub mod error;
+pub mod export;
 pub mod linearization;
 pub mod plonk_sponge;
 pub mod prover;
@@ -6,24 +6,66 @@ use crate::circuits::polynomials::chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFina
 use crate::circuits::polynomials::complete_add::CompleteAdd;
 use crate::circuits::polynomials::endomul_scalar::EndomulScalar;
 use crate::circuits::polynomials::endosclmul::EndosclMul;
+use crate::circuits::polynomials::fixed_base_mul::FixedBaseMul;
 use crate::circuits::polynomials::lookup;
 use crate::circuits::polynomials::permutation;
 use crate::circuits::polynomials::poseidon::Poseidon;
+use crate::circuits::polynomials::poseidon_legacy::PoseidonLegacy;
 use crate::circuits::polynomials::varbasemul::VarbaseMul;
 use crate::circuits::{
-    constraints::LookupConstraintSystem,
-    expr::{Column, ConstantExpr, Expr, Linearization, PolishToken},
+    expr::{prologue::E, Column, ConstantExpr, Domain, Expr, Linearization, PolishToken},
     gate::GateType,
+    program::Program,
     wires::*,
 };
-use ark_ff::{FftField, SquareRootField};
+use ark_ff::{FftField, SquareRootField, Zero};
 use ark_poly::Radix2EvaluationDomain as D;
+use std::collections::BTreeMap;
+use thiserror::Error;
 
-pub fn constraints_expr<F: FftField + SquareRootField>(
+/// The parameters of the lookup argument on which its constraints and the linearization depend,
+/// which both the prover index and the verifier index know
+/// (see [crate::circuits::constraints::ConstraintSystem::lookup_parameters]
+/// and [crate::verifier_index::VerifierIndex::lookup_parameters]).
+#[derive(Clone, Copy, Debug)]
+pub struct LookupParameters<'a, F> {
+    /// the value of the dummy lookups, which is the last entry of the lookup table
+    pub dummy_lookup_value: &'a [F],
+    /// the maximum number of lookups per row
+    pub max_lookups_per_row: usize,
+}
+
+/// Panics if the combined constraints `expr` of `argument` do not fit in the evaluation domain d8,
+/// in which the prover evaluates them, and which bounds the degree of the quotient polynomial
+/// committed in [PERMUTS] chunks.
+fn check_degree<F: FftField>(argument: ArgumentType, expr: &E<F>, domain: D<F>) {
+    let degree = expr.degree(domain.size);
+    assert!(
+        Domain::for_degree(degree, domain.size).is_some(),
+        "the constraints of {:?} have degree {}, which overflows d8 (of size {})",
+        argument,
+        degree,
+        8 * domain.size
+    );
+}
+
+/// Returns the combined constraints of the argument `A`,
+/// after checking that the prover can evaluate them (see [Domain::for_degree]).
+pub fn combined_constraints<F: FftField, A: Argument<F>>(alphas: &Alphas<F>, domain: D<F>) -> E<F> {
+    let expr = A::combined_constraints(alphas);
+    check_degree(A::ARGUMENT_TYPE, &expr, domain);
+    expr
+}
+
+/// Returns the combined constraints of each argument of the circuit, except the generic gate and the permutation,
+/// with the powers of alpha registered for all the arguments.
+pub fn argument_constraints<F: FftField + SquareRootField>(
     domain: D<F>,
     chacha: bool,
-    lookup_constraint_system: &Option<LookupConstraintSystem<F>>,
-) -> (Expr<ConstantExpr<F>>, Alphas<F>) {
+    poseidon_legacy: bool,
+    fixed_base_mul: bool,
+    lookup_parameters: Option<LookupParameters<F>>,
+) -> (Vec<E<F>>, Alphas<F>) {
     // register powers of alpha so that we don't reuse them across mutually inclusive constraints
     let mut powers_of_alpha = Alphas::<F>::default();
 
@@ -34,48 +76,126 @@ pub fn constraints_expr<F: FftField + SquareRootField>(
         highest_constraints,
     );
 
-    let mut expr = Poseidon::combined_constraints(&powers_of_alpha);
-    expr += VarbaseMul::combined_constraints(&powers_of_alpha);
-    expr += CompleteAdd::combined_constraints(&powers_of_alpha);
-    expr += EndosclMul::combined_constraints(&powers_of_alpha);
-    expr += EndomulScalar::combined_constraints(&powers_of_alpha);
+    let mut exprs = vec![
+        combined_constraints::<F, Poseidon<F>>(&powers_of_alpha, domain),
+        combined_constraints::<F, VarbaseMul<F>>(&powers_of_alpha, domain),
+        combined_constraints::<F, CompleteAdd<F>>(&powers_of_alpha, domain),
+        combined_constraints::<F, EndosclMul<F>>(&powers_of_alpha, domain),
+        combined_constraints::<F, EndomulScalar<F>>(&powers_of_alpha, domain),
+    ];
+
+    if fixed_base_mul {
+        exprs.push(combined_constraints::<F, FixedBaseMul<F>>(
+            &powers_of_alpha,
+            domain,
+        ));
+    }
+
+    if poseidon_legacy {
+        exprs.push(combined_constraints::<F, PoseidonLegacy<F>>(
+            &powers_of_alpha,
+            domain,
+        ));
+    }
 
     if chacha {
-        expr += ChaCha0::combined_constraints(&powers_of_alpha);
-        expr += ChaCha1::combined_constraints(&powers_of_alpha);
-        expr += ChaCha2::combined_constraints(&powers_of_alpha);
-        expr += ChaChaFinal::combined_constraints(&powers_of_alpha);
+        exprs.push(combined_constraints::<F, ChaCha0<F>>(
+            &powers_of_alpha,
+            domain,
+        ));
+        exprs.push(combined_constraints::<F, ChaCha1<F>>(
+            &powers_of_alpha,
+            domain,
+        ));
+        exprs.push(combined_constraints::<F, ChaCha2<F>>(
+            &powers_of_alpha,
+            domain,
+        ));
+        exprs.push(combined_constraints::<F, ChaChaFinal<F>>(
+            &powers_of_alpha,
+            domain,
+        ));
     }
 
     // permutation
     powers_of_alpha.register(ArgumentType::Permutation, permutation::CONSTRAINTS);
 
     // lookup
-    if let Some(lcs) = lookup_constraint_system.as_ref() {
+    if let Some(parameters) = lookup_parameters {
         powers_of_alpha.register(ArgumentType::Lookup, lookup::CONSTRAINTS);
         let alphas = powers_of_alpha.get_exponents(ArgumentType::Lookup, lookup::CONSTRAINTS);
 
-        let constraints = lookup::constraints(&lcs.dummy_lookup_value, domain);
+        let constraints = lookup::constraints(parameters.dummy_lookup_value, domain);
         let combined = Expr::combine_constraints(alphas, constraints);
-        expr += combined;
+        check_degree(ArgumentType::Lookup, &combined, domain);
+        exprs.push(combined);
     }
 
-    // return the expression
+    (exprs, powers_of_alpha)
+}
+
+/// Returns the sum of the combined constraints of the arguments (see [argument_constraints]).
+pub fn constraints_expr<F: FftField + SquareRootField>(
+    domain: D<F>,
+    chacha: bool,
+    poseidon_legacy: bool,
+    fixed_base_mul: bool,
+    lookup_parameters: Option<LookupParameters<F>>,
+) -> (Expr<ConstantExpr<F>>, Alphas<F>) {
+    let (exprs, powers_of_alpha) = argument_constraints(
+        domain,
+        chacha,
+        poseidon_legacy,
+        fixed_base_mul,
+        lookup_parameters,
+    );
+    let mut expr = E::zero();
+    for e in exprs {
+        expr += e;
+    }
     (expr, powers_of_alpha)
 }
 
+/// Returns the combined constraints of the arguments (see [argument_constraints])
+/// compiled to a program per evaluation domain (see [Domain::for_degree]),
+/// in which the prover evaluates them, so that the constraints of the arguments
+/// evaluated over the same domain share their subexpressions.
+pub fn constraints_programs<F: FftField + SquareRootField>(
+    domain: D<F>,
+    chacha: bool,
+    poseidon_legacy: bool,
+    fixed_base_mul: bool,
+    lookup_parameters: Option<LookupParameters<F>>,
+) -> Vec<Program<F>> {
+    let (exprs, _) = argument_constraints(
+        domain,
+        chacha,
+        poseidon_legacy,
+        fixed_base_mul,
+        lookup_parameters,
+    );
+    let mut by_domain: BTreeMap<Domain, E<F>> = BTreeMap::new();
+    for e in exprs {
+        let d = Domain::for_degree(e.degree(domain.size), domain.size)
+            .expect("the degree of the constraints is checked");
+        *by_domain.entry(d).or_insert_with(E::zero) += e;
+    }
+    by_domain.values().map(Program::compile).collect()
+}
+
 pub fn linearization_columns<F: FftField + SquareRootField>(
-    lookup_constraint_system: &Option<LookupConstraintSystem<F>>,
+    poseidon_legacy: bool,
+    lookup_parameters: Option<LookupParameters<F>>,
 ) -> std::collections::HashSet<Column> {
     let mut h = std::collections::HashSet::new();
     use Column::*;
     for i in 0..COLUMNS {
         h.insert(Witness(i));
     }
-    match lookup_constraint_system.as_ref() {
+    match lookup_parameters {
         None => (),
-        Some(lcs) => {
-            for i in 0..(lcs.max_lookups_per_row + 1) {
+        Some(parameters) => {
+            for i in 0..(parameters.max_lookups_per_row + 1) {
                 h.insert(LookupSorted(i));
             }
         }
@@ -84,6 +204,9 @@ pub fn linearization_columns<F: FftField + SquareRootField>(
     h.insert(LookupAggreg);
     h.insert(LookupTable);
     h.insert(Index(GateType::Poseidon));
+    if poseidon_legacy {
+        h.insert(Index(GateType::PoseidonLegacy));
+    }
     h.insert(Index(GateType::Generic));
     h
 }
@@ -91,16 +214,81 @@ pub fn linearization_columns<F: FftField + SquareRootField>(
 pub fn expr_linearization<F: FftField + SquareRootField>(
     domain: D<F>,
     chacha: bool,
-    lookup_constraint_system: &Option<LookupConstraintSystem<F>>,
+    poseidon_legacy: bool,
+    fixed_base_mul: bool,
+    lookup_parameters: Option<LookupParameters<F>>,
 ) -> (Linearization<Vec<PolishToken<F>>>, Alphas<F>) {
-    let evaluated_cols = linearization_columns::<F>(lookup_constraint_system);
+    let evaluated_cols = linearization_columns::<F>(poseidon_legacy, lookup_parameters);
 
-    let (expr, powers_of_alpha) = constraints_expr(domain, chacha, lookup_constraint_system);
+    let (expr, powers_of_alpha) = constraints_expr(
+        domain,
+        chacha,
+        poseidon_legacy,
+        fixed_base_mul,
+        lookup_parameters,
+    );
 
     let linearization = expr
         .linearize(evaluated_cols)
         .unwrap()
-        .map(|e| e.to_polish());
+        .map(|e| Program::compile(e).to_polish());
 
     (linearization, powers_of_alpha)
 }
+
+/// A difference between a linearization embedded in an index and the one computed by this library
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum LinearizationError {
+    #[error("the powers of alpha are not registered for the same arguments")]
+    PowersOfAlpha,
+    #[error("the constant terms of the linearizations differ")]
+    ConstantTerm,
+    #[error("the linearizations do not have terms for the same columns")]
+    IndexColumns,
+    #[error("the terms of the column {0:?} differ")]
+    IndexTerm(Column),
+}
+
+/// Checks that the linearization and the powers of alpha embedded in an index
+/// are those computed by [expr_linearization] for the same circuit,
+/// which detects an index created by another version of this library.
+/// The linearization is deterministic (see [crate::circuits::expr::Expr::linearize]),
+/// so that the tokens of the terms, sorted by column, are compared as they are.
+pub fn check_linearization<F: FftField + SquareRootField>(
+    linearization: &Linearization<Vec<PolishToken<F>>>,
+    powers_of_alpha: &Alphas<F>,
+    domain: D<F>,
+    chacha: bool,
+    poseidon_legacy: bool,
+    fixed_base_mul: bool,
+    lookup_parameters: Option<LookupParameters<F>>,
+) -> Result<(), LinearizationError> {
+    let (expected, expected_powers_of_alpha) = expr_linearization(
+        domain,
+        chacha,
+        poseidon_legacy,
+        fixed_base_mul,
+        lookup_parameters,
+    );
+
+    if powers_of_alpha.ranges() != expected_powers_of_alpha.ranges() {
+        return Err(LinearizationError::PowersOfAlpha);
+    }
+    if linearization.constant_term != expected.constant_term {
+        return Err(LinearizationError::ConstantTerm);
+    }
+    let columns = |l: &Linearization<Vec<PolishToken<F>>>| -> Vec<Column> {
+        l.index_terms.iter().map(|(col, _)| *col).collect()
+    };
+    if columns(linearization) != columns(&expected) {
+        return Err(LinearizationError::IndexColumns);
+    }
+    for ((col, term), (_, expected_term)) in
+        linearization.index_terms.iter().zip(&expected.index_terms)
+    {
+        if term != expected_term {
+            return Err(LinearizationError::IndexTerm(*col));
+        }
+    }
+    Ok(())
+}
@@ -43,32 +43,14 @@ impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
         self.last_squeezed = vec![];
         self.sponge.absorb(p);
 
-        let points = [
-            &e.z,
-            &e.generic_selector,
-            &e.poseidon_selector,
-            &e.w[0],
-            &e.w[1],
-            &e.w[2],
-            &e.w[3],
-            &e.w[4],
-            &e.w[5],
-            &e.w[6],
-            &e.w[7],
-            &e.w[8],
-            &e.w[9],
-            &e.w[10],
-            &e.w[11],
-            &e.w[12],
-            &e.w[13],
-            &e.w[14],
-            &e.s[0],
-            &e.s[1],
-            &e.s[2],
-            &e.s[3],
-            &e.s[4],
-            &e.s[5],
-        ];
+        // the poseidon legacy selector is only evaluated if the circuit has legacy Poseidon gates
+        let mut points = vec![&e.z, &e.generic_selector, &e.poseidon_selector];
+        points.extend(&e.poseidon_legacy_selector);
+        points.extend([
+            &e.w[0], &e.w[1], &e.w[2], &e.w[3], &e.w[4], &e.w[5], &e.w[6], &e.w[7], &e.w[8],
+            &e.w[9], &e.w[10], &e.w[11], &e.w[12], &e.w[13], &e.w[14], &e.s[0], &e.s[1], &e.s[2],
+            &e.s[3], &e.s[4], &e.s[5],
+        ]);
 
         for p in &points {
             self.sponge.absorb(p);
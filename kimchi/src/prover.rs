@@ -2,19 +2,11 @@
 
 use crate::{
     circuits::{
-        argument::{Argument, ArgumentType},
+        argument::ArgumentType,
         constraints::{LookupConstraintSystem, ZK_ROWS},
         expr::{l0_1, Constants, Environment, LookupEnvironment},
         gate::{combine_table_entry, GateType, LookupsUsed},
-        polynomials::{
-            chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFinal},
-            complete_add::CompleteAdd,
-            endomul_scalar::EndomulScalar,
-            endosclmul::EndosclMul,
-            generic, lookup, permutation,
-            poseidon::Poseidon,
-            varbasemul::VarbaseMul,
-        },
+        polynomials::{generic, lookup, permutation},
         scalars::{LookupEvaluations, ProofEvaluations},
         wires::{COLUMNS, PERMUTS},
     },
@@ -23,7 +15,7 @@ use crate::{
     prover_index::ProverIndex,
 };
 use ark_ec::AffineCurve;
-use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use ark_ff::{FftField, Field, One, PrimeField, UniformRand, Zero};
 use ark_poly::{
     univariate::DensePolynomial, Evaluations, Polynomial, Radix2EvaluationDomain as D, UVPolynomial,
 };
@@ -32,11 +24,20 @@ use commitment_dlog::{
     commitment::{b_poly_coefficients, CommitmentCurve, PolyComm},
     evaluation_proof::OpeningProof,
 };
-use itertools::Itertools;
 use lookup::CombinedEntry;
-use o1_utils::ExtendedDensePolynomial;
+use o1_utils::{
+    profiling::{self, Span},
+    ExtendedDensePolynomial,
+};
 use oracle::{sponge::ScalarChallenge, FqSponge};
-use std::collections::HashMap;
+use rayon::ThreadPool;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 type Fr<G> = <G as AffineCurve>::ScalarField;
 type Fq<G> = <G as AffineCurve>::BaseField;
@@ -59,6 +60,59 @@ pub struct ProverCommitments<G: AffineCurve> {
     pub lookup: Option<LookupCommitments<G>>,
 }
 
+/// A token that can be used to cancel the creation of a proof, possibly from another thread.
+/// Clones of a token share the same state.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a new token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels all the proofs that are checking this token
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The configuration of the prover (see [ProverProof::create_recursive_with_config])
+#[derive(Clone, Default)]
+pub struct ProverConfig {
+    /// The thread pool to create the proof in.
+    /// If not set, the global rayon thread pool is used.
+    pub thread_pool: Option<Arc<ThreadPool>>,
+    /// A callback invoked with the number of each step of the protocol (as specified in this module),
+    /// before the step is executed.
+    #[allow(clippy::type_complexity)]
+    pub progress: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    /// A token checked before each step of the protocol.
+    /// If it is cancelled, the prover aborts with [ProofError::Cancelled].
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl ProverConfig {
+    /// Checks for cancellation and reports progress before the given step of the protocol.
+    /// The returned span times the step until it is dropped (see the `profiling` feature).
+    fn step(&self, step: usize) -> Result<Span> {
+        if let Some(cancellation) = &self.cancellation {
+            if cancellation.is_cancelled() {
+                return Err(ProofError::Cancelled);
+            }
+        }
+        if let Some(progress) = &self.progress {
+            progress(step);
+        }
+        Ok(profiling::step_span("prover", step))
+    }
+}
+
 #[derive(Clone)]
 pub struct ProverProof<G: AffineCurve> {
     /// All the polynomial commitments required in the proof
@@ -98,11 +152,60 @@ where
     pub fn create_recursive<
         EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>,
         EFrSponge: FrSponge<Fr<G>>,
+    >(
+        group_map: &G::Map,
+        witness: [Vec<Fr<G>>; COLUMNS],
+        index: &ProverIndex<G>,
+        prev_challenges: Vec<(Vec<Fr<G>>, PolyComm<G>)>,
+    ) -> Result<Self> {
+        Self::create_recursive_inner::<EFqSponge, EFrSponge>(
+            group_map,
+            witness,
+            index,
+            prev_challenges,
+            &ProverConfig::default(),
+        )
+    }
+
+    /// This function constructs prover's recursive zk-proof like [ProverProof::create_recursive],
+    /// but runs on the thread pool of the [ProverConfig], reports its progress and can be cancelled.
+    pub fn create_recursive_with_config<
+        EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>,
+        EFrSponge: FrSponge<Fr<G>>,
+    >(
+        group_map: &G::Map,
+        witness: [Vec<Fr<G>>; COLUMNS],
+        index: &ProverIndex<G>,
+        prev_challenges: Vec<(Vec<Fr<G>>, PolyComm<G>)>,
+        config: &ProverConfig,
+    ) -> Result<Self>
+    where
+        G::Map: Sync,
+    {
+        let create = || {
+            Self::create_recursive_inner::<EFqSponge, EFrSponge>(
+                group_map,
+                witness,
+                index,
+                prev_challenges,
+                config,
+            )
+        };
+        match &config.thread_pool {
+            Some(pool) => pool.install(create),
+            None => create(),
+        }
+    }
+
+    fn create_recursive_inner<
+        EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>,
+        EFrSponge: FrSponge<Fr<G>>,
     >(
         group_map: &G::Map,
         mut witness: [Vec<Fr<G>>; COLUMNS],
         index: &ProverIndex<G>,
         prev_challenges: Vec<(Vec<Fr<G>>, PolyComm<G>)>,
+        config: &ProverConfig,
     ) -> Result<Self> {
         let d1_size = index.cs.domain.d1.size as usize;
         // TODO: rng should be passed as arg
@@ -122,6 +225,7 @@ where
         //~    but instead be of the length of the (smaller) circuit.
         //~    If we cannot add `ZK_ROWS` rows to the columns of the witness before reaching
         //~    the size of the domain, abort.
+        let mut _step = config.step(1)?;
         let length_witness = witness[0].len();
         let length_padding = d1_size
             .checked_sub(length_witness)
@@ -132,6 +236,7 @@ where
 
         //~ 2. Pad the witness columns with Zero gates to make them the same length as the domain.
         //~    Then, randomize the last `ZK_ROWS` of each columns.
+        _step = config.step(2)?;
         for w in &mut witness {
             if w.len() != length_witness {
                 return Err(ProofError::WitnessCsInconsistent);
@@ -147,27 +252,31 @@ where
         }
 
         //~ 3. Setup the Fq-Sponge.
+        _step = config.step(3)?;
         let mut fq_sponge = EFqSponge::new(index.fq_sponge_params.clone());
 
         //~ 4. Compute the negated public input polynomial as
         //~    the polynomial that evaluates to $-p_i$ for the first `public_input_size` values of the domain,
         //~    and $0$ for the rest.
+        _step = config.step(4)?;
+        let twiddles = index.twiddles();
         let public = witness[0][0..index.cs.public].to_vec();
-        let public_poly = -Evaluations::<Fr<G>, D<Fr<G>>>::from_vec_and_domain(
-            public.clone(),
-            index.cs.domain.d1,
-        )
-        .interpolate();
+        let public_poly = -twiddles.interpolate(
+            Evaluations::<Fr<G>, D<Fr<G>>>::from_vec_and_domain(public.clone(), index.cs.domain.d1),
+        );
 
         //~ 5. Commit (non-hiding) to the negated public input polynomial. **TODO: seems unecessary**
+        _step = config.step(5)?;
         let public_comm = index.srs.commit_non_hiding(&public_poly, None);
 
         //~ 6. Absorb the public polynomial with the Fq-Sponge. **TODO: seems unecessary**
+        _step = config.step(6)?;
         fq_sponge.absorb_g(&public_comm.unshifted);
 
         //~ 7. Commit to the witness columns by creating `COLUMNS` hidding commitments.
         //~    Note: since the witness is in evaluation form,
         //~    we can use the `commit_evaluation` optimization.
+        _step = config.step(7)?;
         let w_comm: [(PolyComm<G>, PolyComm<Fr<G>>); COLUMNS] = array_init(|i| {
             let e = Evaluations::<Fr<G>, D<Fr<G>>>::from_vec_and_domain(
                 witness[i].clone(),
@@ -179,21 +288,23 @@ where
         });
 
         //~ 8. Absorb the witness commitments with the Fq-Sponge.
+        _step = config.step(8)?;
         w_comm
             .iter()
             .for_each(|c| fq_sponge.absorb_g(&c.0.unshifted));
 
         //~ 9. Compute the witness polynomials by interpolating each `COLUMNS` of the witness.
         //~    TODO: why not do this first, and then commit? Why commit from evaluation directly?
+        _step = config.step(9)?;
         let witness_poly: [DensePolynomial<Fr<G>>; COLUMNS] = array_init(|i| {
-            Evaluations::<Fr<G>, D<Fr<G>>>::from_vec_and_domain(
+            twiddles.interpolate(Evaluations::<Fr<G>, D<Fr<G>>>::from_vec_and_domain(
                 witness[i].clone(),
                 index.cs.domain.d1,
-            )
-            .interpolate()
+            ))
         });
 
         //~ 10. TODO: lookup
+        _step = config.step(10)?;
         let joint_combiner_ = {
             // TODO: how will the verifier circuit handle these kind of things? same with powers of alpha...
             let s = match index.cs.lookup_constraint_system.as_ref() {
@@ -282,7 +393,10 @@ where
                         index.cs.domain.d1,
                         &index.cs.gates,
                         &witness,
-                        joint_combiner,
+                        (
+                            joint_combiner,
+                            joint_combiner.pow(&[lcs.max_joint_size as u64]),
+                        ),
                     )?;
 
                     let lookup_sorted: Vec<_> = lookup_sorted
@@ -303,10 +417,10 @@ where
                         .collect();
                     let coeffs : Vec<_> =
                         // TODO: We can avoid storing these coefficients.
-                        lookup_sorted.iter().map(|e| e.clone().interpolate()).collect();
+                        lookup_sorted.iter().map(|e| twiddles.interpolate(e.clone())).collect();
                     let evals8: Vec<_> = coeffs
                         .iter()
-                        .map(|v| v.evaluate_over_domain_by_ref(index.cs.domain.d8))
+                        .map(|v| twiddles.evaluate_over_domain(v, index.cs.domain.d8))
                         .collect();
 
                     // absorb lookup polynomials
@@ -317,12 +431,15 @@ where
             };
 
         //~ 11. Sample $\beta$ with the Fq-Sponge.
+        _step = config.step(11)?;
         let beta = fq_sponge.challenge();
 
         //~ 12. Sample $\gamma$ with the Fq-Sponge.
+        _step = config.step(12)?;
         let gamma = fq_sponge.challenge();
 
         //~ 13. TODO: lookup
+        _step = config.step(13)?;
         let (lookup_aggreg_coeffs, lookup_aggreg_comm, lookup_aggreg8) =
             // compute lookup aggregation polynomial
             match (index.cs.lookup_constraint_system.as_ref(), lookup_sorted) {
@@ -352,38 +469,46 @@ where
                     let comm = index.srs.commit_evaluations(index.cs.domain.d1, &aggreg, None, rng);
                     fq_sponge.absorb_g(&comm.0.unshifted);
 
-                    let coeffs = aggreg.interpolate();
+                    let coeffs = twiddles.interpolate(aggreg);
 
                     // TODO: There's probably a clever way to expand the domain without
                     // interpolating
-                    let evals8 = coeffs.evaluate_over_domain_by_ref(index.cs.domain.d8);
+                    let evals8 = twiddles.evaluate_over_domain(&coeffs, index.cs.domain.d8);
                     (Some(coeffs), Some(comm), Some(evals8))
                 },
             };
 
         //~ 14. Compute the permutation aggregation polynomial $z$.
+        _step = config.step(14)?;
         let z_poly = index.cs.perm_aggreg(&witness, &beta, &gamma, rng)?;
 
         //~ 15. Commit (hidding) to the permutation aggregation polynomial $z$.
+        _step = config.step(15)?;
         let z_comm = index.srs.commit(&z_poly, None, rng);
 
         //~ 16. Absorb the permutation aggregation polynomial $z$ with the Fq-Sponge.
+        _step = config.step(16)?;
         fq_sponge.absorb_g(&z_comm.0.unshifted);
 
         //~ 17. Sample $\alpha'$ with the Fq-Sponge.
+        _step = config.step(17)?;
         let alpha_chal = ScalarChallenge(fq_sponge.challenge());
 
         //~ 18. Derive $\alpha$ from $\alpha'$ using the endomorphism (TODO: details)
+        _step = config.step(18)?;
         let alpha = alpha_chal.to_field(&index.srs.endo_r);
 
         //~ 19. TODO: instantiate alpha?
+        _step = config.step(19)?;
         let mut all_alphas = index.powers_of_alpha.clone();
         all_alphas.instantiate(alpha);
 
         //~ 20. TODO: this is just an optimization, ignore?
-        let lagrange = index.cs.evaluate(&witness_poly, &z_poly);
+        _step = config.step(20)?;
+        let lagrange = index.cs.evaluate(&witness_poly, &z_poly, twiddles);
 
         //~ 21. TODO: lookup
+        _step = config.step(21)?;
         let lookup_table_combined = index.cs.lookup_constraint_system.as_ref().map(|lcs| {
             let joint_table = &lcs.lookup_table8;
             let mut res = joint_table[joint_table.len() - 1].clone();
@@ -411,14 +536,21 @@ where
             );
 
         //~ 22. TODO: setup the env
+        _step = config.step(22)?;
         let env = {
             let mut index_evals = HashMap::new();
             use GateType::*;
             index_evals.insert(Poseidon, &index.cs.ps8);
+            if let Some(poseidon_legacy8) = &index.cs.poseidon_legacy8 {
+                index_evals.insert(PoseidonLegacy, poseidon_legacy8);
+            }
             index_evals.insert(CompleteAdd, &index.cs.complete_addl4);
             index_evals.insert(VarBaseMul, &index.cs.mull8);
             index_evals.insert(EndoMul, &index.cs.emull);
             index_evals.insert(EndoMulScalar, &index.cs.endomul_scalar8);
+            if let Some(fixed_base_mull4) = &index.cs.fixed_base_mull4 {
+                index_evals.insert(FixedBaseMul, fixed_base_mull4);
+            }
             [ChaCha0, ChaCha1, ChaCha2, ChaChaFinal]
                 .iter()
                 .enumerate()
@@ -456,6 +588,7 @@ where
         //~     - the negated public polynomial
         //~     and by then dividing the resulting polynomial with the vanishing polynomial $Z_H$.
         //~     TODO: specify the split of the permutation polynomial into perm and bnd?
+        _step = config.step(23)?;
         let quotient_poly = {
             // generic
             let alphas =
@@ -473,22 +606,6 @@ where
                 assert!(res.is_zero());
             }
 
-            // complete addition
-            let add_constraint = CompleteAdd::combined_constraints(&all_alphas);
-            let add4 = add_constraint.evaluations(&env);
-            t4 += &add4;
-
-            if cfg!(test) {
-                let (_, res) = add4
-                    .clone()
-                    .interpolate()
-                    .divide_by_vanishing_poly(index.cs.domain.d1)
-                    .unwrap();
-                assert!(res.is_zero());
-            }
-
-            drop(add4);
-
             // permutation
             let alphas = all_alphas.get_alphas(ArgumentType::Permutation, permutation::CONSTRAINTS);
             let (perm, bnd) = index
@@ -505,104 +622,14 @@ where
                 assert!(res.is_zero());
             }
 
-            // scalar multiplication
-            let mul8 = VarbaseMul::combined_constraints(&all_alphas).evaluations(&env);
-            t8 += &mul8;
-
-            if cfg!(test) {
-                let (_, res) = mul8
-                    .clone()
-                    .interpolate()
-                    .divide_by_vanishing_poly(index.cs.domain.d1)
-                    .unwrap();
-                assert!(res.is_zero());
-            }
-
-            drop(mul8);
-
-            // endoscaling
-            let emul8 = EndosclMul::combined_constraints(&all_alphas).evaluations(&env);
-            t8 += &emul8;
-
-            if cfg!(test) {
-                let (_, res) = emul8
-                    .clone()
-                    .interpolate()
-                    .divide_by_vanishing_poly(index.cs.domain.d1)
-                    .unwrap();
-                assert!(res.is_zero());
-            }
-
-            drop(emul8);
-
-            // endoscaling scalar computation
-            let emulscalar8 = EndomulScalar::combined_constraints(&all_alphas).evaluations(&env);
-            t8 += &emulscalar8;
-
-            if cfg!(test) {
-                let (_, res) = emulscalar8
-                    .clone()
-                    .interpolate()
-                    .divide_by_vanishing_poly(index.cs.domain.d1)
-                    .unwrap();
-                assert!(res.is_zero());
-            }
-
-            drop(emulscalar8);
-
-            // poseidon
-            let pos8 = Poseidon::combined_constraints(&all_alphas).evaluations(&env);
-            t8 += &pos8;
-
-            if cfg!(test) {
-                let (_, res) = pos8
-                    .clone()
-                    .interpolate()
-                    .divide_by_vanishing_poly(index.cs.domain.d1)
-                    .unwrap();
-                assert!(res.is_zero());
-            }
-
-            drop(pos8);
-
-            // chacha
-            if index.cs.chacha8.as_ref().is_some() {
-                let chacha0 = ChaCha0::combined_constraints(&all_alphas).evaluations(&env);
-                t4 += &chacha0;
-
-                let chacha1 = ChaCha1::combined_constraints(&all_alphas).evaluations(&env);
-                t4 += &chacha1;
-
-                let chacha2 = ChaCha2::combined_constraints(&all_alphas).evaluations(&env);
-                t4 += &chacha2;
-
-                let chacha_final = ChaChaFinal::combined_constraints(&all_alphas).evaluations(&env);
-                t4 += &chacha_final;
+            // the other gates and the lookup argument, whose combined constraints
+            // are compiled once in the index, with a program per evaluation domain
+            for program in index.constraints_programs() {
+                let evals = program.evaluations(&env);
+                add_to_quotient(&mut t4, &mut t8, &evals);
 
                 if cfg!(test) {
-                    let (_, res) = chacha0
-                        .clone()
-                        .interpolate()
-                        .divide_by_vanishing_poly(index.cs.domain.d1)
-                        .unwrap();
-                    assert!(res.is_zero());
-
-                    let (_, res) = chacha1
-                        .clone()
-                        .interpolate()
-                        .divide_by_vanishing_poly(index.cs.domain.d1)
-                        .unwrap();
-                    assert!(res.is_zero());
-
-                    let (_, res) = chacha2
-                        .clone()
-                        .interpolate()
-                        .divide_by_vanishing_poly(index.cs.domain.d1)
-                        .unwrap();
-                    assert!(res.is_zero());
-
-                    let (_, res) = chacha_final
-                        .clone()
+                    let (_, res) = evals
                         .interpolate()
                         .divide_by_vanishing_poly(index.cs.domain.d1)
                         .unwrap();
@@ -610,28 +637,8 @@ where
                 }
             }
 
-            // lookup
-            if let Some(lcs) = index.cs.lookup_constraint_system.as_ref() {
-                let lookup_alphas =
-                    all_alphas.get_alphas(ArgumentType::Lookup, lookup::CONSTRAINTS);
-                let constraints = lookup::constraints(&lcs.dummy_lookup_value, index.cs.domain.d1);
-
-                for (constraint, alpha_pow) in constraints.into_iter().zip_eq(lookup_alphas) {
-                    let mut eval = constraint.evaluations(&env);
-                    eval.evals.iter_mut().for_each(|x| *x *= alpha_pow);
-
-                    if eval.domain().size == t4.domain().size {
-                        t4 += &eval;
-                    } else if eval.domain().size == t8.domain().size {
-                        t8 += &eval;
-                    } else {
-                        panic!("Bad evaluation")
-                    }
-                }
-            }
-
             // public polynomial
-            let mut f = t4.interpolate() + t8.interpolate();
+            let mut f = twiddles.interpolate(t4) + twiddles.interpolate(t8);
             f += &public_poly;
 
             // divide contributions with vanishing polynomial
@@ -650,6 +657,7 @@ where
 
         //~ 24. commit (hiding) to the quotient polynomial $t$
         //~     TODO: specify the dummies
+        _step = config.step(24)?;
         let t_comm = {
             let (mut t_comm, mut omega_t) = index.srs.commit(&quotient_poly, None, rng);
 
@@ -668,18 +676,22 @@ where
         };
 
         //~ 25. Absorb the the commitment of the quotient polynomial with the Fq-Sponge.
+        _step = config.step(25)?;
         fq_sponge.absorb_g(&t_comm.0.unshifted);
 
         //~ 26. Sample $\zeta'$ with the Fq-Sponge.
+        _step = config.step(26)?;
         let zeta_chal = ScalarChallenge(fq_sponge.challenge());
 
         //~ 27. Derive $\zeta$ from $\zeta'$ using the endomorphism (TODO: specify)
+        _step = config.step(27)?;
         let zeta = zeta_chal.to_field(&index.srs.endo_r);
 
         let omega = index.cs.domain.d1.group_gen;
         let zeta_omega = zeta * omega;
 
         //~ 28. TODO: lookup
+        _step = config.step(28)?;
         let lookup_evals = |e: Fr<G>| {
             lookup_aggreg_coeffs
                 .as_ref()
@@ -723,6 +735,7 @@ where
         //~     $$(f_0(x), f_1(x), f_2(x), \ldots)$$
         //~
         //~      TODO: do we want to specify more on that? It seems unecessary except for the t polynomial (or if for some reason someone sets that to a low value)
+        _step = config.step(29)?;
         let chunked_evals = {
             let chunked_evals_zeta = ProofEvaluations::<Vec<Fr<G>>> {
                 s: array_init(|i| {
@@ -733,6 +746,11 @@ where
                 lookup: lookup_evals(zeta),
                 generic_selector: index.cs.genericm.eval(zeta, index.max_poly_size),
                 poseidon_selector: index.cs.psm.eval(zeta, index.max_poly_size),
+                poseidon_legacy_selector: index
+                    .cs
+                    .poseidon_legacym
+                    .as_ref()
+                    .map(|p| p.eval(zeta, index.max_poly_size)),
             };
             let chunked_evals_zeta_omega = ProofEvaluations::<Vec<Fr<G>>> {
                 s: array_init(|i| {
@@ -743,6 +761,11 @@ where
                 lookup: lookup_evals(zeta_omega),
                 generic_selector: index.cs.genericm.eval(zeta_omega, index.max_poly_size),
                 poseidon_selector: index.cs.psm.eval(zeta_omega, index.max_poly_size),
+                poseidon_legacy_selector: index
+                    .cs
+                    .poseidon_legacym
+                    .as_ref()
+                    .map(|p| p.eval(zeta_omega, index.max_poly_size)),
             };
 
             [chunked_evals_zeta, chunked_evals_zeta_omega]
@@ -757,6 +780,7 @@ where
 
         //~ 30. Evaluate the same polynomials without chunking them
         //~     (so that each polynomial should correspond to a single value this time).
+        _step = config.step(30)?;
         let evals = {
             let power_of_eval_points_for_chunks = [zeta_to_srs_len, zeta_omega_to_srs_len];
             &chunked_evals
@@ -777,12 +801,17 @@ where
                     }),
                     generic_selector: DensePolynomial::eval_polynomial(&es.generic_selector, e1),
                     poseidon_selector: DensePolynomial::eval_polynomial(&es.poseidon_selector, e1),
+                    poseidon_legacy_selector: es
+                        .poseidon_legacy_selector
+                        .as_ref()
+                        .map(|e| DensePolynomial::eval_polynomial(e, e1)),
                 })
                 .collect::<Vec<_>>()
         };
 
         //~ 31. Compute the ft polynomial.
         //~     This is to implement [Maller's optimization](https://o1-labs.github.io/mina-book/crypto/plonk/maller_15.html).
+        _step = config.step(31)?;
         let ft: DensePolynomial<Fr<G>> = {
             let f_chunked = {
                 // TODO: compute the linearization polynomial in evaluation form so
@@ -824,6 +853,7 @@ where
 
         //~ 32. construct the blinding part of the ft polynomial commitment
         //~     see https://o1-labs.github.io/mina-book/crypto/plonk/maller_15.html#evaluation-proof-and-blinding-factors
+        _step = config.step(32)?;
         let blinding_ft = {
             let blinding_t = t_comm.1.chunk_blinding(zeta_to_srs_len);
             let blinding_f = Fr::<G>::zero();
@@ -836,16 +866,20 @@ where
         };
 
         //~ 33. Evaluate the ft polynomial at $\zeta\omega$ only.
+        _step = config.step(33)?;
         let ft_eval1 = ft.evaluate(&zeta_omega);
 
         //~ 34. Setup the Fr-Sponge
+        _step = config.step(34)?;
         let fq_sponge_before_evaluations = fq_sponge.clone();
         let mut fr_sponge = EFrSponge::new(index.cs.fr_sponge_params.clone());
 
         //~ 35. Squeeze the Fq-sponge and absorb the result with the Fr-Sponge.
+        _step = config.step(35)?;
         fr_sponge.absorb(&fq_sponge.digest());
 
         //~ 36. Evaluate the negated public polynomial (if present) at $\zeta$ and $\zeta\omega$.
+        _step = config.step(36)?;
         let public_evals = if public_poly.is_zero() {
             [Vec::new(), Vec::new()]
         } else {
@@ -862,28 +896,35 @@ where
         //~     - poseidon selector
         //~     - the 15 register/witness
         //~     - 6 sigmas evaluations (the last one is not evaluated)
+        _step = config.step(37)?;
         for i in 0..2 {
             fr_sponge.absorb_evaluations(&public_evals[i], &chunked_evals[i])
         }
 
         //~ 38. Absorb the unique evaluation of ft: $ft(\zeta\omega)$.
+        _step = config.step(38)?;
         fr_sponge.absorb(&ft_eval1);
 
         //~ 39. Sample $v'$ with the Fr-Sponge
+        _step = config.step(39)?;
         let v_chal = fr_sponge.challenge();
 
         //~ 40. Derive $v$ from $v'$ using the endomorphism (TODO: specify)
+        _step = config.step(40)?;
         let v = v_chal.to_field(&index.srs.endo_r);
 
         //~ 41. Sample $u'$ with the Fr-Sponge
+        _step = config.step(41)?;
         let u_chal = fr_sponge.challenge();
 
         //~ 42. Derive $u$ from $u'$ using the endomorphism (TODO: specify)
+        _step = config.step(42)?;
         let u = u_chal.to_field(&index.srs.endo_r);
 
         //~ 43. Create a list of all polynomials that will require evaluations
         //~     (and evaluation proofs) in the protocol.
         //~     First, include the previous challenges, in case we are in a recursive prover.
+        _step = config.step(43)?;
         let non_hiding = |d1_size: usize| PolyComm {
             unshifted: vec![Fr::<G>::zero(); d1_size],
             shifted: None,
@@ -910,13 +951,18 @@ where
         //~     - the permutation aggregation polynomial z polynomial
         //~     - the generic selector
         //~     - the poseidon selector
+        //~     - the poseidon legacy selector, if the circuit has legacy Poseidon gates
         //~     - the 15 registers/witness columns
         //~     - the 6 sigmas
+        _step = config.step(44)?;
         polynomials.extend(vec![(&public_poly, None, non_hiding(1))]);
         polynomials.extend(vec![(&ft, None, blinding_ft)]);
         polynomials.extend(vec![(&z_poly, None, z_comm.1)]);
         polynomials.extend(vec![(&index.cs.genericm, None, non_hiding(1))]);
         polynomials.extend(vec![(&index.cs.psm, None, non_hiding(1))]);
+        if let Some(poseidon_legacym) = &index.cs.poseidon_legacym {
+            polynomials.extend(vec![(poseidon_legacym, None, non_hiding(1))]);
+        }
         polynomials.extend(
             witness_poly
                 .iter()
@@ -931,7 +977,8 @@ where
                 .collect::<Vec<_>>(),
         );
 
-        //~ 44. Create an aggregated evaluation proof for all of these polynomials at $\zeta$ and $\zeta\omega$ using $u$ and $v$.
+        //~ 45. Create an aggregated evaluation proof for all of these polynomials at $\zeta$ and $\zeta\omega$ using $u$ and $v$.
+        _step = config.step(45)?;
         let proof = index.srs.open(
             group_map,
             &polynomials,
@@ -963,6 +1010,24 @@ where
     }
 }
 
+/// Adds the evaluations `evals` of combined constraints to the evaluations of the quotient polynomial
+/// over d4 (`t4`) or d8 (`t8`), according to the domain chosen from their degree,
+/// and over d4 if this domain is smaller.
+fn add_to_quotient<F: FftField>(
+    t4: &mut Evaluations<F, D<F>>,
+    t8: &mut Evaluations<F, D<F>>,
+    evals: &Evaluations<F, D<F>>,
+) {
+    let size = evals.domain().size;
+    if size == t8.domain().size {
+        *t8 += evals;
+    } else if size == t4.domain().size {
+        *t4 += evals;
+    } else {
+        *t4 += &evals.interpolate_by_ref().evaluate_over_domain(t4.domain());
+    }
+}
+
 #[cfg(feature = "ocaml_types")]
 pub mod caml {
     use super::*;
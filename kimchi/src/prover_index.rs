@@ -4,12 +4,17 @@ use crate::alphas::Alphas;
 use crate::circuits::{
     constraints::ConstraintSystem,
     expr::{Linearization, PolishToken},
-    wires::*,
+    fft::Twiddles,
+    program::Program,
+    wires::PERMUTS,
+};
+use crate::linearization::{
+    check_linearization, constraints_programs, expr_linearization, LinearizationError,
 };
-use crate::linearization::expr_linearization;
 use ark_ec::AffineCurve;
 use ark_ff::PrimeField;
 use commitment_dlog::{commitment::CommitmentCurve, srs::SRS};
+use once_cell::sync::OnceCell;
 use oracle::poseidon::ArithmeticSpongeParams;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
@@ -28,11 +33,11 @@ pub struct ProverIndex<G: CommitmentCurve> {
     pub cs: ConstraintSystem<Fr<G>>,
 
     /// The symbolic linearization of our circuit, which can compile to concrete types once certain values are learned in the protocol.
-    #[serde(skip)]
+    #[serde(bound = "Linearization<Vec<PolishToken<Fr<G>>>>: Serialize + DeserializeOwned")]
     pub linearization: Linearization<Vec<PolishToken<Fr<G>>>>,
 
     /// The mapping between powers of alpha and constraints
-    #[serde(skip)]
+    #[serde(bound = "Alphas<Fr<G>>: Serialize + DeserializeOwned")]
     pub powers_of_alpha: Alphas<Fr<G>>,
 
     /// polynomial commitment keys
@@ -48,6 +53,16 @@ pub struct ProverIndex<G: CommitmentCurve> {
     /// random oracle argument parameters
     #[serde(skip)]
     pub fq_sponge_params: ArithmeticSpongeParams<Fq<G>>,
+
+    /// The combined constraints of the gates and of the lookup argument,
+    /// compiled the first time they are needed (see [ProverIndex::constraints_programs])
+    #[serde(skip)]
+    constraints_programs: OnceCell<Vec<Program<Fr<G>>>>,
+
+    /// The roots of unity of the evaluation domains,
+    /// computed the first time they are needed (see [ProverIndex::twiddles])
+    #[serde(skip)]
+    twiddles: OnceCell<Twiddles<Fr<G>>>,
 }
 //~spec:endcode
 
@@ -75,13 +90,17 @@ where
         let (linearization, powers_of_alpha) = expr_linearization(
             cs.domain.d1,
             cs.chacha8.is_some(),
-            &cs.lookup_constraint_system,
+            cs.poseidon_legacy8.is_some(),
+            cs.fixed_base_mull4.is_some(),
+            cs.lookup_parameters(),
         );
 
         // set `max_quot_size` to the degree of the quotient polynomial,
         // which is obtained by looking at the highest monomial in the sum
         // $$\sum_{i=0}^{PERMUTS} (w_i(x) + \beta k_i x + \gamma)$$
         // where the $w_i(x)$ are of degree the size of the domain.
+        // The combined constraints of the other arguments are checked to fit in d8
+        // (see [crate::linearization::combined_constraints]), so that they do not exceed it.
         let max_quot_size = PERMUTS * cs.domain.d1.size as usize;
 
         ProverIndex {
@@ -92,19 +111,66 @@ where
             max_poly_size,
             max_quot_size,
             fq_sponge_params,
+            constraints_programs: OnceCell::new(),
+            twiddles: OnceCell::new(),
         }
     }
+
+    /// Returns the combined constraints of the gates (except the generic gate) and of the lookup argument,
+    /// compiled to a program per evaluation domain (see [constraints_programs]).
+    /// They are compiled once, and shared by all the proofs created with the index.
+    pub fn constraints_programs(&self) -> &[Program<Fr<G>>] {
+        self.constraints_programs.get_or_init(|| {
+            constraints_programs(
+                self.cs.domain.d1,
+                self.cs.chacha8.is_some(),
+                self.cs.poseidon_legacy8.is_some(),
+                self.cs.fixed_base_mull4.is_some(),
+                self.cs.lookup_parameters(),
+            )
+        })
+    }
+
+    /// Returns the roots of unity of the evaluation domains, used by the FFTs of the prover.
+    /// They are computed once, and shared by all the proofs created with the index.
+    pub fn twiddles(&self) -> &Twiddles<Fr<G>> {
+        self.twiddles
+            .get_or_init(|| Twiddles::create(&self.cs.domain))
+    }
+
+    /// Checks that the linearization and the powers of alpha of the index,
+    /// which may have been deserialized, are those this library computes for its constraint system.
+    pub fn check_linearization(&self) -> Result<(), LinearizationError> {
+        check_linearization(
+            &self.linearization,
+            &self.powers_of_alpha,
+            self.cs.domain.d1,
+            self.cs.chacha8.is_some(),
+            self.cs.poseidon_legacy8.is_some(),
+            self.cs.fixed_base_mull4.is_some(),
+            self.cs.lookup_parameters(),
+        )
+    }
 }
 
 pub mod testing {
     use super::*;
-    use crate::circuits::gate::CircuitGate;
+    use crate::circuits::gate::{CircuitGate, LookupTable};
     use commitment_dlog::srs::endos;
     use mina_curves::pasta::{pallas::Affine as Other, vesta::Affine, Fp};
 
     pub fn new_index_for_test(gates: Vec<CircuitGate<Fp>>, public: usize) -> ProverIndex<Affine> {
+        new_index_for_test_with_lookups(gates, public, vec![])
+    }
+
+    pub fn new_index_for_test_with_lookups(
+        gates: Vec<CircuitGate<Fp>>,
+        public: usize,
+        lookup_tables: Vec<LookupTable<Fp>>,
+    ) -> ProverIndex<Affine> {
         let fp_sponge_params = oracle::pasta::fp_kimchi::params();
-        let cs = ConstraintSystem::<Fp>::create(gates, vec![], fp_sponge_params, public).unwrap();
+        let cs =
+            ConstraintSystem::<Fp>::create(gates, lookup_tables, fp_sponge_params, public).unwrap();
 
         let mut srs = SRS::<Affine>::create(cs.domain.d1.size as usize);
         srs.add_lagrange_basis(cs.domain.d1);
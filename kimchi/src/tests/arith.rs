@@ -0,0 +1,155 @@
+use crate::{
+    circuits::gadgets::builder::{BuiltCircuit, CircuitBuilder},
+    prover::ProverProof,
+    prover_index::testing::new_index_for_test,
+    verifier::batch_verify,
+};
+use ark_ff::{One, UniformRand, Zero};
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp as F,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    pasta::fp_kimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<F, SpongeParams>;
+
+fn satisfied(built: &BuiltCircuit<F>) -> bool {
+    let index = new_index_for_test(built.gates.clone(), built.public.len());
+    index.cs.verify(&built.witness, &built.public).is_ok()
+}
+
+#[test]
+fn boolean_gadgets() {
+    let mut b = CircuitBuilder::new(fp_kimchi::params());
+    let (x, y) = (b.witness(F::from(5u64)), b.witness(F::from(7u64)));
+    for (u, v) in [(false, false), (false, true), (true, false), (true, true)] {
+        let (bu, bv) = (b.boolean(u), b.boolean(v));
+        let (and, or, xor) = (b.and(bu, bv), b.or(bu, bv), b.xor(bu, bv));
+        let not = b.not(bu);
+        let select = b.select(bu, x, y);
+        assert_eq!(b.value(and), F::from((u && v) as u64));
+        assert_eq!(b.value(or), F::from((u || v) as u64));
+        assert_eq!(b.value(xor), F::from((u ^ v) as u64));
+        assert_eq!(b.value(not), F::from(!u as u64));
+        assert_eq!(b.value(select), if u { b.value(x) } else { b.value(y) });
+    }
+    assert!(satisfied(&b.build()));
+
+    // a boolean is 0 or 1
+    let mut b = CircuitBuilder::new(fp_kimchi::params());
+    let (one, two) = (b.witness(F::one()), b.witness(F::from(2u64)));
+    b.assert_boolean(one);
+    b.assert_boolean(two);
+    assert!(!satisfied(&b.build()));
+}
+
+#[test]
+fn equality_gadgets() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let mut b = CircuitBuilder::new(fp_kimchi::params());
+    let (zero, x) = (b.witness(F::zero()), b.witness(F::rand(rng)));
+    let x2 = b.witness(b.value(x));
+
+    let (z0, z1) = (b.is_zero(zero), b.is_zero(x));
+    let (e0, e1) = (b.is_equal(x, x2), b.is_equal(x, zero));
+    assert_eq!(b.value(z0), F::one());
+    assert_eq!(b.value(z1), F::zero());
+    assert_eq!(b.value(e0), F::one());
+    assert_eq!(b.value(e1), F::zero());
+
+    let inv = b.inv(x);
+    assert_eq!(b.value(inv) * b.value(x), F::one());
+
+    let mut built = b.build();
+    assert!(satisfied(&built));
+
+    // the hint cannot make a non-zero value look like zero
+    let (row, col) = (z1.row, z1.col);
+    built.witness[col][row] = F::one();
+    built.witness[col - 1][row] = F::zero();
+    assert!(!satisfied(&built));
+}
+
+#[test]
+fn bits_gadgets() {
+    let mut b = CircuitBuilder::new(fp_kimchi::params());
+    let x = b.witness(F::from(0b1011_0110u64));
+    let bits = b.unpack(x, 8);
+    let values: Vec<_> = bits.iter().map(|v| b.value(*v)).collect();
+    let expected: Vec<_> = [0, 1, 1, 0, 1, 1, 0, 1]
+        .iter()
+        .map(|b| F::from(*b as u64))
+        .collect();
+    assert_eq!(values, expected);
+    let packed = b.pack(&bits[..4]);
+    assert_eq!(b.value(packed), F::from(0b0110u64));
+    assert!(satisfied(&b.build()));
+
+    // the value does not fit in fewer bits
+    let mut b = CircuitBuilder::new(fp_kimchi::params());
+    let x = b.witness(F::from(0b1011_0110u64));
+    b.unpack(x, 7);
+    assert!(!satisfied(&b.build()));
+}
+
+#[test]
+fn less_than_gadgets() {
+    for num_bits in [10, 16] {
+        let max = (1u64 << num_bits) - 1;
+        let mut b = CircuitBuilder::new(fp_kimchi::params());
+        for (u, v) in [
+            (0, 0),
+            (0, 1),
+            (1, 0),
+            (3, 700),
+            (700, 3),
+            (max, max),
+            (0, max),
+            (max, 0),
+        ] {
+            let (x, y) = (b.witness(F::from(u)), b.witness(F::from(v)));
+            let lt = b.less_than(x, y, num_bits);
+            assert_eq!(b.value(lt), F::from((u < v) as u64), "{} < {}", u, v);
+        }
+        let (x, y) = (b.witness(F::from(41u64)), b.witness(F::from(42u64)));
+        b.assert_less_than(x, y, num_bits);
+        assert!(satisfied(&b.build()));
+
+        let mut b = CircuitBuilder::new(fp_kimchi::params());
+        let (x, y) = (b.witness(F::from(42u64)), b.witness(F::from(42u64)));
+        b.assert_less_than(x, y, num_bits);
+        assert!(!satisfied(&b.build()));
+    }
+}
+
+#[test]
+fn arith_gadgets_proof() {
+    let mut b = CircuitBuilder::new(fp_kimchi::params());
+    let x = b.public_input(F::from(1000u64));
+    let y = b.witness(F::from(1001u64));
+    let lt = b.less_than(x, y, 16);
+    let eq = b.is_equal(x, y);
+    let c = b.or(lt, eq);
+    let out = b.select(c, x, y);
+    assert_eq!(b.value(out), F::from(1000u64));
+
+    let built = b.build();
+    let index = new_index_for_test(built.gates, built.public.len());
+    index.cs.verify(&built.witness, &built.public).unwrap();
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, built.witness, &index).unwrap();
+    let verifier_index = index.verifier_index();
+    let batch: Vec<_> = vec![(&verifier_index, &proof)];
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &batch).unwrap();
+}
@@ -1,6 +1,7 @@
 use crate::{
     circuits::{
-        gate::CircuitGate,
+        constraints::{ConstraintSystem, GateError},
+        gate::{CircuitGate, GateType},
         polynomials::chacha,
         wires::{Wire, COLUMNS},
     },
@@ -99,3 +100,63 @@ fn chacha_prover() {
         }
     }
 }
+
+/// The gates and the witness of a single ChaCha20 block
+fn chacha_circuit() -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let s0: Vec<u32> = (0..16).collect();
+    let gates = chacha::testing::chacha20_gates()
+        .into_iter()
+        .enumerate()
+        .map(|(i, typ)| CircuitGate {
+            typ,
+            coeffs: vec![],
+            wires: Wire::new(i),
+        })
+        .collect();
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![]);
+    for r in chacha::testing::chacha20_rows::<Fp>(s0) {
+        for (col, c) in r.into_iter().enumerate() {
+            witness[col].push(c);
+        }
+    }
+    (gates, witness)
+}
+
+#[test]
+fn chacha_verify_witness() {
+    let (gates, witness) = chacha_circuit();
+    let cs =
+        ConstraintSystem::create(gates, vec![], oracle::pasta::fp_kimchi::params(), 0).unwrap();
+    cs.verify(&witness, &[]).unwrap();
+}
+
+#[test]
+fn chacha_verify_bad_witness() {
+    let (gates, mut witness) = chacha_circuit();
+    let cs =
+        ConstraintSystem::create(gates, vec![], oracle::pasta::fp_kimchi::params(), 0).unwrap();
+
+    // the first gate is a ChaCha0 gate, which reads the cell
+    assert_eq!(cs.gates[0].typ, GateType::ChaCha0);
+    witness[0][0] += Fp::from(1u64);
+    match cs.verify(&witness, &[]) {
+        Err(GateError::Custom { row: 0, err }) => {
+            assert!(err.starts_with("Bad ChaCha0 equation"), "{}", err)
+        }
+        res => panic!("unexpected result {:?}", res),
+    }
+}
+
+#[test]
+fn chacha_verify_wrong_gate() {
+    let (_, witness) = chacha_circuit();
+    let gate = CircuitGate::<Fp> {
+        typ: GateType::Generic,
+        coeffs: vec![],
+        wires: Wire::new(0),
+    };
+    assert_eq!(
+        gate.verify_chacha(0, &witness),
+        Err("incorrect gate type".to_string())
+    );
+}
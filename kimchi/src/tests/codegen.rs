@@ -0,0 +1,179 @@
+use crate::circuits::{
+    codegen::{arguments, c_function, c_module, rust_function, rust_module},
+    evaluators,
+    expr::{prologue::*, Column, ConstantExpr, Constants, Variable},
+    gate::{CurrOrNext, GateType},
+    program::{Op, Program},
+    scalars::ProofEvaluations,
+    wires::COLUMNS,
+};
+use ark_ff::UniformRand;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use array_init::array_init;
+use mina_curves::pasta::{fp::Fp as F, fq::Fq};
+use rand::{rngs::StdRng, SeedableRng};
+
+type Evaluator = fn(&[F], &[F], &[F], F, &[Vec<F>]) -> Vec<F>;
+
+/// The generated evaluators, in the order of [arguments]
+fn evaluators() -> Vec<Evaluator> {
+    vec![
+        |c, n, k, e, m| evaluators::complete_add(c, n, k, e, m).to_vec(),
+        |c, n, k, e, m| evaluators::varbasemul(c, n, k, e, m).to_vec(),
+        |c, n, k, e, m| evaluators::endosclmul(c, n, k, e, m).to_vec(),
+        |c, n, k, e, m| evaluators::endomul_scalar(c, n, k, e, m).to_vec(),
+        |c, n, k, e, m| evaluators::fixed_base_mul(c, n, k, e, m).to_vec(),
+        |c, n, k, e, m| evaluators::poseidon(c, n, k, e, m).to_vec(),
+        |c, n, k, e, m| evaluators::poseidon_legacy(c, n, k, e, m).to_vec(),
+        |c, n, k, e, m| evaluators::chacha0(c, n, k, e, m).to_vec(),
+        |c, n, k, e, m| evaluators::chacha1(c, n, k, e, m).to_vec(),
+        |c, n, k, e, m| evaluators::chacha2(c, n, k, e, m).to_vec(),
+        |c, n, k, e, m| evaluators::chacha_final(c, n, k, e, m).to_vec(),
+    ]
+}
+
+#[test]
+fn generated_code_is_up_to_date() {
+    let gates = arguments::<F>();
+    // regenerate with `cargo run --example codegen`
+    assert!(rust_module(&gates).unwrap() == include_str!("../circuits/evaluators.rs"));
+    assert!(c_module(&gates).unwrap() == include_str!("../../c/evaluators.c"));
+}
+
+#[test]
+fn generated_code_does_not_depend_on_the_field() {
+    // the literals of the constraints of the gates are small fractions
+    let fp = arguments::<F>();
+    let fq = arguments::<Fq>();
+    assert!(rust_module(&fp).unwrap() == rust_module(&fq).unwrap());
+    assert!(c_module(&fp).unwrap() == c_module(&fq).unwrap());
+}
+
+#[test]
+fn evaluators_evaluate_like_expr() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let domain = D::<F>::new(8).unwrap();
+    let gates = arguments::<F>();
+    assert_eq!(gates.len(), evaluators().len());
+
+    for ((name, constraints), evaluator) in gates.iter().zip(evaluators()) {
+        for _ in 0..4 {
+            let curr: [F; COLUMNS] = array_init(|_| F::rand(rng));
+            let next: [F; COLUMNS] = array_init(|_| F::rand(rng));
+            let coeffs: Vec<F> = (0..COLUMNS).map(|_| F::rand(rng)).collect();
+            let c = Constants {
+                alpha: F::rand(rng),
+                beta: F::rand(rng),
+                gamma: F::rand(rng),
+                joint_combiner: F::rand(rng),
+                endo_coefficient: F::rand(rng),
+                mds: (0..3)
+                    .map(|_| (0..3).map(|_| F::rand(rng)).collect())
+                    .collect(),
+            };
+            let pt = F::rand(rng);
+            let values = evaluator(&curr, &next, &coeffs, c.endo_coefficient, &c.mds);
+
+            // the proof evaluations do not include the coefficients,
+            // so the constraints reading them are evaluated by a lowered program
+            let cell = |v: &Variable| match (v.col, v.row) {
+                (Column::Witness(i), CurrOrNext::Curr) => Ok(curr[i]),
+                (Column::Witness(i), CurrOrNext::Next) => Ok(next[i]),
+                (Column::Coefficient(i), CurrOrNext::Curr) => Ok(coeffs[i]),
+                _ => Err("unexpected column"),
+            };
+            let lowered = Program::lower_all(constraints).evaluate_with(domain, pt, &c, cell);
+            assert_eq!(lowered.as_ref(), Ok(&values), "{}", name);
+
+            let evals = [
+                ProofEvaluations::dummy_with_witness_evaluations(curr),
+                ProofEvaluations::dummy_with_witness_evaluations(next),
+            ];
+            let reads_coefficients = constraints.iter().any(|e| {
+                Program::lower(e)
+                    .ops
+                    .iter()
+                    .any(|op| matches!(op, Op::Cell(v) if matches!(v.col, Column::Coefficient(_))))
+            });
+            if !reads_coefficients {
+                for (i, e) in constraints.iter().enumerate() {
+                    assert_eq!(
+                        e.evaluate_(domain, pt, &evals, &c),
+                        Ok(values[i]),
+                        "{} {}",
+                        name,
+                        i
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn generated_functions() {
+    let x = witness_curr::<F>(0);
+    let constraints = vec![
+        x.clone() * x.clone() - E::literal(F::from(3u64) / F::from(2u64)),
+        witness_next(1) + E::Constant(ConstantExpr::EndoCoefficient),
+        witness_next(2) * E::literal(-F::from(5u64))
+            - E::literal(F::from(1u128 << 64) * F::from(1u128 << 64)),
+    ];
+
+    assert_eq!(
+        rust_function("example", &constraints).unwrap(),
+        "\
+/// Evaluates the 3 constraints of the gate `example`.
+pub fn example<F: Field>(curr: &[F], next: &[F], coeffs: &[F], endo_coefficient: F, mds: &[Vec<F>]) -> [F; 3] {
+    let r0 = curr[0];
+    let r1 = r0.square();
+    let r2 = F::from(3u64) / F::from(2u64);
+    let r3 = r1 - r2;
+    let r4 = next[1];
+    let r5 = endo_coefficient;
+    let r6 = r4 + r5;
+    let r7 = next[2];
+    let r8 = -F::from(5u64);
+    let r9 = r7 * r8;
+    let r10 = literal(&[0, 0, 1]);
+    let r11 = r9 - r10;
+    [r3, r6, r11]
+}
+"
+    );
+
+    assert_eq!(
+        c_function("example", &constraints).unwrap(),
+        "\
+/* Evaluates the 3 constraints of the gate `example` into out[0..3]. */
+void example(const field *curr, const field *next, const field *coeffs, const field *endo_coefficient, const field *mds, field *out)
+{
+    (void)curr; (void)next; (void)coeffs; (void)endo_coefficient; (void)mds;
+    field r0 = curr[0];
+    field r1; field_mul(&r1, &r0, &r0);
+    field r2; field_from_fraction(&r2, 0, 0x3, 2);
+    field r3; field_sub(&r3, &r1, &r2);
+    field r4 = next[1];
+    field r5 = *endo_coefficient;
+    field r6; field_add(&r6, &r4, &r5);
+    field r7 = next[2];
+    field r8; field_from_fraction(&r8, 1, 0x5, 1);
+    field r9; field_mul(&r9, &r7, &r8);
+    field r10; field_from_limbs(&r10, (const uint64_t[]){0x0, 0x0, 0x1}, 3);
+    field r11; field_sub(&r11, &r9, &r10);
+    out[0] = r3;
+    out[1] = r6;
+    out[2] = r11;
+}
+"
+    );
+}
+
+#[test]
+fn unsupported_columns() {
+    let constraints =
+        vec![witness_curr::<F>(0) * E::cell(Column::Index(GateType::Poseidon), CurrOrNext::Curr)];
+    assert!(rust_function("index", &constraints).is_err());
+    let constraints = vec![witness_curr::<F>(0) * E::Constant(ConstantExpr::Alpha)];
+    assert!(c_function("alpha", &constraints).is_err());
+}
@@ -0,0 +1,103 @@
+use crate::{
+    alphas::Alphas,
+    circuits::{
+        argument::{Argument, ArgumentType},
+        expr::{prologue::*, Domain},
+        gate::{CircuitGate, GateType},
+        polynomials::{
+            complete_add::CompleteAdd, endosclmul::EndosclMul, fixed_base_mul::FixedBaseMul,
+            poseidon::Poseidon, poseidon_legacy::PoseidonLegacy, varbasemul::VarbaseMul,
+        },
+        wires::{Wire, PERMUTS},
+    },
+    linearization::combined_constraints,
+    prover_index::testing::new_index_for_test,
+};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use mina_curves::pasta::fp::Fp as F;
+use std::marker::PhantomData;
+
+fn max_total_degree<A: Argument<F>>() -> u64 {
+    A::constraints()
+        .iter()
+        .map(|c| c.total_degree())
+        .max()
+        .unwrap()
+}
+
+/// The domain of the combined constraints of `A`, for a circuit of size `n`
+fn domain<A: Argument<F>>(n: u64) -> Option<Domain> {
+    let mut alphas = Alphas::default();
+    alphas.register(A::ARGUMENT_TYPE, A::CONSTRAINTS);
+    Domain::for_degree(A::combined_constraints(&alphas).degree(n), n)
+}
+
+#[test]
+fn constraint_degrees() {
+    assert_eq!(max_total_degree::<Poseidon<F>>(), 7);
+    // the MDS matrix of the legacy gate is in the coefficients
+    assert_eq!(max_total_degree::<PoseidonLegacy<F>>(), 6);
+    assert_eq!(max_total_degree::<CompleteAdd<F>>(), 3);
+
+    let e = witness_curr::<F>(0) * witness_next(1).pow(3) - coeff(0).square();
+    assert_eq!(e.total_degree(), 4);
+    assert_eq!(e.degree(16), 64);
+
+    // the selectors of the complete addition and of the fixed base multiplication are only evaluated over d4
+    let n = 1 << 10;
+    assert_eq!(domain::<CompleteAdd<F>>(n), Some(Domain::D4));
+    assert_eq!(domain::<FixedBaseMul<F>>(n), Some(Domain::D4));
+    assert_eq!(domain::<EndosclMul<F>>(n), Some(Domain::D8));
+    assert_eq!(domain::<VarbaseMul<F>>(n), Some(Domain::D8));
+    assert_eq!(domain::<Poseidon<F>>(n), Some(Domain::D8));
+}
+
+#[test]
+fn domain_for_degree() {
+    for (degree, domain) in [
+        (0, Some(Domain::D1)),
+        (16, Some(Domain::D1)),
+        (17, Some(Domain::D2)),
+        (33, Some(Domain::D4)),
+        (64, Some(Domain::D4)),
+        (65, Some(Domain::D8)),
+        (128, Some(Domain::D8)),
+        (129, None),
+    ] {
+        assert_eq!(Domain::for_degree(degree, 16), domain, "degree {}", degree);
+    }
+}
+
+#[test]
+fn quotient_degree() {
+    let gates: Vec<_> = (0..2)
+        .map(|row| CircuitGate::<F>::zero(Wire::new(row)))
+        .collect();
+    let index = new_index_for_test(gates, 0);
+    assert_eq!(
+        index.max_quot_size,
+        PERMUTS * index.cs.domain.d1.size as usize
+    );
+}
+
+/// A gate whose constraint `w_0^8 = w_1` does not fit in d8 with its selector
+#[derive(Default)]
+struct Overflow<F>(PhantomData<F>);
+
+impl Argument<F> for Overflow<F> {
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::Zero);
+    const CONSTRAINTS: u32 = 1;
+
+    fn constraints() -> Vec<E<F>> {
+        vec![witness_curr(0).pow(8) - witness_curr(1)]
+    }
+}
+
+#[test]
+#[should_panic(expected = "overflows d8")]
+fn constraint_degree_overflow() {
+    let domain = D::<F>::new(1 << 4).unwrap();
+    let mut alphas = Alphas::default();
+    alphas.register(ArgumentType::Gate(GateType::Zero), 1);
+    combined_constraints::<F, Overflow<F>>(&alphas, domain);
+}
@@ -1,5 +1,6 @@
 use crate::{
     circuits::{
+        gadgets::builder::CircuitBuilder,
         gate::{CircuitGate, GateType},
         wires::*,
     },
@@ -11,7 +12,7 @@ use ark_ec::{AffineCurve, ProjectiveCurve};
 use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
 use array_init::array_init;
 use colored::Colorize;
-use commitment_dlog::commitment::CommitmentCurve;
+use commitment_dlog::{commitment::CommitmentCurve, srs::endos};
 use groupmap::GroupMap;
 use mina_curves::pasta::{
     fp::Fp as F,
@@ -20,7 +21,7 @@ use mina_curves::pasta::{
 };
 use oracle::{
     constants::PlonkSpongeConstantsKimchi,
-    sponge::{DefaultFqSponge, DefaultFrSponge},
+    sponge::{DefaultFqSponge, DefaultFrSponge, ScalarChallenge},
 };
 use rand::{rngs::StdRng, SeedableRng};
 use std::time::Instant;
@@ -182,3 +183,55 @@ fn ec_test() {
         }
     }
 }
+
+// Tests the elliptic curve gadgets
+#[test]
+fn ec_gadget_test() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let (endo_q, endo_r) = endos::<Other>();
+    let rand_point = |rng: &mut StdRng| {
+        Other::prime_subgroup_generator()
+            .mul(<Other as AffineCurve>::ScalarField::rand(rng))
+            .into_affine()
+    };
+
+    let mut b = CircuitBuilder::new(oracle::pasta::fp_kimchi::params());
+    let mut expected = vec![];
+    let mut outputs = vec![];
+    for _ in 0..5 {
+        let (p, q) = (rand_point(rng), rand_point(rng));
+        let (p_var, q_var) = (b.witness_point(&p), b.witness_point(&q));
+
+        outputs.push(b.add_points(p_var, q_var));
+        expected.push(p + q);
+
+        outputs.push(b.double_point(p_var));
+        expected.push(p + p);
+
+        let s = <Other as AffineCurve>::ScalarField::rand(rng);
+        let (res, _) = b.scalar_mul(p_var, s);
+        outputs.push(res);
+        expected.push(p.mul(s).into_affine());
+
+        let c = ScalarChallenge(<Other as AffineCurve>::ScalarField::from(u128::rand(rng)));
+        let (res, _) = b.endo_mul(q_var, endo_q, c);
+        outputs.push(res);
+        expected.push(q.mul(c.to_field(&endo_r)).into_affine());
+    }
+
+    for (res, p) in outputs.iter().zip(&expected) {
+        assert_eq!(b.point_value(*res), (p.x, p.y));
+    }
+
+    // create and verify a proof of the circuit
+    let built = b.build();
+    let index = new_index_for_test(built.gates, built.public.len());
+    index.cs.verify(&built.witness, &built.public).unwrap();
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, built.witness, &index).unwrap();
+    let verifier_index = index.verifier_index();
+    let batch: Vec<_> = vec![(&verifier_index, &proof)];
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &batch).unwrap();
+}
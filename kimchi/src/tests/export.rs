@@ -0,0 +1,277 @@
+use crate::circuits::{
+    argument::{Argument, ArgumentType},
+    constraints::{eval_vanishes_on_last_4_rows, ConstraintSystem},
+    expr::{Column, Constants, Variable},
+    gate::{CircuitGate, CurrOrNext, GateType},
+    polynomials::{
+        chacha::{self, ChaCha0, ChaCha1, ChaCha2, ChaChaFinal},
+        complete_add::CompleteAdd,
+        endomul_scalar::EndomulScalar,
+        endosclmul::EndosclMul,
+        fixed_base_mul::FixedBaseMul,
+        poseidon::Poseidon,
+        poseidon_legacy::PoseidonLegacy,
+        varbasemul::VarbaseMul,
+    },
+    program::{Op, Program},
+    wires::{Wire, COLUMNS},
+};
+use crate::export::{export_expr, ConstraintSystemExport, Node, SCHEMA_VERSION};
+use crate::linearization::{constraints_expr, linearization_columns};
+use ark_ff::{BigInteger256, Field, PrimeField, UniformRand};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use mina_curves::pasta::fp::Fp as F;
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::HashMap;
+
+/// A constraint system with the ChaCha gates, which use lookups
+fn chacha_constraint_system() -> ConstraintSystem<F> {
+    let gates = chacha::testing::chacha20_gates()
+        .into_iter()
+        .enumerate()
+        .map(|(i, typ)| CircuitGate {
+            typ,
+            coeffs: vec![],
+            wires: Wire::new(i),
+        })
+        .collect();
+    ConstraintSystem::create(gates, vec![], oracle::pasta::fp_kimchi::params(), 0).unwrap()
+}
+
+fn parse_field(s: &str) -> F {
+    let hex = s.strip_prefix("0x").unwrap();
+    let limbs: Vec<u64> = hex
+        .as_bytes()
+        .chunks(16)
+        .rev()
+        .map(|limb| u64::from_str_radix(std::str::from_utf8(limb).unwrap(), 16).unwrap())
+        .collect();
+    F::from_repr(BigInteger256(limbs.try_into().unwrap())).unwrap()
+}
+
+/// Evaluates exported expressions, as an external verifier would
+struct Evaluator<'a> {
+    domain: D<F>,
+    pt: F,
+    constants: &'a Constants<F>,
+    cells: &'a HashMap<Variable, F>,
+    cache: HashMap<usize, F>,
+}
+
+impl<'a> Evaluator<'a> {
+    fn eval(&mut self, node: &Node) -> F {
+        match node {
+            Node::Literal { value } => parse_field(value),
+            Node::Alpha => self.constants.alpha,
+            Node::Beta => self.constants.beta,
+            Node::Gamma => self.constants.gamma,
+            Node::JointCombiner => self.constants.joint_combiner,
+            Node::EndoCoefficient => self.constants.endo_coefficient,
+            Node::Mds { row, col } => self.constants.mds[*row][*col],
+            Node::Cell { col, row } => {
+                self.cells[&Variable {
+                    col: *col,
+                    row: *row,
+                }]
+            }
+            Node::VanishesOnLast4Rows => eval_vanishes_on_last_4_rows(self.domain, self.pt),
+            Node::UnnormalizedLagrangeBasis { row } => {
+                self.domain.evaluate_vanishing_polynomial(self.pt)
+                    / (self.pt - self.domain.group_gen.pow(&[*row as u64]))
+            }
+            Node::Add { args } => args.iter().map(|x| self.eval(x)).sum(),
+            Node::Mul { args } => args.iter().map(|x| self.eval(x)).product(),
+            Node::Sub { left, right } => self.eval(left) - self.eval(right),
+            Node::Double { arg } => self.eval(arg).double(),
+            Node::Square { arg } => self.eval(arg).square(),
+            Node::Pow { base, exponent } => self.eval(base).pow(&[*exponent]),
+            Node::Cache { id, expr } => {
+                let x = self.eval(expr);
+                assert!(self.cache.insert(*id, x).is_none());
+                x
+            }
+            Node::Cached { id } => self.cache[id],
+        }
+    }
+}
+
+#[test]
+fn export_round_trips() {
+    let cs = chacha_constraint_system();
+    let export = ConstraintSystemExport::create(&cs);
+    assert_eq!(export.version, SCHEMA_VERSION);
+
+    let json = export.to_json();
+    assert_eq!(ConstraintSystemExport::from_json(&json), Ok(export.clone()));
+
+    let mut other = export;
+    other.version += 1;
+    assert!(ConstraintSystemExport::from_json(&other.to_json()).is_err());
+}
+
+#[test]
+fn export_contents() {
+    let cs = chacha_constraint_system();
+    let export = ConstraintSystemExport::create(&cs);
+    let (_, alphas) = constraints_expr(
+        cs.domain.d1,
+        true,
+        cs.poseidon_legacy8.is_some(),
+        cs.fixed_base_mull4.is_some(),
+        cs.lookup_parameters(),
+    );
+
+    assert_eq!(export.domain_size, cs.domain.d1.size);
+    assert_eq!(
+        export.field_modulus,
+        "0x40000000000000000000000000000000224698FC094CF91B992D30ED00000001"
+    );
+    // the circuit has no legacy Poseidon gate and no fixed base scalar multiplication gate
+    assert_eq!(export.gates.len(), 9);
+    assert!(export
+        .gates
+        .iter()
+        .all(|g| g.typ != GateType::PoseidonLegacy && g.typ != GateType::FixedBaseMul));
+    assert!(export
+        .gates
+        .iter()
+        .any(|g| g.typ == GateType::ChaChaFinal && g.constraints.len() == 9));
+
+    // the powers of alpha are contiguous, and registered for the gates, the permutation and the lookups
+    let arguments: Vec<_> = export.alphas.iter().map(|a| a.argument).collect();
+    assert_eq!(
+        arguments,
+        vec![
+            ArgumentType::Gate(GateType::Zero),
+            ArgumentType::Permutation,
+            ArgumentType::Lookup
+        ]
+    );
+    for (a, (argument, range)) in export.alphas.iter().zip(alphas.ranges()) {
+        assert_eq!(
+            (a.argument, a.start, a.start + a.powers),
+            (argument, range.start, range.end)
+        );
+    }
+    for w in export.alphas.windows(2) {
+        assert_eq!(w[0].start + w[0].powers, w[1].start);
+    }
+
+    let mut columns: Vec<_> =
+        linearization_columns::<F>(cs.poseidon_legacy8.is_some(), cs.lookup_parameters())
+            .into_iter()
+            .collect();
+    columns.sort();
+    assert_eq!(export.evaluated_columns, columns);
+    assert!(export.evaluated_columns.contains(&Column::Z));
+
+    let cols: Vec<_> = export
+        .linearization
+        .index_terms
+        .iter()
+        .map(|t| t.col)
+        .collect();
+    assert!(cols.windows(2).all(|w| w[0] < w[1]));
+
+    let lookup = export.lookup.expect("the ChaCha gates use lookups");
+    assert!(!lookup.kinds.is_empty());
+    assert!(lookup
+        .kinds_map
+        .iter()
+        .any(|e| e.gate == GateType::ChaCha0 && e.row == CurrOrNext::Curr));
+    assert!(lookup.kinds_map.iter().all(|e| e.kind < lookup.kinds.len()));
+}
+
+#[test]
+fn exported_expressions_evaluate_like_programs() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let cs = chacha_constraint_system();
+    let domain = cs.domain.d1;
+    let (expr, _) = constraints_expr(
+        domain,
+        true,
+        cs.poseidon_legacy8.is_some(),
+        cs.fixed_base_mull4.is_some(),
+        cs.lookup_parameters(),
+    );
+    let linearization = expr
+        .linearize(linearization_columns::<F>(
+            cs.poseidon_legacy8.is_some(),
+            cs.lookup_parameters(),
+        ))
+        .unwrap();
+    let export = ConstraintSystemExport::create(&cs);
+
+    let constants = Constants {
+        alpha: F::rand(rng),
+        beta: F::rand(rng),
+        gamma: F::rand(rng),
+        joint_combiner: F::rand(rng),
+        endo_coefficient: F::rand(rng),
+        mds: (0..3)
+            .map(|_| (0..3).map(|_| F::rand(rng)).collect())
+            .collect(),
+    };
+    let pt = F::rand(rng);
+    let mut cells = HashMap::new();
+    for e in std::iter::once(&expr).chain(std::iter::once(&linearization.constant_term)) {
+        for op in Program::lower(e).ops {
+            if let Op::Cell(v) = op {
+                cells.entry(v).or_insert_with(|| F::rand(rng));
+            }
+        }
+    }
+    for i in 0..COLUMNS {
+        for row in [CurrOrNext::Curr, CurrOrNext::Next] {
+            for col in [Column::Witness(i), Column::Coefficient(i)] {
+                cells
+                    .entry(Variable { col, row })
+                    .or_insert_with(|| F::rand(rng));
+            }
+        }
+    }
+    let cell = |v: &Variable| Ok(cells[v]);
+    let mut evaluate = |node: &Node| {
+        Evaluator {
+            domain,
+            pt,
+            constants: &constants,
+            cells: &cells,
+            cache: HashMap::new(),
+        }
+        .eval(node)
+    };
+
+    // the combined constraints, and the constant term of the linearization
+    for e in [&expr, &linearization.constant_term] {
+        let expected = Program::lower(e).evaluate_with(domain, pt, &constants, cell);
+        assert_eq!(Ok(vec![evaluate(&export_expr(e))]), expected);
+    }
+    assert_eq!(
+        export_expr(&linearization.constant_term),
+        export.linearization.constant_term
+    );
+
+    // the constraints of the gates
+    for gate in &export.gates {
+        let constraints = match gate.typ {
+            GateType::Poseidon => Poseidon::<F>::constraints(),
+            GateType::VarBaseMul => VarbaseMul::<F>::constraints(),
+            GateType::CompleteAdd => CompleteAdd::<F>::constraints(),
+            GateType::EndoMul => EndosclMul::<F>::constraints(),
+            GateType::EndoMulScalar => EndomulScalar::<F>::constraints(),
+            GateType::FixedBaseMul => FixedBaseMul::<F>::constraints(),
+            GateType::PoseidonLegacy => PoseidonLegacy::<F>::constraints(),
+            GateType::ChaCha0 => ChaCha0::<F>::constraints(),
+            GateType::ChaCha1 => ChaCha1::<F>::constraints(),
+            GateType::ChaCha2 => ChaCha2::<F>::constraints(),
+            GateType::ChaChaFinal => ChaChaFinal::<F>::constraints(),
+            typ => panic!("unexpected gate {:?}", typ),
+        };
+        let values: Vec<_> = gate.constraints.iter().map(&mut evaluate).collect();
+        assert_eq!(
+            Ok(values),
+            Program::lower_all(&constraints).evaluate_with(domain, pt, &constants, cell)
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use crate::circuits::{
+    argument::Argument,
+    expr::{
+        text::{parse_constraints, parse_file, print_constraints, SyntaxError, TextError},
+        Cache, Column, ConstantExpr, Expr, Op2,
+    },
+    gate::{CurrOrNext, GateType},
+    polynomials::{
+        chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFinal},
+        complete_add::CompleteAdd,
+        endomul_scalar::EndomulScalar,
+        endosclmul::EndosclMul,
+        fixed_base_mul::FixedBaseMul,
+        poseidon::Poseidon,
+        poseidon_legacy::PoseidonLegacy,
+        varbasemul::VarbaseMul,
+    },
+};
+use crate::linearization::constraints_expr;
+use ark_ff::{One, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use mina_curves::pasta::fp::Fp as F;
+
+type E = Expr<ConstantExpr<F>>;
+
+fn round_trip(constraints: &[E]) {
+    let text = print_constraints(constraints);
+    let parsed = parse_constraints::<F>(&text).unwrap();
+    assert_eq!(parsed, constraints, "{}", text);
+    // printing is deterministic
+    assert_eq!(print_constraints(&parsed), text);
+}
+
+#[test]
+fn text_round_trips_gate_constraints() {
+    round_trip(&Poseidon::<F>::constraints());
+    round_trip(&VarbaseMul::<F>::constraints());
+    round_trip(&CompleteAdd::<F>::constraints());
+    round_trip(&EndosclMul::<F>::constraints());
+    round_trip(&EndomulScalar::<F>::constraints());
+    round_trip(&FixedBaseMul::<F>::constraints());
+    round_trip(&PoseidonLegacy::<F>::constraints());
+    round_trip(&ChaCha0::<F>::constraints());
+    round_trip(&ChaCha1::<F>::constraints());
+    round_trip(&ChaCha2::<F>::constraints());
+    round_trip(&ChaChaFinal::<F>::constraints());
+
+    // a constraint with the powers of alpha
+    let domain = D::<F>::new(1 << 4).unwrap();
+    let (expr, _) = constraints_expr(domain, false, true, true, None);
+    let parsed = E::from_text(&expr.text_str()).unwrap();
+    assert_eq!(parsed, expr);
+}
+
+#[test]
+fn text_parses_constraints() {
+    let text = "
+        // a cached boolean check, and a cached subexpression with the id 3
+        let bit = w[0]^2 - w[0];
+        let x_3 = double(bit) * index[Poseidon];
+        x_3 + bit;
+        coeff[1] * w_next[14] + const(alpha^2 * mds[0][2]) - 0x10 * -1;
+        square(z_next - (aggreg - table)) * unnormalized_lagrange_basis(3) + vanishes_on_last_4_rows;
+    ";
+    let parsed = parse_constraints::<F>(text).unwrap();
+
+    let op = |op, x, y| E::BinOp(op, Box::new(x), Box::new(y));
+    let cell = |col, row| E::cell(col, row);
+    let mut cache = Cache::default();
+    let ids: Vec<_> = (0..5)
+        .map(|_| match cache.cache(E::zero()) {
+            E::Cache(id, _) => id,
+            _ => unreachable!(),
+        })
+        .collect();
+
+    // the name `bit` gets the id after the ones of the names `x_i`
+    let w0 = cell(Column::Witness(0), CurrOrNext::Curr);
+    let bit = E::Cache(
+        ids[4],
+        Box::new(op(Op2::Sub, E::Pow(Box::new(w0.clone()), 2), w0)),
+    );
+    let x3 = E::Cache(
+        ids[3],
+        Box::new(op(
+            Op2::Mul,
+            E::Double(Box::new(bit.clone())),
+            cell(Column::Index(GateType::Poseidon), CurrOrNext::Curr),
+        )),
+    );
+    let first = op(Op2::Add, x3, bit);
+
+    let constant = ConstantExpr::Mul(
+        Box::new(ConstantExpr::Pow(Box::new(ConstantExpr::Alpha), 2)),
+        Box::new(ConstantExpr::Mds { row: 0, col: 2 }),
+    );
+    let second = op(
+        Op2::Sub,
+        op(
+            Op2::Add,
+            op(
+                Op2::Mul,
+                cell(Column::Coefficient(1), CurrOrNext::Curr),
+                cell(Column::Witness(14), CurrOrNext::Next),
+            ),
+            E::Constant(constant),
+        ),
+        op(
+            Op2::Mul,
+            E::Constant(ConstantExpr::Literal(F::from(16u64))),
+            E::Constant(ConstantExpr::Literal(-F::one())),
+        ),
+    );
+
+    let lookup = op(
+        Op2::Sub,
+        cell(Column::LookupAggreg, CurrOrNext::Curr),
+        cell(Column::LookupTable, CurrOrNext::Curr),
+    );
+    let third = op(
+        Op2::Add,
+        op(
+            Op2::Mul,
+            E::Square(Box::new(op(
+                Op2::Sub,
+                cell(Column::Z, CurrOrNext::Next),
+                lookup,
+            ))),
+            E::UnnormalizedLagrangeBasis(3),
+        ),
+        E::VanishesOnLast4Rows,
+    );
+
+    assert_eq!(parsed, vec![first, second, third]);
+    round_trip(&parsed);
+}
+
+#[test]
+fn text_parse_errors() {
+    for (text, error) in [
+        ("w[0] +;", "line 1: expected an expression"),
+        ("const(w[0]);", "line 1: expected a constant"),
+        ("w[0]\n + y;", "line 2: unknown name y"),
+        ("index[Foo];", "line 1: unknown gate Foo"),
+        ("w[0]", "line 1: expected ';'"),
+        (
+            "let alpha = w[0];",
+            "line 1: the name alpha is already used",
+        ),
+        (
+            "let x_1 = w[0]; let x_01 = w[1];",
+            "line 1: the id of x_01 is already used",
+        ),
+        ("w[0] # 1;", "line 1: unexpected character '#'"),
+    ] {
+        assert_eq!(parse_constraints::<F>(text).unwrap_err().to_string(), error);
+    }
+    assert!(matches!(
+        parse_constraints::<F>("w[0] + 1\n;;"),
+        Err(TextError::Syntax {
+            line: 2,
+            error: SyntaxError::ExpectedExpression
+        })
+    ));
+    assert!(matches!(
+        E::from_text("w[0]; w[1];"),
+        Err(TextError::NotSingleConstraint(2))
+    ));
+    assert!(matches!(
+        parse_file::<F>("missing.txt"),
+        Err(TextError::Io { .. })
+    ));
+}
+
+#[test]
+fn text_parses_gate_file() {
+    // the constraints of the complete addition gate, printed and then commented by hand
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/tests/text/complete_add.txt"
+    );
+    let parsed = parse_file::<F>(path).unwrap();
+    assert_eq!(parsed, CompleteAdd::<F>::constraints());
+}
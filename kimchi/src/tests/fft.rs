@@ -0,0 +1,33 @@
+use crate::circuits::{domains::EvaluationDomains, fft::Twiddles};
+use ark_ff::UniformRand;
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Polynomial,
+    Radix2EvaluationDomain as D, UVPolynomial,
+};
+use mina_curves::pasta::fp::Fp;
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn twiddles_match_ark_fft() {
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+    let domains = EvaluationDomains::<Fp>::create(1 << 8).unwrap();
+    let twiddles = Twiddles::create(&domains);
+
+    // the domains of the circuit, and a domain that is not one of their subgroups
+    let other = D::<Fp>::new(1 << 12).unwrap();
+    for domain in [domains.d1, domains.d2, domains.d4, domains.d8, other] {
+        let size = domain.size as usize;
+        let evals: Vec<_> = (0..size).map(|_| Fp::rand(rng)).collect();
+        let evals = Evaluations::from_vec_and_domain(evals, domain);
+        let poly = twiddles.interpolate(evals.clone());
+        assert_eq!(poly, evals.interpolate_by_ref());
+        assert_eq!(twiddles.evaluate_over_domain(&poly, domain), evals);
+    }
+
+    // the coefficients of the large powers are folded onto the smaller ones
+    let poly = DensePolynomial::<Fp>::rand(2 * domains.d1.size as usize, rng);
+    let evals = twiddles.evaluate_over_domain(&poly, domains.d1);
+    for (i, x) in domains.d1.elements().enumerate() {
+        assert_eq!(evals.evals[i], poly.evaluate(&x));
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{
+    circuits::{
+        argument::ArgumentType,
+        constraints::{ConstraintSystem, LookupConstraintSystem, LookupError, SetupError},
+        domains::EvaluationDomains,
+        expr::Column,
+        gadgets::builder::CircuitBuilder,
+        gate::{CircuitGate, GateType, FIXED_BASE_TABLE_ID},
+        polynomials::{
+            chacha,
+            fixed_base_mul::{FixedBaseTable, SCALAR_BITS},
+        },
+        wires::Wire,
+    },
+    error::ProofError,
+    prover::ProverProof,
+    prover_index::testing::{new_index_for_test, new_index_for_test_with_lookups},
+    verifier::batch_verify,
+};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, Field, PrimeField, UniformRand, Zero};
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp as F,
+    fq::Fq,
+    pallas::{Affine as Other, PallasParameters},
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<F, SpongeParams>;
+
+const WINDOW_BITS: usize = 4;
+
+/// Lays out multiplications of the base of `table` by random scalars,
+/// and checks their results against the native multiplications.
+fn circuit(table: &FixedBaseTable<PallasParameters>) -> CircuitBuilder<F> {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let mut b = CircuitBuilder::new(oracle::pasta::fp_kimchi::params());
+
+    let mut bits = F::rand(rng).into_repr().to_bits_le();
+    bits[SCALAR_BITS..].iter_mut().for_each(|b| *b = false);
+    let scalars = [
+        F::from(1u64),
+        F::from(2u64).pow(&[SCALAR_BITS as u64]) - F::from(1u64),
+        F::from_repr(<F as PrimeField>::BigInt::from_bits_le(&bits)).unwrap(),
+    ];
+
+    for n in scalars {
+        let n_var = b.witness(n);
+        let res = b.fixed_base_mul(table, n_var);
+
+        let n = Fq::from_repr(<Fq as PrimeField>::BigInt::from_bits_le(
+            &n.into_repr().to_bits_le(),
+        ))
+        .unwrap();
+        let expected = table.base.mul(n).into_affine();
+        assert_eq!(b.point_value(res), (expected.x, expected.y));
+    }
+
+    b
+}
+
+#[test]
+fn fixed_base_mul_test() {
+    let base = Other::prime_subgroup_generator();
+    let table = FixedBaseTable::create(base, WINDOW_BITS);
+    assert_eq!(table.num_windows(), 64);
+
+    // create and verify a proof of the circuit
+    let built = circuit(&table).build();
+    let index = new_index_for_test_with_lookups(
+        built.gates,
+        built.public.len(),
+        vec![table.lookup_table()],
+    );
+    index.cs.verify(&built.witness, &built.public).unwrap();
+    assert!(index.cs.fixed_base_mull4.is_some());
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, built.witness, &index).unwrap();
+    let verifier_index = index.verifier_index();
+    assert!(verifier_index.fixed_base_mul_comm.is_some());
+    let batch: Vec<_> = vec![(&verifier_index, &proof)];
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &batch).unwrap();
+}
+
+#[test]
+fn fixed_base_mul_optional() {
+    // without fixed base multiplication gates, the selector is neither committed nor linearized
+    let mut b = CircuitBuilder::new(oracle::pasta::fp_kimchi::params());
+    let x = b.witness(F::from(2u64));
+    let y = b.mul(x, x);
+    b.mul(y, y);
+    let built = b.build();
+    let index = new_index_for_test(built.gates, built.public.len());
+    assert!(index.cs.fixed_base_mull4.is_none());
+    assert!(index.verifier_index().fixed_base_mul_comm.is_none());
+    assert!(index
+        .linearization
+        .index_terms
+        .iter()
+        .all(|(col, _)| *col != Column::Index(GateType::FixedBaseMul)));
+    assert!(index
+        .powers_of_alpha
+        .ranges()
+        .iter()
+        .all(|(argument, _)| *argument != ArgumentType::Gate(GateType::FixedBaseMul)));
+}
+
+#[test]
+fn fixed_base_mul_wrong_table() {
+    let base = Other::prime_subgroup_generator();
+    let table = FixedBaseTable::create(base, WINDOW_BITS);
+    let other_table = FixedBaseTable::create(base + base, WINDOW_BITS);
+
+    // the gates are satisfied, but the points are not in the table of the constraint system
+    let built = circuit(&table).build();
+    let index = new_index_for_test_with_lookups(
+        built.gates,
+        built.public.len(),
+        vec![other_table.lookup_table()],
+    );
+    index.cs.verify(&built.witness, &built.public).unwrap();
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let res = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, built.witness, &index);
+    assert!(matches!(res, Err(ProofError::ValueNotInTable)));
+}
+
+#[test]
+#[should_panic(expected = "[0] G is the point at infinity")]
+fn fixed_base_mul_zero() {
+    let base = Other::prime_subgroup_generator();
+    let table = FixedBaseTable::create(base, WINDOW_BITS);
+
+    let mut b = CircuitBuilder::new(oracle::pasta::fp_kimchi::params());
+    let n = b.witness(F::zero());
+    b.fixed_base_mul(&table, n);
+}
+
+#[test]
+fn fixed_base_mul_with_chacha() {
+    let base = Other::prime_subgroup_generator();
+    let table = FixedBaseTable::create(base, WINDOW_BITS);
+
+    // the ChaCha gates look up the XOR table, in addition to the table of the fixed base
+    let built = circuit(&table).build();
+    let mut gates = built.gates;
+    let mut witness = built.witness;
+    let first = gates.len();
+    gates.extend(
+        chacha::testing::chacha20_gates()
+            .into_iter()
+            .enumerate()
+            .map(|(i, typ)| CircuitGate {
+                typ,
+                coeffs: vec![],
+                wires: Wire::new(first + i),
+            }),
+    );
+    let s0 = (0..16).collect();
+    for row in chacha::testing::chacha20_rows::<F>(s0) {
+        for (col, x) in row.into_iter().enumerate() {
+            witness[col].push(x);
+        }
+    }
+
+    let index =
+        new_index_for_test_with_lookups(gates, built.public.len(), vec![table.lookup_table()]);
+    let lcs = index.cs.lookup_constraint_system.as_ref().unwrap();
+    // the columns of the two tables, padded to the joint size of the lookups, and the column of the ids
+    assert_eq!(lcs.lookup_table.len(), 5);
+    assert_eq!(
+        lcs.dummy_lookup_value[4],
+        F::from(FIXED_BASE_TABLE_ID as u64)
+    );
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &index).unwrap();
+    let verifier_index = index.verifier_index();
+    let batch: Vec<_> = vec![(&verifier_index, &proof)];
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &batch).unwrap();
+}
+
+#[test]
+fn fixed_base_mul_lookup_errors() {
+    let base = Other::prime_subgroup_generator();
+    let table = FixedBaseTable::create(base, WINDOW_BITS);
+    let gates = circuit(&table).build().gates;
+    let create = |tables| {
+        ConstraintSystem::create(gates.clone(), tables, oracle::pasta::fp_kimchi::params(), 0)
+            .map(|_| ())
+    };
+
+    // the table of the fixed base must be passed with the gates
+    assert_eq!(
+        create(vec![]),
+        Err(SetupError::Lookup(LookupError::MissingTable(
+            FIXED_BASE_TABLE_ID
+        )))
+    );
+
+    // and only once, as the gates cannot tell apart the tables of two bases
+    let other_table = FixedBaseTable::create(base + base, WINDOW_BITS);
+    assert_eq!(
+        create(vec![table.lookup_table(), other_table.lookup_table()]),
+        Err(SetupError::Lookup(LookupError::DuplicateTableId(
+            FIXED_BASE_TABLE_ID
+        )))
+    );
+
+    let domain = EvaluationDomains::<F>::create(gates.len()).unwrap();
+    let mut wide = table.lookup_table();
+    wide.data.push(wide.data[0].clone());
+    assert!(matches!(
+        LookupConstraintSystem::create(&gates, vec![wide], &domain),
+        Err(LookupError::TableTooWide(FIXED_BASE_TABLE_ID))
+    ));
+}
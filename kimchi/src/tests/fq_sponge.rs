@@ -0,0 +1,126 @@
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gadgets::{builder::CircuitBuilder, scalar::SplitScalar, sponge::FqSpongeGadget},
+};
+use ark_ec::{
+    short_weierstrass_jacobian::{GroupAffine, GroupProjective},
+    ProjectiveCurve, SWModelParameters,
+};
+use ark_ff::{BigInteger, Field, One, PrimeField, SquareRootField, UniformRand, Zero};
+use mina_curves::pasta::{fp::Fp, pallas::PallasParameters, vesta::VestaParameters};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    poseidon::ArithmeticSpongeParams,
+    sponge::{DefaultFqSponge, FqSponge},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Runs a random transcript through the gadget and through the native sponge,
+/// checks that they agree, and that the circuit is satisfied.
+fn check_random_transcript<P>(params: fn() -> ArithmeticSpongeParams<P::BaseField>, seed: u8)
+where
+    P: SWModelParameters,
+    P::BaseField: PrimeField + SquareRootField,
+    <P::BaseField as PrimeField>::BigInt: Into<<P::ScalarField as PrimeField>::BigInt>,
+{
+    let rng = &mut StdRng::from_seed([seed; 32]);
+    let to_scalar = |x: P::BaseField| {
+        P::ScalarField::from_repr(x.into_repr().into()).expect("the value fits in the scalar field")
+    };
+
+    let mut native = DefaultFqSponge::<P, PlonkSpongeConstantsKimchi>::new(params());
+    let mut b = CircuitBuilder::new(params());
+    let mut sponge = FqSpongeGadget::new(&mut b);
+
+    for _ in 0..30 {
+        match rng.gen_range(0..5) {
+            0 => {
+                let g: Vec<GroupAffine<P>> = (0..rng.gen_range(1..4))
+                    .map(|_| GroupProjective::<P>::rand(rng).into_affine())
+                    .collect();
+                native.absorb_g(&g);
+                let g: Vec<_> = g.iter().map(|g| b.witness_point(g)).collect();
+                sponge.absorb_g(&mut b, &g);
+            }
+            1 => {
+                let x = P::ScalarField::rand(rng);
+                native.absorb_fr(&[x]);
+                let x = b.split_scalar(x);
+                sponge.absorb_fr::<P::ScalarField>(&mut b, &[x]);
+            }
+            2 => {
+                let num_limbs = rng.gen_range(1..4);
+                let expected = native.squeeze_limbs(num_limbs);
+                let limbs = sponge.squeeze_limbs(&mut b, num_limbs);
+                let limbs: Vec<_> = limbs.iter().map(|l| b.value(*l)).collect();
+                let expected: Vec<_> = expected.into_iter().map(P::BaseField::from).collect();
+                assert_eq!(limbs, expected);
+            }
+            3 => {
+                let expected = native.challenge();
+                let chal = sponge.challenge(&mut b);
+                assert_eq!(to_scalar(b.value(chal)), expected);
+            }
+            _ => {
+                let expected = native.challenge_fq();
+                let chal = sponge.challenge_fq(&mut b);
+                assert_eq!(b.value(chal), expected);
+            }
+        }
+    }
+
+    let expected = native.digest();
+    let digest = sponge.digest(&mut b);
+    let bits = b.value(digest).into_repr().to_bits_le();
+    let digest =
+        P::ScalarField::from_repr(<P::ScalarField as PrimeField>::BigInt::from_bits_le(&bits));
+    assert_eq!(digest.unwrap_or_else(P::ScalarField::zero), expected);
+
+    let built = b.build();
+    let cs = ConstraintSystem::create(built.gates, vec![], params(), built.public.len()).unwrap();
+    cs.verify(&built.witness, &built.public).unwrap();
+}
+
+#[test]
+fn test_fq_sponge_gadget_vesta() {
+    // the scalars of Vesta fit in its base field
+    for seed in 0..4 {
+        check_random_transcript::<VestaParameters>(oracle::pasta::fq_kimchi::params, seed);
+    }
+}
+
+#[test]
+fn test_fq_sponge_gadget_pallas() {
+    // the scalars of Pallas are absorbed as their high bits and their low bit
+    for seed in 0..4 {
+        check_random_transcript::<PallasParameters>(oracle::pasta::fp_kimchi::params, seed);
+    }
+}
+
+#[test]
+fn test_split_scalar_is_canonical() {
+    let params = oracle::pasta::fp_kimchi::params;
+    let two_253 = Fp::from(2u64).pow(&[253]);
+    // the witness of the range checks can't be generated for values out of their range
+    let check = |hi: Fp, lo: Fp| {
+        std::panic::catch_unwind(|| {
+            let mut b = CircuitBuilder::new(params());
+            let split = SplitScalar {
+                hi: b.witness(hi),
+                lo: b.witness(lo),
+            };
+            b.check_split(split);
+            let built = b.build();
+            let cs = ConstraintSystem::create(built.gates, vec![], params(), built.public.len())
+                .unwrap();
+            cs.verify(&built.witness, &built.public).is_ok()
+        })
+        .unwrap_or(false)
+    };
+
+    // 2 * hi + lo is smaller than 2^254, so that the split is unique
+    assert!(check(two_253 - Fp::one(), Fp::one()));
+    assert!(!check(two_253, Fp::zero()));
+    assert!(!check(Fp::zero() - Fp::one(), Fp::zero()));
+    assert!(!check(Fp::zero(), Fp::from(2u64)));
+}
@@ -1,12 +1,17 @@
+use crate::batch_prover::{BatchConfig, BatchWitness};
 use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
 use crate::circuits::{gate::CircuitGate, wires::COLUMNS};
-use crate::prover::ProverProof;
+use crate::error::ProofError;
+use crate::prover::{CancellationToken, ProverConfig, ProverProof};
 use crate::prover_index::testing::new_index_for_test;
-use crate::verifier::batch_verify;
+use crate::verifier::{batch_verify, batch_verify_deferred};
 use ark_ff::{UniformRand, Zero};
 use ark_poly::{univariate::DensePolynomial, UVPolynomial};
 use array_init::array_init;
-use commitment_dlog::commitment::{b_poly_coefficients, ceil_log2, CommitmentCurve};
+use commitment_dlog::{
+    accumulator::Accumulator,
+    commitment::{b_poly_coefficients, ceil_log2, CommitmentCurve},
+};
 use groupmap::GroupMap;
 use mina_curves::pasta::{
     fp::Fp,
@@ -17,6 +22,7 @@ use oracle::{
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
 use rand::{rngs::StdRng, SeedableRng};
+use std::sync::{Arc, Mutex};
 
 // aliases
 
@@ -49,6 +55,195 @@ fn test_generic_gate_pub() {
     verify_proof(gates, witness, &public);
 }
 
+#[test]
+fn test_generic_gate_batch() {
+    let num_public = 5;
+    let gates = create_circuit(0, num_public);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let index = new_index_for_test(gates.clone(), num_public);
+
+    // create one witness per public input
+    let publics: Vec<Vec<Fp>> = (1u8..5).map(|i| vec![Fp::from(i); num_public]).collect();
+    let witnesses: Vec<BatchWitness<Affine>> = publics
+        .iter()
+        .map(|public| {
+            let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+            fill_in_witness(0, &mut witness, public);
+            witness.into()
+        })
+        .collect();
+
+    // only allow two proofs at a time
+    let config = BatchConfig {
+        memory_budget: Some(2 * crate::batch_prover::proof_memory_estimate(&index)),
+    };
+    assert_eq!(config.max_concurrent_proofs(&index), 2);
+
+    let proofs: Vec<_> = ProverProof::create_batch::<BaseSponge, ScalarSponge>(
+        &group_map, witnesses, &index, &config,
+    )
+    .into_iter()
+    .map(|proof| proof.unwrap())
+    .collect();
+
+    // the proofs are returned in the order of the witnesses
+    for (proof, public) in proofs.iter().zip(&publics) {
+        assert_eq!(&proof.public, public);
+    }
+
+    // verify the proofs
+    let verifier_index = index.verifier_index();
+    let batch: Vec<_> = proofs
+        .iter()
+        .map(|proof| (&verifier_index, proof))
+        .collect();
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &batch).unwrap();
+}
+
+#[test]
+fn test_generic_gate_config() {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let index = new_index_for_test(gates, 0);
+
+    // prove on a dedicated thread pool, and record the progress
+    let steps = Arc::new(Mutex::new(vec![]));
+    let config = ProverConfig {
+        thread_pool: Some(Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(2)
+                .build()
+                .unwrap(),
+        )),
+        progress: Some({
+            let steps = Arc::clone(&steps);
+            Arc::new(move |step| steps.lock().unwrap().push(step))
+        }),
+        cancellation: None,
+    };
+    let proof = ProverProof::create_recursive_with_config::<BaseSponge, ScalarSponge>(
+        &group_map,
+        witness.clone(),
+        &index,
+        vec![],
+        &config,
+    )
+    .unwrap();
+    assert_eq!(*steps.lock().unwrap(), (1..=45).collect::<Vec<_>>());
+
+    let verifier_index = index.verifier_index();
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &[(&verifier_index, &proof)])
+        .unwrap();
+
+    // cancel the proof in the middle of the protocol
+    let cancellation = CancellationToken::new();
+    let config = ProverConfig {
+        thread_pool: None,
+        progress: Some({
+            let cancellation = cancellation.clone();
+            Arc::new(move |step| {
+                if step == 10 {
+                    cancellation.cancel()
+                }
+            })
+        }),
+        cancellation: Some(cancellation),
+    };
+    let res = ProverProof::create_recursive_with_config::<BaseSponge, ScalarSponge>(
+        &group_map,
+        witness,
+        &index,
+        vec![],
+        &config,
+    );
+    assert!(matches!(res, Err(ProofError::Cancelled)));
+}
+
+#[test]
+fn test_generic_gate_deferred() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let index = new_index_for_test(gates, public.len());
+    let verifier_index = index.verifier_index();
+
+    // verify two proofs separately, deferring the expensive part of their verification
+    let mut accumulator = Accumulator::new();
+    for _ in 0..2 {
+        let proof =
+            ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.clone(), &index)
+                .unwrap();
+        let mut other = Accumulator::new();
+        batch_verify_deferred::<Affine, BaseSponge, ScalarSponge>(
+            &group_map,
+            &[(&verifier_index, &proof)],
+            &mut other,
+        )
+        .unwrap();
+        accumulator.merge(other);
+    }
+    assert_eq!(accumulator.len(), 2);
+
+    // the accumulator can be serialized before being checked
+    let serialized = rmp_serde::to_vec(&accumulator).unwrap();
+    let accumulator: Accumulator<Affine> = rmp_serde::from_read_ref(&serialized).unwrap();
+    assert!(accumulator.check(&index.srs, &mut rand::rngs::OsRng));
+}
+
+#[test]
+fn test_generic_gate_mixed_srs() {
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    // create two circuits of different sizes, with SRS of different lengths
+    let (indexes, proofs): (Vec<_>, Vec<_>) = [0, 40]
+        .iter()
+        .map(|&num_public| {
+            let public = vec![Fp::from(3u8); num_public];
+            let gates = create_circuit(0, num_public);
+            let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+            fill_in_witness(0, &mut witness, &public);
+
+            let index = new_index_for_test(gates, num_public);
+            let proof =
+                ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &index)
+                    .unwrap();
+            (index, proof)
+        })
+        .unzip();
+    assert!(indexes[0].srs.g.len() < indexes[1].srs.g.len());
+
+    // verify the proofs together
+    let verifier_indexes: Vec<_> = indexes.iter().map(|i| i.verifier_index()).collect();
+    let batch: Vec<_> = verifier_indexes.iter().zip(&proofs).collect();
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &batch).unwrap();
+
+    // the deferred checks can be performed with the largest SRS
+    let mut accumulator = Accumulator::new();
+    batch_verify_deferred::<Affine, BaseSponge, ScalarSponge>(&group_map, &batch, &mut accumulator)
+        .unwrap();
+    assert!(accumulator.check(&indexes[1].srs, &mut rand::rngs::OsRng));
+
+    // SRS that do not extend one another are rejected
+    let mut other_index = indexes[0].verifier_index();
+    let mut srs = (*other_index.srs).clone();
+    srs.g.swap(0, 1);
+    other_index.srs = Arc::new(srs);
+    let res = batch_verify::<Affine, BaseSponge, ScalarSponge>(
+        &group_map,
+        &[
+            (&other_index, &proofs[0]),
+            (&verifier_indexes[1], &proofs[1]),
+        ],
+    );
+    assert!(matches!(res, Err(ProofError::IncompatibleSrs)));
+}
+
 fn verify_proof(gates: Vec<CircuitGate<Fp>>, witness: [Vec<Fp>; COLUMNS], public: &[Fp]) {
     // set up
     let rng = &mut StdRng::from_seed([0u8; 32]);
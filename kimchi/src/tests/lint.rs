@@ -0,0 +1,240 @@
+use crate::circuits::{
+    gate::{CircuitGate, GateType},
+    lint::{lint, lint_witness, lookup_cells, Lint},
+    polynomials::{chacha, generic::GenericGateSpec},
+    wires::{Wire, COLUMNS, PERMUTS},
+};
+use ark_ff::Zero;
+use array_init::array_init;
+use mina_curves::pasta::fp::Fp;
+
+fn add() -> GenericGateSpec<Fp> {
+    GenericGateSpec::Add {
+        left_coeff: None,
+        right_coeff: None,
+        output_coeff: None,
+    }
+}
+
+/// A public input, added to itself, and hashed with Poseidon
+fn circuit() -> Vec<CircuitGate<Fp>> {
+    let mut gates = vec![
+        CircuitGate::create_generic_gadget(Wire::new(0), GenericGateSpec::Pub, None),
+        CircuitGate::create_generic_gadget(Wire::new(1), add(), None),
+    ];
+    let round_constants = &oracle::pasta::fp_kimchi::params().round_constants;
+    let (poseidon, output) =
+        CircuitGate::create_poseidon_gadget(2, [Wire::new(2), Wire::new(13)], round_constants);
+    gates.extend(poseidon);
+    assert_eq!(output, 13);
+    assert_eq!(gates[13].typ, GateType::Zero);
+
+    // the public input is the left and right operands of the addition,
+    // whose output is the first input of poseidon
+    gates[0].wires[0] = Wire { row: 1, col: 0 };
+    gates[1].wires[0] = Wire { row: 1, col: 1 };
+    gates[1].wires[1] = Wire { row: 0, col: 0 };
+    gates[1].wires[2] = Wire { row: 2, col: 0 };
+    gates[2].wires[0] = Wire { row: 1, col: 2 };
+    gates
+}
+
+#[test]
+fn lint_clean_circuit() {
+    assert_eq!(lint(&circuit(), 1), vec![]);
+}
+
+#[test]
+fn lint_unconstrained_cells() {
+    // the second halves of the generic gates are not used, but wired together
+    let mut gates = vec![
+        CircuitGate::<Fp>::create_generic_gadget(Wire::new(0), add(), None),
+        CircuitGate::create_generic_gadget(Wire::new(1), add(), None),
+    ];
+    gates[0].wires[3] = Wire { row: 1, col: 3 };
+    gates[1].wires[3] = Wire { row: 0, col: 3 };
+    let cells = vec![Wire { row: 0, col: 3 }, Wire { row: 1, col: 3 }];
+    assert_eq!(lint(&gates, 0), vec![Lint::UnconstrainedCells { cells }]);
+
+    // an addition in the second half reads them
+    gates[1] = CircuitGate::create_generic_gadget(gates[1].wires, add(), Some(add()));
+    assert_eq!(lint(&gates, 0), vec![]);
+
+    // so does the multiplication of the two first registers
+    let mul = GenericGateSpec::Mul {
+        output_coeff: Some(Fp::from(0u8)),
+        mul_coeff: None,
+    };
+    gates[1] = CircuitGate::create_generic_gadget(gates[1].wires, add(), Some(mul));
+    assert_eq!(lint(&gates, 0), vec![]);
+
+    // but not its output, whose coefficient is zero
+    gates[0].wires[4] = Wire { row: 1, col: 5 };
+    gates[1].wires[5] = Wire { row: 0, col: 4 };
+    assert_eq!(
+        lint(&gates, 0),
+        vec![Lint::UnconstrainedCells {
+            cells: vec![Wire { row: 0, col: 4 }, Wire { row: 1, col: 5 }]
+        }]
+    );
+}
+
+#[test]
+fn lint_unconnected_gates() {
+    // the public input is only wired to itself
+    let mut gates = circuit();
+    gates[0].wires[0] = Wire { row: 0, col: 0 };
+    gates[1].wires[0] = Wire { row: 1, col: 1 };
+    gates[1].wires[1] = Wire { row: 1, col: 0 };
+    assert_eq!(lint(&gates, 1), vec![Lint::UnconnectedPublic { row: 0 }]);
+
+    // the addition and poseidon are not wired to each other anymore,
+    // poseidon being checked as a whole with its output row
+    gates[1].wires[2] = Wire { row: 1, col: 2 };
+    gates[2].wires[0] = Wire { row: 2, col: 0 };
+    assert_eq!(
+        lint(&gates, 1),
+        vec![
+            Lint::UnconnectedPublic { row: 0 },
+            Lint::IdentityWiring { rows: 1..2 },
+            Lint::IdentityWiring { rows: 2..14 },
+        ]
+    );
+
+    // each ChaCha gate reads the next row, where its lookups also apply
+    let gates: Vec<_> = chacha::testing::chacha20_gates()
+        .into_iter()
+        .enumerate()
+        .map(|(i, typ)| CircuitGate::<Fp> {
+            typ,
+            coeffs: vec![],
+            wires: Wire::new(i),
+        })
+        .collect();
+    let blocks: Vec<_> = (0..gates.len())
+        .step_by(2)
+        .map(|row| Lint::IdentityWiring { rows: row..row + 2 })
+        .collect();
+    assert_eq!(lint(&gates, 0), blocks);
+}
+
+#[test]
+fn lint_lookup_cells() {
+    let mut gates: Vec<_> = chacha::testing::chacha20_gates()
+        .into_iter()
+        .enumerate()
+        .map(|(i, typ)| CircuitGate::<Fp> {
+            typ,
+            coeffs: vec![],
+            wires: Wire::new(i),
+        })
+        .collect();
+
+    // the lookups of a ChaCha gate read its row and the next one
+    let cells = lookup_cells(&gates);
+    assert!(cells.iter().any(|(row, _)| *row == 0));
+    assert!(cells.iter().any(|(row, _)| *row == 1));
+    assert!(cells.iter().all(|(row, _)| *row <= gates.len()));
+    assert!(lookup_cells(&circuit()).is_empty());
+
+    // wiring a cell read by the lookups of the first block of gates to one of the second block
+    // connects the blocks, without unconstrained cells
+    let wired = |row| {
+        let (row, col) = *cells
+            .iter()
+            .find(|(r, col)| *r == row && *col < PERMUTS)
+            .unwrap();
+        Wire { row, col }
+    };
+    let (first, second) = (wired(0), wired(2));
+    gates[first.row].wires[first.col] = second;
+    gates[second.row].wires[second.col] = first;
+    let blocks: Vec<_> = (4..gates.len())
+        .step_by(2)
+        .map(|row| Lint::IdentityWiring { rows: row..row + 2 })
+        .collect();
+    assert_eq!(lint(&gates, 0), blocks);
+}
+
+#[test]
+fn lint_zero_gates() {
+    // a zero gate, between the output of poseidon and a gate reading it
+    let mut gates = circuit();
+    gates.push(CircuitGate::zero(Wire::new(14)));
+    gates.push(CircuitGate::create_generic_gadget(
+        Wire::new(15),
+        add(),
+        None,
+    ));
+    gates.push(CircuitGate::zero(Wire::new(16)));
+    gates[13].wires[0] = Wire { row: 15, col: 0 };
+    gates[15].wires[0] = Wire { row: 13, col: 0 };
+    assert_eq!(lint(&gates, 1), vec![Lint::ZeroGate { row: 14 }]);
+}
+
+#[test]
+fn lint_unused_cells() {
+    let gates = circuit();
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    assert_eq!(lint_witness(&gates, 1, &witness), vec![]);
+
+    // the values of the cells read by the constraints, the lookups or the public inputs are not flagged
+    witness[0][0] = Fp::from(1u8);
+    witness[0][1] = Fp::from(1u8);
+    witness[1][2] = Fp::from(2u8);
+    assert_eq!(lint_witness(&gates, 1, &witness), vec![]);
+
+    // but those of the registers of the generic gate without coefficients,
+    // and of the columns of the poseidon output row that the last round does not read, are
+    witness[4][1] = Fp::from(3u8);
+    witness[10][1] = Fp::from(3u8);
+    witness[5][13] = Fp::from(3u8);
+    assert_eq!(
+        lint_witness(&gates, 1, &witness),
+        vec![
+            Lint::UnusedCell { row: 1, col: 4 },
+            Lint::UnusedCell { row: 1, col: 10 },
+            Lint::UnusedCell { row: 13, col: 5 },
+        ]
+    );
+
+    // unless they are wired to a cell that is read
+    let mut gates = gates;
+    gates[1].wires[4] = Wire { row: 1, col: 2 };
+    gates[1].wires[2] = Wire { row: 2, col: 0 };
+    gates[2].wires[0] = Wire { row: 1, col: 4 };
+    assert_eq!(
+        lint_witness(&gates, 1, &witness),
+        vec![
+            Lint::UnusedCell { row: 1, col: 10 },
+            Lint::UnusedCell { row: 13, col: 5 },
+        ]
+    );
+    assert_eq!(lint(&gates, 1), vec![]);
+}
+
+#[test]
+fn lint_messages() {
+    let lints = [
+        Lint::UnconstrainedCells {
+            cells: vec![Wire { row: 0, col: 3 }, Wire { row: 1, col: 3 }],
+        },
+        Lint::IdentityWiring { rows: 1..2 },
+        Lint::IdentityWiring { rows: 2..14 },
+        Lint::UnconnectedPublic { row: 0 },
+        Lint::ZeroGate { row: 14 },
+        Lint::UnusedCell { row: 1, col: 10 },
+    ];
+    let messages: Vec<_> = lints.iter().map(|lint| lint.to_string()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "the cells (0, 3), (1, 3) are wired together, but no constraint reads them",
+            "the gate of row 1 is not wired to any other gate",
+            "the gates of rows 2 to 13 are not wired to any other gate",
+            "the public input of row 0 is not wired to any gate",
+            "the zero gate of row 14 is in the middle of the circuit",
+            "the cell (1, 10) holds a value, but no constraint reads it",
+        ]
+    );
+}
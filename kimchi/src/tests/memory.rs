@@ -0,0 +1,142 @@
+use crate::{
+    circuits::gadgets::{
+        builder::CircuitBuilder,
+        memory::{sorted_trace, Access, Memory},
+    },
+    prover::ProverProof,
+    prover_index::testing::new_index_for_test,
+    verifier::batch_verify,
+};
+use ark_ff::{UniformRand, Zero};
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp as F,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    pasta::fp_kimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<F, SpongeParams>;
+
+const NUM_BITS: usize = 16;
+
+/// the trace of random reads and writes to a few addresses
+fn random_trace(rng: &mut StdRng, len: usize) -> Vec<Access<F>> {
+    let mut memory = Memory::new();
+    for _ in 0..len {
+        let addr = F::from(rng.gen_range(0..6u64));
+        if rng.gen() {
+            memory.write(addr, F::rand(rng));
+        } else {
+            memory.read(addr);
+        }
+    }
+    memory.trace
+}
+
+fn circuit(trace: &[Access<F>]) -> CircuitBuilder<F> {
+    let mut b = CircuitBuilder::new(fp_kimchi::params());
+    let trace: Vec<_> = trace.iter().map(|a| b.witness_access(a)).collect();
+    b.assert_memory_consistency(&trace, NUM_BITS);
+    b
+}
+
+fn satisfied(trace: &[Access<F>]) -> bool {
+    let built = circuit(trace).build();
+    let index = new_index_for_test(built.gates, built.public.len());
+    index.cs.verify(&built.witness, &built.public).is_ok()
+}
+
+#[test]
+fn memory_sorted_trace() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let trace = random_trace(rng, 20);
+    let sorted = sorted_trace(&trace);
+    assert_eq!(sorted.len(), trace.len());
+    for pair in sorted.windows(2) {
+        let key = |a: &Access<F>| (a.addr, a.timestamp);
+        assert!(key(&pair[0]) < key(&pair[1]));
+    }
+}
+
+#[test]
+fn memory_consistency() {
+    let rng = &mut StdRng::from_seed([1; 32]);
+    let trace = random_trace(rng, 30);
+    assert!(satisfied(&trace));
+
+    // the order of the trace does not matter
+    let mut shuffled = trace.clone();
+    shuffled.reverse();
+    assert!(satisfied(&shuffled));
+
+    // a read of a wrong value
+    let i = trace.iter().position(|a| !a.is_write).unwrap();
+    let mut bad = trace.clone();
+    bad[i].value += F::from(1u64);
+    assert!(!satisfied(&bad));
+
+    // a read of an address never written to is zero
+    let mut bad = trace.clone();
+    bad.push(Access {
+        addr: F::from(1000u64),
+        value: F::from(1u64),
+        timestamp: F::from(trace.len() as u64),
+        is_write: false,
+    });
+    assert!(!satisfied(&bad));
+    bad.last_mut().unwrap().value = F::zero();
+    assert!(satisfied(&bad));
+
+    // a read before the write it returns
+    let bad = vec![
+        Access {
+            addr: F::from(3u64),
+            value: F::from(7u64),
+            timestamp: F::from(0u64),
+            is_write: false,
+        },
+        Access {
+            addr: F::from(3u64),
+            value: F::from(7u64),
+            timestamp: F::from(1u64),
+            is_write: true,
+        },
+    ];
+    assert!(!satisfied(&bad));
+}
+
+#[test]
+fn memory_consistency_proof() {
+    let rng = &mut StdRng::from_seed([2; 32]);
+    let trace = random_trace(rng, 10);
+    let built = circuit(&trace).build();
+    let index = new_index_for_test(built.gates, built.public.len());
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, built.witness, &index).unwrap();
+    let verifier_index = index.verifier_index();
+    let batch: Vec<_> = vec![(&verifier_index, &proof)];
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &batch).unwrap();
+}
+
+#[test]
+fn memory_rows_per_access() {
+    // the layout of the gadget only depends on the length of the trace
+    let rng = &mut StdRng::from_seed([3; 32]);
+    let mut rows = |len| circuit(&random_trace(rng, len)).build().gates.len();
+    let (short, long) = (rows(16), rows(32));
+    assert_eq!((long - short) % 16, 0);
+
+    // of which the sponge absorbing the keys and the values of both traces takes two permutations of 12 rows
+    let per_access = (long - short) / 16;
+    assert!(per_access <= 50, "{} rows per access", per_access);
+}
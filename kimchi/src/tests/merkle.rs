@@ -0,0 +1,81 @@
+use crate::{
+    circuits::gadgets::builder::CircuitBuilder, prover::ProverProof,
+    prover_index::testing::new_index_for_test, verifier::batch_verify,
+};
+use ark_ff::{UniformRand, Zero};
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp as F,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    merkle::MerkleTree,
+    pasta::fp_kimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<F, SpongeParams>;
+
+const DEPTH: usize = 10;
+
+/// Lays out the membership of `leaf` under the public `root`, at position `index` of `tree`.
+fn circuit(tree: &MerkleTree<F>, root: F, leaf: F, index: usize) -> CircuitBuilder<F> {
+    let mut b = CircuitBuilder::new(fp_kimchi::params());
+    let root = b.public_input(root);
+    let leaf = b.witness(leaf);
+    let path = b.witness_merkle_path(&tree.path(index));
+    b.assert_merkle_membership(root, leaf, &path);
+    b
+}
+
+#[test]
+fn merkle_membership() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let leaves: Vec<F> = (0..100).map(|_| F::rand(rng)).collect();
+    let mut tree = MerkleTree::new(fp_kimchi::params(), DEPTH, leaves);
+    tree.update(517, F::rand(rng));
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    for index in [0, 42, 517, (1 << DEPTH) - 1] {
+        // the root computed in the circuit is the native one
+        let mut b = circuit(&tree, tree.root(), tree.leaf(index), index);
+        let leaf = b.witness(tree.leaf(index));
+        let path = b.witness_merkle_path(&tree.path(index));
+        let root = b.merkle_root(leaf, &path);
+        assert_eq!(b.value(root), tree.root());
+
+        // create and verify a proof of the membership
+        let built = b.build();
+        let index = new_index_for_test(built.gates, built.public.len());
+        index.cs.verify(&built.witness, &built.public).unwrap();
+
+        let proof =
+            ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, built.witness, &index)
+                .unwrap();
+        let verifier_index = index.verifier_index();
+        let batch: Vec<_> = vec![(&verifier_index, &proof)];
+        batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &batch).unwrap();
+    }
+}
+
+#[test]
+fn merkle_membership_wrong_leaf() {
+    let rng = &mut StdRng::from_seed([1; 32]);
+    let leaves: Vec<F> = (0..10).map(|_| F::rand(rng)).collect();
+    let tree = MerkleTree::new(fp_kimchi::params(), DEPTH, leaves);
+
+    // a missing leaf is zero
+    let built = circuit(&tree, tree.root(), F::zero(), 10).build();
+    let index = new_index_for_test(built.gates, built.public.len());
+    index.cs.verify(&built.witness, &built.public).unwrap();
+
+    // but not a leaf of the tree at another position
+    let built = circuit(&tree, tree.root(), tree.leaf(3), 4).build();
+    let index = new_index_for_test(built.gates, built.public.len());
+    assert!(index.cs.verify(&built.witness, &built.public).is_err());
+}
@@ -1,7 +1,23 @@
+mod arith;
 mod chacha;
+mod codegen;
+mod degree;
 mod ec;
 mod endomul;
 mod endomul_scalar;
+mod export;
+mod expr_text;
+mod fft;
+mod fixed_base_mul;
+mod fq_sponge;
 mod generic;
+mod lint;
+mod memory;
+mod merkle;
 mod poseidon;
+mod poseidon_legacy;
+mod program;
+mod recursion;
+mod schnorr;
+mod serialization;
 mod varbasemul;
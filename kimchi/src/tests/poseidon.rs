@@ -13,6 +13,7 @@ use mina_curves::pasta::{
 };
 use oracle::{
     constants::{PlonkSpongeConstantsKimchi, SpongeConstants},
+    poseidon::{ArithmeticSponge, Sponge},
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
 use rand::{rngs::StdRng, SeedableRng};
@@ -21,7 +22,7 @@ use crate::{
     circuits::{
         gate::CircuitGate,
         polynomials,
-        polynomials::poseidon::ROUNDS_PER_ROW,
+        polynomials::poseidon::{generate_hash_witness, hash_cells, ROUNDS_PER_ROW, SPONGE_RATE},
         wires::{Wire, COLUMNS},
     },
     prover_index::testing::new_index_for_test,
@@ -183,3 +184,61 @@ fn positive(index: &ProverIndex<Affine>) {
         }
     }
 }
+
+/// creates a circuit hashing `input`, and returns it with its witness and the hash
+fn hash_circuit(input: &[Fp]) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], Fp) {
+    let params = oracle::pasta::fp_kimchi::params();
+    let (gates, next_row) = CircuitGate::<Fp>::create_poseidon_hash_gadget(0, input.len(), &params);
+    assert_eq!(next_row, gates.len());
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); next_row]);
+    let hash = generate_hash_witness(0, params.clone(), &mut witness, input);
+
+    // the inputs and the output are in the expected cells
+    let (inputs, output) = hash_cells(0, input.len(), &params);
+    for (cell, x) in inputs.iter().zip(input) {
+        assert_eq!(witness[cell.col][cell.row], *x);
+    }
+    assert_eq!(witness[output.col][output.row], hash);
+
+    (gates, witness, hash)
+}
+
+#[test]
+fn test_poseidon_hash() {
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+
+    // the hash is the one of the sponge, for any input length
+    for input_len in 0..8 {
+        let input: Vec<Fp> = (0..input_len).map(|_| Fp::rand(rng)).collect();
+        let mut sponge =
+            ArithmeticSponge::<Fp, SpongeParams>::new(oracle::pasta::fp_kimchi::params());
+        sponge.absorb(&input);
+        let expected = sponge.squeeze();
+
+        let (gates, witness, hash) = hash_circuit(&input);
+        assert_eq!(hash, expected);
+        let index = new_index_for_test(gates, PUBLIC);
+        index.cs.verify(&witness, &[]).unwrap();
+
+        // the additions between the permutations are constrained
+        if input_len > SPONGE_RATE {
+            let mut witness = witness;
+            let (inputs, _) = hash_cells(0, input_len, &oracle::pasta::fp_kimchi::params());
+            let cell = inputs[SPONGE_RATE];
+            witness[cell.col][cell.row] += Fp::from(1u64);
+            assert!(index.cs.verify(&witness, &[]).is_err());
+        }
+    }
+
+    // create and verify a proof of a hash
+    let input: Vec<Fp> = (0..5).map(|_| Fp::rand(rng)).collect();
+    let (gates, witness, _) = hash_circuit(&input);
+    let index = new_index_for_test(gates, PUBLIC);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &index).unwrap();
+    let verifier_index = index.verifier_index();
+    let batch: Vec<_> = vec![(&verifier_index, &proof)];
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &batch).unwrap();
+}
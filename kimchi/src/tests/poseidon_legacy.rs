@@ -0,0 +1,177 @@
+use ark_ff::{UniformRand, Zero};
+use array_init::array_init;
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::{PlonkSpongeConstantsKimchi, PlonkSpongeConstantsLegacy},
+    pasta::fp_legacy,
+    poseidon::{poseidon_block_cipher, ArithmeticSponge, Sponge},
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    circuits::{
+        gadgets::{builder::CircuitBuilder, sponge::SpongeGadget},
+        gate::CircuitGate,
+        polynomials::poseidon_legacy::{generate_witness, OUTPUT_COLS, ROWS_PER_HASH},
+        wires::{Wire, COLUMNS},
+    },
+    error::ProofError,
+    prover::ProverProof,
+    prover_index::testing::new_index_for_test,
+    verifier::batch_verify,
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+/// creates the gates of a legacy permutation, and the witness of the permutation of `input`
+fn permutation_circuit(input: [Fp; 3]) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let params = fp_legacy::params();
+    let (gates, next_row) = CircuitGate::<Fp>::create_poseidon_legacy_gadget(
+        0,
+        [Wire::new(0), Wire::new(ROWS_PER_HASH)],
+        &params,
+    );
+    assert_eq!(next_row, ROWS_PER_HASH + 1);
+    assert_eq!(gates.len(), next_row);
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); next_row]);
+    generate_witness(0, &params, &mut witness, input);
+    (gates, witness)
+}
+
+#[test]
+fn test_poseidon_legacy() {
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+    let params = fp_legacy::params();
+
+    for _ in 0..4 {
+        let input = [Fp::rand(rng), Fp::rand(rng), Fp::rand(rng)];
+        let (gates, witness) = permutation_circuit(input);
+
+        // the first row holds the state after the initial addition of the round constants,
+        // and the last gate the output of the permutation
+        let mut state = input.to_vec();
+        poseidon_block_cipher::<Fp, PlonkSpongeConstantsLegacy>(&params, &mut state);
+        for i in 0..3 {
+            assert_eq!(witness[i][0], input[i] + params.round_constants[0][i]);
+        }
+        let output: Vec<_> = OUTPUT_COLS
+            .map(|col| witness[col][ROWS_PER_HASH - 1])
+            .collect();
+        assert_eq!(output, state);
+
+        let index = new_index_for_test(gates, 0);
+        index.cs.verify(&witness, &[]).unwrap();
+
+        // every round is constrained
+        for (row, col) in [(1, 0), (7, 4), (ROWS_PER_HASH - 1, 5)] {
+            let mut witness = witness.clone();
+            witness[col][row] += Fp::from(1u64);
+            assert!(index.cs.verify(&witness, &[]).is_err());
+        }
+    }
+
+    // create and verify a proof of a permutation
+    let input = [Fp::rand(rng), Fp::rand(rng), Fp::rand(rng)];
+    let (gates, witness) = permutation_circuit(input);
+    let index = new_index_for_test(gates, 0);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &index).unwrap();
+    let verifier_index = index.verifier_index();
+    let batch: Vec<_> = vec![(&verifier_index, &proof)];
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &batch).unwrap();
+}
+
+#[test]
+fn test_poseidon_legacy_sponge() {
+    let rng = &mut StdRng::from_seed([1u8; 32]);
+    let input: Vec<Fp> = (0..5).map(|_| Fp::rand(rng)).collect();
+
+    let mut sponge = ArithmeticSponge::<Fp, PlonkSpongeConstantsLegacy>::new(fp_legacy::params());
+    sponge.absorb(&input);
+    let expected = sponge.squeeze();
+
+    // the legacy sponge of the builder, mixed with the Poseidon gates of the circuit
+    let mut b = CircuitBuilder::new(oracle::pasta::fp_kimchi::params());
+    let mut gadget = SpongeGadget::new_legacy(&mut b, fp_legacy::params());
+    for x in &input {
+        let x = b.witness(*x);
+        gadget.absorb(&mut b, x);
+    }
+    let hash = gadget.squeeze(&mut b);
+    assert_eq!(b.value(hash), expected);
+    let zero = b.constant(Fp::zero());
+    b.poseidon([hash, zero, zero]);
+
+    let built = b.build();
+    let index = new_index_for_test(built.gates, built.public.len());
+    index.cs.verify(&built.witness, &built.public).unwrap();
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, built.witness, &index).unwrap();
+    let verifier_index = index.verifier_index();
+    let batch: Vec<_> = vec![(&verifier_index, &proof)];
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &batch).unwrap();
+}
+
+#[test]
+fn test_poseidon_legacy_optional() {
+    let rng = &mut StdRng::from_seed([2u8; 32]);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let prove = |gates, witness| {
+        let index = new_index_for_test(gates, 0);
+        let proof =
+            ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &index).unwrap();
+        (index, proof)
+    };
+
+    // without legacy Poseidon gates, the selector is neither committed nor evaluated
+    let mut b = CircuitBuilder::new(oracle::pasta::fp_kimchi::params());
+    let state = [(); 3].map(|_| b.witness(Fp::rand(rng)));
+    b.poseidon(state);
+    let built = b.build();
+    let (index, proof) = prove(built.gates, built.witness);
+    assert!(index.cs.poseidon_legacym.is_none());
+    let verifier_index = index.verifier_index();
+    assert!(verifier_index.poseidon_legacy_comm.is_none());
+    assert!(proof
+        .evals
+        .iter()
+        .all(|e| e.poseidon_legacy_selector.is_none()));
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &[(&verifier_index, &proof)])
+        .unwrap();
+
+    // and the evaluations of a proof must match the verifier index
+    let mut bad = proof.clone();
+    bad.evals
+        .iter_mut()
+        .for_each(|e| e.poseidon_legacy_selector = Some(vec![Fp::zero()]));
+    let res =
+        batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &[(&verifier_index, &bad)]);
+    assert!(matches!(res, Err(ProofError::UnexpectedEvaluations)));
+
+    // with legacy Poseidon gates, it is
+    let (gates, witness) = permutation_circuit([Fp::rand(rng), Fp::rand(rng), Fp::rand(rng)]);
+    let (index, proof) = prove(gates, witness);
+    assert!(index.cs.poseidon_legacym.is_some());
+    let verifier_index = index.verifier_index();
+    assert!(verifier_index.poseidon_legacy_comm.is_some());
+
+    let mut bad = proof.clone();
+    bad.evals
+        .iter_mut()
+        .for_each(|e| e.poseidon_legacy_selector = None);
+    let res =
+        batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &[(&verifier_index, &bad)]);
+    assert!(matches!(res, Err(ProofError::UnexpectedEvaluations)));
+}
@@ -0,0 +1,312 @@
+use crate::circuits::{
+    constraints::{ConstraintSystem, LookupConstraintSystem},
+    domains::EvaluationDomains,
+    expr::{
+        l0_1, prologue::*, Column, ConstantExpr, Constants, Environment, LookupEnvironment,
+        PolishToken, Variable,
+    },
+    gate::{CircuitGate, GateType},
+    polynomials::chacha,
+    program::{Op, Program},
+    scalars::{LookupEvaluations, ProofEvaluations},
+    wires::{Wire, COLUMNS},
+};
+use crate::linearization::{
+    argument_constraints, constraints_expr, constraints_programs, expr_linearization,
+    linearization_columns,
+};
+use ark_ff::{Field, One, UniformRand, Zero};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain as D};
+use array_init::array_init;
+use mina_curves::pasta::fp::Fp as F;
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::HashMap;
+
+fn random_constants(rng: &mut StdRng) -> Constants<F> {
+    Constants {
+        alpha: F::rand(rng),
+        beta: F::rand(rng),
+        gamma: F::rand(rng),
+        joint_combiner: F::rand(rng),
+        endo_coefficient: F::rand(rng),
+        mds: (0..3)
+            .map(|_| (0..3).map(|_| F::rand(rng)).collect())
+            .collect(),
+    }
+}
+
+fn random_evaluations(rng: &mut StdRng) -> ProofEvaluations<F> {
+    ProofEvaluations {
+        w: array_init(|_| F::rand(rng)),
+        z: F::rand(rng),
+        s: array_init(|_| F::rand(rng)),
+        lookup: Some(LookupEvaluations {
+            sorted: (0..5).map(|_| F::rand(rng)).collect(),
+            aggreg: F::rand(rng),
+            table: F::rand(rng),
+        }),
+        generic_selector: F::rand(rng),
+        poseidon_selector: F::rand(rng),
+        poseidon_legacy_selector: Some(F::rand(rng)),
+    }
+}
+
+#[test]
+fn optimized_program_evaluates_like_lowered_program() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let domain = D::<F>::new(1 << 10).unwrap();
+    let (expr, _) = constraints_expr(domain, true, true, true, None);
+
+    let lowered = Program::lower(&expr);
+    let optimized = lowered.optimize();
+    assert!(optimized.ops.len() < lowered.ops.len());
+    assert_eq!(optimized.degree(domain.size), expr.degree(domain.size));
+
+    let mut cells: HashMap<Variable, F> = HashMap::new();
+    for op in &lowered.ops {
+        if let Op::Cell(v) = op {
+            cells.entry(*v).or_insert_with(|| F::rand(rng));
+        }
+    }
+    let c = random_constants(rng);
+    for _ in 0..4 {
+        let pt = F::rand(rng);
+        let cell = |v: &Variable| Ok(cells[v]);
+        assert_eq!(
+            optimized.evaluate_with(domain, pt, &c, cell),
+            lowered.evaluate_with(domain, pt, &c, cell)
+        );
+    }
+}
+
+/// Checks that the compiled constraints of each argument evaluate like their expression,
+/// in an environment of random polynomials of degree smaller than the domain,
+/// and that the programs compiled per evaluation domain evaluate like their sum.
+fn check_evaluations(
+    rng: &mut StdRng,
+    domain: EvaluationDomains<F>,
+    lcs: &Option<LookupConstraintSystem<F>>,
+) {
+    let mut random = || {
+        let evals = (0..domain.d1.size()).map(|_| F::rand(rng)).collect();
+        Evaluations::from_vec_and_domain(evals, domain.d1)
+            .interpolate()
+            .evaluate_over_domain(domain.d8)
+    };
+
+    let lookup_parameters = lcs.as_ref().map(LookupConstraintSystem::parameters);
+    let witness = array_init(|_| random());
+    let coefficient = array_init(|_| random());
+    let vanishes_on_last_4_rows = random();
+    let z = random();
+    let gates = [
+        GateType::Generic,
+        GateType::Poseidon,
+        GateType::CompleteAdd,
+        GateType::VarBaseMul,
+        GateType::EndoMul,
+        GateType::EndoMulScalar,
+        GateType::ChaCha0,
+        GateType::ChaCha1,
+        GateType::ChaCha2,
+        GateType::ChaChaFinal,
+        GateType::FixedBaseMul,
+        GateType::PoseidonLegacy,
+    ];
+    let selectors: Vec<_> = gates.iter().map(|_| random()).collect();
+    let (sorted, aggreg, lookup_selectors, table) = match lcs {
+        None => (vec![], random(), vec![], random()),
+        Some(lcs) => (
+            (0..=lcs.max_lookups_per_row).map(|_| random()).collect(),
+            random(),
+            lcs.lookup_selectors.iter().map(|_| random()).collect(),
+            random(),
+        ),
+    };
+    let env = Environment {
+        constants: random_constants(rng),
+        witness: &witness,
+        coefficient: &coefficient,
+        vanishes_on_last_4_rows: &vanishes_on_last_4_rows,
+        z: &z,
+        l0_1: l0_1(domain.d1),
+        domain,
+        index: gates.into_iter().zip(&selectors).collect(),
+        lookup: lcs.as_ref().map(|_| LookupEnvironment {
+            sorted: &sorted,
+            aggreg: &aggreg,
+            selectors: &lookup_selectors,
+            table: &table,
+        }),
+    };
+
+    let lift = |e: Evaluations<F, D<F>>| e.interpolate().evaluate_over_domain(domain.d8);
+    let zero = || Evaluations::from_vec_and_domain(vec![F::zero(); domain.d8.size()], domain.d8);
+
+    let (exprs, _) = argument_constraints(domain.d1, true, true, true, lookup_parameters);
+    let mut sum = zero();
+    for e in &exprs {
+        let expected = e.evaluations(&env);
+        let evals = Program::compile(e).evaluations(&env);
+        assert_eq!(evals.domain(), expected.domain());
+        assert!(evals.evals == expected.evals);
+        sum += &lift(expected);
+    }
+
+    // the caches of the constraints of different gates have the same ids,
+    // which the programs tell apart, but not the evaluations of their sum as an expression
+    let mut programs_sum = zero();
+    for program in constraints_programs(domain.d1, true, true, true, lookup_parameters) {
+        programs_sum += &lift(program.evaluations(&env));
+    }
+    assert!(programs_sum.evals == sum.evals);
+}
+
+#[test]
+fn program_evaluations_match_expr_evaluations() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    check_evaluations(rng, EvaluationDomains::create(1 << 4).unwrap(), &None);
+
+    // with the lookups of chacha
+    let gates = chacha::testing::chacha20_gates()
+        .into_iter()
+        .enumerate()
+        .map(|(i, typ)| CircuitGate {
+            typ,
+            coeffs: vec![],
+            wires: Wire::new(i),
+        })
+        .collect();
+    let params = oracle::pasta::fp_kimchi::params();
+    let cs = ConstraintSystem::<F>::create(gates, vec![], params, 0).unwrap();
+    assert!(cs.lookup_constraint_system.is_some());
+    check_evaluations(rng, cs.domain, &cs.lookup_constraint_system);
+}
+
+#[test]
+fn compiled_linearization_evaluates_like_expr() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let domain = D::<F>::new(1 << 10).unwrap();
+    let (expr, _) = constraints_expr(domain, true, true, true, None);
+    let linearization = expr
+        .linearize(linearization_columns::<F>(true, None))
+        .unwrap();
+    let (compiled, _) = expr_linearization(domain, true, true, true, None);
+
+    let c = random_constants(rng);
+    let evals = [random_evaluations(rng), random_evaluations(rng)];
+    let pt = F::rand(rng);
+    // the index terms are matched by column, as their order is not deterministic
+    let terms = std::iter::once((None, &linearization.constant_term)).chain(
+        linearization
+            .index_terms
+            .iter()
+            .map(|(col, term)| (Some(*col), term)),
+    );
+    for (col, term) in terms {
+        let compiled_term = match col {
+            None => &compiled.constant_term,
+            Some(col) => {
+                &compiled
+                    .index_terms
+                    .iter()
+                    .find(|(c, _)| *c == col)
+                    .unwrap()
+                    .1
+            }
+        };
+        let expected = PolishToken::evaluate(&term.to_polish(), domain, pt, &evals, &c).unwrap();
+        assert_eq!(
+            PolishToken::evaluate(compiled_term, domain, pt, &evals, &c),
+            Ok(expected),
+            "{:?}",
+            col
+        );
+        assert_eq!(
+            Program::compile(term).evaluate(domain, pt, &evals, &c),
+            Ok(expected)
+        );
+    }
+    assert_eq!(compiled.index_terms.len(), linearization.index_terms.len());
+}
+
+#[test]
+fn fold_constants() {
+    let x = witness_curr::<F>(0);
+    let e = (x.clone() + E::zero()) * E::one()
+        + E::literal(F::from(2u64)) * E::literal(3u64.into())
+        - (x.clone() - x) * witness_next(1);
+    let program = Program::compile(&e);
+    assert_eq!(
+        program.ops,
+        vec![
+            Op::Cell(Variable {
+                col: Column::Witness(0),
+                row: crate::circuits::gate::CurrOrNext::Curr,
+            }),
+            Op::Literal(F::from(6u64)),
+            Op::Add(0, 1),
+        ]
+    );
+    assert_eq!(program.outputs, vec![2]);
+}
+
+#[test]
+fn factor_powers() {
+    let alpha = || E::<F>::Constant(ConstantExpr::Alpha);
+    let e = (0..COLUMNS).fold(E::zero(), |acc, i| {
+        acc + alpha().pow(i as u64 + 2) * witness_curr(i)
+    });
+    let program = Program::compile(&e);
+    // alpha^2 is a square, and the other powers are multiplications by alpha
+    let muls = program
+        .ops
+        .iter()
+        .filter(|op| matches!(op, Op::Mul(..)))
+        .count();
+    assert!(!program.ops.iter().any(|op| matches!(op, Op::Pow(..))));
+    assert_eq!(muls, 2 * COLUMNS);
+
+    // x^7 takes 4 multiplications
+    let seventh = Program::compile(&witness_curr::<F>(0).pow(7));
+    assert_eq!(seventh.ops.len(), 5);
+
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let domain = D::<F>::new(8).unwrap();
+    let c = random_constants(rng);
+    let w: [F; COLUMNS] = array_init(|_| F::rand(rng));
+    let evals = [
+        ProofEvaluations::dummy_with_witness_evaluations(w),
+        ProofEvaluations::dummy_with_witness_evaluations(w),
+    ];
+    let pt = F::rand(rng);
+    let expected = (0..COLUMNS).fold(F::zero(), |acc, i| {
+        acc + c.alpha.pow(&[i as u64 + 2]) * w[i]
+    });
+    assert_eq!(program.evaluate(domain, pt, &evals, &c), Ok(expected));
+}
+
+#[test]
+fn polish_stores_shared_registers() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let x = witness_curr::<F>(0) * witness_curr(1);
+    let e = x.clone() * x.clone() + x;
+    let program = Program::compile(&e);
+    let toks = program.to_polish();
+    assert_eq!(
+        toks.iter()
+            .filter(|t| matches!(t, PolishToken::Store))
+            .count(),
+        1
+    );
+
+    let domain = D::<F>::new(8).unwrap();
+    let c = random_constants(rng);
+    let evals = [random_evaluations(rng), random_evaluations(rng)];
+    let pt = F::rand(rng);
+    let xv = evals[0].w[0] * evals[0].w[1];
+    assert_eq!(
+        PolishToken::evaluate(&toks, domain, pt, &evals, &c),
+        Ok(xv * xv + xv)
+    );
+}
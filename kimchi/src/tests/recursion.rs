@@ -0,0 +1,275 @@
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gadgets::{
+        builder::CircuitBuilder,
+        scalar::{from_bits, ShiftedScalar},
+        verifier::DeferredValues,
+    },
+    polynomials::generic::testing::{create_circuit, fill_in_witness},
+    wires::COLUMNS,
+};
+use crate::plonk_sponge::FrSponge;
+use crate::prover::ProverProof;
+use crate::prover_index::ProverIndex;
+use crate::verifier_index::VerifierIndex;
+use ark_ec::{short_weierstrass_jacobian::GroupAffine, ModelParameters, SWModelParameters};
+use ark_ff::{BigInteger, Field, One, PrimeField, SquareRootField, UniformRand, Zero};
+use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+use array_init::array_init;
+use commitment_dlog::{
+    commitment::{b_poly_coefficients, ceil_log2, shift_scalar, CommitmentCurve},
+    srs::{endos, SRS},
+};
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp,
+    fq::Fq,
+    pallas::{self, PallasParameters},
+    vesta::{self, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    poseidon::ArithmeticSpongeParams,
+    sponge::{DefaultFqSponge, DefaultFrSponge, ScalarChallenge},
+    FqSponge,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::sync::Arc;
+
+// aliases
+
+type Fr<P> = <P as ModelParameters>::ScalarField;
+type Fq_<P> = <P as ModelParameters>::BaseField;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type VestaBaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type VestaScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+type PallasBaseSponge = DefaultFqSponge<PallasParameters, SpongeParams>;
+type PallasScalarSponge = DefaultFrSponge<Fq, SpongeParams>;
+
+/// Creates a proof over the curve `P` of a generic circuit with the public inputs `public` and a previous challenge.
+fn create_proof<P, EFqSponge, EFrSponge>(
+    public: Vec<Fr<P>>,
+    fr_sponge_params: ArithmeticSpongeParams<Fr<P>>,
+    fq_sponge_params: ArithmeticSpongeParams<Fq_<P>>,
+    endo_q: Fr<P>,
+) -> (VerifierIndex<GroupAffine<P>>, ProverProof<GroupAffine<P>>)
+where
+    P: SWModelParameters + Clone,
+    Fq_<P>: PrimeField,
+    EFqSponge: Clone + FqSponge<Fq_<P>, GroupAffine<P>, Fr<P>>,
+    EFrSponge: FrSponge<Fr<P>>,
+{
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+    let group_map = <GroupAffine<P> as CommitmentCurve>::Map::setup();
+
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fr<P>>; COLUMNS] = array_init(|_| vec![Fr::<P>::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let cs = ConstraintSystem::create(gates, vec![], fr_sponge_params, public.len()).unwrap();
+    let mut srs = SRS::<GroupAffine<P>>::create(cs.domain.d1.size as usize);
+    srs.add_lagrange_basis(cs.domain.d1);
+    let index = ProverIndex::create(cs, fq_sponge_params, endo_q, Arc::new(srs));
+
+    let prev = {
+        let k = ceil_log2(index.srs.g.len());
+        let chals: Vec<_> = (0..k).map(|_| Fr::<P>::rand(rng)).collect();
+        let coeffs = b_poly_coefficients(&chals);
+        let b = DensePolynomial::from_coefficients_vec(coeffs);
+        let comm = index.srs.commit_non_hiding(&b, None);
+        (chals, comm)
+    };
+    let proof = ProverProof::create_recursive::<EFqSponge, EFrSponge>(
+        &group_map,
+        witness,
+        &index,
+        vec![prev],
+    )
+    .unwrap();
+
+    (index.verifier_index(), proof)
+}
+
+fn create_vesta_proof(
+    public: Vec<Fp>,
+) -> (VerifierIndex<vesta::Affine>, ProverProof<vesta::Affine>) {
+    create_proof::<VestaParameters, VestaBaseSponge, VestaScalarSponge>(
+        public,
+        oracle::pasta::fp_kimchi::params(),
+        oracle::pasta::fq_kimchi::params(),
+        endos::<pallas::Affine>().0,
+    )
+}
+
+fn create_pallas_proof(
+    public: Vec<Fq>,
+) -> (VerifierIndex<pallas::Affine>, ProverProof<pallas::Affine>) {
+    create_proof::<PallasParameters, PallasBaseSponge, PallasScalarSponge>(
+        public,
+        oracle::pasta::fq_kimchi::params(),
+        oracle::pasta::fp_kimchi::params(),
+        endos::<vesta::Affine>().0,
+    )
+}
+
+/// Casts a value smaller than both moduli from one field to another.
+fn cast<F: PrimeField, S: PrimeField>(x: F) -> S {
+    from_bits(&x.into_repr().to_bits_le())
+}
+
+/// Checks the circuit laid out by `b` against its own witness.
+fn check_circuit<F: PrimeField + SquareRootField>(
+    b: CircuitBuilder<F>,
+    params: ArithmeticSpongeParams<F>,
+    endo: F,
+) -> Result<(), String> {
+    let built = b.build();
+    let mut cs =
+        ConstraintSystem::<F>::create(built.gates, vec![], params, built.public.len()).unwrap();
+    cs.endo = endo;
+    cs.verify(&built.witness, &built.public)
+        .map_err(|e| format!("{:?}", e))
+}
+
+/// Lays out the verification of a proof over the curve `P`,
+/// checks that the outputs of the circuit are the values computed by the verifier,
+/// and that the circuit is satisfied.
+fn check_verifier_gadget<P, EFqSponge, EFrSponge>(
+    index: &VerifierIndex<GroupAffine<P>>,
+    proof: &ProverProof<GroupAffine<P>>,
+) where
+    P: SWModelParameters + Clone,
+    Fq_<P>: PrimeField + SquareRootField,
+    EFqSponge: Clone + FqSponge<Fq_<P>, GroupAffine<P>, Fr<P>>,
+    EFrSponge: FrSponge<Fr<P>>,
+{
+    let group_map = <GroupAffine<P> as CommitmentCurve>::Map::setup();
+    let params = index.fq_sponge_params.clone();
+
+    let mut b = CircuitBuilder::new(params.clone());
+    let vars = b.verify_proof::<P, EFqSponge, EFrSponge>(&group_map, index, proof);
+
+    // the outputs of the circuit are the values computed by the verifier
+    let deferred = DeferredValues::new::<EFqSponge, EFrSponge>(index, proof);
+    let endo_r = index.srs.endo_r;
+    let scalar = |b: &CircuitBuilder<Fq_<P>>, v| cast::<_, Fr<P>>(b.value(v));
+    let chal = |b: &CircuitBuilder<Fq_<P>>, v| ScalarChallenge(scalar(b, v)).to_field(&endo_r);
+    assert_eq!(scalar(&b, vars.beta), deferred.oracles.beta);
+    assert_eq!(scalar(&b, vars.gamma), deferred.oracles.gamma);
+    assert_eq!(chal(&b, vars.alpha_chal), deferred.oracles.alpha);
+    assert_eq!(chal(&b, vars.zeta_chal), deferred.oracles.zeta);
+    assert_eq!(chal(&b, vars.v_chal), deferred.oracles.v);
+    let two_128 = Fr::<P>::from(2u64).pow(&[128]);
+    let (hi, lo) = vars.digest;
+    assert_eq!(scalar(&b, hi) * two_128 + scalar(&b, lo), deferred.digest);
+    for (v, c) in vars.challenges.iter().zip(&deferred.challenges) {
+        assert_eq!(chal(&b, *v), *c);
+    }
+    assert_eq!(b.point_value(vars.sg), (proof.proof.sg.x, proof.proof.sg.y));
+
+    // the hi/lo representation of the scalars is the one of scale_shifted
+    let shifted = |b: &CircuitBuilder<Fq_<P>>, s: ShiftedScalar| {
+        let t = scalar(b, s.hi).double() + scalar(b, s.lo);
+        Fr::<P>::from(2u64).pow(&[255]) + Fr::<P>::one() + t.double()
+    };
+    assert_eq!(shifted(&b, vars.b0), deferred.b0);
+    assert_eq!(
+        shifted(&b, vars.combined_inner_product),
+        deferred.combined_inner_product
+    );
+    for (s, x) in vars.public.iter().zip(&proof.public) {
+        assert_eq!(shifted(&b, *s), -*x);
+    }
+
+    // the shifted combined inner product absorbed by the sponge is the one of the verifier
+    let split = vars.shifted_combined_inner_product;
+    assert_eq!(
+        scalar(&b, split.hi).double() + scalar(&b, split.lo),
+        shift_scalar::<GroupAffine<P>>(deferred.combined_inner_product)
+    );
+
+    check_circuit(b, params, index.srs.endo_q).unwrap();
+}
+
+/// Lays out the verification of a proof over the curve `P`, and returns whether the circuit is satisfied.
+fn verifier_gadget_accepts<P, EFqSponge, EFrSponge>(
+    index: &VerifierIndex<GroupAffine<P>>,
+    proof: &ProverProof<GroupAffine<P>>,
+) -> bool
+where
+    P: SWModelParameters + Clone,
+    Fq_<P>: PrimeField + SquareRootField,
+    EFqSponge: Clone + FqSponge<Fq_<P>, GroupAffine<P>, Fr<P>>,
+    EFrSponge: FrSponge<Fr<P>>,
+{
+    let group_map = <GroupAffine<P> as CommitmentCurve>::Map::setup();
+    let params = index.fq_sponge_params.clone();
+    let mut b = CircuitBuilder::new(params.clone());
+    b.verify_proof::<P, EFqSponge, EFrSponge>(&group_map, index, proof);
+    check_circuit(b, params, index.srs.endo_q).is_ok()
+}
+
+#[test]
+fn test_verifier_gadget() {
+    // the scalars of Vesta fit in the base field of the circuit
+    let (index, proof) = create_vesta_proof(vec![Fp::from(3u8); 5]);
+    check_verifier_gadget::<VestaParameters, VestaBaseSponge, VestaScalarSponge>(&index, &proof);
+}
+
+#[test]
+fn test_verifier_gadget_pallas() {
+    // the scalars of Pallas do not fit in the base field of the circuit,
+    // so that the sponge absorbs them split in two
+    let (index, proof) = create_pallas_proof(vec![Fq::from(3u8); 5]);
+    check_verifier_gadget::<PallasParameters, PallasBaseSponge, PallasScalarSponge>(&index, &proof);
+}
+
+#[test]
+fn test_verifier_gadget_zero_public_inputs() {
+    // the terms of the zero scalars are multiplied, and then skipped
+    let public = vec![
+        Fp::zero(),
+        Fp::from(3u8),
+        Fp::zero(),
+        Fp::zero(),
+        Fp::from(7u8),
+    ];
+    let (index, proof) = create_vesta_proof(public);
+    check_verifier_gadget::<VestaParameters, VestaBaseSponge, VestaScalarSponge>(&index, &proof);
+
+    // and the layout of the circuit does not depend on the scalars
+    let group_map = <vesta::Affine as CommitmentCurve>::Map::setup();
+    let gates = |proof: &ProverProof<vesta::Affine>| {
+        let mut b = CircuitBuilder::new(index.fq_sponge_params.clone());
+        b.verify_proof::<VestaParameters, VestaBaseSponge, VestaScalarSponge>(
+            &group_map, &index, proof,
+        );
+        let gates = b.build().gates;
+        gates
+            .into_iter()
+            .map(|g| (g.typ, g.wires, g.coeffs))
+            .collect::<Vec<_>>()
+    };
+    let (_, other) = create_vesta_proof(vec![Fp::from(3u8); 5]);
+    assert_eq!(gates(&proof), gates(&other));
+}
+
+#[test]
+fn test_verifier_gadget_bad_proof() {
+    let (index, mut proof) = create_vesta_proof(vec![Fp::from(3u8); 5]);
+    proof.proof.z1 += Fp::one();
+    assert!(!verifier_gadget_accepts::<
+        VestaParameters,
+        VestaBaseSponge,
+        VestaScalarSponge,
+    >(&index, &proof));
+
+    let (index, mut proof) = create_pallas_proof(vec![Fq::from(3u8); 5]);
+    proof.proof.z1 += Fq::one();
+    assert!(!verifier_gadget_accepts::<
+        PallasParameters,
+        PallasBaseSponge,
+        PallasScalarSponge,
+    >(&index, &proof));
+}
@@ -0,0 +1,129 @@
+use crate::{
+    circuits::gadgets::{builder::CircuitBuilder, sponge::SpongeGadget},
+    prover::ProverProof,
+    prover_index::testing::new_index_for_test,
+    verifier::batch_verify,
+};
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp as F,
+    vesta::{Affine, VestaParameters},
+};
+use mina_signer::{
+    BaseField, Hashable, Keypair, NetworkId, PubKey, ROInput, Signable, Signature, Signer,
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    pasta::{fp_kimchi, fp_legacy},
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<F, SpongeParams>;
+
+const NETWORK_ID: NetworkId = NetworkId::TESTNET;
+
+/// A message with both field elements and bits.
+#[derive(Clone)]
+struct Message {
+    account: BaseField,
+    amount: u64,
+    flag: bool,
+}
+
+impl Hashable for Message {
+    fn to_roinput(self) -> ROInput {
+        let mut roi = ROInput::new();
+        roi.append_field(self.account);
+        roi.append_u64(self.amount);
+        roi.append_bit(self.flag);
+        roi
+    }
+}
+
+impl Signable for Message {
+    fn domain_string(network_id: NetworkId) -> &'static str {
+        match network_id {
+            NetworkId::MAINNET => "TestSigMainnet",
+            NetworkId::TESTNET => "TestSigTestnet",
+        }
+    }
+}
+
+/// Lays out the verification of `sig`, with the legacy or the kimchi parameters.
+fn circuit(legacy: bool, sig: &Signature, pk: &PubKey, message: &Message) -> CircuitBuilder<F> {
+    let mut b = CircuitBuilder::new(fp_kimchi::params());
+    let sig = b.witness_signature(sig);
+    let pk = b.witness_public_key(pk);
+    let message = b.witness_message(&message.clone().to_roinput());
+    let sponge = if legacy {
+        SpongeGadget::new_legacy(&mut b, fp_legacy::params())
+    } else {
+        SpongeGadget::new(&mut b)
+    };
+    b.verify_signature(
+        sponge,
+        Message::domain_string(NETWORK_ID),
+        pk,
+        sig,
+        &message,
+    );
+    b
+}
+
+fn check_signature(legacy: bool) {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let kp = Keypair::rand(rng);
+    let message = Message {
+        account: BaseField::from(42u64),
+        amount: 1_000_000,
+        flag: true,
+    };
+
+    let sig = if legacy {
+        let mut ctx = mina_signer::create(NETWORK_ID);
+        let sig = ctx.sign(kp, message.clone());
+        assert!(ctx.verify(sig, kp.public, message.clone()));
+        sig
+    } else {
+        let mut ctx =
+            mina_signer::custom::<PlonkSpongeConstantsKimchi>(fp_kimchi::params(), NETWORK_ID);
+        let sig = ctx.sign(kp, message.clone());
+        assert!(ctx.verify(sig, kp.public, message.clone()));
+        sig
+    };
+
+    // create and verify a proof of the verification of the signature
+    let built = circuit(legacy, &sig, &kp.public, &message).build();
+    let index = new_index_for_test(built.gates, built.public.len());
+    index.cs.verify(&built.witness, &built.public).unwrap();
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, built.witness, &index).unwrap();
+    let verifier_index = index.verifier_index();
+    let batch: Vec<_> = vec![(&verifier_index, &proof)];
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &batch).unwrap();
+
+    // the signature is not valid for another message
+    let other = Message {
+        amount: message.amount + 1,
+        ..message
+    };
+    let built = circuit(legacy, &sig, &kp.public, &other).build();
+    let index = new_index_for_test(built.gates, built.public.len());
+    assert!(index.cs.verify(&built.witness, &built.public).is_err());
+}
+
+#[test]
+fn schnorr_legacy() {
+    check_signature(true);
+}
+
+#[test]
+fn schnorr_kimchi() {
+    check_signature(false);
+}
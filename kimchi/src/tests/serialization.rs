@@ -0,0 +1,211 @@
+use crate::alphas::Alphas;
+use crate::circuits::{
+    argument::ArgumentType,
+    expr::PolishToken,
+    gate::{CircuitGate, GateType},
+    polynomials::{
+        chacha,
+        generic::testing::{create_circuit, fill_in_witness},
+    },
+    wires::{Wire, COLUMNS},
+};
+use crate::linearization::{expr_linearization, LinearizationError};
+use crate::prover::ProverProof;
+use crate::prover_index::testing::new_index_for_test;
+use crate::verifier::batch_verify;
+use crate::verifier_index::VerifierIndex;
+use ark_ff::Zero;
+use array_init::array_init;
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use std::{fs::File, sync::Arc};
+
+// aliases
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+#[test]
+fn deserialized_verifier_index_verifies() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let index = new_index_for_test(gates, public.len());
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &index).unwrap();
+
+    // write the verifier index to a file, and read it back
+    let path = std::env::temp_dir().join(format!("kimchi_verifier_index_{}", std::process::id()));
+    File::create(&path).unwrap();
+    index.verifier_index().to_file(&path, Some(true)).unwrap();
+    let verifier_index = VerifierIndex::<Affine>::from_file(
+        Arc::clone(&index.srs),
+        &path,
+        None,
+        index.cs.endo,
+        index.fq_sponge_params.clone(),
+        index.cs.fr_sponge_params.clone(),
+    )
+    .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // the linearization and the powers of alpha are read from the file
+    assert_eq!(verifier_index.check_linearization(), Ok(()));
+    assert_eq!(
+        verifier_index.powers_of_alpha.ranges(),
+        index.powers_of_alpha.ranges()
+    );
+    batch_verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &[(&verifier_index, &proof)])
+        .unwrap();
+}
+
+#[test]
+fn deserialized_verifier_index_checks_linearization() {
+    let index = new_index_for_test(create_circuit(0, 0), 0);
+    let mut verifier_index = index.verifier_index();
+    verifier_index
+        .linearization
+        .constant_term
+        .push(PolishToken::Alpha);
+    verifier_index
+        .linearization
+        .constant_term
+        .push(PolishToken::Add);
+
+    let path = std::env::temp_dir().join(format!(
+        "kimchi_other_verifier_index_{}",
+        std::process::id()
+    ));
+    File::create(&path).unwrap();
+    verifier_index.to_file(&path, Some(true)).unwrap();
+    let res = VerifierIndex::<Affine>::from_file(
+        Arc::clone(&index.srs),
+        &path,
+        None,
+        index.cs.endo,
+        index.fq_sponge_params.clone(),
+        index.cs.fr_sponge_params.clone(),
+    );
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        res.err(),
+        Some(LinearizationError::ConstantTerm.to_string())
+    );
+}
+
+#[test]
+fn linearization_is_deterministic() {
+    let index = new_index_for_test(create_circuit(0, 0), 0);
+    let (linearization, _) = expr_linearization(
+        index.cs.domain.d1,
+        true,
+        true,
+        true,
+        index.cs.lookup_parameters(),
+    );
+    for _ in 0..4 {
+        let (other, _) = expr_linearization(
+            index.cs.domain.d1,
+            true,
+            true,
+            true,
+            index.cs.lookup_parameters(),
+        );
+        assert_eq!(other.constant_term, linearization.constant_term);
+        assert_eq!(other.index_terms, linearization.index_terms);
+    }
+    let columns: Vec<_> = linearization.index_terms.iter().map(|(c, _)| *c).collect();
+    assert!(columns.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn linearization_mismatches() {
+    let index = new_index_for_test(create_circuit(0, 0), 0);
+    assert_eq!(index.check_linearization(), Ok(()));
+    let verifier_index = index.verifier_index();
+    assert_eq!(verifier_index.check_linearization(), Ok(()));
+
+    let mut other = index.verifier_index();
+    other.linearization.constant_term.push(PolishToken::Alpha);
+    other.linearization.constant_term.push(PolishToken::Add);
+    assert_eq!(
+        other.check_linearization(),
+        Err(LinearizationError::ConstantTerm)
+    );
+
+    let mut other = index.verifier_index();
+    let (col, _) = other.linearization.index_terms.pop().unwrap();
+    assert_eq!(
+        other.check_linearization(),
+        Err(LinearizationError::IndexColumns)
+    );
+    let mut other = index.verifier_index();
+    let term = other
+        .linearization
+        .index_terms
+        .iter_mut()
+        .find(|(c, _)| *c == col)
+        .unwrap();
+    term.1.push(PolishToken::Beta);
+    term.1.push(PolishToken::Mul);
+    assert_eq!(
+        other.check_linearization(),
+        Err(LinearizationError::IndexTerm(col))
+    );
+
+    let mut other = index.verifier_index();
+    other.powers_of_alpha = Alphas::default();
+    other
+        .powers_of_alpha
+        .register(ArgumentType::Gate(GateType::Zero), 1);
+    assert_eq!(
+        other.check_linearization(),
+        Err(LinearizationError::PowersOfAlpha)
+    );
+}
+
+#[test]
+fn lookup_linearization() {
+    let gates: Vec<_> = chacha::testing::chacha20_gates()
+        .into_iter()
+        .enumerate()
+        .map(|(i, typ)| CircuitGate::<Fp> {
+            typ,
+            coeffs: vec![],
+            wires: Wire::new(i),
+        })
+        .collect();
+    let index = new_index_for_test(gates, 0);
+    assert_eq!(index.check_linearization(), Ok(()));
+    assert!(index
+        .powers_of_alpha
+        .ranges()
+        .iter()
+        .any(|(argument, _)| *argument == ArgumentType::Lookup));
+    assert_eq!(index.verifier_index().check_linearization(), Ok(()));
+
+    // the constant term depends on the dummy lookup value
+    let mut other = index.verifier_index();
+    other.lookup_index.as_mut().unwrap().dummy_lookup_value[0] += Fp::from(1u8);
+    assert_eq!(
+        other.check_linearization(),
+        Err(LinearizationError::ConstantTerm)
+    );
+
+    // the mapping of the powers of alpha can be serialized in JSON
+    let json = serde_json::to_string(&index.powers_of_alpha).unwrap();
+    let powers_of_alpha: Alphas<Fp> = serde_json::from_str(&json).unwrap();
+    assert_eq!(powers_of_alpha.ranges(), index.powers_of_alpha.ranges());
+}
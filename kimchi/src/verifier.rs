@@ -8,7 +8,7 @@ use crate::{
         expr::{Column, Constants, PolishToken},
         gate::{GateType, LookupsUsed},
         polynomials::{generic, permutation},
-        scalars::RandomOracles,
+        scalars::{ProofEvaluations, RandomOracles},
         wires::*,
     },
     error::{ProofError, Result},
@@ -19,11 +19,16 @@ use crate::{
 use ark_ec::AffineCurve;
 use ark_ff::{Field, One, PrimeField, Zero};
 use ark_poly::{EvaluationDomain, Polynomial};
-use commitment_dlog::commitment::{
-    b_poly, b_poly_coefficients, BatchEvaluationProof, CommitmentCurve, Evaluation, PolyComm,
+use commitment_dlog::{
+    accumulator::Accumulator,
+    commitment::{
+        b_poly, b_poly_coefficients, BatchEvaluationProof, CommitmentCurve, Evaluation, PolyComm,
+    },
 };
+use o1_utils::profiling;
 use oracle::{sponge::ScalarChallenge, FqSponge};
 use rand::thread_rng;
+use rayon::prelude::*;
 
 type Fr<G> = <G as AffineCurve>::ScalarField;
 type Fq<G> = <G as AffineCurve>::BaseField;
@@ -110,6 +115,8 @@ where
         index: &VerifierIndex<G>,
         p_comm: &PolyComm<G>,
     ) -> OraclesResult<G, EFqSponge> {
+        let _span = profiling::span("verifier.oracles");
+
         let n = index.domain.size;
 
         // Run random oracle argument to sample verifier oracles
@@ -342,7 +349,7 @@ where
     }
 }
 
-fn to_batch<'a, G, EFqSponge, EFrSponge>(
+pub(crate) fn to_batch<'a, G, EFqSponge, EFrSponge>(
     index: &VerifierIndex<G>,
     proof: &'a ProverProof<G>,
 ) -> BatchEvaluationProof<'a, G, EFqSponge>
@@ -352,7 +359,10 @@ where
     EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>,
     EFrSponge: FrSponge<Fr<G>>,
 {
+    let _span = profiling::span("verifier.to_batch");
+
     // commit to public input polynomial
+    let public_span = profiling::span("verifier.to_batch.public_comm");
     let lgr_comm = index
         .srs
         .lagrange_bases
@@ -369,6 +379,7 @@ where
     let com_ref: Vec<_> = com.iter().collect();
     let elm: Vec<_> = proof.public.iter().map(|s| -*s).collect();
     let p_comm = PolyComm::<G>::multi_scalar_mul(&com_ref, &elm);
+    drop(public_span);
 
     // run the oracles argument
     let OraclesResult {
@@ -395,121 +406,8 @@ where
     //
 
     let f_comm = {
-        // permutation
-        let zkp = index.zkpm.evaluate(&oracles.zeta);
-
-        let alphas = all_alphas.get_alphas(ArgumentType::Permutation, permutation::CONSTRAINTS);
-
-        let mut commitments = vec![&index.sigma_comm[PERMUTS - 1]];
-        let mut scalars = vec![ConstraintSystem::perm_scalars(
-            &evals,
-            oracles.beta,
-            oracles.gamma,
-            alphas,
-            zkp,
-        )];
-
-        // generic
-        {
-            let alphas =
-                all_alphas.get_alphas(ArgumentType::Gate(GateType::Generic), generic::CONSTRAINTS);
-
-            let generic_scalars =
-                &ConstraintSystem::gnrc_scalars(alphas, &evals[0].w, evals[0].generic_selector);
-
-            let generic_com = index.coefficients_comm.iter().take(generic_scalars.len());
-
-            assert_eq!(generic_scalars.len(), generic_com.len());
-
-            scalars.extend(generic_scalars);
-            commitments.extend(generic_com);
-        }
-
-        // other gates are implemented using the expression framework
-        {
-            // TODO: Reuse constants from oracles function
-            let constants = Constants {
-                alpha: oracles.alpha,
-                beta: oracles.beta,
-                gamma: oracles.gamma,
-                joint_combiner: oracles.joint_combiner.1,
-                endo_coefficient: index.endo,
-                mds: index.fr_sponge_params.mds.clone(),
-            };
-
-            for (col, tokens) in &index.linearization.index_terms {
-                let scalar =
-                    PolishToken::evaluate(tokens, index.domain, oracles.zeta, &evals, &constants)
-                        .expect("should evaluate");
-                let l = proof.commitments.lookup.as_ref();
-                use Column::*;
-                match col {
-                    Witness(i) => {
-                        scalars.push(scalar);
-                        commitments.push(&proof.commitments.w_comm[*i])
-                    }
-                    Coefficient(i) => {
-                        scalars.push(scalar);
-                        commitments.push(&index.coefficients_comm[*i])
-                    }
-                    Z => {
-                        scalars.push(scalar);
-                        commitments.push(&proof.commitments.z_comm);
-                    }
-                    LookupSorted(i) => {
-                        scalars.push(scalar);
-                        commitments.push(&l.unwrap().sorted[*i])
-                    }
-                    LookupAggreg => {
-                        scalars.push(scalar);
-                        commitments.push(&l.unwrap().aggreg)
-                    }
-                    LookupKindIndex(i) => match index.lookup_index.as_ref() {
-                        None => {
-                            panic!("Attempted to use {:?}, but no lookup index was given", col)
-                        }
-                        Some(lindex) => {
-                            scalars.push(scalar);
-                            commitments.push(&lindex.lookup_selectors[*i]);
-                        }
-                    },
-                    LookupTable => match index.lookup_index.as_ref() {
-                        None => {
-                            panic!("Attempted to use {:?}, but no lookup index was given", col)
-                        }
-                        Some(lindex) => {
-                            let mut j = Fr::<G>::one();
-                            scalars.push(scalar);
-                            commitments.push(&lindex.lookup_table[0]);
-                            for t in lindex.lookup_table.iter().skip(1) {
-                                j *= constants.joint_combiner;
-                                scalars.push(scalar * j);
-                                commitments.push(t);
-                            }
-                        }
-                    },
-                    Index(t) => {
-                        use GateType::*;
-                        let c = match t {
-                            Zero | Generic => panic!("Selector for {:?} not defined", t),
-                            CompleteAdd => &index.complete_add_comm,
-                            VarBaseMul => &index.mul_comm,
-                            EndoMul => &index.emul_comm,
-                            EndoMulScalar => &index.endomul_scalar_comm,
-                            Poseidon => &index.psm_comm,
-                            ChaCha0 => &index.chacha_comm.as_ref().unwrap()[0],
-                            ChaCha1 => &index.chacha_comm.as_ref().unwrap()[1],
-                            ChaCha2 => &index.chacha_comm.as_ref().unwrap()[2],
-                            ChaChaFinal => &index.chacha_comm.as_ref().unwrap()[3],
-                        };
-                        scalars.push(scalar);
-                        commitments.push(c);
-                    }
-                }
-            }
-        }
-
-        // MSM
+        let _span = profiling::span("verifier.to_batch.f_comm");
+        let (commitments, scalars) = f_comm_terms(index, proof, &oracles, &all_alphas, &evals);
         PolyComm::multi_scalar_mul(&commitments, &scalars)
     };
 
@@ -572,6 +470,21 @@ where
             .collect(),
         degree_bound: None,
     });
+    if let Some(poseidon_legacy_comm) = &index.poseidon_legacy_comm {
+        evaluations.push(Evaluation {
+            commitment: poseidon_legacy_comm.clone(),
+            evaluations: proof
+                .evals
+                .iter()
+                .map(|e| {
+                    e.poseidon_legacy_selector
+                        .clone()
+                        .expect("the evaluations are checked by batch_verify")
+                })
+                .collect(),
+            degree_bound: None,
+        });
+    }
 
     // witness commitments
     evaluations.extend(
@@ -623,17 +536,155 @@ where
     // prepare for the opening proof verification
     let omega = index.domain.group_gen;
 
-    BatchEvaluationProof {
-        sponge: fq_sponge,
+    // the batch can be verified with a longer SRS than the one of the index, see [batch_verify]
+    BatchEvaluationProof::new(
+        fq_sponge,
         evaluations,
-        evaluation_points: vec![oracles.zeta, oracles.zeta * omega],
-        xi: oracles.v,
-        r: oracles.u,
-        opening: &proof.proof,
+        vec![oracles.zeta, oracles.zeta * omega],
+        oracles.v,
+        oracles.u,
+        &proof.proof,
+    )
+    .with_srs_length(index.srs.g.len())
+}
+
+/// Returns the commitments and scalars of the multi-scalar multiplication
+/// that computes the commitment to the linearized polynomial f.
+/// The first commitment is the one of the last sigma polynomial,
+/// followed by the ones of the generic coefficients, and by one commitment per index term of the linearization.
+pub(crate) fn f_comm_terms<'a, G>(
+    index: &'a VerifierIndex<G>,
+    proof: &'a ProverProof<G>,
+    oracles: &RandomOracles<Fr<G>>,
+    all_alphas: &Alphas<Fr<G>>,
+    evals: &[ProofEvaluations<Fr<G>>],
+) -> (Vec<&'a PolyComm<G>>, Vec<Fr<G>>)
+where
+    G: CommitmentCurve,
+    G::BaseField: PrimeField,
+{
+    // permutation
+    let zkp = index.zkpm.evaluate(&oracles.zeta);
+
+    let alphas = all_alphas.get_alphas(ArgumentType::Permutation, permutation::CONSTRAINTS);
+
+    let mut commitments = vec![&index.sigma_comm[PERMUTS - 1]];
+    let mut scalars = vec![ConstraintSystem::perm_scalars(
+        evals,
+        oracles.beta,
+        oracles.gamma,
+        alphas,
+        zkp,
+    )];
+
+    // generic
+    {
+        let alphas =
+            all_alphas.get_alphas(ArgumentType::Gate(GateType::Generic), generic::CONSTRAINTS);
+
+        let generic_scalars =
+            &ConstraintSystem::gnrc_scalars(alphas, &evals[0].w, evals[0].generic_selector);
+
+        let generic_com = index.coefficients_comm.iter().take(generic_scalars.len());
+
+        assert_eq!(generic_scalars.len(), generic_com.len());
+
+        scalars.extend(generic_scalars);
+        commitments.extend(generic_com);
+    }
+
+    // other gates are implemented using the expression framework
+    {
+        // TODO: Reuse constants from oracles function
+        let constants = Constants {
+            alpha: oracles.alpha,
+            beta: oracles.beta,
+            gamma: oracles.gamma,
+            joint_combiner: oracles.joint_combiner.1,
+            endo_coefficient: index.endo,
+            mds: index.fr_sponge_params.mds.clone(),
+        };
+
+        for (col, tokens) in &index.linearization.index_terms {
+            let scalar =
+                PolishToken::evaluate(tokens, index.domain, oracles.zeta, evals, &constants)
+                    .expect("should evaluate");
+            let l = proof.commitments.lookup.as_ref();
+            use Column::*;
+            match col {
+                Witness(i) => {
+                    scalars.push(scalar);
+                    commitments.push(&proof.commitments.w_comm[*i])
+                }
+                Coefficient(i) => {
+                    scalars.push(scalar);
+                    commitments.push(&index.coefficients_comm[*i])
+                }
+                Z => {
+                    scalars.push(scalar);
+                    commitments.push(&proof.commitments.z_comm);
+                }
+                LookupSorted(i) => {
+                    scalars.push(scalar);
+                    commitments.push(&l.unwrap().sorted[*i])
+                }
+                LookupAggreg => {
+                    scalars.push(scalar);
+                    commitments.push(&l.unwrap().aggreg)
+                }
+                LookupKindIndex(i) => match index.lookup_index.as_ref() {
+                    None => {
+                        panic!("Attempted to use {:?}, but no lookup index was given", col)
+                    }
+                    Some(lindex) => {
+                        scalars.push(scalar);
+                        commitments.push(&lindex.lookup_selectors[*i]);
+                    }
+                },
+                LookupTable => match index.lookup_index.as_ref() {
+                    None => {
+                        panic!("Attempted to use {:?}, but no lookup index was given", col)
+                    }
+                    Some(lindex) => {
+                        let mut j = Fr::<G>::one();
+                        scalars.push(scalar);
+                        commitments.push(&lindex.lookup_table[0]);
+                        for t in lindex.lookup_table.iter().skip(1) {
+                            j *= constants.joint_combiner;
+                            scalars.push(scalar * j);
+                            commitments.push(t);
+                        }
+                    }
+                },
+                Index(t) => {
+                    use GateType::*;
+                    let c = match t {
+                        Zero | Generic => panic!("Selector for {:?} not defined", t),
+                        CompleteAdd => &index.complete_add_comm,
+                        VarBaseMul => &index.mul_comm,
+                        EndoMul => &index.emul_comm,
+                        EndoMulScalar => &index.endomul_scalar_comm,
+                        Poseidon => &index.psm_comm,
+                        ChaCha0 => &index.chacha_comm.as_ref().unwrap()[0],
+                        ChaCha1 => &index.chacha_comm.as_ref().unwrap()[1],
+                        ChaCha2 => &index.chacha_comm.as_ref().unwrap()[2],
+                        ChaChaFinal => &index.chacha_comm.as_ref().unwrap()[3],
+                        FixedBaseMul => index.fixed_base_mul_comm.as_ref().unwrap(),
+                        PoseidonLegacy => index.poseidon_legacy_comm.as_ref().unwrap(),
+                    };
+                    scalars.push(scalar);
+                    commitments.push(c);
+                }
+            }
+        }
     }
+
+    (commitments, scalars)
 }
 
 /// This function verifies the batch of zk-proofs
+/// The proofs can be created for different circuits, with SRS of different lengths,
+/// as long as the SRS of the verifier indexes are prefixes of one another.
 ///     proofs: vector of Plonk proofs
 ///     index: VerifierIndex
 ///     RETURN: verification status
@@ -645,7 +696,44 @@ pub fn batch_verify<G, EFqSponge, EFrSponge>(
 where
     G: CommitmentCurve,
     G::BaseField: PrimeField,
-    EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>,
+    EFqSponge: Clone + Send + FqSponge<Fq<G>, G, Fr<G>>,
+    EFrSponge: FrSponge<Fr<G>>,
+{
+    batch_verify_helper::<G, EFqSponge, EFrSponge>(group_map, proofs, None)
+}
+
+/// This function verifies the batch of zk-proofs, except for the expensive part of the opening proofs
+/// (the check of their `sg` commitments), which is deferred to the `accumulator`.
+/// The deferred checks can then be merged with others, and performed at once with [Accumulator::check].
+///     proofs: vector of Plonk proofs
+///     index: VerifierIndex
+///     accumulator: the accumulator the deferred checks are added to, if the proofs are valid
+///     RETURN: verification status
+#[allow(clippy::type_complexity)]
+pub fn batch_verify_deferred<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    proofs: &[(&VerifierIndex<G>, &ProverProof<G>)],
+    accumulator: &mut Accumulator<G>,
+) -> Result<()>
+where
+    G: CommitmentCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + Send + FqSponge<Fq<G>, G, Fr<G>>,
+    EFrSponge: FrSponge<Fr<G>>,
+{
+    batch_verify_helper::<G, EFqSponge, EFrSponge>(group_map, proofs, Some(accumulator))
+}
+
+#[allow(clippy::type_complexity)]
+fn batch_verify_helper<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    proofs: &[(&VerifierIndex<G>, &ProverProof<G>)],
+    accumulator: Option<&mut Accumulator<G>>,
+) -> Result<()>
+where
+    G: CommitmentCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + Send + FqSponge<Fq<G>, G, Fr<G>>,
     EFrSponge: FrSponge<Fr<G>>,
 {
     // if there's no proof to verify, return early
@@ -653,21 +741,51 @@ where
         return Ok(());
     }
 
-    // TODO: Account for the different SRS lengths
-    let srs = &proofs[0].0.srs;
-    for (index, _) in proofs.iter() {
-        assert_eq!(index.srs.g.len(), srs.g.len());
+    let _span = profiling::span("verifier.batch_verify");
+
+    // The opening proofs are all verified with the largest SRS,
+    // the proofs created with a shorter SRS using a prefix of its generators.
+    let srs = proofs
+        .iter()
+        .map(|(index, _)| &index.srs)
+        .max_by_key(|srs| srs.g.len())
+        .expect("the batch is not empty");
+    for (index, _) in proofs {
+        if index.srs.h != srs.h || index.srs.g[..] != srs.g[..index.srs.g.len()] {
+            return Err(ProofError::IncompatibleSrs);
+        }
     }
 
-    // Validate each proof separately (f(zeta) = t(zeta) * Z_H(zeta))
-    // + build objects required to batch verify all the evaluation proofs
-    let mut batch = vec![];
+    // the optional selectors are evaluated if and only if they are in the verifier index
     for (index, proof) in proofs {
-        batch.push(to_batch::<G, EFqSponge, EFrSponge>(index, proof));
+        let poseidon_legacy = index.poseidon_legacy_comm.is_some();
+        if proof
+            .evals
+            .iter()
+            .any(|e| e.poseidon_legacy_selector.is_some() != poseidon_legacy)
+        {
+            return Err(ProofError::UnexpectedEvaluations);
+        }
     }
 
+    // Validate each proof separately (f(zeta) = t(zeta) * Z_H(zeta))
+    // + build objects required to batch verify all the evaluation proofs
+    let mut batch: Vec<_> = proofs
+        .par_iter()
+        .map(|(index, proof)| to_batch::<G, EFqSponge, EFrSponge>(index, proof))
+        .collect();
+
     // final check to verify the evaluation proofs
-    match srs.verify::<EFqSponge, _>(group_map, &mut batch, &mut thread_rng()) {
+    let res = match accumulator {
+        None => srs.verify::<EFqSponge, _>(group_map, &mut batch, &mut thread_rng()),
+        Some(accumulator) => srs.verify_succinct::<EFqSponge, _>(
+            group_map,
+            &mut batch,
+            &mut thread_rng(),
+            accumulator,
+        ),
+    };
+    match res {
         false => Err(ProofError::OpenProof),
         true => Ok(()),
     }
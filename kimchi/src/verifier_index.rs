@@ -8,6 +8,7 @@ use crate::circuits::{
     gate::LookupsUsed,
     wires::*,
 };
+use crate::linearization::{check_linearization, LinearizationError, LookupParameters};
 use crate::prover_index::ProverIndex;
 use ark_ec::AffineCurve;
 use ark_ff::PrimeField;
@@ -39,6 +40,12 @@ pub struct LookupVerifierIndex<G: CommitmentCurve> {
     pub lookup_table: Vec<PolyComm<G>>,
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub lookup_selectors: Vec<PolyComm<G>>,
+
+    /// The value of the dummy lookups, which the lookup constraints depend on
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub dummy_lookup_value: Vec<Fr<G>>,
+    /// The maximum number of lookups per row
+    pub max_lookups_per_row: usize,
 }
 
 #[serde_as]
@@ -71,6 +78,9 @@ pub struct VerifierIndex<G: CommitmentCurve> {
     /// poseidon constraint selector polynomial commitment
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub psm_comm: PolyComm<G>,
+    /// poseidon legacy constraint selector polynomial commitment, if the circuit has legacy Poseidon gates
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    pub poseidon_legacy_comm: Option<PolyComm<G>>,
 
     // ECC arithmetic polynomial commitments
     /// EC addition selector polynomial commitment
@@ -85,6 +95,10 @@ pub struct VerifierIndex<G: CommitmentCurve> {
     /// endoscalar multiplication scalar computation selector polynomial commitment
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub endomul_scalar_comm: PolyComm<G>,
+    /// fixed base scalar multiplication selector polynomial commitment,
+    /// if the circuit has fixed base scalar multiplication gates
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    pub fixed_base_mul_comm: Option<PolyComm<G>>,
 
     /// Chacha polynomial commitments
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
@@ -107,10 +121,10 @@ pub struct VerifierIndex<G: CommitmentCurve> {
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub lookup_index: Option<LookupVerifierIndex<G>>,
 
-    #[serde(skip)]
+    #[serde(bound = "Linearization<Vec<PolishToken<Fr<G>>>>: Serialize + DeserializeOwned")]
     pub linearization: Linearization<Vec<PolishToken<Fr<G>>>>,
     /// The mapping between powers of alpha and constraints
-    #[serde(skip)]
+    #[serde(bound = "Alphas<Fr<G>>: Serialize + DeserializeOwned")]
     pub powers_of_alpha: Alphas<Fr<G>>,
 
     // random oracle argument parameters
@@ -144,6 +158,8 @@ where
                         .iter()
                         .map(|e| self.srs.commit_evaluations_non_hiding(domain, e, None))
                         .collect(),
+                    dummy_lookup_value: cs.dummy_lookup_value.clone(),
+                    max_lookups_per_row: cs.max_lookups_per_row,
                 })
         };
 
@@ -163,6 +179,11 @@ where
             generic_comm: self.srs.commit_non_hiding(&self.cs.genericm, None),
 
             psm_comm: self.srs.commit_non_hiding(&self.cs.psm, None),
+            poseidon_legacy_comm: self
+                .cs
+                .poseidon_legacym
+                .as_ref()
+                .map(|p| self.srs.commit_non_hiding(p, None)),
 
             complete_add_comm: self.srs.commit_evaluations_non_hiding(
                 domain,
@@ -182,6 +203,12 @@ where
                 None,
             ),
 
+            fixed_base_mul_comm: self
+                .cs
+                .fixed_base_mull4
+                .as_ref()
+                .map(|l4| self.srs.commit_evaluations_non_hiding(domain, l4, None)),
+
             chacha_comm: self.cs.chacha8.as_ref().map(|c| {
                 array_init(|i| self.srs.commit_evaluations_non_hiding(domain, &c[i], None))
             }),
@@ -203,6 +230,8 @@ where
     G: CommitmentCurve,
 {
     /// Deserializes a [VerifierIndex] from a file, given a pointer to an SRS and an optional offset in the file.
+    /// It fails if the linearization embedded in the file is not the one of this library
+    /// (see [VerifierIndex::check_linearization]), as the verifier follows it.
     pub fn from_file(
         srs: Arc<SRS<G>>,
         path: &Path,
@@ -233,9 +262,37 @@ where
         verifier_index.w = zk_w3(verifier_index.domain);
         verifier_index.zkpm = zk_polynomial(verifier_index.domain);
 
+        // the embedded linearization must be the one of this library
+        verifier_index
+            .check_linearization()
+            .map_err(|e| e.to_string())?;
+
         Ok(verifier_index)
     }
 
+    /// Returns the parameters of the lookup argument which the linearization depends on,
+    /// if the circuit uses lookups.
+    pub fn lookup_parameters(&self) -> Option<LookupParameters<'_, Fr<G>>> {
+        self.lookup_index.as_ref().map(|l| LookupParameters {
+            dummy_lookup_value: &l.dummy_lookup_value,
+            max_lookups_per_row: l.max_lookups_per_row,
+        })
+    }
+
+    /// Checks that the linearization and the powers of alpha of the index,
+    /// which may have been deserialized, are those this library computes for the same circuit.
+    pub fn check_linearization(&self) -> Result<(), LinearizationError> {
+        check_linearization(
+            &self.linearization,
+            &self.powers_of_alpha,
+            self.domain,
+            self.chacha_comm.is_some(),
+            self.poseidon_legacy_comm.is_some(),
+            self.fixed_base_mul_comm.is_some(),
+            self.lookup_parameters(),
+        )
+    }
+
     /// Writes a [VerifierIndex] to a file, potentially appending it to the already-existing content (if append is set to true)
     // TODO: append should be a bool, not an option
     pub fn to_file(&self, path: &Path, append: Option<bool>) -> Result<(), String> {
@@ -1,4 +1,5 @@
 pub mod constants;
+pub mod merkle;
 pub mod pasta;
 pub mod poseidon;
 pub mod sponge;
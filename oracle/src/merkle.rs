@@ -0,0 +1,124 @@
+//! This module implements Merkle trees whose nodes are Poseidon hashes
+//! (an [ArithmeticSponge] with the Kimchi constants) of their two children.
+
+use crate::constants::PlonkSpongeConstantsKimchi;
+use crate::poseidon::{ArithmeticSponge, ArithmeticSpongeParams, Sponge};
+use ark_ff::Field;
+
+/// Hashes two children into their parent node,
+/// by absorbing them in a new sponge and squeezing it.
+pub fn hash_children<F: Field>(params: &ArithmeticSpongeParams<F>, left: F, right: F) -> F {
+    let mut sponge = ArithmeticSponge::<F, PlonkSpongeConstantsKimchi>::new(params.clone());
+    sponge.absorb(&[left, right]);
+    sponge.squeeze()
+}
+
+/// A Merkle tree of fixed depth, whose missing leaves are zero.
+#[derive(Clone, Debug)]
+pub struct MerkleTree<F: Field> {
+    params: ArithmeticSpongeParams<F>,
+    /// the nodes of the tree, by level: the leaves first, and the root last
+    levels: Vec<Vec<F>>,
+}
+
+/// The path from a leaf to the root of a [MerkleTree].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerklePath<F: Field> {
+    /// the position of the leaf, whose bits (least significant first)
+    /// tell whether the node is the right child at each level
+    pub index: usize,
+    /// the siblings of the nodes on the path, from the leaf up to the root (excluded)
+    pub siblings: Vec<F>,
+}
+
+impl<F: Field> MerkleTree<F> {
+    /// Creates a tree with `2^depth` leaves, the first ones being `leaves`.
+    pub fn new(params: ArithmeticSpongeParams<F>, depth: usize, leaves: Vec<F>) -> Self {
+        assert!(
+            leaves.len() <= 1 << depth,
+            "too many leaves for a tree of depth {}",
+            depth
+        );
+        let mut level = leaves;
+        level.resize(1 << depth, F::zero());
+
+        let mut levels = vec![level];
+        for _ in 0..depth {
+            let children = levels.last().unwrap();
+            let parents = children
+                .chunks(2)
+                .map(|pair| hash_children(&params, pair[0], pair[1]))
+                .collect();
+            levels.push(parents);
+        }
+
+        MerkleTree { params, levels }
+    }
+
+    /// The number of levels above the leaves.
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// The root of the tree.
+    pub fn root(&self) -> F {
+        self.levels[self.depth()][0]
+    }
+
+    /// The leaf at position `index`.
+    pub fn leaf(&self, index: usize) -> F {
+        self.levels[0][index]
+    }
+
+    /// Replaces the leaf at position `index`, and updates the nodes on its path.
+    pub fn update(&mut self, index: usize, leaf: F) {
+        self.levels[0][index] = leaf;
+        let mut index = index;
+        for level in 1..=self.depth() {
+            index /= 2;
+            let children = &self.levels[level - 1];
+            let parent = hash_children(&self.params, children[2 * index], children[2 * index + 1]);
+            self.levels[level][index] = parent;
+        }
+    }
+
+    /// The path from the leaf at position `index` to the root.
+    pub fn path(&self, index: usize) -> MerklePath<F> {
+        assert!(index < self.levels[0].len(), "index out of the tree");
+        let siblings = self.levels[..self.depth()]
+            .iter()
+            .enumerate()
+            .map(|(level, nodes)| nodes[(index >> level) ^ 1])
+            .collect();
+        MerklePath { index, siblings }
+    }
+}
+
+impl<F: Field> MerklePath<F> {
+    /// The bits of the position of the leaf, least significant first,
+    /// which are set when the node on the path is the right child.
+    pub fn bits(&self) -> Vec<bool> {
+        (0..self.siblings.len())
+            .map(|level| (self.index >> level) & 1 == 1)
+            .collect()
+    }
+
+    /// The root of a tree holding `leaf` on this path.
+    pub fn root(&self, params: &ArithmeticSpongeParams<F>, leaf: F) -> F {
+        self.siblings
+            .iter()
+            .zip(self.bits())
+            .fold(leaf, |node, (sibling, is_right)| {
+                if is_right {
+                    hash_children(params, *sibling, node)
+                } else {
+                    hash_children(params, node, *sibling)
+                }
+            })
+    }
+
+    /// Checks that `leaf` is under `root` on this path.
+    pub fn verify(&self, params: &ArithmeticSpongeParams<F>, leaf: F, root: F) -> bool {
+        self.root(params, leaf) == root
+    }
+}
@@ -7,7 +7,7 @@ pub use crate::FqSponge;
 
 pub const CHALLENGE_LENGTH_IN_LIMBS: usize = 2;
 
-const HIGH_ENTROPY_LIMBS: usize = 2;
+pub const HIGH_ENTROPY_LIMBS: usize = 2;
 
 // TODO: move to a different file / module
 /// A challenge which is used as a scalar on a group element in the verifier
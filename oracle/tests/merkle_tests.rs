@@ -0,0 +1,45 @@
+use ark_ff::UniformRand;
+use mina_curves::pasta::Fp;
+use oracle::merkle::{hash_children, MerkleTree};
+use oracle::pasta::fp_kimchi as SpongeParametersKimchi;
+use rand::{rngs::StdRng, SeedableRng};
+
+const DEPTH: usize = 5;
+
+#[test]
+fn merkle_tree_small() {
+    let params = SpongeParametersKimchi::params();
+    let leaves: Vec<Fp> = (1..=3u64).map(Fp::from).collect();
+    let tree = MerkleTree::new(params.clone(), 2, leaves.clone());
+
+    let left = hash_children(&params, leaves[0], leaves[1]);
+    let right = hash_children(&params, leaves[2], Fp::from(0u64));
+    assert_eq!(tree.root(), hash_children(&params, left, right));
+    assert_eq!(tree.path(2).siblings, vec![Fp::from(0u64), left]);
+}
+
+#[test]
+fn merkle_tree_paths() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let params = SpongeParametersKimchi::params();
+    let leaves: Vec<Fp> = (0..20).map(|_| Fp::rand(rng)).collect();
+    let mut tree = MerkleTree::new(params.clone(), DEPTH, leaves);
+
+    // every leaf, including the missing ones, is under the root
+    for index in 0..1 << DEPTH {
+        let path = tree.path(index);
+        assert_eq!(path.bits().len(), DEPTH);
+        assert!(path.verify(&params, tree.leaf(index), tree.root()));
+        assert!(!path.verify(&params, tree.leaf(index) + Fp::from(1u64), tree.root()));
+    }
+
+    // updating a leaf gives the root of the tree built with it
+    for index in [0, 7, 19, 31] {
+        let leaf = Fp::rand(rng);
+        tree.update(index, leaf);
+        let leaves: Vec<_> = (0..1 << DEPTH).map(|i| tree.leaf(i)).collect();
+        let rebuilt = MerkleTree::new(params.clone(), DEPTH, leaves);
+        assert_eq!(tree.root(), rebuilt.root());
+        assert!(tree.path(index).verify(&params, leaf, tree.root()));
+    }
+}
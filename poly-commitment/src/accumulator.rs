@@ -0,0 +1,131 @@
+//! This module implements an accumulator of deferred opening proof checks.
+//!
+//! Verifying an opening proof requires checking that its `sg` component
+//! is the commitment to the polynomial `b(X)` defined by the challenges of the proof
+//! (see [b_poly_coefficients]).
+//! This is an MSM of the size of the SRS, and is the expensive part of the verification.
+//! With [SRS::verify_succinct], the rest of the verification is done immediately,
+//! while the `(challenges, sg, srs_length)` triples are stored in an [Accumulator].
+//! Accumulators can be merged, serialized, and finally checked with a single MSM.
+
+use crate::commitment::{b_poly_coefficients, ceil_log2, CommitmentCurve, PolyComm};
+use crate::srs::SRS;
+use ark_ec::{msm::VariableBaseMSM, AffineCurve};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+type Fr<G> = <G as AffineCurve>::ScalarField;
+
+/// A list of deferred checks that `sg` is the commitment to the polynomial `b(X)` defined by `challenges`,
+/// using the first `srs_length` generators of the SRS
+#[serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Accumulator<G: AffineCurve> {
+    #[serde_as(
+        as = "Vec<(Vec<o1_utils::serialization::SerdeAs>, o1_utils::serialization::SerdeAs, _)>"
+    )]
+    deferred: Vec<(Vec<Fr<G>>, G, usize)>,
+}
+
+impl<G: AffineCurve> Default for Accumulator<G> {
+    fn default() -> Self {
+        Self { deferred: vec![] }
+    }
+}
+
+impl<G: CommitmentCurve> Accumulator<G> {
+    /// Creates an empty accumulator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defers the check that `sg` is the commitment to the polynomial `b(X)` defined by `challenges`,
+    /// for an opening proof created with an SRS of length `srs_length`
+    pub fn push(&mut self, challenges: Vec<Fr<G>>, sg: G, srs_length: usize) {
+        self.deferred.push((challenges, sg, srs_length));
+    }
+
+    /// Adds all the deferred checks of `other` to this accumulator
+    pub fn merge(&mut self, other: Accumulator<G>) {
+        self.deferred.extend(other.deferred);
+    }
+
+    /// The deferred `(challenges, sg, srs_length)` triples
+    pub fn deferred(&self) -> &[(Vec<Fr<G>>, G, usize)] {
+        &self.deferred
+    }
+
+    /// The number of deferred checks
+    pub fn len(&self) -> usize {
+        self.deferred.len()
+    }
+
+    /// Returns true if no check has been deferred
+    pub fn is_empty(&self) -> bool {
+        self.deferred.is_empty()
+    }
+
+    /// Returns the deferred checks in the format expected by a recursive prover,
+    /// so that a proof can attest of their verification instead.
+    #[allow(clippy::type_complexity)]
+    pub fn prev_challenges(&self) -> Vec<(Vec<Fr<G>>, PolyComm<G>)> {
+        self.deferred
+            .iter()
+            .map(|(chals, sg, _)| {
+                (
+                    chals.clone(),
+                    PolyComm {
+                        unshifted: vec![*sg],
+                        shifted: None,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Performs all the deferred checks with a single MSM.
+    /// The checks are combined with random powers of a scalar sampled from `rng`.
+    /// The SRS must be at least as long as the SRS of every deferred proof, and extend them.
+    pub fn check<RNG: RngCore + CryptoRng>(&self, srs: &SRS<G>, rng: &mut RNG) -> bool {
+        if self.deferred.is_empty() {
+            return true;
+        }
+
+        // we check that
+        // 0 == sum_i rand_base^i (< s_i, g > - sg_i)
+        // where s_i are the coefficients of the polynomial b(X) of the i-th check
+        let rand_base = Fr::<G>::rand(rng);
+        let mut rand_base_i = Fr::<G>::one();
+
+        let mut g_scalars = vec![Fr::<G>::zero(); srs.g.len()];
+        let mut sg_points = vec![];
+        let mut sg_scalars = vec![];
+
+        for (chals, sg, srs_length) in &self.deferred {
+            if *srs_length > srs.g.len() || chals.len() != ceil_log2(*srs_length) {
+                return false;
+            }
+            let s = b_poly_coefficients(chals);
+
+            // the coefficients past srs_length correspond to the zero padding of the proof's SRS
+            for (scalar, s) in g_scalars[..*srs_length].iter_mut().zip(s) {
+                *scalar += rand_base_i * s;
+            }
+
+            sg_points.push(*sg);
+            sg_scalars.push(-rand_base_i);
+
+            rand_base_i *= rand_base;
+        }
+
+        let points = [&srs.g[..], &sg_points].concat();
+        let scalars: Vec<_> = g_scalars
+            .iter()
+            .chain(&sg_scalars)
+            .map(|x| x.into_repr())
+            .collect();
+        VariableBaseMSM::multi_scalar_mul(&points, &scalars) == G::Projective::zero()
+    }
+}
@@ -6,7 +6,7 @@
 //!     producing the batched opening proof
 //! 3. Verify batch of batched opening proofs
 
-use crate::srs::SRS;
+use crate::{accumulator::Accumulator, srs::SRS};
 use ark_ec::{
     models::short_weierstrass_jacobian::GroupAffine as SWJAffine, msm::VariableBaseMSM,
     AffineCurve, ProjectiveCurve, SWModelParameters,
@@ -21,7 +21,7 @@ use ark_poly::{
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use core::ops::{Add, Sub};
 use groupmap::{BWParameters, GroupMap};
-use o1_utils::ExtendedDensePolynomial as _;
+use o1_utils::{profiling, ExtendedDensePolynomial as _};
 use oracle::{sponge::ScalarChallenge, FqSponge};
 use rand_core::{CryptoRng, RngCore};
 use rayon::prelude::*;
@@ -531,6 +531,48 @@ where
     pub r: Fr<G>,
     /// batched opening proof
     pub opening: &'a OpeningProof<G>,
+    /// length of the SRS the opening proof was created with, if it is not the SRS used to verify it
+    srs_length: Option<usize>,
+}
+
+impl<'a, G, EFqSponge> BatchEvaluationProof<'a, G, EFqSponge>
+where
+    G: AffineCurve,
+    EFqSponge: FqSponge<Fq<G>, G, Fr<G>>,
+{
+    /// Creates the batch evaluation of an opening proof created with the SRS used to verify it.
+    pub fn new(
+        sponge: EFqSponge,
+        evaluations: Vec<Evaluation<G>>,
+        evaluation_points: Vec<Fr<G>>,
+        xi: Fr<G>,
+        r: Fr<G>,
+        opening: &'a OpeningProof<G>,
+    ) -> Self {
+        BatchEvaluationProof {
+            sponge,
+            evaluations,
+            evaluation_points,
+            xi,
+            r,
+            opening,
+            srs_length: None,
+        }
+    }
+
+    /// Sets the length of the SRS the opening proof was created with.
+    /// It can be shorter than the SRS used to verify it,
+    /// as long as its generators are a prefix of the generators of the verifier SRS,
+    /// which the verification checks.
+    pub fn with_srs_length(mut self, srs_length: usize) -> Self {
+        self.srs_length = Some(srs_length);
+        self
+    }
+
+    /// The length of the SRS the opening proof was created with, if it was set by [Self::with_srs_length]
+    pub fn srs_length(&self) -> Option<usize> {
+        self.srs_length
+    }
 }
 
 impl<G: CommitmentCurve> SRS<G> {
@@ -692,6 +734,43 @@ impl<G: CommitmentCurve> SRS<G> {
         batch: &mut Vec<BatchEvaluationProof<G, EFqSponge>>,
         rng: &mut RNG,
     ) -> bool
+    where
+        EFqSponge: FqSponge<Fq<G>, G, Fr<G>>,
+        RNG: RngCore + CryptoRng,
+        G::BaseField: PrimeField,
+    {
+        self.verify_helper(group_map, batch, rng, None)
+    }
+
+    /// This function verifies batch of batched polynomial commitment opening proofs like [SRS::verify],
+    /// except for the check that the `sg` component of each opening proof is the commitment
+    /// to the polynomial defined by its challenges.
+    /// If the rest of the verification succeeds, these checks are deferred to the `accumulator`,
+    /// otherwise the `accumulator` is left untouched.
+    ///     RETURN: verification status of the succinct part of the opening proofs
+    #[allow(clippy::type_complexity)]
+    pub fn verify_succinct<EFqSponge, RNG>(
+        &self,
+        group_map: &G::Map,
+        batch: &mut Vec<BatchEvaluationProof<G, EFqSponge>>,
+        rng: &mut RNG,
+        accumulator: &mut Accumulator<G>,
+    ) -> bool
+    where
+        EFqSponge: FqSponge<Fq<G>, G, Fr<G>>,
+        RNG: RngCore + CryptoRng,
+        G::BaseField: PrimeField,
+    {
+        self.verify_helper(group_map, batch, rng, Some(accumulator))
+    }
+
+    fn verify_helper<EFqSponge, RNG>(
+        &self,
+        group_map: &G::Map,
+        batch: &mut Vec<BatchEvaluationProof<G, EFqSponge>>,
+        rng: &mut RNG,
+        accumulator: Option<&mut Accumulator<G>>,
+    ) -> bool
     where
         EFqSponge: FqSponge<Fq<G>, G, Fr<G>>,
         RNG: RngCore + CryptoRng,
@@ -715,21 +794,26 @@ impl<G: CommitmentCurve> SRS<G> {
         // - (r^i z1_i b_i) U_i
 
         // We also check that the sg component of the proof is equal to the polynomial commitment
-        // to the "s" array
+        // to the "s" array, unless this check is deferred to an accumulator.
+        //
+        // Proofs created with a shorter SRS than self are verified with the prefix of self.g
+        // of the length of their SRS.
 
-        let nonzero_length = self.g.len();
+        let _span = profiling::span("srs.verify");
 
-        let max_rounds = ceil_log2(nonzero_length);
+        let defer = accumulator.is_some();
+        let mut deferred = Accumulator::new();
 
-        let padded_length = 1 << max_rounds;
+        let nonzero_length = self.g.len();
 
-        // TODO: This will need adjusting
-        let padding = padded_length - nonzero_length;
+        // the zero padding of the SRS is not needed,
+        // as the "s" arrays are truncated to the length of the SRS of their proof
         let mut points = vec![self.h];
-        points.extend(self.g.clone());
-        points.extend(vec![G::zero(); padding]);
-
-        let mut scalars = vec![Fr::<G>::zero(); padded_length + 1];
+        let mut scalars = vec![Fr::<G>::zero()];
+        if !defer {
+            points.extend(self.g.clone());
+            scalars.extend(vec![Fr::<G>::zero(); nonzero_length]);
+        }
         assert_eq!(scalars.len(), points.len());
 
         // sample randomiser to scale the proofs with
@@ -746,8 +830,17 @@ impl<G: CommitmentCurve> SRS<G> {
             r,
             evaluations,
             opening,
+            srs_length,
         } in batch.iter_mut()
         {
+            let _span = profiling::span("srs.verify.proof");
+
+            // the proof must have been created with a prefix of our SRS
+            let srs_length = srs_length.unwrap_or(nonzero_length);
+            if srs_length > nonzero_length || opening.lr.len() != ceil_log2(srs_length) {
+                return false;
+            }
+
             // TODO: This computation is repeated in ProverProof::oracles
             let combined_inner_product0 = {
                 let es: Vec<_> = evaluations
@@ -771,7 +864,7 @@ impl<G: CommitmentCurve> SRS<G> {
                         },
                     )
                     .collect();
-                combined_inner_product::<G>(evaluation_points, xi, r, &es, self.g.len())
+                combined_inner_product::<G>(evaluation_points, xi, r, &es, srs_length)
             };
 
             sponge.absorb_fr(&[shift_scalar::<G>(combined_inner_product0)]);
@@ -799,25 +892,37 @@ impl<G: CommitmentCurve> SRS<G> {
                 res
             };
 
-            let s = b_poly_coefficients(&chal);
-
             let neg_rand_base_i = -rand_base_i;
 
-            // TERM
-            // - rand_base_i z1 G
-            //
-            // we also add -sg_rand_base_i * G to check correctness of sg.
-            points.push(opening.sg);
-            scalars.push(neg_rand_base_i * opening.z1 - sg_rand_base_i);
-
-            // Here we add
-            // sg_rand_base_i * ( < s, self.g > )
-            // =
-            // < sg_rand_base_i s, self.g >
-            //
-            // to check correctness of the sg component.
-            {
-                let terms: Vec<_> = s.par_iter().map(|s| sg_rand_base_i * s).collect();
+            if defer {
+                // TERM
+                // - rand_base_i z1 G
+                points.push(opening.sg);
+                scalars.push(neg_rand_base_i * opening.z1);
+
+                // the correctness of sg is checked later
+                deferred.push(chal.clone(), opening.sg, srs_length);
+            } else {
+                let s = b_poly_coefficients(&chal);
+
+                // TERM
+                // - rand_base_i z1 G
+                //
+                // we also add -sg_rand_base_i * G to check correctness of sg.
+                points.push(opening.sg);
+                scalars.push(neg_rand_base_i * opening.z1 - sg_rand_base_i);
+
+                // Here we add
+                // sg_rand_base_i * ( < s, self.g > )
+                // =
+                // < sg_rand_base_i s, self.g >
+                //
+                // to check correctness of the sg component.
+                // The coefficients past srs_length correspond to the zero padding of the proof's SRS.
+                let terms: Vec<_> = s[..srs_length]
+                    .par_iter()
+                    .map(|s| sg_rand_base_i * s)
+                    .collect();
 
                 for (i, term) in terms.iter().enumerate() {
                     scalars[i + 1] += term;
@@ -895,8 +1000,18 @@ impl<G: CommitmentCurve> SRS<G> {
         }
 
         // verify the equation
+        let _span = profiling::span("srs.verify.msm");
         let scalars: Vec<_> = scalars.iter().map(|x| x.into_repr()).collect();
-        VariableBaseMSM::multi_scalar_mul(&points, &scalars) == G::Projective::zero()
+        let res = VariableBaseMSM::multi_scalar_mul(&points, &scalars) == G::Projective::zero();
+
+        // only defer the checks of valid proofs
+        if res {
+            if let Some(accumulator) = accumulator {
+                accumulator.merge(deferred);
+            }
+        }
+
+        res
     }
 }
 
@@ -1039,9 +1154,80 @@ mod tests {
                 },
             ],
             opening: &opening_proof,
+            srs_length: None,
         }];
 
         assert!(srs.verify(&group_map, &mut batch, rng));
+
+        // the proof cannot have been created with a longer SRS than the one verifying it
+        let mut batch: Vec<_> = batch
+            .into_iter()
+            .map(|proof| proof.with_srs_length(srs.g.len() + 1))
+            .collect();
+        assert!(!srs.verify(&group_map, &mut batch, rng));
+    }
+
+    #[test]
+    fn test_deferred_opening_proof() {
+        let coeffs: [Fp; 10] = array_init(|i| Fp::from(i as u32));
+        let poly = DensePolynomial::<Fp>::from_coefficients_slice(&coeffs);
+
+        let srs = SRS::<VestaG>::create(20);
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+        let group_map = <VestaG as CommitmentCurve>::Map::setup();
+        let sponge = DefaultFqSponge::<_, SC>::new(spongeFqParams());
+
+        // create two opening proofs of the same polynomial at different points
+        let commitment = srs.commit(&poly, None, rng);
+        let proofs: Vec<_> = (0..2)
+            .map(|_| {
+                let (u, v) = (Fp::rand(rng), Fp::rand(rng));
+                let elm = vec![Fp::rand(rng)];
+                let polys = vec![(&poly, None, commitment.1.clone())];
+                let proof = srs.open(&group_map, &polys, &elm, v, u, sponge.clone(), rng);
+                let evals = vec![poly.eval(elm[0], srs.g.len())];
+                (u, v, elm, evals, proof)
+            })
+            .collect();
+
+        let batch = |i: usize, evals: Vec<Vec<Fp>>| {
+            let (u, v, elm, _, proof) = &proofs[i];
+            vec![BatchEvaluationProof {
+                sponge: sponge.clone(),
+                evaluation_points: elm.clone(),
+                xi: *v,
+                r: *u,
+                evaluations: vec![Evaluation {
+                    commitment: commitment.0.clone(),
+                    evaluations: evals,
+                    degree_bound: None,
+                }],
+                opening: proof,
+                srs_length: None,
+            }]
+        };
+
+        // verify the succinct part of each proof, and merge the deferred checks
+        let mut accumulator = Accumulator::new();
+        for i in 0..2 {
+            let mut other = Accumulator::new();
+            let evals = proofs[i].3.clone();
+            assert!(srs.verify_succinct(&group_map, &mut batch(i, evals), rng, &mut other));
+            accumulator.merge(other);
+        }
+        assert_eq!(accumulator.len(), 2);
+        assert!(accumulator.check(&srs, rng));
+
+        // an invalid proof is not accumulated
+        let mut other = Accumulator::new();
+        let wrong_evals = vec![vec![Fp::rand(rng)]];
+        assert!(!srs.verify_succinct(&group_map, &mut batch(0, wrong_evals), rng, &mut other));
+        assert!(other.is_empty());
+
+        // an incorrect sg is caught by the deferred check
+        let (chals, _, srs_length) = accumulator.deferred()[0].clone();
+        accumulator.push(chals, proofs[1].4.sg, srs_length);
+        assert!(!accumulator.check(&srs, rng));
     }
 }
 
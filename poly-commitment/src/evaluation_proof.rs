@@ -3,6 +3,7 @@ use crate::srs::SRS;
 use ark_ec::{msm::VariableBaseMSM, AffineCurve, ProjectiveCurve};
 use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
 use ark_poly::univariate::DensePolynomial;
+use o1_utils::profiling;
 use oracle::{sponge::ScalarChallenge, FqSponge};
 use rand_core::{CryptoRng, RngCore};
 use rayon::prelude::*;
@@ -38,6 +39,8 @@ impl<G: CommitmentCurve> SRS<G> {
         RNG: RngCore + CryptoRng,
         G::BaseField: PrimeField,
     {
+        let _span = profiling::span("srs.open");
+
         let rounds = ceil_log2(self.g.len());
         let padded_length = 1 << rounds;
 
@@ -47,6 +50,7 @@ impl<G: CommitmentCurve> SRS<G> {
         g.extend(vec![G::zero(); padding]);
 
         let (p, blinding_factor) = {
+            let _span = profiling::span("srs.open.combine");
             let mut plnm = ChunkedPolynomial::<Fr<G>, &[Fr<G>]>::default();
             // let mut plnm_chunks: Vec<(Fr<G>, OptShiftedPolynomial<_>)> = vec![];
 
@@ -146,6 +150,7 @@ impl<G: CommitmentCurve> SRS<G> {
         let mut chals = vec![];
         let mut chal_invs = vec![];
 
+        let rounds_span = profiling::span("srs.open.rounds");
         for _ in 0..rounds {
             let n = g.len() / 2;
             let (g_lo, g_hi) = (g[0..n].to_vec(), g[n..].to_vec());
@@ -215,6 +220,8 @@ impl<G: CommitmentCurve> SRS<G> {
             g = G::combine_one_endo(self.endo_r, self.endo_q, &g_lo, &g_hi, u_pre);
         }
 
+        drop(rounds_span);
+
         assert!(g.len() == 1);
         let a0 = a[0];
         let b0 = b[0];
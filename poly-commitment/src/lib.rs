@@ -1,3 +1,4 @@
+pub mod accumulator;
 pub mod chunked;
 mod combine;
 pub mod commitment;
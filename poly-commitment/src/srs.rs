@@ -52,7 +52,7 @@ where
     (endo_q, endo_r)
 }
 
-fn point_of_random_bytes<G: CommitmentCurve>(map: &G::Map, random_bytes: &[u8]) -> G
+pub fn point_of_random_bytes<G: CommitmentCurve>(map: &G::Map, random_bytes: &[u8]) -> G
 where
     G::BaseField: PrimeField,
 {
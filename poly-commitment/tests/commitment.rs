@@ -86,14 +86,14 @@ impl AggregatedEvaluationProof {
             });
         }
 
-        BatchEvaluationProof {
-            sponge: self.fq_sponge.clone(),
-            evaluation_points: self.eval_points.clone(),
-            xi: self.polymask,
-            r: self.evalmask,
-            evaluations: coms,
-            opening: &self.proof,
-        }
+        BatchEvaluationProof::new(
+            self.fq_sponge.clone(),
+            coms,
+            self.eval_points.clone(),
+            self.polymask,
+            self.evalmask,
+            &self.proof,
+        )
     }
 }
 
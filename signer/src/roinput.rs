@@ -106,6 +106,12 @@ impl ROInput {
         bits.into()
     }
 
+    /// Number of base field elements appended to the random oracle input,
+    /// which come first in [ROInput::to_fields]
+    pub fn num_fields(&self) -> usize {
+        self.fields.len()
+    }
+
     /// Serialize random oracle input to vector of base field elements
     pub fn to_fields(&self) -> Vec<BaseField> {
         let mut fields: Vec<BaseField> = self.fields.clone();
@@ -8,6 +8,7 @@ let public = cs.public;
 // display witness table headers if present
 if (witness) {
     document.querySelector("#gates thead tr").innerHTML += '<th colspan="15" scope="col" class="px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider">Registers</th > ';
+    document.querySelector("#gates thead tr").innerHTML += '<th scope="col" class="px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider">Constraints</th > ';
 
     // pad witness
     const witness_padding = cs.gates.length - witness.inner[0].length;
@@ -52,13 +53,24 @@ cs.gates.forEach((g, row) => {
     }
 
     let witness_cols = '';
+    let row_color = '';
     if (witness) {
-        witness.inner.forEach((col) => {
-            witness_cols += `<td class="px-6 py-4 whitespace-nowrap text-xs text-gray-500">${toTruncatedHex(col[row])}</td>`;
+        const check = checks[row];
+        witness.inner.forEach((col, i) => {
+            const broken = check.broken_wires.find((b) => b.col == i);
+            if (broken) {
+                witness_cols += `<td class="px-6 py-4 whitespace-nowrap text-xs bg-red-300 text-red-900" title="not equal to (row: ${broken.wire.row}, col: ${broken.wire.col})">${toTruncatedHex(col[row])}</td>`;
+            } else {
+                witness_cols += `<td class="px-6 py-4 whitespace-nowrap text-xs text-gray-500">${toTruncatedHex(col[row])}</td>`;
+            }
         });
+        witness_cols += `<td class="px-6 py-4 whitespace-nowrap text-xs">${parseConstraints(check)}</td>`;
+        if (!isOk(check)) {
+            row_color = 'bg-red-100';
+        }
     }
 
-    const gate = `<tr>
+    const gate = `<tr id="row-${row}" class="${row_color}">
                 <td class="px-6 py-4 whitespace-nowrap">${row}</td>
                 <td class="px-6 py-4 whitespace-nowrap ${gateColor(g.typ)}">${typ}</td>
                 <td class="px-6 py-4 whitespace-nowrap">${wiring}</td>
@@ -68,6 +80,14 @@ cs.gates.forEach((g, row) => {
     document.querySelector("#gates tbody").innerHTML += gate;
 });
 
+// display failures
+if (checks) {
+    displayFailures();
+}
+
+// display wiring
+displayWiring();
+
 // display constraints
 for (const [gate, cs] of Object.entries(constraints)) {
     const div = document.querySelector("#constraints");
@@ -81,10 +101,148 @@ for (const [gate, cs] of Object.entries(constraints)) {
     });
 }
 
+// render the LaTeX
+renderMathInElement(document.body);
+
 //
 // Helpers
 //
 
+// a row is valid if it satisfies its gate and copy constraints
+function isOk(check) {
+    return check.failing.length == 0 && check.error == null && check.broken_wires.length == 0;
+}
+
+// values of the constraints of a row, the failing ones in red
+function parseConstraints(check) {
+    if (check.constraints.length == 0) {
+        return "/";
+    }
+    let values = '';
+    check.constraints.forEach((c, i) => {
+        if (check.failing.includes(i)) {
+            values += `<li class="text-red-600 font-bold">${i}: ${toTruncatedHex(c)}</li>`;
+        } else {
+            values += `<li class="text-gray-500">${i}: ${toTruncatedHex(c)}</li>`;
+        }
+    });
+    return `<ul>${values}</ul>`;
+}
+
+// a color per permutation cycle
+function cycleColor(i) {
+    return `hsl(${(i * 137.5) % 360}, 70%, 45%)`;
+}
+
+// draw the permutation cycles on a grid of rows and columns
+function displayWiring() {
+    document.querySelector("#dot").href = "data:text/vnd.graphviz;charset=utf-8," + encodeURIComponent(dot);
+
+    const key = (cell) => `${cell.row},${cell.col}`;
+    const public_cells = new Set(wiring.public.map(key));
+    const lookup_cells = new Set(wiring.lookups.map(key));
+
+    // only keep the rows with marked cells
+    const cells = wiring.cycles.flat().concat(wiring.public, wiring.lookups);
+    const rows = [...new Set(cells.map((cell) => cell.row))].sort((a, b) => a - b);
+    const y_of_row = new Map(rows.map((row, i) => [row, 40 + i * 24]));
+    const x = (col) => 80 + col * 48;
+    const y = (cell) => y_of_row.get(cell.row);
+
+    let svg = '';
+
+    // grid
+    for (let col = 0; col < 15; col++) {
+        svg += `<text x="${x(col)}" y="20" text-anchor="middle" class="text-xs" fill="gray">${col}</text>`;
+    }
+    rows.forEach((row) => {
+        const gate = cs.gates[row] ? cs.gates[row].typ : '';
+        svg += `<text x="8" y="${y_of_row.get(row) + 4}" class="text-xs" fill="gray"><title>${gate}</title>${row}</text>`;
+        svg += `<line x1="${x(0)}" x2="${x(14)}" y1="${y_of_row.get(row)}" y2="${y_of_row.get(row)}" stroke="#eee"/>`;
+    });
+
+    // cycles
+    wiring.cycles.forEach((cycle, i) => {
+        const color = cycleColor(i);
+        cycle.forEach((from, j) => {
+            const to = cycle[(j + 1) % cycle.length];
+            // bend the edges, so that the cycles within a column are visible
+            const cx = (x(from.col) + x(to.col)) / 2 + 16 + (i % 4) * 6;
+            const cy = (y(from) + y(to)) / 2;
+            svg += `<path d="M ${x(from.col)} ${y(from)} Q ${cx} ${cy} ${x(to.col)} ${y(to)}" stroke="${color}" fill="none" stroke-width="1.5"/>`;
+        });
+    });
+
+    // cells
+    const drawn = new Set();
+    const drawCell = (cell, title, stroke) => {
+        if (drawn.has(key(cell))) {
+            return;
+        }
+        drawn.add(key(cell));
+        let fill = "white";
+        if (public_cells.has(key(cell))) {
+            fill = "#93c5fd";
+            title += " (public input)";
+        } else if (lookup_cells.has(key(cell))) {
+            fill = "#fdba74";
+            title += " (lookup)";
+        }
+        svg += `<circle cx="${x(cell.col)}" cy="${y(cell)}" r="6" fill="${fill}" stroke="${stroke}" stroke-width="2"><title>${title}</title></circle>`;
+    };
+    wiring.cycles.forEach((cycle, i) => {
+        cycle.forEach((cell) => drawCell(cell, `row ${cell.row}, col ${cell.col}: cycle ${i}`, cycleColor(i)));
+    });
+    wiring.public.concat(wiring.lookups).forEach((cell) => drawCell(cell, `row ${cell.row}, col ${cell.col}`, "gray"));
+
+    const width = x(14) + 40;
+    const height = 40 + rows.length * 24;
+    document.querySelector("#wiring-graph").innerHTML = `<svg width="${width}" height="${height}" xmlns="http://www.w3.org/2000/svg">${svg}</svg>`;
+
+    // list of the cycles
+    let list = '';
+    wiring.cycles.forEach((cycle, i) => {
+        const cells = cycle.map((cell) => `(row: ${cell.row}, col: ${cell.col})`).join(" → ");
+        list += `<li class="mb-1"><span class="inline-block w-3 h-3 mr-2" style="background: ${cycleColor(i)}"></span>cycle ${i}: ${cells} → …</li>`;
+    });
+    if (list == '') {
+        list = "<li>No copy constraints.</li>";
+    }
+    document.querySelector("#cycles").innerHTML = list;
+}
+
+// list of the failing rows, linking to the rows of the circuit
+function displayFailures() {
+    const div = document.querySelector("#failures");
+    div.classList.remove("hidden");
+
+    let failures = '';
+    checks.forEach((check, row) => {
+        if (isOk(check)) {
+            return;
+        }
+        let reasons = '';
+        if (check.failing.length > 0) {
+            reasons += `<li>constraints ${check.failing.join(", ")} do not evaluate to zero</li>`;
+        }
+        if (check.error != null) {
+            reasons += `<li>${check.error}</li>`;
+        }
+        check.broken_wires.forEach((b) => {
+            reasons += `<li>col ${b.col} is not equal to (row: ${b.wire.row}, col: ${b.wire.col})</li>`;
+        });
+        failures += `<li class="mb-2"><a href="#row-${row}" class="font-medium text-red-700 underline">row ${row} (${cs.gates[row].typ})</a><ul class="ml-4 list-disc">${reasons}</ul></li>`;
+    });
+
+    if (failures == '') {
+        div.classList.add("bg-green-100", "text-green-800");
+        div.innerHTML = "The witness satisfies all the gate and copy constraints.";
+    } else {
+        div.classList.add("bg-red-100", "text-red-800");
+        div.innerHTML = `<h2 class="text-lg font-medium mb-2">Failures</h2><ul>${failures}</ul>`;
+    }
+}
+
 // detect wiring
 function parseWiring(row, wires) {
     let wiring = '';
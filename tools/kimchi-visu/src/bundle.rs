@@ -0,0 +1,255 @@
+//! The third-party JS and CSS used by the page,
+//! loaded from their CDN or inlined in the page so that it works offline.
+//!
+//! The inlined assets are downloaded once with `curl`, and cached in a directory:
+//! the pinned ones are checked against their integrity each time they are inlined,
+//! and the fonts of KaTeX are embedded in its stylesheet as data URIs (see [inline_fonts]).
+
+use sha2::{Digest, Sha384};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Where the page loads its third-party assets from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Assets {
+    /// From their CDN.
+    Cdn,
+    /// Inlined in the page, from copies of the [ASSETS] cached in a directory,
+    /// which are downloaded into it the first time (see [Asset::fetch]).
+    Inline(PathBuf),
+}
+
+/// The kind of an asset, which decides how it is included in the page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetKind {
+    Script,
+    Stylesheet,
+}
+
+/// A third-party asset of the page.
+#[derive(Debug, Clone, Copy)]
+pub struct Asset {
+    /// The name of the cached copy of the asset, in the directory of [Assets::Inline].
+    pub file: &'static str,
+    /// Where the asset is downloaded from.
+    pub url: &'static str,
+    /// The subresource integrity of the asset, if it is pinned.
+    pub integrity: Option<&'static str>,
+    /// Whether the asset is a script or a stylesheet.
+    pub kind: AssetKind,
+    /// Whether the asset is loaded in the head of the page, or at the end of its body.
+    pub in_head: bool,
+}
+
+/// The assets of the page, in the order they are loaded.
+pub const ASSETS: [Asset; 6] = [
+    Asset {
+        file: "tailwindcss.js",
+        url: "https://cdn.tailwindcss.com",
+        integrity: None,
+        kind: AssetKind::Script,
+        in_head: true,
+    },
+    Asset {
+        file: "flowbite.min.css",
+        url: "https://unpkg.com/flowbite@1.3.4/dist/flowbite.min.css",
+        integrity: None,
+        kind: AssetKind::Stylesheet,
+        in_head: true,
+    },
+    Asset {
+        file: "katex.min.css",
+        url: "https://cdn.jsdelivr.net/npm/katex@0.15.2/dist/katex.min.css",
+        integrity: Some("sha384-MlJdn/WNKDGXveldHDdyRP1R4CTHr3FeuDNfhsLPYrq2t0UBkUdK2jyTnXPEK1NQ"),
+        kind: AssetKind::Stylesheet,
+        in_head: true,
+    },
+    Asset {
+        file: "flowbite.js",
+        url: "https://unpkg.com/flowbite@1.3.4/dist/flowbite.js",
+        integrity: None,
+        kind: AssetKind::Script,
+        in_head: false,
+    },
+    Asset {
+        file: "katex.min.js",
+        url: "https://cdn.jsdelivr.net/npm/katex@0.15.2/dist/katex.min.js",
+        integrity: Some("sha384-VQ8d8WVFw0yHhCk5E8I86oOhv48xLpnDZx5T9GogA/Y84DcCKWXDmSDfn13bzFZY"),
+        kind: AssetKind::Script,
+        in_head: false,
+    },
+    Asset {
+        file: "auto-render.min.js",
+        url: "https://cdn.jsdelivr.net/npm/katex@0.15.2/dist/contrib/auto-render.min.js",
+        integrity: Some("sha384-+XBljXPPiv+OzfbB3cVmLHf4hdUFHlWNZN5spNQ7rmHTXpd7WvJum6fIACpNNfIR"),
+        kind: AssetKind::Script,
+        in_head: false,
+    },
+];
+
+/// Downloads `url` to `path` with `curl`, creating the directories of `path`.
+fn download(url: &str, path: &Path) -> io::Result<()> {
+    let status = Command::new("curl")
+        .args([
+            "--fail",
+            "--silent",
+            "--show-error",
+            "--location",
+            "--create-dirs",
+        ])
+        .arg("--output")
+        .arg(path)
+        .arg(url)
+        .status()
+        .map_err(|e| io::Error::new(e.kind(), format!("curl: {e}")))?;
+    if status.success() {
+        Ok(())
+    } else {
+        // don't cache a partial download
+        let _ = fs::remove_file(path);
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("curl couldn't download {url} ({status})"),
+        ))
+    }
+}
+
+/// Returns the content of `path`, which is downloaded from `url` if it does not exist.
+fn cached(url: &str, path: &Path) -> io::Result<Vec<u8>> {
+    if !path.exists() {
+        download(url, path)?;
+    }
+    fs::read(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))
+}
+
+/// Encodes `bytes` in base64, with padding.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Replaces the WOFF2 fonts that the stylesheet `css` downloaded from `url` references
+/// (as `url(fonts/<name>.woff2)`, as KaTeX does) by data URIs,
+/// from their copies cached in the `fonts` folder of `dir`, which are downloaded the first time.
+/// The fonts in other formats are left as they are, as browsers pick the first format they support.
+pub fn inline_fonts(css: &str, url: &str, dir: &Path) -> io::Result<String> {
+    const PREFIX: &str = "url(fonts/";
+    let base = &url[..url.rfind('/').map_or(0, |i| i + 1)];
+    let mut inlined = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find(PREFIX) {
+        let end = start
+            + rest[start..]
+                .find(')')
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unclosed url("))?;
+        let font = &rest[start + 4..end];
+        inlined.push_str(&rest[..start]);
+        if font.ends_with(".woff2") {
+            let content = cached(&format!("{base}{font}"), &dir.join(font))?;
+            inlined.push_str(&format!("url(data:font/woff2;base64,{})", base64(&content)));
+        } else {
+            inlined.push_str(&rest[start..=end]);
+        }
+        rest = &rest[end + 1..];
+    }
+    inlined.push_str(rest);
+    Ok(inlined)
+}
+
+impl Asset {
+    /// Returns the content of the asset, from its copy cached in `dir`, which is downloaded the first time.
+    /// Fails if it does not match the integrity of the asset, if pinned.
+    pub fn fetch(&self, dir: &Path) -> io::Result<String> {
+        let path = dir.join(self.file);
+        let content = cached(self.url, &path)?;
+        if let Some(integrity) = self.integrity {
+            let digest = format!("sha384-{}", base64(&Sha384::digest(&content)));
+            if digest != integrity {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "{} does not match the integrity of {} (remove it to download it again)",
+                        path.display(),
+                        self.url
+                    ),
+                ));
+            }
+        }
+        String::from_utf8(content).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {e}", path.display()),
+            )
+        })
+    }
+
+    /// Returns the HTML tag loading the asset, inlining its content if given.
+    fn tag(&self, content: Option<&str>) -> String {
+        let integrity = self
+            .integrity
+            .map(|integrity| format!(r#" integrity="{integrity}" crossorigin="anonymous""#))
+            .unwrap_or_default();
+        match (self.kind, content) {
+            (AssetKind::Script, None) => {
+                format!(r#"<script src="{}"{integrity}></script>"#, self.url)
+            }
+            (AssetKind::Stylesheet, None) => {
+                format!(
+                    r#"<link rel="stylesheet" href="{}"{integrity} />"#,
+                    self.url
+                )
+            }
+            // a script can't contain its closing tag
+            (AssetKind::Script, Some(content)) => {
+                format!(
+                    "<script>{}</script>",
+                    content.replace("</script", r"<\/script")
+                )
+            }
+            (AssetKind::Stylesheet, Some(content)) => format!("<style>{content}</style>"),
+        }
+    }
+}
+
+impl Assets {
+    /// Returns the tags loading the assets in the head of the page, and at the end of its body.
+    pub fn tags(&self) -> io::Result<(String, String)> {
+        let mut head = vec![];
+        let mut body = vec![];
+        for asset in &ASSETS {
+            let tag = match self {
+                Assets::Cdn => asset.tag(None),
+                Assets::Inline(dir) => {
+                    let mut content = asset.fetch(dir)?;
+                    if asset.kind == AssetKind::Stylesheet {
+                        content = inline_fonts(&content, asset.url, dir)?;
+                    }
+                    asset.tag(Some(&content))
+                }
+            };
+            if asset.in_head {
+                head.push(tag);
+            } else {
+                body.push(tag);
+            }
+        }
+        Ok((head.join("\n    "), body.join("\n    ")))
+    }
+}
@@ -0,0 +1,152 @@
+//! Checks a witness against a circuit, row by row,
+//! so that the failing constraints can be highlighted.
+
+use ark_ff::{FftField, Field, SquareRootField};
+use array_init::array_init;
+use kimchi::circuits::{
+    constraints::ConstraintSystem,
+    evaluators,
+    gate::{CircuitGate, GateType},
+    polynomials::generic::{GENERIC_COEFFS, GENERIC_REGISTERS},
+    wires::{Wire, COLUMNS, PERMUTS},
+};
+use serde::Serialize;
+use serde_with::serde_as;
+
+/// A cell that does not have the same value as the cell it is wired to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BrokenWire {
+    /// The column of the cell, in the checked row.
+    pub col: usize,
+    /// The cell it is wired to.
+    pub wire: Wire,
+}
+
+/// The result of checking a row of the circuit against the witness.
+#[serde_as]
+#[derive(Debug, Serialize)]
+pub struct RowCheck<F>
+where
+    F: Field,
+{
+    /// The values of the constraints of the gate, which are all zero on a valid row.
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub constraints: Vec<F>,
+    /// The indexes of the constraints that do not evaluate to zero.
+    pub failing: Vec<usize>,
+    /// The error returned by [CircuitGate::verify], if any.
+    pub error: Option<String>,
+    /// The copy constraints of the row that do not hold.
+    pub broken_wires: Vec<BrokenWire>,
+}
+
+impl<F> RowCheck<F>
+where
+    F: Field,
+{
+    /// Returns true if the row satisfies its gate and copy constraints.
+    pub fn is_ok(&self) -> bool {
+        self.failing.is_empty() && self.error.is_none() && self.broken_wires.is_empty()
+    }
+}
+
+/// Checks each row of the circuit against the witness.
+/// The witness is padded with zeros up to the number of rows of the circuit,
+/// and the public inputs are read from the first column, as done by the prover.
+/// As the rows of the domain wrap around, the first row of the witness is copied after its last row,
+/// so that a gate of the last row that reads the next row reads the first row instead of panicking.
+pub fn check_witness<F>(cs: &ConstraintSystem<F>, witness: &[Vec<F>; COLUMNS]) -> Vec<RowCheck<F>>
+where
+    F: FftField + SquareRootField,
+{
+    let rows = cs.gates.len();
+    let witness: [Vec<F>; COLUMNS] = array_init(|col| {
+        let mut column = witness[col].clone();
+        column.resize(std::cmp::max(rows, column.len()), F::zero());
+        column.push(column[0]);
+        column
+    });
+    let public = &witness[0][0..cs.public];
+
+    cs.gates
+        .iter()
+        .enumerate()
+        .map(|(row, gate)| {
+            let constraints = gate_constraints(gate, row, &witness, cs, public);
+            let failing = constraints
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| !c.is_zero())
+                .map(|(i, _)| i)
+                .collect();
+
+            let broken_wires = gate
+                .wires
+                .iter()
+                .enumerate()
+                .take(PERMUTS)
+                .filter(|(col, wire)| witness[*col][row] != witness[wire.col][wire.row])
+                .map(|(col, wire)| BrokenWire { col, wire: *wire })
+                .collect();
+
+            RowCheck {
+                constraints,
+                failing,
+                error: gate.verify(row, &witness, cs, public).err(),
+                broken_wires,
+            }
+        })
+        .collect()
+}
+
+/// Evaluates the constraints of the gate at `row`.
+fn gate_constraints<F>(
+    gate: &CircuitGate<F>,
+    row: usize,
+    witness: &[Vec<F>; COLUMNS],
+    cs: &ConstraintSystem<F>,
+    public: &[F],
+) -> Vec<F>
+where
+    F: FftField,
+{
+    let curr: [F; COLUMNS] = array_init(|i| witness[i][row]);
+    let next: [F; COLUMNS] = array_init(|i| witness[i][row + 1]);
+    let coeffs: Vec<F> = (0..COLUMNS)
+        .map(|i| gate.coeffs.get(i).cloned().unwrap_or_else(F::zero))
+        .collect();
+    let (curr, next, coeffs, endo, mds) =
+        (&curr, &next, &coeffs, cs.endo, &cs.fr_sponge_params.mds);
+
+    use GateType::*;
+    match gate.typ {
+        Zero => vec![],
+        Generic => {
+            // see [CircuitGate::verify_generic]
+            let generic = |coeffs_offset, register_offset| {
+                let sum = (0..GENERIC_REGISTERS)
+                    .map(|i| coeffs[coeffs_offset + i] * curr[register_offset + i])
+                    .sum::<F>();
+                let mul =
+                    coeffs[coeffs_offset + 3] * curr[register_offset] * curr[register_offset + 1];
+                sum + mul + coeffs[coeffs_offset + 4]
+            };
+            let public = public.get(row).cloned().unwrap_or_else(F::zero);
+            vec![
+                generic(0, 0) - public,
+                generic(GENERIC_COEFFS, GENERIC_REGISTERS),
+            ]
+        }
+        Poseidon => evaluators::poseidon(curr, next, coeffs, endo, mds).to_vec(),
+        CompleteAdd => evaluators::complete_add(curr, next, coeffs, endo, mds).to_vec(),
+        VarBaseMul => evaluators::varbasemul(curr, next, coeffs, endo, mds).to_vec(),
+        EndoMul => evaluators::endosclmul(curr, next, coeffs, endo, mds).to_vec(),
+        EndoMulScalar => evaluators::endomul_scalar(curr, next, coeffs, endo, mds).to_vec(),
+        ChaCha0 => evaluators::chacha0(curr, next, coeffs, endo, mds).to_vec(),
+        ChaCha1 => evaluators::chacha1(curr, next, coeffs, endo, mds).to_vec(),
+        ChaCha2 => evaluators::chacha2(curr, next, coeffs, endo, mds).to_vec(),
+        ChaChaFinal => evaluators::chacha_final(curr, next, coeffs, endo, mds).to_vec(),
+        FixedBaseMul => evaluators::fixed_base_mul(curr, next, coeffs, endo, mds).to_vec(),
+        PoseidonLegacy => evaluators::poseidon_legacy(curr, next, coeffs, endo, mds).to_vec(),
+    }
+}
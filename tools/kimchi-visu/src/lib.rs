@@ -11,7 +11,9 @@ use kimchi::{
             complete_add::CompleteAdd,
             endomul_scalar::EndomulScalar,
             endosclmul::EndosclMul,
+            fixed_base_mul::FixedBaseMul,
             poseidon::Poseidon,
+            poseidon_legacy::PoseidonLegacy,
             varbasemul::VarbaseMul,
         },
     },
@@ -21,19 +23,32 @@ use serde::Serialize;
 use std::{
     collections::HashMap,
     fmt::Display,
-    fs::{self, File},
-    io::Write,
-    path::Path,
+    fs::File,
+    io::{self, Write},
 };
 use tinytemplate::TinyTemplate;
 
+pub mod bundle;
+pub mod checks;
+pub mod wiring;
 pub mod witness;
 
+pub use bundle::Assets;
+pub use checks::{check_witness, RowCheck};
+pub use wiring::Wiring;
 pub use witness::Witness;
 
+/// The template of the page
+const TEMPLATE: &str = include_str!("assets/template.html");
+
+/// The script rendering the circuit in the page
+const SCRIPT: &str = include_str!("assets/script.js");
+
 /// Contains variable used in the template
 #[derive(Serialize)]
 struct Context {
+    head: String,
+    body: String,
     js: String,
     data: String,
 }
@@ -73,23 +88,43 @@ where
     map.insert("ChaCha1", ChaCha1::<Fr<G>>::latex());
     map.insert("ChaCha2", ChaCha2::<Fr<G>>::latex());
     map.insert("ChaChaFinal", ChaChaFinal::<Fr<G>>::latex());
+    map.insert("FixedBaseMul", FixedBaseMul::<Fr<G>>::latex());
+    map.insert("PoseidonLegacy", PoseidonLegacy::<Fr<G>>::latex());
     map
 }
 
-/// Produces a `circuit.html` in the current folder.
-pub fn visu<G>(index: &ProverIndex<G>, witness: Option<Witness<Fr<G>>>)
+/// Renders the HTML page of a circuit, loading its third-party assets as specified.
+/// If a witness is given, the rows that do not satisfy their gate or copy constraints are highlighted.
+pub fn render<G>(
+    index: &ProverIndex<G>,
+    witness: Option<Witness<Fr<G>>>,
+    assets: &Assets,
+) -> io::Result<String>
 where
     G: CommitmentCurve,
 {
     // serialize index
-    let index = serde_json::to_string(index).expect("couldn't serialize index");
-    let mut data = format!("const index = {index};");
-
-    // serialize witness
+    let mut data = {
+        let index = serde_json::to_string(index).expect("couldn't serialize index");
+        format!("const index = {index};")
+    };
+
+    // serialize the permutation cycles, and their DOT export
+    let wiring = Wiring::create(&index.cs.gates, index.cs.public);
+    let dot = serde_json::to_string(&wiring.to_dot()).expect("couldn't serialize dot");
+    let wiring = serde_json::to_string(&wiring).expect("couldn't serialize wiring");
+    data.push_str(&format!("const wiring = {wiring};const dot = {dot};"));
+
+    // serialize witness, and the result of checking it against the circuit
     if let Some(witness) = witness {
+        let checks = check_witness(&index.cs, witness.columns());
+        let checks = serde_json::to_string(&checks).expect("couldn't serialize checks");
+        data.push_str(&format!("const checks = {checks};"));
+
         let witness = serde_json::to_string(&witness).expect("couldn't serialize witness");
         data.push_str(&format!("const witness = {witness};"));
     } else {
+        data.push_str("const checks = null;");
         data.push_str("const witness = null;");
     }
 
@@ -99,34 +134,36 @@ where
     data.push_str(&format!("const constraints = {constraints};"));
 
     // create template
-    let template_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/assets/template.html");
-    let template = fs::read_to_string(&template_path).unwrap_or_else(|e| {
-        format!(
-            "could not read template file {}: {e}",
-            template_path.display()
-        )
-    });
-
     let mut tt = TinyTemplate::new();
     tt.set_default_formatter(&tinytemplate::format_unescaped);
-    tt.add_template("circuit", &template)
+    tt.add_template("circuit", TEMPLATE)
         .expect("could not create template");
 
     // render
+    let (head, body) = assets.tags()?;
+    let context = Context {
+        head,
+        body,
+        js: SCRIPT.to_string(),
+        data,
+    };
+
+    Ok(tt
+        .render("circuit", &context)
+        .unwrap_or_else(|e| panic!("template file can't be rendered: {}", e)))
+}
+
+/// Produces a `circuit.html` in the current folder, loading its assets from their CDN.
+/// If a witness is given, the rows that do not satisfy their gate or copy constraints are highlighted.
+pub fn visu<G>(index: &ProverIndex<G>, witness: Option<Witness<Fr<G>>>)
+where
+    G: CommitmentCurve,
+{
+    let rendered = render(index, witness, &Assets::Cdn).unwrap_or_else(|e| panic!("{e}"));
+
     let html_output = std::env::current_dir()
         .expect("no current directory?")
         .join("circuit.html");
-
-    let js_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/assets/script.js");
-    let js = fs::read_to_string(&js_path)
-        .unwrap_or_else(|e| format!("could not read js file {}: {e}", js_path.display()));
-
-    let context = Context { js, data };
-
-    let rendered = tt
-        .render("circuit", &context)
-        .unwrap_or_else(|e| panic!("template file can't be rendered: {}", e));
-
     let mut file = File::create(html_output).unwrap_or_else(|e| panic!("{e}"));
     write!(&mut file, "{rendered}").expect("couldn't write the file on disk");
 }
@@ -1,15 +1,117 @@
+//! Renders a circuit over the Pasta curves, and optionally a witness, as an HTML page.
+
+use clap::{ArgEnum, Parser};
 use kimchi::{
     circuits::{
         gate::CircuitGate,
         polynomials::{generic::GenericGateSpec, poseidon::generate_witness},
         wires::Wire,
     },
-    prover_index::testing::new_index_for_test,
+    prover_index::{testing::new_index_for_test, ProverIndex},
 };
-use kimchi_visu::{visu, Witness};
-use mina_curves::pasta::Fp;
+use kimchi_visu::{render, Assets, Witness};
+use mina_curves::pasta::{vesta::Affine, Fp};
+use serde::de::DeserializeOwned;
+use std::{fs, path::Path, path::PathBuf, process};
 
-fn main() {
+/// The format of the serialized circuit and witness
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+enum Format {
+    Json,
+    Msgpack,
+}
+
+impl Format {
+    /// Guesses the format of a file from its extension.
+    fn of_path(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Ok(Format::Json),
+            Some("msgpack" | "mpk" | "mp") => Ok(Format::Msgpack),
+            _ => Err(format!(
+                "can't guess the format of {} from its extension, use --format",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// Visualizes a kimchi circuit as an HTML page
+#[derive(Debug, Parser)]
+#[clap(name = "kimchi-visu")]
+struct Cli {
+    /// The circuit, as a serialized `Vec<CircuitGate<Fp>>` or `ProverIndex<vesta::Affine>`.
+    /// Without it, a circuit hashing its public input with Poseidon is rendered.
+    circuit: Option<PathBuf>,
+
+    /// The witness, as a serialized `Witness<Fp>`
+    #[clap(short, long)]
+    witness: Option<PathBuf>,
+
+    /// The number of public inputs of a circuit given as gates
+    #[clap(short, long, default_value_t = 0)]
+    public: usize,
+
+    /// The format of the circuit and the witness, guessed from their extension by default
+    #[clap(short, long, arg_enum)]
+    format: Option<Format>,
+
+    /// The HTML page to write
+    #[clap(short, long, default_value = "circuit.html")]
+    output: PathBuf,
+
+    /// Inlines the JS and CSS assets and the fonts of KaTeX, so that the page works offline.
+    /// They are cached in this directory, and downloaded into it the first time
+    #[clap(long, value_name = "ASSETS_DIR")]
+    static_bundle: Option<PathBuf>,
+}
+
+/// Reads a file, and the format of its content.
+fn read(path: &Path, format: Option<Format>) -> Result<(Vec<u8>, Format), String> {
+    let format = format.map_or_else(|| Format::of_path(path), Ok)?;
+    let bytes = fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok((bytes, format))
+}
+
+/// Deserializes a value.
+fn parse<T: DeserializeOwned>(bytes: &[u8], format: Format) -> Result<T, String> {
+    match format {
+        Format::Json => serde_json::from_slice(bytes).map_err(|e| e.to_string()),
+        Format::Msgpack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+    }
+}
+
+/// Reads a circuit given as gates, or as a prover index.
+fn read_index(
+    path: &Path,
+    format: Option<Format>,
+    public: usize,
+) -> Result<ProverIndex<Affine>, String> {
+    let (bytes, format) = read(path, format)?;
+    let gates = match parse::<Vec<CircuitGate<Fp>>>(&bytes, format) {
+        Ok(gates) => return Ok(new_index_for_test(gates, public)),
+        Err(e) => e,
+    };
+    let mut index = parse::<ProverIndex<Affine>>(&bytes, format).map_err(|index| {
+        format!(
+            "{} is neither a list of gates ({gates}) nor a prover index ({index})",
+            path.display()
+        )
+    })?;
+
+    // the sponge parameters are not serialized
+    index.cs.fr_sponge_params = oracle::pasta::fp_kimchi::params();
+    index.fq_sponge_params = oracle::pasta::fq_kimchi::params();
+    Ok(index)
+}
+
+/// Reads a witness.
+fn read_witness(path: &Path, format: Option<Format>) -> Result<Witness<Fp>, String> {
+    let (bytes, format) = read(path, format)?;
+    parse(&bytes, format).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// A circuit hashing its 3 public inputs with Poseidon, and its witness.
+fn example() -> (ProverIndex<Affine>, Witness<Fp>) {
     let public = 3;
     let poseidon_params = oracle::pasta::fp_kimchi::params();
 
@@ -50,8 +152,8 @@ fn main() {
 
             let poseidon_output = &mut gates[row].wires;
             poseidon_output[0] = Wire { row: 0, col: 0 };
-            poseidon_output[1] = Wire { row: 0, col: 1 };
-            poseidon_output[2] = Wire { row: 0, col: 2 };
+            poseidon_output[1] = Wire { row: 1, col: 0 };
+            poseidon_output[2] = Wire { row: 2, col: 0 };
         }
 
         (gates, row)
@@ -65,6 +167,38 @@ fn main() {
     let input = [1u32.into(), 2u32.into(), 3u32.into()];
     generate_witness(3, poseidon_params, &mut witness, input);
 
+    // the public input is the output of poseidon
+    let output: Vec<Fp> = witness[0..public].iter().map(|col| col[row]).collect();
+    witness[0][0..public].copy_from_slice(&output);
+
+    (index, witness.into())
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let (index, witness) = match &cli.circuit {
+        Some(circuit) => (read_index(circuit, cli.format, cli.public)?, None),
+        None => {
+            let (index, witness) = example();
+            (index, Some(witness))
+        }
+    };
+    let witness = match &cli.witness {
+        Some(path) => Some(read_witness(path, cli.format)?),
+        None => witness,
+    };
+    let assets = match cli.static_bundle {
+        Some(dir) => Assets::Inline(dir),
+        None => Assets::Cdn,
+    };
+
     // create the HTML
-    visu(&index, Some(witness.into()));
+    let rendered = render(&index, witness, &assets).map_err(|e| e.to_string())?;
+    fs::write(&cli.output, rendered).map_err(|e| format!("{}: {e}", cli.output.display()))
+}
+
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("error: {e}");
+        process::exit(1);
+    }
 }
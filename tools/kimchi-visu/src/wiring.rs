@@ -0,0 +1,106 @@
+//! Computes the permutation cycles of a circuit (its copy constraints),
+//! so that they can be drawn or exported to Graphviz.
+
+use ark_ff::FftField;
+use kimchi::circuits::{
+    gate::CircuitGate,
+    lint::{cycles, lookup_cells},
+    wires::Wire,
+};
+use serde::Serialize;
+use std::{collections::BTreeSet, fmt::Write};
+
+/// Returns the positions of `cells`, so that the membership of a cell can be tested in logarithmic time.
+fn positions<'a>(cells: impl IntoIterator<Item = &'a Wire>) -> BTreeSet<(usize, usize)> {
+    cells.into_iter().map(|cell| (cell.row, cell.col)).collect()
+}
+
+/// The copy constraints of a circuit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Wiring {
+    /// The cycles of the permutation with more than one cell.
+    /// Each cell of a cycle is wired to the next one, and the last one to the first one.
+    pub cycles: Vec<Vec<Wire>>,
+    /// The cells containing the public inputs.
+    pub public: Vec<Wire>,
+    /// The cells read by the lookups of the gates.
+    pub lookups: Vec<Wire>,
+}
+
+impl Wiring {
+    /// Computes the permutation cycles of the gates,
+    /// and marks the cells of the `public` first rows and the cells used by lookups.
+    pub fn create<F>(gates: &[CircuitGate<F>], public: usize) -> Self
+    where
+        F: FftField,
+    {
+        let cycles = cycles(gates).filter(|cycle| cycle.len() > 1).collect();
+        let public = (0..public).map(|row| Wire { row, col: 0 }).collect();
+        let lookups = lookup_cells(gates)
+            .into_iter()
+            .map(|(row, col)| Wire { row, col })
+            .collect();
+
+        Wiring {
+            cycles,
+            public,
+            lookups,
+        }
+    }
+
+    /// Exports the cycles in the Graphviz DOT format,
+    /// with a cluster per cycle and the public and lookup cells filled in color.
+    /// The public inputs that are not wired are exported as isolated nodes.
+    pub fn to_dot(&self) -> String {
+        let name = |cell: &Wire| format!("\"{}_{}\"", cell.row, cell.col);
+        let public = positions(&self.public);
+        let lookups = positions(&self.lookups);
+        let wired = positions(self.cycles.iter().flatten());
+        let mut dot = String::from(
+            "digraph wiring {\n    node [shape=box, style=filled, fillcolor=white];\n",
+        );
+
+        for (i, cycle) in self.cycles.iter().enumerate() {
+            writeln!(dot, "    subgraph cluster_{i} {{").unwrap();
+            writeln!(dot, "        label=\"cycle {i}\";").unwrap();
+            for cell in cycle {
+                let color = if public.contains(&(cell.row, cell.col)) {
+                    "lightblue"
+                } else if lookups.contains(&(cell.row, cell.col)) {
+                    "orange"
+                } else {
+                    "white"
+                };
+                writeln!(
+                    dot,
+                    "        {} [label=\"row {}, col {}\", fillcolor={color}];",
+                    name(cell),
+                    cell.row,
+                    cell.col
+                )
+                .unwrap();
+            }
+            for (from, to) in cycle.iter().zip(cycle.iter().cycle().skip(1)) {
+                writeln!(dot, "        {} -> {};", name(from), name(to)).unwrap();
+            }
+            dot.push_str("    }\n");
+        }
+
+        // the public inputs that are not wired to anything
+        for cell in &self.public {
+            if !wired.contains(&(cell.row, cell.col)) {
+                writeln!(
+                    dot,
+                    "    {} [label=\"row {}, col {}\", fillcolor=lightblue];",
+                    name(cell),
+                    cell.row,
+                    cell.col
+                )
+                .unwrap();
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -1,14 +1,15 @@
 use ark_ff::Field;
 use array_init::array_init;
 use kimchi::circuits::polynomial::COLUMNS;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 /// The type that represents the execution trace.
 /// It represents a table of [COLUMNS] columns, with `n` rows.
 /// `n` being the maximum size of the circuit, and the size of the domain.
 #[serde_as]
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound = "F: Field")]
 pub struct Witness<F>
 where
     F: Field,
@@ -29,6 +30,11 @@ where
         }
     }
 
+    /// Returns the columns of the witness.
+    pub fn columns(&self) -> &[Vec<F>; COLUMNS] {
+        &self.inner
+    }
+
     /// Returns the inner witness.
     // TODO: deprecate this
     pub fn inner(self) -> [Vec<F>; COLUMNS] {
@@ -0,0 +1,46 @@
+use kimchi_visu::bundle::{inline_fonts, ASSETS};
+use std::{fs, io, path::PathBuf};
+
+/// An empty directory for the cached assets of a test
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("kimchi-visu-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("fonts")).unwrap();
+    dir
+}
+
+#[test]
+fn bundle_inline_fonts() {
+    let dir = cache_dir("fonts");
+    fs::write(dir.join("fonts/KaTeX_Main-Regular.woff2"), b"foobar!").unwrap();
+    fs::write(dir.join("fonts/KaTeX_AMS-Regular.woff2"), b"fo").unwrap();
+    let css = r#"@font-face{src:url(fonts/KaTeX_Main-Regular.woff2) format("woff2"),url(fonts/KaTeX_Main-Regular.woff) format("woff")}@font-face{src:url(fonts/KaTeX_AMS-Regular.woff2)}"#;
+    let url = "https://cdn.jsdelivr.net/npm/katex@0.15.2/dist/katex.min.css";
+
+    // the cached WOFF2 font is embedded, and the other formats are left as they are
+    let inlined = inline_fonts(css, url, &dir).unwrap();
+    assert_eq!(
+        inlined,
+        r#"@font-face{src:url(data:font/woff2;base64,Zm9vYmFyIQ==) format("woff2"),url(fonts/KaTeX_Main-Regular.woff) format("woff")}@font-face{src:url(data:font/woff2;base64,Zm8=)}"#
+    );
+    assert_eq!(inline_fonts("body{}", url, &dir).unwrap(), "body{}");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn bundle_cached_assets() {
+    let dir = cache_dir("assets");
+
+    // the cached copies are read instead of being downloaded
+    let flowbite = ASSETS.iter().find(|a| a.file == "flowbite.js").unwrap();
+    assert!(flowbite.integrity.is_none());
+    fs::write(dir.join(flowbite.file), "var x;").unwrap();
+    assert_eq!(flowbite.fetch(&dir).unwrap(), "var x;");
+
+    // and checked against the integrity of the pinned assets
+    let katex = ASSETS.iter().find(|a| a.file == "katex.min.js").unwrap();
+    fs::write(dir.join(katex.file), "var katex;").unwrap();
+    let err = katex.fetch(&dir).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    fs::remove_dir_all(dir).unwrap();
+}
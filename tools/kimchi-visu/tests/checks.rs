@@ -0,0 +1,95 @@
+use ark_ff::Zero;
+use kimchi::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, GateType},
+    polynomials::generic::testing::{create_circuit, fill_in_witness},
+    wires::{Wire, COLUMNS},
+};
+use kimchi_visu::{check_witness, checks::BrokenWire};
+use mina_curves::pasta::fp::Fp;
+
+/// The public inputs of the generic circuit
+const PUBLIC: [u64; 2] = [3, 7];
+
+/// A generic circuit with public inputs, and its witness
+fn generic_circuit(gates: Vec<CircuitGate<Fp>>) -> (ConstraintSystem<Fp>, [Vec<Fp>; COLUMNS]) {
+    let public: Vec<Fp> = PUBLIC.iter().map(|x| Fp::from(*x)).collect();
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init::array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+    let cs = ConstraintSystem::create(
+        gates,
+        vec![],
+        oracle::pasta::fp_kimchi::params(),
+        public.len(),
+    )
+    .unwrap();
+    (cs, witness)
+}
+
+#[test]
+fn check_witness_generic() {
+    let (cs, mut witness) = generic_circuit(create_circuit(0, PUBLIC.len()));
+    let checks = check_witness(&cs, &witness);
+    assert_eq!(checks.len(), cs.gates.len());
+    assert!(checks.iter().all(|check| check.is_ok()));
+
+    // the output of the addition of the first generic gate after the public inputs
+    let row = PUBLIC.len();
+    witness[2][row] += Fp::from(1u64);
+    let checks = check_witness(&cs, &witness);
+    assert_eq!(checks[row].failing, vec![0]);
+    assert!(checks[row].error.is_some());
+    assert!(checks[row].broken_wires.is_empty());
+    assert!(checks
+        .iter()
+        .enumerate()
+        .all(|(i, check)| i == row || check.is_ok()));
+}
+
+#[test]
+fn check_witness_broken_wires() {
+    // the public inputs are wired together, but differ
+    let mut gates = create_circuit(0, PUBLIC.len());
+    gates[0].wires[0] = Wire { row: 1, col: 0 };
+    gates[1].wires[0] = Wire { row: 0, col: 0 };
+    let (cs, witness) = generic_circuit(gates);
+
+    let checks = check_witness(&cs, &witness);
+    for (row, check) in checks.iter().enumerate().take(2) {
+        assert!(check.failing.is_empty());
+        assert_eq!(
+            check.broken_wires,
+            vec![BrokenWire {
+                col: 0,
+                wire: Wire {
+                    row: 1 - row,
+                    col: 0
+                }
+            }]
+        );
+        assert!(!check.is_ok());
+    }
+    assert!(checks[2..].iter().all(|check| check.is_ok()));
+}
+
+#[test]
+fn check_witness_last_row() {
+    // the Poseidon gate of the last row reads the next row, which is not in the witness
+    let gates = vec![
+        CircuitGate::<Fp>::zero(Wire::new(0)),
+        CircuitGate {
+            typ: GateType::Poseidon,
+            wires: Wire::new(1),
+            coeffs: vec![Fp::zero(); COLUMNS],
+        },
+    ];
+    let mut cs =
+        ConstraintSystem::create(gates, vec![], oracle::pasta::fp_kimchi::params(), 0).unwrap();
+    // the constraint system pads the gates with zero gates up to the size of the domain
+    cs.gates.truncate(2);
+    let witness: [Vec<Fp>; COLUMNS] = array_init::array_init(|_| vec![Fp::zero(); 2]);
+
+    let checks = check_witness(&cs, &witness);
+    assert_eq!(checks.len(), 2);
+    assert!(checks.iter().all(|check| check.is_ok()), "{:?}", checks);
+}
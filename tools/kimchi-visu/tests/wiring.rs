@@ -0,0 +1,77 @@
+use kimchi::circuits::{
+    gate::{CircuitGate, GateType},
+    polynomials::chacha,
+    wires::Wire,
+};
+use kimchi_visu::Wiring;
+use mina_curves::pasta::fp::Fp;
+
+/// Three zero gates, with the first cells of the first two rows wired together
+fn wired_gates() -> Vec<CircuitGate<Fp>> {
+    let mut gates: Vec<_> = (0..3)
+        .map(|row| CircuitGate::zero(Wire::new(row)))
+        .collect();
+    gates[0].wires[0] = Wire { row: 1, col: 1 };
+    gates[1].wires[1] = Wire { row: 0, col: 0 };
+    gates
+}
+
+#[test]
+fn wiring_cycles() {
+    let wiring = Wiring::create(&wired_gates(), 2);
+
+    // the identity wires are not cycles
+    assert_eq!(
+        wiring.cycles,
+        vec![vec![Wire { row: 0, col: 0 }, Wire { row: 1, col: 1 }]]
+    );
+    assert_eq!(
+        wiring.public,
+        vec![Wire { row: 0, col: 0 }, Wire { row: 1, col: 0 }]
+    );
+    assert!(wiring.lookups.is_empty());
+}
+
+#[test]
+fn wiring_lookups() {
+    let gates: Vec<_> = chacha::testing::chacha20_gates()
+        .into_iter()
+        .enumerate()
+        .map(|(i, typ)| CircuitGate::<Fp> {
+            typ,
+            coeffs: vec![],
+            wires: Wire::new(i),
+        })
+        .collect();
+    let wiring = Wiring::create(&gates, 0);
+
+    assert!(wiring.cycles.is_empty());
+    assert!(!wiring.lookups.is_empty());
+    // the lookups of a ChaCha0 gate read its row and the next one
+    assert_eq!(gates[0].typ, GateType::ChaCha0);
+    assert!(wiring.lookups.iter().any(|cell| cell.row == 0));
+    assert!(wiring.lookups.iter().any(|cell| cell.row == 1));
+    assert!(wiring.lookups.iter().all(|cell| cell.row <= gates.len()));
+}
+
+#[test]
+fn wiring_to_dot() {
+    let mut wiring = Wiring::create(&wired_gates(), 2);
+    wiring.lookups.push(Wire { row: 1, col: 1 });
+    let dot = wiring.to_dot();
+
+    assert!(dot.starts_with("digraph wiring {\n"));
+    assert!(dot.ends_with("}\n"));
+    assert!(dot.contains("subgraph cluster_0 {"));
+    assert!(!dot.contains("cluster_1"));
+    assert!(dot.contains("\"0_0\" -> \"1_1\";"));
+    assert!(dot.contains("\"1_1\" -> \"0_0\";"));
+
+    // the wired public input is in its cycle, and the other one is an isolated node
+    assert!(dot.contains("\"0_0\" [label=\"row 0, col 0\", fillcolor=lightblue];"));
+    assert_eq!(dot.matches("\"0_0\" [label").count(), 1);
+    assert!(dot.contains("    \"1_0\" [label=\"row 1, col 0\", fillcolor=lightblue];"));
+
+    // the lookup cell is filled in orange
+    assert!(dot.contains("\"1_1\" [label=\"row 1, col 1\", fillcolor=orange];"));
+}
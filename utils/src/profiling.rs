@@ -0,0 +1,228 @@
+//! This module provides a lightweight timing collector,
+//! used to attribute the time spent by the prover and the verifier to their different phases.
+//!
+//! Timings are only collected when the `profiling` feature is enabled.
+//! Otherwise, spans are zero-sized and the summary is always empty.
+//!
+//! Timings are collected by all the threads of the process,
+//! except those of the thread pools of [scoped], which have their own collector.
+//!
+//! ```
+//! use o1_utils::profiling;
+//!
+//! {
+//!     let _span = profiling::span("my_phase");
+//!     // ... do some work
+//! }
+//!
+//! let summary = profiling::take_summary();
+//! #[cfg(feature = "profiling")]
+//! println!("{}", summary.to_json());
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// A guard that measures the time between its creation and its drop,
+/// and records it under the name of its phase.
+#[must_use = "the span is recorded when it is dropped"]
+pub struct Span {
+    #[cfg(feature = "profiling")]
+    phase: Option<(String, std::time::Instant)>,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        #[cfg(feature = "profiling")]
+        if let Some((name, start)) = self.phase.take() {
+            collector::record(name, start.elapsed());
+        }
+    }
+}
+
+/// Starts timing the phase `name`.
+#[cfg_attr(not(feature = "profiling"), allow(unused_variables))]
+pub fn span(name: &'static str) -> Span {
+    Span {
+        #[cfg(feature = "profiling")]
+        phase: Some((name.to_string(), std::time::Instant::now())),
+    }
+}
+
+/// Starts timing the numbered step `step` of the protocol `protocol` (e.g. `prover.step_07`).
+#[cfg_attr(not(feature = "profiling"), allow(unused_variables))]
+pub fn step_span(protocol: &'static str, step: usize) -> Span {
+    Span {
+        #[cfg(feature = "profiling")]
+        phase: Some((
+            format!("{}.step_{:02}", protocol, step),
+            std::time::Instant::now(),
+        )),
+    }
+}
+
+/// The time spent in one phase
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PhaseTiming {
+    /// The name of the phase
+    pub name: String,
+    /// The number of times the phase was executed
+    pub calls: usize,
+    /// The total time spent in the phase, in nanoseconds
+    pub total_ns: u64,
+}
+
+/// The timings collected since the last call to [take_summary],
+/// with phases ordered by their first execution.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub phases: Vec<PhaseTiming>,
+}
+
+impl Summary {
+    /// Returns the timing of the phase `name`, if it was executed.
+    pub fn phase(&self, name: &str) -> Option<&PhaseTiming> {
+        self.phases.iter().find(|p| p.name == name)
+    }
+
+    /// Serializes the summary to JSON.
+    #[cfg(feature = "profiling")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a summary can always be serialized")
+    }
+}
+
+/// Returns the timings collected so far (by all threads outside of [scoped]) and resets the collector.
+#[cfg(feature = "profiling")]
+pub fn take_summary() -> Summary {
+    collector::take(&collector::GLOBAL)
+}
+
+/// Returns the timings collected so far (by all threads outside of [scoped]) and resets the collector.
+#[cfg(not(feature = "profiling"))]
+pub fn take_summary() -> Summary {
+    Summary::default()
+}
+
+/// Runs `f` in a dedicated rayon thread pool, and returns its result
+/// with the timings of the spans of the threads of this pool only.
+/// These timings are not collected by [take_summary],
+/// and the spans of the other threads of the process are not part of them.
+#[cfg(feature = "profiling")]
+pub fn scoped<R: Send>(f: impl FnOnce() -> R + Send) -> (R, Summary) {
+    let timings = collector::Timings::default();
+    let thread_timings = timings.clone();
+    let pool = rayon::ThreadPoolBuilder::new()
+        .start_handler(move |_| collector::install(thread_timings.clone()))
+        .build()
+        .expect("failed to create the profiling thread pool");
+    let res = pool.install(f);
+    (res, collector::take(&timings))
+}
+
+/// Runs `f` in a dedicated rayon thread pool, and returns its result
+/// with the timings of the spans of the threads of this pool only.
+#[cfg(not(feature = "profiling"))]
+pub fn scoped<R: Send>(f: impl FnOnce() -> R + Send) -> (R, Summary) {
+    (f(), Summary::default())
+}
+
+#[cfg(feature = "profiling")]
+mod collector {
+    use super::{PhaseTiming, Summary};
+    use once_cell::sync::Lazy;
+    use std::{
+        cell::RefCell,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    /// The timings collected by a set of threads
+    pub(super) type Timings = Arc<Mutex<Vec<PhaseTiming>>>;
+
+    /// The timings of the threads without their own collector
+    pub(super) static GLOBAL: Lazy<Timings> = Lazy::new(Timings::default);
+
+    thread_local! {
+        /// The collector of the current thread, if it is not the global one
+        static LOCAL: RefCell<Option<Timings>> = RefCell::new(None);
+    }
+
+    /// Makes the current thread record its timings in `timings`.
+    pub(super) fn install(timings: Timings) {
+        LOCAL.with(|local| *local.borrow_mut() = Some(timings));
+    }
+
+    pub(super) fn record(name: String, elapsed: Duration) {
+        let elapsed = elapsed.as_nanos() as u64;
+        let timings = LOCAL
+            .with(|local| local.borrow().clone())
+            .unwrap_or_else(|| GLOBAL.clone());
+        let mut timings = timings.lock().expect("profiling collector poisoned");
+        match timings.iter_mut().find(|p| p.name == name) {
+            Some(phase) => {
+                phase.calls += 1;
+                phase.total_ns += elapsed;
+            }
+            None => timings.push(PhaseTiming {
+                name,
+                calls: 1,
+                total_ns: elapsed,
+            }),
+        }
+    }
+
+    pub(super) fn take(timings: &Timings) -> Summary {
+        let mut timings = timings.lock().expect("profiling collector poisoned");
+        Summary {
+            phases: std::mem::take(&mut *timings),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "profiling"))]
+mod tests {
+    use super::*;
+    use rayon::prelude::*;
+
+    #[test]
+    fn test_profiling_summary() {
+        let ((), summary) = scoped(|| {
+            for step in 0..3 {
+                let _outer = span("outer");
+                let _step = step_span("proto", step % 2);
+            }
+        });
+
+        let names: Vec<_> = summary.phases.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["proto.step_00", "outer", "proto.step_01"]);
+        assert_eq!(summary.phase("outer").unwrap().calls, 3);
+        assert_eq!(summary.phase("proto.step_00").unwrap().calls, 2);
+
+        let json: Summary = serde_json::from_str(&summary.to_json()).unwrap();
+        assert_eq!(json, summary);
+    }
+
+    #[test]
+    fn test_profiling_scoped() {
+        // the spans of the worker threads of the pool are collected too
+        let (sum, summary) = scoped(|| {
+            (0..64u64)
+                .into_par_iter()
+                .map(|i| {
+                    let _span = span("scoped.worker");
+                    i
+                })
+                .sum::<u64>()
+        });
+        assert_eq!(sum, 63 * 64 / 2);
+        assert_eq!(summary.phase("scoped.worker").unwrap().calls, 64);
+
+        // but not by the global collector, which collects the other threads
+        {
+            let _span = span("scoped.global");
+        }
+        let global = take_summary();
+        assert!(global.phase("scoped.worker").is_none());
+        assert_eq!(global.phase("scoped.global").unwrap().calls, 1);
+    }
+}